@@ -3,15 +3,15 @@ use std::time::Duration;
 use anyhow::{ensure, Context};
 use prometheus_client::registry::Registry;
 use sqd_data::{
-    bitcoin::tables::BitcoinChunkBuilder, evm::tables::EvmChunkBuilder,
+    bitcoin::tables::BitcoinChunkBuilder, evm::tables::EvmChunkBuilder, fuel::tables::FuelChunkBuilder,
     hyperliquid_fills::tables::HyperliquidFillsChunkBuilder,
     hyperliquid_replica_cmds::tables::HyperliquidReplicaCmdsChunkBuilder, solana::tables::SolanaChunkBuilder,
-    tron::tables::TronChunkBuilder
+    substrate::tables::SubstrateChunkBuilder, tron::tables::TronChunkBuilder
 };
 use sqd_primitives::BlockNumber;
 
 use crate::{
-    cli::{Cli, NetworkKind},
+    cli::{NetworkKind, WriteArgs},
     fs::create_fs,
     ingest::ingest_from_service,
     layout::Layout,
@@ -21,7 +21,7 @@ use crate::{
     writer::Writer
 };
 
-pub async fn run(args: Cli) -> anyhow::Result<()> {
+pub async fn run(args: WriteArgs) -> anyhow::Result<()> {
     ensure!(
         args.first_block <= args.last_block.unwrap_or(BlockNumber::MAX),
         "--first-block is greater than --last-block"
@@ -72,7 +72,9 @@ pub async fn run(args: Cli) -> anyhow::Result<()> {
         NetworkKind::HyperliquidFills => proc!(HyperliquidFillsChunkBuilder::default()),
         NetworkKind::HyperliquidReplicaCmds => proc!(HyperliquidReplicaCmdsChunkBuilder::default()),
         NetworkKind::Evm => proc!(EvmChunkBuilder::default()),
-        NetworkKind::Tron => proc!(TronChunkBuilder::default())
+        NetworkKind::Tron => proc!(TronChunkBuilder::default()),
+        NetworkKind::Substrate => proc!(SubstrateChunkBuilder::default()),
+        NetworkKind::Fuel => proc!(FuelChunkBuilder::default())
     };
 
     let attach_idx_field = args.attach_idx_field;
@@ -98,7 +100,7 @@ pub async fn run(args: Cli) -> anyhow::Result<()> {
     Ok(())
 }
 
-fn chunk_check(filelist: &[String]) -> bool {
+pub fn chunk_check(filelist: &[String]) -> bool {
     for file in filelist {
         if file.starts_with("blocks.parquet") {
             return true;
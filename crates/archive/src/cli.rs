@@ -1,4 +1,4 @@
-use clap::{value_parser, Parser, ValueEnum};
+use clap::{value_parser, Args, Parser, Subcommand, ValueEnum};
 use sqd_primitives::BlockNumber;
 use url::Url;
 
@@ -9,12 +9,49 @@ pub enum NetworkKind {
     HyperliquidFills,
     HyperliquidReplicaCmds,
     Evm,
-    Tron
+    Tron,
+    Substrate,
+    Fuel
+}
+
+#[derive(ValueEnum, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Repair {
+    /// Only report problems
+    None,
+    /// Delete bad chunks
+    Delete,
+    /// Delete bad chunks and write their block ranges again from the ingestion service
+    Reingest
 }
 
 #[derive(Parser, Debug)]
-#[command(version, about, long_about = None)]
+#[command(version, about, long_about = None, args_conflicts_with_subcommands = true)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
+    #[command(flatten)]
+    pub write: Option<WriteArgs>,
+
+    /// Whether the logs should be structured in JSON format
+    #[arg(long, global = true)]
+    pub json_log: bool
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Check the integrity of an existing archive
+    Verify(VerifyArgs),
+    /// Merge small consecutive chunks of an existing archive
+    Rechunk(RechunkArgs),
+    /// Write finalized chunks of a hotblocks service to the archive
+    FromHotblocks(FromHotblocksArgs),
+    /// Serve queries over a local archive
+    Serve(ServeArgs)
+}
+
+#[derive(Args, Debug)]
+pub struct WriteArgs {
     /// First block of a range to write
     #[arg(long, value_name = "N", default_value_t = 0)]
     pub first_block: BlockNumber,
@@ -51,15 +88,136 @@ pub struct Cli {
     #[arg(long, value_enum)]
     pub network_kind: NetworkKind,
 
-    /// Whether the logs should be structured in JSON format
+    /// Port to use for built-in prometheus metrics server
+    #[arg(long)]
+    pub prom_port: Option<u16>,
+
+    // Interval between attempts to stream new blocks in seconds
+    #[arg(long, value_parser = value_parser!(u16).range(1..), default_value_t = 300)]
+    pub block_stream_interval: u16,
+
+    /// Whether to attach an index field to each record
     #[arg(long)]
-    pub json_log: bool,
+    pub attach_idx_field: bool
+}
+
+#[derive(Args, Debug)]
+pub struct VerifyArgs {
+    /// Archive dir or s3 location to verify
+    #[arg(short, long, value_name = "ARCHIVE")]
+    pub dest: String,
+
+    /// Network kind
+    #[arg(long, value_enum)]
+    pub network_kind: NetworkKind,
+
+    /// First block of a range to verify
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub first_block: BlockNumber,
+
+    /// Last block of a range to verify
+    #[arg(long, value_name = "N")]
+    pub last_block: Option<BlockNumber>,
+
+    /// Check if block parent hash matches previous block hash
+    #[arg(long, require_equals = false, num_args = 0..=1, default_value_t = true)]
+    pub validate_chain_continuity: bool,
+
+    /// What to do with chunks, that failed verification
+    #[arg(long, value_enum, default_value_t = Repair::None)]
+    pub repair: Repair,
+
+    /// URL of the data ingestion service to re-ingest bad chunks from
+    #[arg(short, long, value_name = "URL", required_if_eq("repair", "reingest"))]
+    pub src: Option<Url>,
+
+    // Interval between attempts to stream new blocks in seconds
+    #[arg(long, value_parser = value_parser!(u16).range(1..), default_value_t = 300)]
+    pub block_stream_interval: u16,
+
+    /// Whether to attach an index field to each record of re-ingested chunks
+    #[arg(long)]
+    pub attach_idx_field: bool
+}
+
+#[derive(Args, Debug)]
+pub struct RechunkArgs {
+    /// Archive dir or s3 location to rechunk
+    #[arg(short, long, value_name = "ARCHIVE")]
+    pub dest: String,
+
+    /// Network kind
+    #[arg(long, value_enum)]
+    pub network_kind: NetworkKind,
+
+    /// First block of a range to rechunk
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub first_block: BlockNumber,
+
+    /// Last block of a range to rechunk
+    #[arg(long, value_name = "N")]
+    pub last_block: Option<BlockNumber>,
+
+    /// Data chunk size in megabytes
+    #[arg(long, value_name = "MB", default_value_t = 2048)]
+    pub chunk_size: usize,
+
+    /// Upper limit on the per-file rows
+    #[arg(long, value_name = "N", default_value_t = 200_000)]
+    pub max_num_rows: usize,
+
+    /// Whether to attach an index field to each record
+    #[arg(long)]
+    pub attach_idx_field: bool
+}
+
+#[derive(Args, Debug)]
+pub struct FromHotblocksArgs {
+    /// First block of a range to write
+    #[arg(long, value_name = "N", default_value_t = 0)]
+    pub first_block: BlockNumber,
+
+    /// Last block of a range to write
+    #[arg(long, value_name = "N")]
+    pub last_block: Option<BlockNumber>,
+
+    /// URL of the hotblocks service
+    #[arg(short, long, value_name = "URL")]
+    pub src: Url,
+
+    /// Dataset of the hotblocks service to copy
+    #[arg(long, value_name = "ID")]
+    pub dataset: String,
+
+    /// Target dir or s3 location to write data to
+    #[arg(short, long, value_name = "ARCHIVE")]
+    pub dest: String,
+
+    /// Number of chunks in top-level dir
+    #[arg(long, value_name = "N", default_value_t = 1000)]
+    pub top_dir_size: usize,
+
+    /// Data chunk size in megabytes
+    #[arg(long, value_name = "MB", default_value_t = 2048)]
+    pub chunk_size: usize,
+
+    /// Upper limit on the per-file rows
+    #[arg(long, value_name = "N", default_value_t = 200_000)]
+    pub max_num_rows: usize,
+
+    /// Check if block parent hash matches previous block hash
+    #[arg(long, require_equals = false, num_args = 0..=1, default_value_t = true)]
+    pub validate_chain_continuity: bool,
+
+    /// Network kind
+    #[arg(long, value_enum)]
+    pub network_kind: NetworkKind,
 
     /// Port to use for built-in prometheus metrics server
     #[arg(long)]
     pub prom_port: Option<u16>,
 
-    // Interval between attempts to stream new blocks in seconds
+    // Interval between attempts to fetch new finalized chunks in seconds
     #[arg(long, value_parser = value_parser!(u16).range(1..), default_value_t = 300)]
     pub block_stream_interval: u16,
 
@@ -67,3 +225,18 @@ pub struct Cli {
     #[arg(long)]
     pub attach_idx_field: bool
 }
+
+#[derive(Args, Debug)]
+pub struct ServeArgs {
+    /// Local archive dir to serve
+    #[arg(short, long, value_name = "ARCHIVE")]
+    pub dest: String,
+
+    /// Dataset id, under which the archive is served
+    #[arg(long, value_name = "ID", default_value = "archive")]
+    pub dataset: String,
+
+    /// Port to listen on
+    #[arg(long, default_value_t = 3000)]
+    pub port: u16
+}
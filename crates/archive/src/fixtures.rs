@@ -0,0 +1,104 @@
+//! Small on-disk EVM archives for tests
+
+use std::{path::Path, sync::Arc};
+
+use serde_json::{json, Value};
+use sqd_data::evm::{model::Block, tables::EvmChunkBuilder};
+use sqd_primitives::BlockNumber;
+
+use crate::{
+    archive::chunk_check,
+    chunk_writer::ChunkWriter,
+    fs::{local::LocalFs, FSRef},
+    layout::{DataChunk, Layout},
+    proc::short_hash,
+    writer::{upload_chunk, WriterItem}
+};
+
+pub fn block_hash(number: BlockNumber) -> String {
+    format!("0x{:064x}", number)
+}
+
+/// Number of logs in the block `number` of a fixture archive
+pub fn log_count(number: BlockNumber) -> usize {
+    (number % 3) as usize
+}
+
+/// JSON of the block `number`, as the ingestion service streams it
+pub fn evm_block_json(number: BlockNumber) -> Value {
+    let logs: Vec<_> = (0..log_count(number))
+        .map(|i| {
+            json!({
+                "logIndex": i,
+                "transactionIndex": 0,
+                "transactionHash": block_hash(number),
+                "address": format!("0x{:040x}", i),
+                "data": "0x",
+                "topics": []
+            })
+        })
+        .collect();
+
+    json!({
+        "header": {
+            "number": number,
+            "hash": block_hash(number),
+            "parentHash": block_hash(number - 1),
+            "timestamp": 1_700_000_000 + number,
+            "transactionsRoot": block_hash(0),
+            "receiptsRoot": block_hash(0),
+            "stateRoot": block_hash(0),
+            "logsBloom": "0x00",
+            "sha3Uncles": block_hash(0),
+            "extraData": "0x",
+            "miner": format!("0x{:040x}", 0),
+            "size": 100,
+            "gasLimit": "0x0",
+            "gasUsed": "0x0"
+        },
+        "transactions": [],
+        "logs": logs
+    })
+}
+
+pub fn evm_block(number: BlockNumber) -> Block {
+    serde_json::from_value(evm_block_json(number)).unwrap()
+}
+
+pub fn local_fs(root: &Path) -> FSRef {
+    Arc::new(LocalFs::new(root))
+}
+
+/// Writes chunks with the given block ranges, like `sqd-archive` would.
+pub async fn write_archive(
+    fs: &FSRef,
+    ranges: &[(BlockNumber, BlockNumber)],
+    top_dir_size: usize
+) -> anyhow::Result<Vec<DataChunk>> {
+    let layout = Layout::new(fs.clone());
+    let mut tracker = layout
+        .create_chunk_tracker(&chunk_check, top_dir_size, ranges[0].0, None)
+        .await?;
+
+    let mut chunks = Vec::with_capacity(ranges.len());
+    for &(first_block, last_block) in ranges {
+        let chunk = tracker.next_chunk(first_block, last_block, short_hash(&block_hash(last_block)).to_string());
+        write_chunk(fs, &chunk).await?;
+        chunks.push(chunk);
+    }
+    Ok(chunks)
+}
+
+/// Writes the blocks of `chunk` to its dir.
+pub async fn write_chunk(fs: &FSRef, chunk: &DataChunk) -> anyhow::Result<()> {
+    let mut writer = ChunkWriter::new(EvmChunkBuilder::default())?;
+    for number in chunk.first_block..=chunk.last_block {
+        writer.push(&evm_block(number))?;
+    }
+    let item = WriterItem {
+        chunk: chunk.clone(),
+        data: writer.finish()?,
+        description: EvmChunkBuilder::dataset_description()
+    };
+    upload_chunk(fs, item, false).await
+}
@@ -4,6 +4,7 @@ use std::{
 };
 
 use async_trait::async_trait;
+use bytes::Bytes;
 
 use crate::fs::{FSRef, Fs};
 
@@ -38,6 +39,11 @@ impl Fs for LocalFs {
         }
     }
 
+    async fn read(&self, path: &str) -> anyhow::Result<Bytes> {
+        let data = tokio::fs::read(self.root.join(path)).await?;
+        Ok(data.into())
+    }
+
     async fn move_local(&self, local_src: &Path, dest: &str) -> anyhow::Result<()> {
         let dest = self.root.join(dest);
         if let Some(dir) = dest.parent() {
@@ -2,6 +2,7 @@ use std::{path::Path, sync::Arc};
 
 use anyhow::{anyhow, bail, ensure};
 use async_trait::async_trait;
+use bytes::Bytes;
 use url::Url;
 
 use crate::fs::{local::LocalFs, s3::S3Fs};
@@ -17,6 +18,8 @@ pub trait Fs {
 
     async fn ls(&self) -> anyhow::Result<Vec<String>>;
 
+    async fn read(&self, path: &str) -> anyhow::Result<Bytes>;
+
     async fn move_local(&self, local_src: &Path, dest: &str) -> anyhow::Result<()>;
 
     async fn delete(&self, path: &str) -> anyhow::Result<()>;
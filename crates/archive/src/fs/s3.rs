@@ -3,6 +3,7 @@ use std::{path::Path, sync::Arc};
 use anyhow::{ensure, Context};
 use async_trait::async_trait;
 use aws_sdk_s3::primitives::ByteStream;
+use bytes::Bytes;
 
 use crate::fs::{FSRef, Fs};
 
@@ -154,6 +155,18 @@ impl Fs for S3Fs {
         Ok(items)
     }
 
+    async fn read(&self, path: &str) -> anyhow::Result<Bytes> {
+        let output = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(&self.resolve_item_key(path)?)
+            .send()
+            .await?;
+        let data = output.body.collect().await?;
+        Ok(data.into_bytes())
+    }
+
     async fn move_local(&self, local_src: &Path, dest: &str) -> anyhow::Result<()> {
         let dest_key = self.resolve_item_key(dest)?;
 
@@ -0,0 +1,447 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    time::Duration
+};
+
+use anyhow::{anyhow, ensure};
+use arrow::{
+    array::{AsArray, RecordBatch, UInt64Array},
+    compute::{
+        and, cast, filter_record_batch,
+        kernels::cmp::{gt_eq, lt_eq}
+    },
+    datatypes::{DataType, UInt64Type},
+    ipc::reader::StreamReader
+};
+use prometheus_client::{metrics::gauge::Atomic, registry::Registry};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use sqd_data::{
+    bitcoin::tables::BitcoinChunkBuilder, evm::tables::EvmChunkBuilder, fuel::tables::FuelChunkBuilder,
+    hyperliquid_fills::tables::HyperliquidFillsChunkBuilder,
+    hyperliquid_replica_cmds::tables::HyperliquidReplicaCmdsChunkBuilder, solana::tables::SolanaChunkBuilder,
+    substrate::tables::SubstrateChunkBuilder, tron::tables::TronChunkBuilder
+};
+use sqd_data_core::{ChunkBuilder, ChunkProcessor};
+use sqd_primitives::BlockNumber;
+use tracing::{error, info};
+use url::Url;
+
+use crate::{
+    archive::chunk_check,
+    cli::{FromHotblocksArgs, NetworkKind},
+    fs::create_fs,
+    layout::{ChunkTracker, Layout},
+    metrics,
+    proc::{fallback_short_hash, short_hash},
+    rechunk::push_record_batch,
+    server::run_server,
+    writer::{Writer, WriterItem}
+};
+
+pub async fn run(args: FromHotblocksArgs) -> anyhow::Result<()> {
+    ensure!(
+        args.first_block <= args.last_block.unwrap_or(BlockNumber::MAX),
+        "--first-block is greater than --last-block"
+    );
+
+    let fs = create_fs(&args.dest).await?;
+    let layout = Layout::new(fs.clone());
+
+    let chunk_tracker = layout
+        .create_chunk_tracker(&chunk_check, args.top_dir_size, args.first_block, args.last_block)
+        .await?;
+
+    if let Some(last_block) = args.last_block {
+        if chunk_tracker.next_block() > last_block {
+            info!("nothing to do");
+            return Ok(());
+        }
+    }
+
+    if let Some(prom_port) = args.prom_port {
+        let mut metrics_registry = Registry::default();
+        metrics::register_metrics(&mut metrics_registry);
+        let server = run_server(metrics_registry, prom_port);
+        tokio::spawn(server);
+    }
+
+    let (chunk_sender, chunk_receiver) = tokio::sync::mpsc::channel(5);
+    let mut writer = Writer::new(fs, chunk_receiver, args.attach_idx_field);
+
+    macro_rules! import {
+        ($chunk_builder:expr) => {{
+            let import = Import::new(&args, $chunk_builder, chunk_tracker, chunk_sender)?;
+            tokio::try_join!(import.run(), writer.start())?;
+        }};
+    }
+
+    match args.network_kind {
+        NetworkKind::Bitcoin => import!(BitcoinChunkBuilder::default()),
+        NetworkKind::Solana => import!(SolanaChunkBuilder::default()),
+        NetworkKind::HyperliquidFills => import!(HyperliquidFillsChunkBuilder::default()),
+        NetworkKind::HyperliquidReplicaCmds => import!(HyperliquidReplicaCmdsChunkBuilder::default()),
+        NetworkKind::Evm => import!(EvmChunkBuilder::default()),
+        NetworkKind::Tron => import!(TronChunkBuilder::default()),
+        NetworkKind::Substrate => import!(SubstrateChunkBuilder::default()),
+        NetworkKind::Fuel => import!(FuelChunkBuilder::default())
+    }
+
+    Ok(())
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HotblocksChunk {
+    first_block: BlockNumber,
+    last_block: BlockNumber,
+    last_block_time: Option<i64>,
+    tables: Vec<String>
+}
+
+struct HotblocksClient {
+    http: reqwest::Client,
+    dataset_url: String
+}
+
+impl HotblocksClient {
+    fn new(url: &Url, dataset: &str) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            dataset_url: format!("{}/datasets/{}", url.as_str().trim_end_matches('/'), dataset)
+        }
+    }
+
+    async fn list_chunks(&self, from_block: BlockNumber) -> anyhow::Result<Vec<HotblocksChunk>> {
+        let chunks = self
+            .http
+            .get(format!("{}/finalized-chunks", self.dataset_url))
+            .query(&[("fromBlock", from_block)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(chunks)
+    }
+
+    /// Returns `None`, if the chunk no longer exists
+    async fn get_table(&self, first_block: BlockNumber, table: &str) -> anyhow::Result<Option<Vec<RecordBatch>>> {
+        let res = self
+            .http
+            .get(format!(
+                "{}/finalized-chunks/{}/{}",
+                self.dataset_url, first_block, table
+            ))
+            .send()
+            .await?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let bytes = res.error_for_status()?.bytes().await?;
+        let batches = StreamReader::try_new(bytes.as_ref(), None)?.collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(batches))
+    }
+
+    async fn get_tables(&self, chunk: &HotblocksChunk) -> anyhow::Result<Option<BTreeMap<String, Vec<RecordBatch>>>> {
+        let mut tables = BTreeMap::new();
+        for table in &chunk.tables {
+            let Some(batches) = self.get_table(chunk.first_block, table).await? else {
+                return Ok(None);
+            };
+            tables.insert(table.clone(), batches);
+        }
+        Ok(Some(tables))
+    }
+}
+
+/// Copies finalized chunks of a hotblocks dataset into the archive.
+///
+/// Hotblocks chunks are merged into archive chunks of the configured size.
+/// Hotblocks may merge its own chunks at any moment, so the data is requested by block number,
+/// and the rows below the next archive block are dropped.
+struct Import<'a, B> {
+    args: &'a FromHotblocksArgs,
+    client: HotblocksClient,
+    chunk_builder: B,
+    processor: ChunkProcessor,
+    chunk_tracker: ChunkTracker,
+    chunk_sender: tokio::sync::mpsc::Sender<WriterItem>,
+    /// Tables of the data pushed to the `processor`
+    tables: BTreeSet<String>,
+    /// Last block pushed to the `processor`
+    last_block: Option<BlockNumber>,
+    last_block_hash: Option<String>,
+    /// Whether any data was received from hotblocks
+    received: bool
+}
+
+impl<'a, B: ChunkBuilder> Import<'a, B> {
+    fn new(
+        args: &'a FromHotblocksArgs,
+        chunk_builder: B,
+        chunk_tracker: ChunkTracker,
+        chunk_sender: tokio::sync::mpsc::Sender<WriterItem>
+    ) -> anyhow::Result<Self> {
+        Ok(Self {
+            args,
+            client: HotblocksClient::new(&args.src, &args.dataset),
+            processor: chunk_builder.new_chunk_processor()?,
+            chunk_builder,
+            chunk_tracker,
+            chunk_sender,
+            tables: BTreeSet::new(),
+            last_block: None,
+            last_block_hash: None,
+            received: false
+        })
+    }
+
+    fn next_block(&self) -> BlockNumber {
+        self.last_block
+            .map(|block| block + 1)
+            .unwrap_or_else(|| self.chunk_tracker.next_block())
+    }
+
+    async fn run(mut self) -> anyhow::Result<()> {
+        let last_block = self.args.last_block.unwrap_or(BlockNumber::MAX);
+        let block_stream_interval = Duration::from_secs(self.args.block_stream_interval.into());
+
+        'fetch: while self.next_block() <= last_block {
+            let chunks = match self.client.list_chunks(self.next_block()).await {
+                Ok(chunks) => chunks,
+                Err(err) if self.received => {
+                    error!(err =? err, "failed to list hotblocks chunks, will pause for 5 sec and try again");
+                    tokio::time::sleep(Duration::from_secs(5)).await;
+                    continue;
+                }
+                Err(err) => return Err(err)
+            };
+
+            if chunks.is_empty() {
+                info!(
+                    "no finalized chunks were found. waiting {} sec for a new try",
+                    block_stream_interval.as_secs()
+                );
+                tokio::time::sleep(block_stream_interval).await;
+                continue;
+            }
+
+            for chunk in chunks {
+                ensure!(
+                    chunk.first_block <= self.next_block(),
+                    "blocks {}-{} are not available in hotblocks, perhaps they were already removed by retention",
+                    self.next_block(),
+                    chunk.first_block - 1
+                );
+
+                let tables = match self.client.get_tables(&chunk).await {
+                    Ok(Some(tables)) => tables,
+                    // the chunk was merged with its neighbours, list the chunks again
+                    Ok(None) => continue 'fetch,
+                    Err(err) if self.received => {
+                        error!(err =? err, "failed to fetch a hotblocks chunk, will pause for 5 sec and try again");
+                        tokio::time::sleep(Duration::from_secs(5)).await;
+                        continue 'fetch;
+                    }
+                    Err(err) => return Err(err)
+                };
+                self.received = true;
+
+                if !self.push_chunk(&chunk, tables).await? {
+                    break 'fetch;
+                }
+            }
+        }
+
+        self.flush().await
+    }
+
+    /// Returns `false`, when the chunk contains no blocks of the remaining range
+    async fn push_chunk(
+        &mut self,
+        chunk: &HotblocksChunk,
+        tables: BTreeMap<String, Vec<RecordBatch>>
+    ) -> anyhow::Result<bool> {
+        let first_block = self.next_block();
+        let last_block = self.args.last_block.unwrap_or(BlockNumber::MAX);
+
+        let tables = if chunk.first_block < first_block || chunk.last_block > last_block {
+            let description = self.chunk_builder.dataset_description();
+            tables
+                .into_iter()
+                .map(|(name, batches)| {
+                    let column = description
+                        .tables
+                        .get(name.as_str())
+                        .and_then(|desc| desc.downcast.block_number.first())
+                        .ok_or_else(|| anyhow!("block number column of table '{}' is unknown", name))?;
+                    let batches = batches
+                        .iter()
+                        .map(|batch| filter_block_range(batch, column, first_block, last_block))
+                        .collect::<anyhow::Result<Vec<_>>>()?;
+                    Ok((name, batches))
+                })
+                .collect::<anyhow::Result<BTreeMap<_, _>>>()?
+        } else {
+            tables
+        };
+
+        let blocks = tables
+            .get("blocks")
+            .ok_or_else(|| anyhow!("chunk {}-{} has no blocks table", chunk.first_block, chunk.last_block))?;
+
+        let Some(span) = BlockSpan::read(blocks)? else {
+            return Ok(false);
+        };
+
+        if let Some(hash) = self.last_block_hash.as_ref() {
+            if self.args.validate_chain_continuity {
+                ensure!(
+                    hash == &span.parent_hash,
+                    "parent hash mismatch for block {}: expected {}, but got {}",
+                    span.first_block,
+                    hash,
+                    span.parent_hash
+                );
+            }
+        } else if let Some(hash) = self.chunk_tracker.prev_chunk_hash() {
+            ensure!(
+                hash == short_hash(&span.parent_hash) || hash == fallback_short_hash(&span.parent_hash),
+                "previous chunk hash {} does not match parent hash {} of block {}",
+                hash,
+                span.parent_hash,
+                span.first_block
+            );
+        }
+
+        let table_names: BTreeSet<String> = tables.keys().cloned().collect();
+        if self.last_block.is_some() && self.tables != table_names {
+            self.flush().await?;
+        }
+
+        tokio::task::block_in_place(|| -> anyhow::Result<()> {
+            for (name, batches) in &tables {
+                for batch in batches {
+                    push_record_batch(&mut self.processor, name, batch)?;
+                }
+            }
+            Ok(())
+        })?;
+
+        self.tables = table_names;
+        self.last_block = Some(span.last_block);
+        self.last_block_hash = Some(span.last_hash);
+
+        metrics::LATEST_BLOCK.set(span.last_block);
+        metrics::LAST_BLOCK.inner().set(span.last_block);
+        if let Some(timestamp) = chunk.last_block_time.filter(|_| span.last_block == chunk.last_block) {
+            metrics::LATEST_BLOCK_TIMESTAMP.set(timestamp / 1000);
+        }
+
+        if self.processor.byte_size() > self.args.chunk_size * 1024 * 1024
+            || self.processor.max_num_rows() > self.args.max_num_rows
+        {
+            self.flush().await?;
+        }
+
+        Ok(true)
+    }
+
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        let Some(last_block) = self.last_block.take() else {
+            return Ok(());
+        };
+
+        let processor = std::mem::replace(&mut self.processor, self.chunk_builder.new_chunk_processor()?);
+        let mut data = tokio::task::spawn_blocking(move || processor.finish()).await??;
+        data.retain(|name, _| self.tables.contains(*name));
+
+        let last_hash = self
+            .last_block_hash
+            .as_deref()
+            .expect("hash of the last block must be known");
+        let chunk = self.chunk_tracker.next_chunk(
+            self.chunk_tracker.next_block(),
+            last_block,
+            short_hash(last_hash).to_string()
+        );
+
+        self.chunk_sender
+            .send(WriterItem {
+                chunk,
+                data,
+                description: self.chunk_builder.dataset_description()
+            })
+            .await?;
+
+        Ok(())
+    }
+}
+
+/// Block range of a blocks table
+struct BlockSpan {
+    first_block: BlockNumber,
+    parent_hash: String,
+    last_block: BlockNumber,
+    last_hash: String
+}
+
+impl BlockSpan {
+    fn read(batches: &[RecordBatch]) -> anyhow::Result<Option<Self>> {
+        let mut span: Option<Self> = None;
+
+        for batch in batches {
+            let column = |name: &str| {
+                batch
+                    .column_by_name(name)
+                    .ok_or_else(|| anyhow!("column '{}' is missing in blocks table", name))
+            };
+            let numbers = cast(column("number")?, &DataType::UInt64)?;
+            let numbers = numbers.as_primitive::<UInt64Type>();
+            let hashes = cast(column("hash")?, &DataType::Utf8)?;
+            let hashes = hashes.as_string::<i32>();
+            let parent_hashes = cast(column("parent_hash")?, &DataType::Utf8)?;
+            let parent_hashes = parent_hashes.as_string::<i32>();
+
+            for i in 0..batch.num_rows() {
+                let number = numbers.value(i);
+                let current = span.get_or_insert_with(|| Self {
+                    first_block: number,
+                    parent_hash: parent_hashes.value(i).to_string(),
+                    last_block: number,
+                    last_hash: hashes.value(i).to_string()
+                });
+                if number < current.first_block {
+                    current.first_block = number;
+                    current.parent_hash = parent_hashes.value(i).to_string();
+                }
+                if number > current.last_block {
+                    current.last_block = number;
+                    current.last_hash = hashes.value(i).to_string();
+                }
+            }
+        }
+
+        Ok(span)
+    }
+}
+
+fn filter_block_range(
+    batch: &RecordBatch,
+    column: &str,
+    first_block: BlockNumber,
+    last_block: BlockNumber
+) -> anyhow::Result<RecordBatch> {
+    let numbers = batch
+        .column_by_name(column)
+        .ok_or_else(|| anyhow!("column '{}' is missing", column))?;
+    let numbers = cast(numbers, &DataType::UInt64)?;
+    let mask = and(
+        &gt_eq(&numbers, &UInt64Array::new_scalar(first_block))?,
+        &lt_eq(&numbers, &UInt64Array::new_scalar(last_block))?
+    )?;
+    Ok(filter_record_batch(batch, &mask)?)
+}
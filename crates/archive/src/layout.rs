@@ -1,4 +1,4 @@
-use std::{pin::pin, sync::LazyLock};
+use std::{cmp::Reverse, pin::pin, sync::LazyLock};
 
 use anyhow::ensure;
 use async_stream::try_stream;
@@ -6,7 +6,7 @@ use futures::{Stream, StreamExt, TryStreamExt};
 use regex::Regex;
 use sqd_primitives::BlockNumber;
 
-use crate::fs::FSRef;
+use crate::{archive::chunk_check, fs::FSRef};
 
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct DataChunk {
@@ -70,7 +70,42 @@ impl Layout {
         Ok(tops)
     }
 
-    async fn get_top_chunks(&self, top: u64) -> anyhow::Result<Vec<DataChunk>> {
+    /// Lists the chunks of a top dir, as they are visible to readers.
+    ///
+    /// A chunk, that contains other chunks of the same top dir (like one written by `rechunk`),
+    /// replaces them as soon as it is complete, i.e. has `blocks.parquet`.
+    /// Until then it is not listed.
+    pub async fn get_top_chunks(&self, top: u64) -> anyhow::Result<Vec<DataChunk>> {
+        let mut chunks = self.list_top_chunks(top).await?;
+        chunks.sort_by_key(|chunk| (chunk.first_block, Reverse(chunk.last_block)));
+
+        let mut result: Vec<DataChunk> = Vec::with_capacity(chunks.len());
+        for chunk in chunks {
+            if let Some(prev) = result.last() {
+                if chunk.last_block <= prev.last_block && self.is_complete(prev).await? {
+                    continue;
+                }
+            }
+            while let Some(prev) = result.last() {
+                if chunk.last_block <= prev.last_block {
+                    result.pop();
+                } else {
+                    break;
+                }
+            }
+            result.push(chunk);
+        }
+
+        Ok(result)
+    }
+
+    async fn is_complete(&self, chunk: &DataChunk) -> anyhow::Result<bool> {
+        let files = self.fs.cd(&chunk.path()).ls().await?;
+        Ok(chunk_check(&files))
+    }
+
+    /// Lists all chunk dirs of a top dir, including incomplete and replaced ones
+    pub async fn list_top_chunks(&self, top: u64) -> anyhow::Result<Vec<DataChunk>> {
         self.fs
             .cd(&format_block_number(top))
             .ls()
@@ -223,6 +258,7 @@ impl Layout {
         Ok(ChunkTracker {
             top_dir_size,
             base_chunk_hash: prev_hash,
+            base_block: top,
             last_block_limit: last_block.unwrap_or(BlockNumber::MAX),
             top,
             chunks
@@ -233,12 +269,27 @@ impl Layout {
 pub struct ChunkTracker {
     top_dir_size: usize,
     base_chunk_hash: Option<String>,
+    base_block: BlockNumber,
     last_block_limit: BlockNumber,
     top: BlockNumber,
     chunks: Vec<DataChunk>
 }
 
 impl ChunkTracker {
+    /// Creates a tracker for writing the block range of `chunk` again.
+    ///
+    /// New chunks are placed into the top dir of `chunk`, regardless of its size.
+    pub fn for_chunk(chunk: &DataChunk, prev_chunk_hash: Option<String>) -> ChunkTracker {
+        ChunkTracker {
+            top_dir_size: usize::MAX,
+            base_chunk_hash: prev_chunk_hash,
+            base_block: chunk.first_block,
+            last_block_limit: chunk.last_block,
+            top: chunk.top,
+            chunks: vec![]
+        }
+    }
+
     pub fn prev_chunk_hash(&self) -> Option<&str> {
         self.chunks
             .last()
@@ -247,7 +298,7 @@ impl ChunkTracker {
     }
 
     pub fn next_block(&self) -> BlockNumber {
-        self.chunks.last().map(|c| c.last_block + 1).unwrap_or(self.top)
+        self.chunks.last().map(|c| c.last_block + 1).unwrap_or(self.base_block)
     }
 
     pub fn next_chunk(&mut self, first_block: BlockNumber, last_block: BlockNumber, last_hash: String) -> DataChunk {
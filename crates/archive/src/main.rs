@@ -1,13 +1,19 @@
 mod archive;
 mod chunk_writer;
 mod cli;
+#[cfg(test)]
+mod fixtures;
 mod fs;
+mod hotblocks;
 mod ingest;
 mod layout;
 mod metrics;
 mod proc;
 mod progress;
+mod rechunk;
+mod serve;
 mod server;
+mod verify;
 mod writer;
 
 fn main() -> anyhow::Result<()> {
@@ -15,10 +21,19 @@ fn main() -> anyhow::Result<()> {
 
     init_logging(args.json_log);
 
-    tokio::runtime::Builder::new_multi_thread()
-        .enable_all()
-        .build()?
-        .block_on(archive::run(args))
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+
+    match (args.command, args.write) {
+        (Some(cli::Command::Verify(args)), _) => runtime.block_on(verify::run(args)),
+        (Some(cli::Command::Rechunk(args)), _) => runtime.block_on(rechunk::run(args)),
+        (Some(cli::Command::FromHotblocks(args)), _) => runtime.block_on(hotblocks::run(args)),
+        (Some(cli::Command::Serve(args)), _) => runtime.block_on(serve::run(args)),
+        (None, Some(args)) => runtime.block_on(archive::run(args)),
+        (None, None) => {
+            <cli::Cli as clap::CommandFactory>::command().print_help()?;
+            std::process::exit(2)
+        }
+    }
 }
 
 fn init_logging(json: bool) {
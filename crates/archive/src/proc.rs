@@ -176,11 +176,11 @@ impl<B: BlockChunkBuilder<Block: Block>> Proc<B> {
     }
 }
 
-fn short_hash(value: &str) -> &str {
+pub fn short_hash(value: &str) -> &str {
     let offset = value.len().saturating_sub(8);
     value.get(offset..).unwrap_or_default()
 }
 
-fn fallback_short_hash(value: &str) -> &str {
+pub fn fallback_short_hash(value: &str) -> &str {
     value.get(2..10).unwrap_or_default()
 }
@@ -0,0 +1,352 @@
+use std::{cmp::Reverse, collections::BTreeSet};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use arrow::{array::RecordBatch, compute::cast};
+use bytes::Bytes;
+use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+use sqd_array::slice::AsSlice;
+use sqd_data::{
+    bitcoin::tables::BitcoinChunkBuilder, evm::tables::EvmChunkBuilder, fuel::tables::FuelChunkBuilder,
+    hyperliquid_fills::tables::HyperliquidFillsChunkBuilder,
+    hyperliquid_replica_cmds::tables::HyperliquidReplicaCmdsChunkBuilder, solana::tables::SolanaChunkBuilder,
+    substrate::tables::SubstrateChunkBuilder, tron::tables::TronChunkBuilder
+};
+use sqd_data_core::{ChunkBuilder, ChunkProcessor};
+use sqd_primitives::BlockNumber;
+
+use crate::{
+    archive::chunk_check,
+    cli::{NetworkKind, RechunkArgs},
+    fs::{create_fs, FSRef},
+    layout::{DataChunk, Layout},
+    writer::{upload_chunk, WriterItem}
+};
+
+pub async fn run(args: RechunkArgs) -> anyhow::Result<()> {
+    ensure!(
+        args.first_block <= args.last_block.unwrap_or(BlockNumber::MAX),
+        "--first-block is greater than --last-block"
+    );
+
+    let fs = create_fs(&args.dest).await?;
+
+    match args.network_kind {
+        NetworkKind::Bitcoin => Rechunk::new(fs, &args, BitcoinChunkBuilder::default())?.run().await,
+        NetworkKind::Solana => Rechunk::new(fs, &args, SolanaChunkBuilder::default())?.run().await,
+        NetworkKind::HyperliquidFills => {
+            Rechunk::new(fs, &args, HyperliquidFillsChunkBuilder::default())?
+                .run()
+                .await
+        }
+        NetworkKind::HyperliquidReplicaCmds => {
+            Rechunk::new(fs, &args, HyperliquidReplicaCmdsChunkBuilder::default())?
+                .run()
+                .await
+        }
+        NetworkKind::Evm => Rechunk::new(fs, &args, EvmChunkBuilder::default())?.run().await,
+        NetworkKind::Tron => Rechunk::new(fs, &args, TronChunkBuilder::default())?.run().await,
+        NetworkKind::Substrate => Rechunk::new(fs, &args, SubstrateChunkBuilder::default())?.run().await,
+        NetworkKind::Fuel => Rechunk::new(fs, &args, FuelChunkBuilder::default())?.run().await
+    }
+}
+
+/// Merges runs of consecutive chunks within each top dir.
+///
+/// A merged chunk is written next to the chunks it replaces, with `blocks.parquet` uploaded last.
+/// [`Layout`] lists a chunk containing other chunks only once it is complete and then hides the contained ones,
+/// so readers switch to the merged chunk in one step, when its `blocks.parquet` appears.
+/// The replaced chunks are deleted afterwards. If the process is interrupted in between,
+/// the next run deletes the leftovers.
+struct Rechunk<'a, B> {
+    fs: FSRef,
+    args: &'a RechunkArgs,
+    chunk_builder: B,
+    processor: ChunkProcessor,
+    /// Chunks pushed to the `processor`
+    group: Vec<DataChunk>,
+    /// Tables of the chunks in the `group`
+    group_tables: BTreeSet<String>
+}
+
+impl<'a, B: ChunkBuilder> Rechunk<'a, B> {
+    fn new(fs: FSRef, args: &'a RechunkArgs, chunk_builder: B) -> anyhow::Result<Self> {
+        Ok(Self {
+            fs,
+            args,
+            processor: chunk_builder.new_chunk_processor()?,
+            chunk_builder,
+            group: Vec::new(),
+            group_tables: BTreeSet::new()
+        })
+    }
+
+    async fn run(mut self) -> anyhow::Result<()> {
+        let layout = Layout::new(self.fs.clone());
+        let first_block = self.args.first_block;
+        let last_block = self.args.last_block.unwrap_or(BlockNumber::MAX);
+
+        let tops = layout.get_tops().await?;
+        for (i, &top) in tops.iter().enumerate() {
+            if last_block < top {
+                break;
+            }
+            if i + 1 < tops.len() && tops[i + 1] <= first_block {
+                continue;
+            }
+
+            let chunks = layout
+                .list_top_chunks(top)
+                .await?
+                .into_iter()
+                .filter(|chunk| first_block <= chunk.first_block && chunk.last_block <= last_block)
+                .collect();
+
+            for chunk in self.remove_leftovers(chunks).await? {
+                self.push_chunk(chunk).await?;
+            }
+            // merged chunks never cross top dir boundaries
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn push_chunk(&mut self, chunk: DataChunk) -> anyhow::Result<()> {
+        let chunk_fs = self.fs.cd(&chunk.path());
+        let files = chunk_fs.ls().await?;
+        ensure!(chunk_check(&files), "chunk {} is incomplete", chunk.path());
+
+        let tables: BTreeSet<String> = files
+            .iter()
+            .filter_map(|file| file.strip_suffix(".parquet"))
+            .map(|table| table.to_string())
+            .collect();
+
+        if let Some(last) = self.group.last() {
+            if last.last_block + 1 != chunk.first_block || self.group_tables != tables {
+                self.flush().await?;
+            }
+        }
+
+        for table in &tables {
+            let data = chunk_fs.read(&format!("{}.parquet", table)).await?;
+            tokio::task::block_in_place(|| push_table(&mut self.processor, table, data))
+                .with_context(|| format!("failed to read {}/{}.parquet", chunk.path(), table))?;
+        }
+
+        self.group.push(chunk);
+        self.group_tables = tables;
+
+        if self.processor.byte_size() > self.args.chunk_size * 1024 * 1024
+            || self.processor.max_num_rows() > self.args.max_num_rows
+        {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    async fn flush(&mut self) -> anyhow::Result<()> {
+        let group = std::mem::take(&mut self.group);
+        let processor = std::mem::replace(&mut self.processor, self.chunk_builder.new_chunk_processor()?);
+
+        if group.len() < 2 {
+            return Ok(());
+        }
+
+        let first = group.first().unwrap();
+        let last = group.last().unwrap();
+        let chunk = DataChunk {
+            first_block: first.first_block,
+            last_block: last.last_block,
+            last_hash: last.last_hash.clone(),
+            top: first.top
+        };
+
+        tracing::info!("merging {} chunks into {}", group.len(), chunk.path());
+
+        let mut data = tokio::task::spawn_blocking(move || processor.finish()).await??;
+        data.retain(|name, _| self.group_tables.contains(*name));
+
+        let item = WriterItem {
+            chunk,
+            data,
+            description: self.chunk_builder.dataset_description()
+        };
+        upload_chunk(&self.fs, item, self.args.attach_idx_field).await?;
+
+        for chunk in group {
+            self.fs.delete(&chunk.path()).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Deletes chunks, that were already replaced by a merged chunk, but were not removed
+    /// because of an interrupted run. Incomplete merged chunks are deleted instead.
+    async fn remove_leftovers(&self, mut chunks: Vec<DataChunk>) -> anyhow::Result<Vec<DataChunk>> {
+        chunks.sort_by_key(|chunk| (chunk.first_block, Reverse(chunk.last_block)));
+
+        let mut result: Vec<DataChunk> = Vec::with_capacity(chunks.len());
+        'chunks: for chunk in chunks {
+            while let Some(prev) = result.last() {
+                if chunk.last_block > prev.last_block {
+                    if chunk.first_block <= prev.last_block {
+                        bail!("chunks {} and {} overlap", prev.path(), chunk.path())
+                    }
+                    break;
+                }
+                if chunk_check(&self.fs.cd(&prev.path()).ls().await?) {
+                    tracing::info!("deleting {}, it was replaced by {}", chunk.path(), prev.path());
+                    self.fs.delete(&chunk.path()).await?;
+                    continue 'chunks;
+                } else {
+                    tracing::info!("deleting incomplete chunk {}", prev.path());
+                    self.fs.delete(&prev.path()).await?;
+                    result.pop();
+                }
+            }
+            result.push(chunk);
+        }
+
+        Ok(result)
+    }
+}
+
+fn push_table(processor: &mut ChunkProcessor, name: &str, data: Bytes) -> anyhow::Result<()> {
+    for batch in ParquetRecordBatchReaderBuilder::try_new(data)?.build()? {
+        push_record_batch(processor, name, &batch?)?;
+    }
+    Ok(())
+}
+
+/// Pushes a record batch of an already written table back to the `processor`.
+///
+/// Columns are selected by name to skip `_idx` and cast back from their downcast types.
+pub fn push_record_batch(processor: &mut ChunkProcessor, name: &str, batch: &RecordBatch) -> anyhow::Result<()> {
+    let schema = processor
+        .table_schema(name)
+        .ok_or_else(|| anyhow!("unknown table '{}'", name))?;
+
+    let columns = schema
+        .fields()
+        .iter()
+        .map(|field| {
+            let column = batch
+                .column_by_name(field.name())
+                .ok_or_else(|| anyhow!("column '{}' is missing", field.name()))?;
+            Ok(cast(column, field.data_type())?)
+        })
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let batch = RecordBatch::try_new(schema, columns)?;
+    processor.push_table(name, &batch.as_slice())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::{local_fs, log_count, write_archive, write_chunk};
+
+    fn args(dest: &std::path::Path) -> RechunkArgs {
+        RechunkArgs {
+            dest: dest.to_str().unwrap().to_string(),
+            network_kind: NetworkKind::Evm,
+            first_block: 0,
+            last_block: None,
+            chunk_size: 2048,
+            max_num_rows: 200_000,
+            attach_idx_field: false
+        }
+    }
+
+    async fn num_rows(fs: &FSRef, chunk: &DataChunk, table: &str) -> usize {
+        let data = fs.cd(&chunk.path()).read(&format!("{}.parquet", table)).await.unwrap();
+        let reader = ParquetRecordBatchReaderBuilder::try_new(data).unwrap();
+        reader.metadata().file_metadata().num_rows() as usize
+    }
+
+    async fn check_merged(fs: &FSRef, first_block: BlockNumber, last_block: BlockNumber) {
+        let layout = Layout::new(fs.clone());
+        let top = layout.get_tops().await.unwrap()[0];
+
+        let visible = layout.get_top_chunks(top).await.unwrap();
+        let all = layout.list_top_chunks(top).await.unwrap();
+        assert_eq!(visible, all, "replaced chunks must be deleted");
+        assert_eq!(visible.len(), 1);
+
+        let chunk = &visible[0];
+        assert_eq!((chunk.first_block, chunk.last_block), (first_block, last_block));
+        assert_eq!(
+            num_rows(fs, chunk, "blocks").await,
+            (last_block - first_block + 1) as usize
+        );
+        assert_eq!(
+            num_rows(fs, chunk, "logs").await,
+            (first_block..=last_block).map(log_count).sum::<usize>()
+        );
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn merges_consecutive_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = local_fs(dir.path());
+        write_archive(&fs, &[(100, 109), (110, 119), (120, 129)], 10)
+            .await
+            .unwrap();
+
+        run(args(dir.path())).await.unwrap();
+
+        check_merged(&fs, 100, 129).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn deletes_chunks_replaced_by_interrupted_run() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = local_fs(dir.path());
+        let chunks = write_archive(&fs, &[(100, 109), (110, 119), (120, 129)], 10)
+            .await
+            .unwrap();
+
+        // the merged chunk was written, but the old ones were not deleted
+        let merged = DataChunk {
+            first_block: 100,
+            last_block: 129,
+            last_hash: chunks[2].last_hash.clone(),
+            top: chunks[0].top
+        };
+        write_chunk(&fs, &merged).await.unwrap();
+
+        let layout = Layout::new(fs.clone());
+        assert_eq!(layout.get_top_chunks(merged.top).await.unwrap(), vec![merged.clone()]);
+
+        run(args(dir.path())).await.unwrap();
+
+        check_merged(&fs, 100, 129).await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn replaces_incomplete_merged_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = local_fs(dir.path());
+        let chunks = write_archive(&fs, &[(100, 109), (110, 119), (120, 129)], 10)
+            .await
+            .unwrap();
+
+        // the merged chunk was interrupted before `blocks.parquet` was uploaded
+        let merged = DataChunk {
+            first_block: 100,
+            last_block: 119,
+            last_hash: chunks[1].last_hash.clone(),
+            top: chunks[0].top
+        };
+        write_chunk(&fs, &merged).await.unwrap();
+        fs.cd(&merged.path()).delete("blocks.parquet").await.unwrap();
+
+        let layout = Layout::new(fs.clone());
+        assert_eq!(layout.get_top_chunks(merged.top).await.unwrap(), chunks);
+
+        run(args(dir.path())).await.unwrap();
+
+        check_merged(&fs, 100, 129).await;
+    }
+}
@@ -0,0 +1,409 @@
+use std::{
+    net::SocketAddr,
+    path::{Path as FsPath, PathBuf},
+    pin::pin,
+    sync::Arc,
+    time::{Duration, Instant}
+};
+
+use anyhow::ensure;
+use async_stream::stream;
+use axum::{
+    body::{Body, Bytes},
+    extract::Path,
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::post,
+    Extension, Json, Router
+};
+use flate2::{write::GzEncoder, Compression};
+use futures::TryStreamExt;
+use serde::Serialize;
+use sqd_primitives::{BlockNumber, BlockRef};
+use sqd_query::{JsonLinesWriter, ParquetChunk, Plan, Query, TableDoesNotExist, UnexpectedBaseBlock};
+use tracing::{error, info};
+
+use crate::{
+    cli::ServeArgs,
+    fs::local::LocalFs,
+    layout::{DataChunk, Layout}
+};
+
+/// New chunks are not queried once the response took that long
+const QUERY_TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// Serves queries over a local archive with the HTTP contract of the hotblocks `/datasets/{id}/stream` endpoint.
+///
+/// The response is a multi-member gzip of JSON lines, one member per plan execution.
+/// The response ends at the query's last block, at a gap in the archive, once the result limit of the query
+/// is reached or once [`QUERY_TIME_LIMIT`] is exceeded, so clients are expected to continue from the last received block.
+pub async fn run(args: ServeArgs) -> anyhow::Result<()> {
+    ensure!(!args.dest.starts_with("s3://"), "only local archives can be served");
+
+    let server = Arc::new(Server {
+        root: PathBuf::from(&args.dest),
+        layout: Layout::new(Arc::new(LocalFs::new(&args.dest))),
+        dataset: args.dataset
+    });
+
+    let app = Router::new()
+        .route("/datasets/{id}/stream", post(stream))
+        .layer(Extension(server));
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], args.port));
+    info!("serving {} on {}", args.dest, addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app).await?;
+
+    Ok(())
+}
+
+struct Server {
+    root: PathBuf,
+    layout: Layout,
+    dataset: String
+}
+
+impl Server {
+    fn chunk_dir(&self, chunk: &DataChunk) -> PathBuf {
+        self.root.join(chunk.path())
+    }
+
+    async fn get_head(&self) -> anyhow::Result<Option<DataChunk>> {
+        pin!(self.layout.get_chunks_in_reversed_order(0, None)).try_next().await
+    }
+
+    /// Returns the chunk containing `block`, if it is complete
+    async fn get_chunk(&self, block: BlockNumber) -> anyhow::Result<Option<DataChunk>> {
+        let chunk = pin!(self.layout.get_chunks(block, None)).try_next().await?;
+        Ok(chunk.filter(|chunk| chunk.first_block <= block && self.chunk_dir(chunk).join("blocks.parquet").exists()))
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BaseBlockConflict<'a> {
+    previous_blocks: &'a [BlockRef]
+}
+
+async fn stream(Extension(server): Extension<Arc<Server>>, Path(dataset_id): Path<String>, body: Bytes) -> Response {
+    if dataset_id != server.dataset {
+        return (StatusCode::NOT_FOUND, format!("dataset {} does not exist", dataset_id)).into_response();
+    }
+
+    let query = match Query::from_json_bytes(&body) {
+        Ok(query) => query,
+        Err(err) => return (StatusCode::BAD_REQUEST, err.to_string()).into_response()
+    };
+
+    if let Err(err) = query.validate() {
+        return (StatusCode::BAD_REQUEST, err.to_string()).into_response();
+    }
+
+    match stream_internal(server, query).await {
+        Ok(res) => res,
+        Err(err) => error_to_response(err)
+    }
+}
+
+async fn stream_internal(server: Arc<Server>, query: Query) -> anyhow::Result<Response> {
+    let Some(head) = server.get_head().await? else {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    };
+
+    if query.first_block() > head.last_block {
+        return Ok(StatusCode::NO_CONTENT.into_response());
+    }
+
+    let Some(first_chunk) = server.get_chunk(query.first_block()).await? else {
+        return Ok((
+            StatusCode::BAD_REQUEST,
+            format!("block {} is not available in the archive", query.first_block())
+        )
+            .into_response());
+    };
+
+    // errors of the first chunk are reported with a status code,
+    // after that the response can only end early
+    let started = Instant::now();
+    let (plan, first_output) = query_chunk(&server, query.compile(), &query, &first_chunk).await?;
+
+    let body = stream! {
+        let mut plan = plan;
+        let mut chunk = first_chunk;
+        let mut output = first_output;
+        loop {
+            let next_block = match output {
+                Some(output) => {
+                    yield Ok::<_, std::io::Error>(output.bytes);
+                    match output.last_block {
+                        Some(last_block) if !output.limit_reached => last_block + 1,
+                        _ => break
+                    }
+                }
+                None => chunk.last_block + 1
+            };
+
+            if query.last_block().map_or(false, |last_block| next_block > last_block)
+                || started.elapsed() >= QUERY_TIME_LIMIT
+            {
+                break
+            }
+
+            if next_block <= chunk.last_block {
+                // the output was cut by its size, the rest of the chunk comes next
+                plan.set_first_block(next_block);
+            } else {
+                chunk = match server.get_chunk(next_block).await {
+                    Ok(Some(chunk)) => chunk,
+                    Ok(None) => break,
+                    Err(err) => {
+                        error!(err =? err, "terminating response stream due to chunk listing error");
+                        break
+                    }
+                };
+            }
+
+            match query_chunk(&server, plan, &query, &chunk).await {
+                Ok((next_plan, next_output)) => {
+                    plan = next_plan;
+                    output = next_output;
+                }
+                Err(err) => {
+                    error!(err =? err, chunk = %chunk.path(), "terminating response stream due to query error");
+                    break
+                }
+            }
+        }
+    };
+
+    Ok(Response::builder()
+        .status(200)
+        .header("content-type", "text/plain")
+        .header("content-encoding", "gzip")
+        .header("x-sqd-head-number", head.last_block)
+        .body(Body::from_stream(body))
+        .unwrap())
+}
+
+struct ChunkOutput {
+    /// Gzipped JSON lines
+    bytes: Bytes,
+    /// `None`, when the next block didn't fit into the rest of the limit
+    last_block: Option<BlockNumber>,
+    /// Whether the result limit of the query was reached
+    limit_reached: bool
+}
+
+/// Executes the plan against the chunk and returns its output, if there were any results.
+///
+/// The plan is passed back with the first block and the parent hash reset
+/// and with the result limits reduced by the returned output.
+async fn query_chunk(
+    server: &Server,
+    mut plan: Plan,
+    query: &Query,
+    chunk: &DataChunk
+) -> anyhow::Result<(Plan, Option<ChunkOutput>)> {
+    if query
+        .last_block()
+        .map_or(false, |last_block| last_block < chunk.last_block)
+    {
+        plan.set_last_block(query.last_block());
+    } else {
+        plan.set_last_block(None);
+    }
+
+    let chunk_dir = server.chunk_dir(chunk);
+    let (mut plan, result) = tokio::task::spawn_blocking(move || {
+        let result = execute_plan(&mut plan, &chunk_dir);
+        (plan, result)
+    })
+    .await?;
+
+    plan.set_first_block(None);
+    plan.set_parent_block_hash(None);
+
+    Ok((plan, result?))
+}
+
+fn execute_plan(plan: &mut Plan, chunk_dir: &FsPath) -> anyhow::Result<Option<ChunkOutput>> {
+    let chunk = ParquetChunk::new(chunk_dir.to_string_lossy());
+
+    let Some(mut blocks) = plan.execute(&chunk)? else {
+        return Ok(None);
+    };
+
+    let max_blocks = plan
+        .max_blocks()
+        .map(|max| max.saturating_sub(blocks.num_counted_blocks()));
+    let max_items = plan.max_items().map(|max| max.saturating_sub(blocks.max_num_items()));
+    let limit_reached = blocks.limit_reached() || max_blocks == Some(0) || max_items == Some(0);
+    plan.set_max_blocks(max_blocks);
+    plan.set_max_items(max_items);
+
+    let last_block = (blocks.num_blocks() > 0).then(|| blocks.last_block());
+    if last_block.is_some() {
+        // only the first returned block may exceed the limit
+        plan.set_allow_first_block_overflow(false);
+    }
+    let mut writer = JsonLinesWriter::new(GzEncoder::new(Vec::new(), Compression::default()));
+    writer.write_blocks(&mut blocks)?;
+    let bytes = writer.finish()?.finish()?;
+
+    Ok(Some(ChunkOutput {
+        bytes: bytes.into(),
+        last_block,
+        limit_reached
+    }))
+}
+
+fn error_to_response(err: anyhow::Error) -> Response {
+    if let Some(fork) = err.downcast_ref::<UnexpectedBaseBlock>() {
+        return (
+            StatusCode::CONFLICT,
+            Json(BaseBlockConflict {
+                previous_blocks: &fork.prev_blocks
+            })
+        )
+            .into_response();
+    }
+
+    if let Some(err) = err.downcast_ref::<TableDoesNotExist>() {
+        return (
+            StatusCode::BAD_REQUEST,
+            format!("'{}' data is not available in the archive", err.table_name)
+        )
+            .into_response();
+    }
+
+    error!(err =? err, "unhandled error, returning 500");
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err)).into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Read, path::Path};
+
+    use flate2::read::MultiGzDecoder;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::fixtures::{local_fs, log_count, write_archive};
+
+    async fn start(dir: &Path) -> Arc<Server> {
+        write_archive(&local_fs(dir), &[(100, 109), (110, 119), (120, 129)], 10)
+            .await
+            .unwrap();
+
+        Arc::new(Server {
+            root: dir.to_path_buf(),
+            layout: Layout::new(local_fs(dir)),
+            dataset: "archive".to_string()
+        })
+    }
+
+    async fn query(server: &Arc<Server>, query: Value) -> Vec<Value> {
+        let query = Query::from_json_value(query).unwrap();
+        let response = stream_internal(server.clone(), query).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let mut text = String::new();
+        MultiGzDecoder::new(body.as_ref()).read_to_string(&mut text).unwrap();
+        text.lines().map(|line| serde_json::from_str(line).unwrap()).collect()
+    }
+
+    fn numbers(blocks: &[Value]) -> Vec<u64> {
+        blocks
+            .iter()
+            .map(|block| block["header"]["number"].as_u64().unwrap())
+            .collect()
+    }
+
+    fn logs_per_block(blocks: &[Value]) -> Vec<(u64, usize)> {
+        blocks
+            .iter()
+            .filter_map(|block| {
+                let logs = block.get("logs")?.as_array()?.len();
+                Some((block["header"]["number"].as_u64().unwrap(), logs))
+            })
+            .collect()
+    }
+
+    fn logs_query(limit: Value) -> Value {
+        json!({
+            "type": "evm",
+            "fromBlock": 100,
+            "fields": {
+                "block": {"number": true},
+                "log": {"logIndex": true}
+            },
+            "logs": [{}],
+            "limit": limit
+        })
+    }
+
+    #[tokio::test]
+    async fn streams_all_chunks_of_the_range() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = start(dir.path()).await;
+
+        let blocks = query(
+            &server,
+            json!({
+                "type": "evm",
+                "fromBlock": 105,
+                "toBlock": 124,
+                "includeAllBlocks": true,
+                "fields": {"block": {"number": true}}
+            })
+        )
+        .await;
+
+        assert_eq!(numbers(&blocks), (105..=124).collect::<Vec<_>>());
+    }
+
+    #[tokio::test]
+    async fn item_limit_spans_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = start(dir.path()).await;
+
+        let mut total = 0;
+        let expected: Vec<(u64, usize)> = (100..130)
+            .map(|number| (number, log_count(number)))
+            .filter(|(_, logs)| *logs > 0)
+            .take_while(|(_, logs)| {
+                total += logs;
+                total <= 25
+            })
+            .collect();
+        let overflow = (expected.last().unwrap().0 + 1..)
+            .find(|number| log_count(*number) > 0)
+            .unwrap();
+        assert!(overflow > 120, "the limit must be reached in the last chunk");
+
+        let blocks = query(&server, logs_query(json!({"items": 25}))).await;
+
+        assert_eq!(logs_per_block(&blocks), expected);
+        assert!(*numbers(&blocks).last().unwrap() < overflow);
+    }
+
+    #[tokio::test]
+    async fn block_limit_spans_chunks() {
+        let dir = tempfile::tempdir().unwrap();
+        let server = start(dir.path()).await;
+
+        let expected: Vec<(u64, usize)> = (100..130)
+            .map(|number| (number, log_count(number)))
+            .filter(|(_, logs)| *logs > 0)
+            .take(12)
+            .collect();
+
+        let blocks = query(&server, logs_query(json!({"blocks": 12}))).await;
+
+        assert_eq!(logs_per_block(&blocks), expected);
+        assert_eq!(numbers(&blocks).last(), Some(&expected.last().unwrap().0));
+    }
+}
@@ -0,0 +1,665 @@
+use std::{collections::BTreeMap, pin::pin, sync::Arc, time::Duration};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use arrow::{
+    array::AsArray,
+    compute::cast,
+    datatypes::{DataType, Schema, SchemaRef, UInt64Type}
+};
+use bytes::Bytes;
+use futures::TryStreamExt;
+use parquet::arrow::{arrow_reader::ParquetRecordBatchReaderBuilder, ProjectionMask};
+use serde::de::DeserializeOwned;
+use sqd_data::{
+    bitcoin::tables::BitcoinChunkBuilder, evm::tables::EvmChunkBuilder, fuel::tables::FuelChunkBuilder,
+    hyperliquid_fills::tables::HyperliquidFillsChunkBuilder,
+    hyperliquid_replica_cmds::tables::HyperliquidReplicaCmdsChunkBuilder, solana::tables::SolanaChunkBuilder,
+    substrate::tables::SubstrateChunkBuilder, tron::tables::TronChunkBuilder
+};
+use sqd_data_core::BlockChunkBuilder;
+use sqd_dataset::{DatasetDescriptionRef, TableDescription};
+use sqd_primitives::{Block, BlockNumber, DataMask};
+use url::Url;
+
+use crate::{
+    cli::{NetworkKind, Repair, VerifyArgs},
+    fs::{create_fs, FSRef},
+    ingest::ingest_from_service,
+    layout::{ChunkTracker, DataChunk, Layout},
+    proc::{fallback_short_hash, short_hash, Proc},
+    writer::upload_chunk
+};
+
+pub async fn run(args: VerifyArgs) -> anyhow::Result<()> {
+    ensure!(
+        args.first_block <= args.last_block.unwrap_or(BlockNumber::MAX),
+        "--first-block is greater than --last-block"
+    );
+
+    let fs = create_fs(&args.dest).await?;
+
+    match args.network_kind {
+        NetworkKind::Bitcoin => verify::<BitcoinChunkBuilder>(fs, &args).await,
+        NetworkKind::Solana => verify::<SolanaChunkBuilder>(fs, &args).await,
+        NetworkKind::HyperliquidFills => verify::<HyperliquidFillsChunkBuilder>(fs, &args).await,
+        NetworkKind::HyperliquidReplicaCmds => verify::<HyperliquidReplicaCmdsChunkBuilder>(fs, &args).await,
+        NetworkKind::Evm => verify::<EvmChunkBuilder>(fs, &args).await,
+        NetworkKind::Tron => verify::<TronChunkBuilder>(fs, &args).await,
+        NetworkKind::Substrate => verify::<SubstrateChunkBuilder>(fs, &args).await,
+        NetworkKind::Fuel => verify::<FuelChunkBuilder>(fs, &args).await
+    }
+}
+
+/// Tables and their schemas, that chunks of a dataset are written with
+struct DatasetSchema {
+    description: DatasetDescriptionRef,
+    tables: BTreeMap<&'static str, SchemaRef>,
+    /// Tables, that are present in every chunk regardless of the data availability mask
+    required: Vec<&'static str>
+}
+
+impl DatasetSchema {
+    fn new<B: BlockChunkBuilder<Block: Block> + Default>() -> anyhow::Result<Self> {
+        let mut builder = B::default();
+        let description = builder.dataset_description();
+
+        let tables: BTreeMap<_, _> = builder
+            .prepare_in_memory()?
+            .into_iter()
+            .map(|(name, table)| (name, table.schema()))
+            .collect();
+
+        let required = tables
+            .keys()
+            .copied()
+            .filter(|name| B::Block::has_data(DataMask::default(), name))
+            .collect();
+
+        Ok(Self {
+            description,
+            tables,
+            required
+        })
+    }
+}
+
+struct ChunkCheck {
+    problems: Vec<String>,
+    /// Parent hash of the first block of the chunk, if it could be read
+    first_parent_hash: Option<String>
+}
+
+async fn verify<B>(fs: FSRef, args: &VerifyArgs) -> anyhow::Result<()>
+where
+    B: BlockChunkBuilder<Block: Block + DeserializeOwned> + Default
+{
+    let dataset_schema = Arc::new(DatasetSchema::new::<B>()?);
+    let layout = Layout::new(fs.clone());
+    let mut chunks = pin!(layout.get_chunks(args.first_block, args.last_block));
+
+    let mut num_chunks = 0;
+    let mut num_layout_problems = 0;
+    let mut bad_chunks: Vec<(DataChunk, Option<DataChunk>)> = Vec::new();
+    let mut prev: Option<DataChunk> = None;
+
+    while let Some(chunk) = chunks.try_next().await? {
+        num_chunks += 1;
+
+        let check = check_chunk(&fs, &chunk, &dataset_schema, args.validate_chain_continuity).await?;
+        let mut problems = check.problems;
+
+        if chunk.top > chunk.first_block {
+            tracing::error!("chunk {} starts before its top dir", chunk.path());
+            num_layout_problems += 1;
+        }
+
+        if let Some(prev) = &prev {
+            if chunk.first_block <= prev.last_block {
+                tracing::error!("chunk {} overlaps with {}", chunk.path(), prev.path());
+                num_layout_problems += 1;
+            } else if chunk.first_block > prev.last_block + 1 {
+                tracing::error!(
+                    "blocks {}-{} are missing between {} and {}",
+                    prev.last_block + 1,
+                    chunk.first_block - 1,
+                    prev.path(),
+                    chunk.path()
+                );
+                num_layout_problems += 1;
+            } else if let Some(parent_hash) = &check.first_parent_hash {
+                if prev.last_hash != short_hash(parent_hash) && prev.last_hash != fallback_short_hash(parent_hash) {
+                    problems.push(format!(
+                        "parent hash {} of the first block does not match previous chunk {}",
+                        parent_hash,
+                        prev.path()
+                    ));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            tracing::debug!("chunk {} is ok", chunk.path());
+        } else {
+            for problem in &problems {
+                tracing::error!("chunk {}: {}", chunk.path(), problem);
+            }
+            bad_chunks.push((chunk.clone(), prev.clone()));
+        }
+
+        prev = Some(chunk);
+    }
+
+    tracing::info!(
+        "verified {} chunks: {} are bad, {} layout problems found",
+        num_chunks,
+        bad_chunks.len(),
+        num_layout_problems
+    );
+
+    match args.repair {
+        Repair::None => {}
+        Repair::Delete => {
+            for (chunk, _) in &bad_chunks {
+                tracing::info!("deleting {}", chunk.path());
+                fs.delete(&chunk.path()).await?;
+            }
+        }
+        Repair::Reingest => {
+            let src = args.src.clone().context("--src is required to re-ingest chunks")?;
+            // the last re-ingested chunk and the new hash of its last block
+            let mut replaced: Option<(DataChunk, String)> = None;
+            for (chunk, prev) in &bad_chunks {
+                let prev_chunk_hash = prev
+                    .as_ref()
+                    .filter(|prev| prev.last_block + 1 == chunk.first_block)
+                    .map(|prev| match &replaced {
+                        Some((old, new_hash)) if old == prev => new_hash.clone(),
+                        _ => prev.last_hash.clone()
+                    });
+                let new_hash = reingest::<B>(&fs, args, src.clone(), chunk, prev_chunk_hash).await?;
+                replaced = Some((chunk.clone(), new_hash));
+            }
+        }
+    }
+
+    ensure!(
+        num_layout_problems == 0,
+        "archive has {} layout problems",
+        num_layout_problems
+    );
+    ensure!(
+        bad_chunks.is_empty() || args.repair != Repair::None,
+        "archive has {} bad chunks",
+        bad_chunks.len()
+    );
+    Ok(())
+}
+
+async fn check_chunk(
+    fs: &FSRef,
+    chunk: &DataChunk,
+    dataset_schema: &Arc<DatasetSchema>,
+    validate_chain_continuity: bool
+) -> anyhow::Result<ChunkCheck> {
+    let chunk_fs = fs.cd(&chunk.path());
+    let files = chunk_fs.ls().await?;
+
+    let mut problems = Vec::new();
+    let mut first_parent_hash = None;
+
+    for table in &dataset_schema.required {
+        let file = format!("{}.parquet", table);
+        if !files.contains(&file) {
+            problems.push(format!("{} is missing", file));
+        }
+    }
+
+    for file in files {
+        let Some(table) = file
+            .strip_suffix(".parquet")
+            .and_then(|name| dataset_schema.tables.get_key_value(name))
+            .map(|(name, _)| *name)
+        else {
+            problems.push(format!("unexpected item {}", file));
+            continue;
+        };
+
+        let data = chunk_fs.read(&file).await?;
+        let dataset_schema = dataset_schema.clone();
+        let chunk = chunk.clone();
+
+        let result = tokio::task::spawn_blocking(move || -> anyhow::Result<Option<String>> {
+            let desc = dataset_schema.description.tables.get(table);
+            let default_desc = TableDescription::default();
+            check_table(
+                table,
+                data.clone(),
+                &dataset_schema.tables[table],
+                desc.unwrap_or(&default_desc)
+            )?;
+            if table == "blocks" {
+                check_blocks(data, &chunk, validate_chain_continuity).map(Some)
+            } else {
+                Ok(None)
+            }
+        })
+        .await
+        .context("table check panicked")?;
+
+        match result {
+            Ok(Some(parent_hash)) => first_parent_hash = Some(parent_hash),
+            Ok(None) => {}
+            Err(err) => problems.push(format!("{}: {:#}", file, err))
+        }
+    }
+
+    Ok(ChunkCheck {
+        problems,
+        first_parent_hash
+    })
+}
+
+/// Checks, that a parquet file is readable to the end and has the expected schema
+fn check_table(name: &str, data: Bytes, expected: &Schema, desc: &TableDescription) -> anyhow::Result<()> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data).context("failed to read parquet metadata")?;
+
+    check_schema(name, builder.schema(), expected, desc)?;
+
+    let declared_rows = builder.metadata().file_metadata().num_rows() as usize;
+    let mut num_rows = 0;
+    for batch in builder.build()? {
+        num_rows += batch?.num_rows();
+    }
+    ensure!(
+        num_rows == declared_rows,
+        "file has {} rows, but its metadata declares {}",
+        num_rows,
+        declared_rows
+    );
+
+    Ok(())
+}
+
+fn check_schema(name: &str, actual: &Schema, expected: &Schema, desc: &TableDescription) -> anyhow::Result<()> {
+    let mut fields = actual.fields().iter().peekable();
+
+    // see `--attach-idx-field`
+    if name != "blocks" {
+        fields.next_if(|f| f.name() == "_idx" && f.data_type() == &DataType::Int32);
+    }
+
+    let mut expected_fields = expected.fields().iter();
+    loop {
+        match (fields.next(), expected_fields.next()) {
+            (Some(field), Some(expected)) => {
+                ensure!(
+                    field.name() == expected.name(),
+                    "expected column {}, but got {}",
+                    expected.name(),
+                    field.name()
+                );
+                let downcast = desc
+                    .downcast
+                    .block_number
+                    .iter()
+                    .chain(desc.downcast.item_index.iter())
+                    .any(|column| *column == field.name());
+                let type_matches = if downcast {
+                    field.data_type().is_unsigned_integer()
+                } else {
+                    field.data_type() == expected.data_type()
+                };
+                ensure!(
+                    type_matches,
+                    "column {} has type {}, but {} was expected",
+                    field.name(),
+                    field.data_type(),
+                    expected.data_type()
+                );
+            }
+            (Some(field), None) => bail!("unexpected column {}", field.name()),
+            (None, Some(expected)) => bail!("column {} is missing", expected.name()),
+            (None, None) => return Ok(())
+        }
+    }
+}
+
+/// Checks, that the blocks table covers the chunk range and its hashes form a chain.
+///
+/// Returns the parent hash of the first block.
+fn check_blocks(data: Bytes, chunk: &DataChunk, validate_chain_continuity: bool) -> anyhow::Result<String> {
+    let builder = ParquetRecordBatchReaderBuilder::try_new(data)?;
+    let columns = ["number", "hash", "parent_hash"]
+        .into_iter()
+        .map(|name| builder.schema().index_of(name))
+        .collect::<Result<Vec<_>, _>>()?;
+    let projection = ProjectionMask::roots(builder.parquet_schema(), columns);
+
+    let mut first_parent_hash = None;
+    let mut last: Option<(BlockNumber, String)> = None;
+
+    for batch in builder.with_projection(projection).build()? {
+        let batch = batch?;
+        let column = |name: &str| {
+            batch
+                .column_by_name(name)
+                .ok_or_else(|| anyhow!("column {} is not present in the batch", name))
+        };
+        let numbers = cast(column("number")?, &DataType::UInt64)?;
+        let numbers = numbers.as_primitive::<UInt64Type>();
+        let hashes = cast(column("hash")?, &DataType::Utf8)?;
+        let hashes = hashes.as_string::<i32>();
+        let parent_hashes = cast(column("parent_hash")?, &DataType::Utf8)?;
+        let parent_hashes = parent_hashes.as_string::<i32>();
+
+        for i in 0..batch.num_rows() {
+            let number = numbers.value(i);
+            let parent_hash = parent_hashes.value(i);
+            match &last {
+                None => {
+                    ensure!(
+                        number >= chunk.first_block,
+                        "first block {} is below the chunk range",
+                        number
+                    );
+                    first_parent_hash = Some(parent_hash.to_string());
+                }
+                Some((prev_number, prev_hash)) => {
+                    ensure!(
+                        number > *prev_number,
+                        "blocks are not ordered: {} follows {}",
+                        number,
+                        prev_number
+                    );
+                    if validate_chain_continuity {
+                        ensure!(
+                            parent_hash == prev_hash,
+                            "parent hash mismatch for block {}: expected {}, but got {}",
+                            number,
+                            prev_hash,
+                            parent_hash
+                        );
+                    }
+                }
+            }
+            last = Some((number, hashes.value(i).to_string()));
+        }
+    }
+
+    let (last_block, last_hash) = last.ok_or_else(|| anyhow!("blocks table is empty"))?;
+    ensure!(
+        last_block == chunk.last_block,
+        "last block is {}, but the chunk ends at {}",
+        last_block,
+        chunk.last_block
+    );
+    ensure!(
+        short_hash(&last_hash) == chunk.last_hash,
+        "hash {} of the last block does not match the chunk name",
+        last_hash
+    );
+
+    Ok(first_parent_hash.unwrap())
+}
+
+/// Replaces `chunk` with the data fetched from the ingestion service.
+///
+/// The replacement is written before `chunk` is deleted. [`Layout`] hides new chunks contained in `chunk`
+/// until then, so readers switch to them in one step, and a failed re-ingestion leaves `chunk` in place.
+///
+/// Returns the short hash of the last written chunk.
+async fn reingest<B>(
+    fs: &FSRef,
+    args: &VerifyArgs,
+    src: Url,
+    chunk: &DataChunk,
+    prev_chunk_hash: Option<String>
+) -> anyhow::Result<String>
+where
+    B: BlockChunkBuilder<Block: Block + DeserializeOwned> + Default
+{
+    tracing::info!("re-ingesting {}", chunk.path());
+
+    let chunk_tracker = ChunkTracker::for_chunk(chunk, prev_chunk_hash);
+    let (chunk_sender, mut chunk_receiver) = tokio::sync::mpsc::channel(5);
+
+    let block_stream = ingest_from_service(
+        src,
+        chunk.first_block,
+        Some(chunk.last_block),
+        Duration::from_secs(args.block_stream_interval.into())
+    );
+    let mut proc = Proc::new(B::default(), chunk_tracker, chunk_sender)?;
+    proc.set_validate_chain_continuity(args.validate_chain_continuity);
+
+    let mut written: Vec<DataChunk> = Vec::new();
+    let write = async {
+        while let Some(item) = chunk_receiver.recv().await {
+            written.push(item.chunk.clone());
+            if item.chunk == *chunk {
+                // the same block range and hash, the files are overwritten in place
+                let tables: Vec<String> = item.data.keys().map(|name| format!("{}.parquet", name)).collect();
+                let chunk_fs = fs.cd(&chunk.path());
+                upload_chunk(fs, item, args.attach_idx_field).await?;
+                for file in chunk_fs.ls().await? {
+                    if !tables.contains(&file) {
+                        chunk_fs.delete(&file).await?;
+                    }
+                }
+            } else {
+                upload_chunk(fs, item, args.attach_idx_field).await?;
+            }
+        }
+        Ok::<_, anyhow::Error>(())
+    };
+
+    let result = tokio::try_join!(proc.run(block_stream), write);
+    if let Err(err) = result {
+        for new_chunk in written.iter().filter(|new_chunk| *new_chunk != chunk) {
+            fs.delete(&new_chunk.path()).await?;
+        }
+        return Err(err.context(format!("failed to re-ingest {}", chunk.path())));
+    }
+
+    let new_chunk = written.last().ok_or_else(|| {
+        anyhow!(
+            "no chunks were written for blocks {}-{}",
+            chunk.first_block,
+            chunk.last_block
+        )
+    })?;
+    ensure!(
+        new_chunk.last_block == chunk.last_block,
+        "re-ingested blocks end at {}, but {} ends at {}",
+        new_chunk.last_block,
+        chunk.path(),
+        chunk.last_block
+    );
+    let new_hash = new_chunk.last_hash.clone();
+
+    if !written.contains(chunk) {
+        tracing::info!("deleting {}, it was replaced by {} chunks", chunk.path(), written.len());
+        fs.delete(&chunk.path()).await?;
+    }
+
+    Ok(new_hash)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use axum::{http::StatusCode, response::IntoResponse, routing::post, Json, Router};
+    use serde_json::Value;
+
+    use super::*;
+    use crate::{
+        archive::chunk_check,
+        fixtures::{block_hash, evm_block_json, local_fs, write_archive}
+    };
+
+    const RANGES: [(BlockNumber, BlockNumber); 3] = [(100, 109), (110, 119), (120, 129)];
+
+    fn args(dest: &Path, repair: Repair, src: Option<Url>) -> VerifyArgs {
+        VerifyArgs {
+            dest: dest.to_str().unwrap().to_string(),
+            network_kind: NetworkKind::Evm,
+            first_block: 0,
+            last_block: None,
+            validate_chain_continuity: true,
+            repair,
+            src,
+            block_stream_interval: 1,
+            attach_idx_field: false
+        }
+    }
+
+    /// Starts an ingestion service, that streams fixture blocks or fails with `status`
+    async fn start_service(status: StatusCode) -> Url {
+        let handler = move |Json(range): Json<Value>| async move {
+            if status != StatusCode::OK {
+                return status.into_response();
+            }
+            let from = range["from"].as_u64().unwrap();
+            let to = range["to"].as_u64().unwrap();
+            let body: String = (from..=to)
+                .map(|number| format!("{}\n", evm_block_json(number)))
+                .collect();
+            body.into_response()
+        };
+        let app = Router::new().route("/", post(handler));
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        format!("http://{}/", addr).parse().unwrap()
+    }
+
+    fn corrupt(dir: &Path, chunk: &DataChunk, file: &str) {
+        std::fs::write(dir.join(chunk.path()).join(file), b"not a parquet file").unwrap();
+    }
+
+    async fn check(fs: &FSRef, chunk: &DataChunk) -> ChunkCheck {
+        let dataset_schema = Arc::new(DatasetSchema::new::<EvmChunkBuilder>().unwrap());
+        check_chunk(fs, chunk, &dataset_schema, true).await.unwrap()
+    }
+
+    async fn list_chunks(fs: &FSRef) -> Vec<DataChunk> {
+        let layout = Layout::new(fs.clone());
+        let top = layout.get_tops().await.unwrap()[0];
+        let mut chunks = layout.list_top_chunks(top).await.unwrap();
+        chunks.sort();
+        chunks
+    }
+
+    #[tokio::test]
+    async fn check_blocks_validates_range_and_hash() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = local_fs(dir.path());
+        let chunks = write_archive(&fs, &RANGES, 10).await.unwrap();
+        let chunk = &chunks[1];
+        let data = fs.cd(&chunk.path()).read("blocks.parquet").await.unwrap();
+
+        let parent_hash = check_blocks(data.clone(), chunk, true).unwrap();
+        assert_eq!(parent_hash, block_hash(chunk.first_block - 1));
+
+        let longer = DataChunk {
+            last_block: chunk.last_block + 1,
+            ..chunk.clone()
+        };
+        let err = check_blocks(data.clone(), &longer, true).unwrap_err();
+        assert!(err.to_string().contains("last block is 119"), "{}", err);
+
+        let renamed = DataChunk {
+            last_hash: "00000000".to_string(),
+            ..chunk.clone()
+        };
+        let err = check_blocks(data, &renamed, true).unwrap_err();
+        assert!(err.to_string().contains("does not match the chunk name"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn check_chunk_reports_problems() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = local_fs(dir.path());
+        let chunks = write_archive(&fs, &RANGES, 10).await.unwrap();
+
+        let ok = check(&fs, &chunks[0]).await;
+        assert!(ok.problems.is_empty(), "{:?}", ok.problems);
+        assert_eq!(ok.first_parent_hash, Some(block_hash(99)));
+
+        corrupt(dir.path(), &chunks[1], "logs.parquet");
+        corrupt(dir.path(), &chunks[1], "extra.txt");
+        let bad = check(&fs, &chunks[1]).await;
+        assert_eq!(bad.problems.len(), 2, "{:?}", bad.problems);
+        assert!(bad.problems.iter().any(|p| p.starts_with("logs.parquet: ")));
+        assert!(bad.problems.contains(&"unexpected item extra.txt".to_string()));
+
+        fs.cd(&chunks[2].path()).delete("blocks.parquet").await.unwrap();
+        let incomplete = check(&fs, &chunks[2]).await;
+        assert_eq!(incomplete.problems, vec!["blocks.parquet is missing".to_string()]);
+        assert_eq!(incomplete.first_parent_hash, None);
+    }
+
+    #[tokio::test]
+    async fn reingest_replaces_corrupted_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = local_fs(dir.path());
+        let chunks = write_archive(&fs, &RANGES, 10).await.unwrap();
+        corrupt(dir.path(), &chunks[1], "logs.parquet");
+        corrupt(dir.path(), &chunks[1], "extra.txt");
+
+        let src = start_service(StatusCode::OK).await;
+        verify::<EvmChunkBuilder>(fs.clone(), &args(dir.path(), Repair::Reingest, Some(src)))
+            .await
+            .unwrap();
+
+        // the chunk was written again under the same name
+        assert_eq!(list_chunks(&fs).await, chunks);
+        let fixed = check(&fs, &chunks[1]).await;
+        assert!(fixed.problems.is_empty(), "{:?}", fixed.problems);
+        verify::<EvmChunkBuilder>(fs, &args(dir.path(), Repair::None, None))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn reingest_replaces_misnamed_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = local_fs(dir.path());
+        let chunks = write_archive(&fs, &RANGES, 10).await.unwrap();
+
+        let misnamed = DataChunk {
+            last_hash: "00000000".to_string(),
+            ..chunks[1].clone()
+        };
+        std::fs::rename(dir.path().join(chunks[1].path()), dir.path().join(misnamed.path())).unwrap();
+
+        let src = start_service(StatusCode::OK).await;
+        verify::<EvmChunkBuilder>(fs.clone(), &args(dir.path(), Repair::Reingest, Some(src)))
+            .await
+            .unwrap();
+
+        // the replacement is written under the correct name and the misnamed chunk is gone
+        assert_eq!(list_chunks(&fs).await, chunks);
+        verify::<EvmChunkBuilder>(fs, &args(dir.path(), Repair::None, None))
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn failed_reingest_keeps_bad_chunk() {
+        let dir = tempfile::tempdir().unwrap();
+        let fs = local_fs(dir.path());
+        let chunks = write_archive(&fs, &RANGES, 10).await.unwrap();
+        corrupt(dir.path(), &chunks[1], "logs.parquet");
+
+        let src = start_service(StatusCode::INTERNAL_SERVER_ERROR).await;
+        verify::<EvmChunkBuilder>(fs.clone(), &args(dir.path(), Repair::Reingest, Some(src)))
+            .await
+            .unwrap_err();
+
+        assert_eq!(list_chunks(&fs).await, chunks);
+        assert!(chunk_check(&fs.cd(&chunks[1].path()).ls().await.unwrap()));
+    }
+}
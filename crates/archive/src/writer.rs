@@ -39,30 +39,10 @@ impl Writer {
     }
 
     pub async fn start(&mut self) -> anyhow::Result<()> {
-        while let Some(mut item) = self.chunk_receiver.recv().await {
-            tracing::info!("writing {}", item.chunk.path());
-
+        while let Some(item) = self.chunk_receiver.recv().await {
             let last_block = item.chunk.last_block;
-            let target_dir = tempfile::tempdir()?.into_path();
-            let attach_index_field = self.attach_index_field;
-            let writer_handle = tokio::task::spawn_blocking(move || {
-                write_chunk(&mut item.data, &item.description, &target_dir, attach_index_field)
-            });
-
-            let mut files = writer_handle.await??;
-
-            let blocks_pos = files.iter().position(|file| file.ends_with("blocks.parquet")).unwrap();
-            let blocks = files.swap_remove(blocks_pos);
-
-            let chunk_path = item.chunk.path();
-            for file in files {
-                let file_name = file.file_name().unwrap().to_str().unwrap();
-                let dest = format!("{}/{}", chunk_path, file_name);
-                self.fs.move_local(&file, &dest).await?;
-            }
 
-            let dest = format!("{}/blocks.parquet", chunk_path);
-            self.fs.move_local(&blocks, &dest).await?;
+            upload_chunk(&self.fs, item, self.attach_index_field).await?;
 
             metrics::LATEST_SAVED_BLOCK.set(last_block);
             metrics::LAST_SAVED_BLOCK.inner().set(last_block);
@@ -71,6 +51,35 @@ impl Writer {
     }
 }
 
+/// Writes the chunk data to parquet files and uploads them to the chunk dir.
+///
+/// `blocks.parquet` is uploaded last, so that its presence marks the chunk as complete.
+pub async fn upload_chunk(fs: &FSRef, mut item: WriterItem, attach_index_field: bool) -> anyhow::Result<()> {
+    let chunk_path = item.chunk.path();
+    tracing::info!("writing {}", chunk_path);
+
+    let target_dir = tempfile::tempdir()?.into_path();
+    let writer_handle = tokio::task::spawn_blocking(move || {
+        write_chunk(&mut item.data, &item.description, &target_dir, attach_index_field)
+    });
+
+    let mut files = writer_handle.await??;
+
+    let blocks_pos = files.iter().position(|file| file.ends_with("blocks.parquet")).unwrap();
+    let blocks = files.swap_remove(blocks_pos);
+
+    for file in files {
+        let file_name = file.file_name().unwrap().to_str().unwrap();
+        let dest = format!("{}/{}", chunk_path, file_name);
+        fs.move_local(&file, &dest).await?;
+    }
+
+    let dest = format!("{}/blocks.parquet", chunk_path);
+    fs.move_local(&blocks, &dest).await?;
+
+    Ok(())
+}
+
 fn add_index_column(batch: &mut RecordBatch, offset: usize) -> anyhow::Result<()> {
     let num_rows = batch.num_rows();
     let iter = offset as i32..(offset + num_rows) as i32;
@@ -123,6 +132,14 @@ fn write_chunk(
                 if options.dictionary_encoding {
                     builder = builder.set_column_dictionary_enabled((*column).into(), true);
                 }
+
+                if options.bloom_filter_enable {
+                    // a row group can't have more distinct values than rows
+                    builder = builder
+                        .set_column_bloom_filter_enabled((*column).into(), true)
+                        .set_column_bloom_filter_fpp((*column).into(), options.bloom_filter_fpp)
+                        .set_column_bloom_filter_ndv((*column).into(), desc.options.row_group_size as u64);
+                }
             }
 
             let props = builder.build();
@@ -57,6 +57,10 @@ impl<W: Writer> AnyArrayWriter<W> {
         self.buffers
     }
 
+    pub fn buffers_mut(&mut self) -> &mut [AnyWriter<W>] {
+        &mut self.buffers
+    }
+
     pub fn from_factory(factory: &mut impl WriterFactory<Writer = W>, data_type: &DataType) -> anyhow::Result<Self> {
         let mut buffers = Vec::with_capacity(get_num_buffers(data_type));
 
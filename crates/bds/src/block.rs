@@ -82,3 +82,12 @@ impl<'a> BlockHeader<'a> {
         }
     }
 }
+
+impl<'a> Block<'a> {
+    pub fn to_static(&self) -> Block<'static> {
+        Block {
+            header: self.header.to_static(),
+            data: self.data.to_vec().into()
+        }
+    }
+}
@@ -1,6 +1,7 @@
 mod block_batch;
 mod ingest;
 mod row_batch;
+mod serve;
 mod store;
 mod types;
 
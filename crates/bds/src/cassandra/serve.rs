@@ -0,0 +1,50 @@
+use futures::TryStreamExt;
+use sqd_primitives::BlockNumber;
+
+use super::store::CassandraStorage;
+use crate::{
+    block::{Block, BlockHeader},
+    serve::{BlockReader, Heads}
+};
+
+impl BlockReader for CassandraStorage {
+    async fn get_heads(&self) -> anyhow::Result<Heads> {
+        let states = self.fetch_write_states().await?;
+
+        let head = states.iter().map(|s| &s.head).max_by_key(|h| h.number).cloned();
+
+        let finalized_head = states
+            .iter()
+            .filter_map(|s| s.finalized_head.as_ref())
+            .max_by_key(|h| h.number)
+            .cloned();
+
+        Ok(Heads { head, finalized_head })
+    }
+
+    async fn list_headers(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber
+    ) -> anyhow::Result<Vec<BlockHeader<'static>>> {
+        self.list_blocks(first_block, last_block)
+            .try_fold(Vec::new(), |mut headers, batch| async move {
+                headers.extend(batch.blocks().iter().map(|b| b.to_static()));
+                Ok(headers)
+            })
+            .await
+    }
+
+    async fn fetch_blocks(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber
+    ) -> anyhow::Result<Vec<Block<'static>>> {
+        CassandraStorage::fetch_blocks(self, first_block, last_block)
+            .try_fold(Vec::new(), |mut blocks, batch| async move {
+                blocks.extend(batch.blocks().iter().map(|b| b.to_static()));
+                Ok(blocks)
+            })
+            .await
+    }
+}
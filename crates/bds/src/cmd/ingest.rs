@@ -1,20 +1,18 @@
-use std::sync::Arc;
-
-use anyhow::Context;
 use sqd_data_client::reqwest::ReqwestDataClient;
 use sqd_primitives::BlockNumber;
-use tracing::debug;
 use url::Url;
 
-use crate::{block::BlockArc, cassandra::CassandraStorage, data_source::create_data_source, ingest::Ingest};
+use super::storage::{Storage, StorageArgs};
+use crate::{
+    block::BlockArc,
+    data_source::create_data_source,
+    ingest::{Ingest, Store}
+};
 
 #[derive(clap::Args)]
 pub struct Args {
-    #[arg(required = true, short = 'c', long, value_name = "HOST[:PORT]")]
-    pub cassandra_node: Vec<String>,
-
-    #[arg(required = true, short = 'k', long, value_name = "NAME")]
-    pub cassandra_keyspace: String,
+    #[command(flatten)]
+    pub storage: StorageArgs,
 
     #[arg(required = true, short = 's', long, value_name = "URL")]
     pub data_source: Vec<Url>,
@@ -34,22 +32,13 @@ pub fn run(args: Args) -> anyhow::Result<()> {
 }
 
 async fn run_async(args: Args) -> anyhow::Result<()> {
-    let cassandra_session = {
-        use scylla::client::session_builder::SessionBuilder;
-
-        let session = SessionBuilder::new()
-            .known_nodes(args.cassandra_node.iter())
-            .build()
-            .await
-            .context("cassandra connection failed")?;
-
-        Arc::new(session)
-    };
-
-    let storage = CassandraStorage::new(cassandra_session, &args.cassandra_keyspace).await?;
-
-    debug!("cassandra storage initialized");
+    match args.storage.open().await? {
+        Storage::Cassandra(storage) => ingest(storage, args).await,
+        Storage::Local(storage) => ingest(storage, args).await
+    }
+}
 
+async fn ingest<S: Store<Block = BlockArc>>(storage: S, args: Args) -> anyhow::Result<()> {
     let data_source = create_data_source(args.data_source.into_iter().map(ReqwestDataClient::from_url).collect());
 
     let (_, handle) = Ingest::new(storage, data_source)
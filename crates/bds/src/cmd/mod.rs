@@ -1,2 +1,4 @@
 pub mod ingest;
+pub mod serve;
+mod storage;
 mod util;
@@ -0,0 +1,46 @@
+use std::net::SocketAddr;
+
+use tracing::info;
+
+use super::{
+    storage::{Storage, StorageArgs},
+    util::shutdown_signal
+};
+use crate::serve::{BlockReader, build_router};
+
+#[derive(clap::Args)]
+pub struct Args {
+    #[command(flatten)]
+    pub storage: StorageArgs,
+
+    #[arg(long, value_name = "PORT", default_value = "3000")]
+    pub port: u16
+}
+
+pub fn run(args: Args) -> anyhow::Result<()> {
+    tokio::runtime::Builder::new_multi_thread()
+        .enable_all()
+        .build()?
+        .block_on(run_async(args))
+}
+
+async fn run_async(args: Args) -> anyhow::Result<()> {
+    match args.storage.open().await? {
+        Storage::Cassandra(storage) => serve(storage, args.port).await,
+        Storage::Local(storage) => serve(storage, args.port).await
+    }
+}
+
+async fn serve<R: BlockReader>(reader: R, port: u16) -> anyhow::Result<()> {
+    let app = build_router(reader);
+
+    let addr = SocketAddr::from(([0, 0, 0, 0], port));
+    info!("listening on {}", addr);
+
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown_signal())
+        .await?;
+
+    Ok(())
+}
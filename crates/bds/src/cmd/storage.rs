@@ -0,0 +1,65 @@
+use std::{path::PathBuf, sync::Arc};
+
+use anyhow::Context;
+use tracing::debug;
+
+use crate::{cassandra::CassandraStorage, local::LocalStorage};
+
+#[derive(clap::Args)]
+pub struct StorageArgs {
+    #[arg(
+        short = 'c',
+        long,
+        value_name = "HOST[:PORT]",
+        required_unless_present = "data_dir",
+        requires = "cassandra_keyspace"
+    )]
+    pub cassandra_node: Vec<String>,
+
+    #[arg(short = 'k', long, value_name = "NAME", requires = "cassandra_node")]
+    pub cassandra_keyspace: Option<String>,
+
+    /// Store blocks in a local database instead of Cassandra
+    #[arg(long, value_name = "DIR", conflicts_with_all = ["cassandra_node", "cassandra_keyspace"])]
+    pub data_dir: Option<PathBuf>
+}
+
+pub enum Storage {
+    Cassandra(CassandraStorage),
+    Local(LocalStorage)
+}
+
+impl StorageArgs {
+    pub async fn open(&self) -> anyhow::Result<Storage> {
+        if let Some(data_dir) = self.data_dir.as_ref() {
+            let storage = LocalStorage::open(data_dir)
+                .with_context(|| format!("failed to open local storage at {}", data_dir.display()))?;
+
+            debug!("local storage initialized");
+
+            return Ok(Storage::Local(storage));
+        }
+
+        let cassandra_session = {
+            use scylla::client::session_builder::SessionBuilder;
+
+            let session = SessionBuilder::new()
+                .known_nodes(self.cassandra_node.iter())
+                .build()
+                .await
+                .context("cassandra connection failed")?;
+
+            Arc::new(session)
+        };
+
+        let keyspace = self
+            .cassandra_keyspace
+            .as_deref()
+            .expect("keyspace is required by clap");
+        let storage = CassandraStorage::new(cassandra_session, keyspace).await?;
+
+        debug!("cassandra storage initialized");
+
+        Ok(Storage::Cassandra(storage))
+    }
+}
@@ -0,0 +1,433 @@
+use anyhow::{bail, ensure};
+use sqd_primitives::{Block, BlockNumber, BlockPtr, BlockRef};
+
+use super::{run, store::LocalStorage};
+use crate::{block::BlockArc, chain::HeadChain, ingest::Store};
+
+impl Store for LocalStorage {
+    type Block = BlockArc;
+
+    async fn get_chain_head(&self, first_block: BlockNumber, parent_hash: Option<&str>) -> anyhow::Result<HeadChain> {
+        let parent_hash = parent_hash.map(|hash| hash.to_string());
+        run(self, move |storage| {
+            build_chain(storage, first_block, parent_hash.as_deref())
+        })
+        .await
+    }
+
+    async fn save(&self, block: &Self::Block) -> anyhow::Result<()> {
+        let block = block.clone();
+        run(self, move |storage| storage.save_block(&block)).await
+    }
+
+    async fn set_head(&self, head: BlockPtr<'_>) -> anyhow::Result<()> {
+        let head = head.to_ref();
+        run(self, move |storage| storage.set_head(head.number, &head.hash)).await
+    }
+
+    async fn finalize(&self, from: BlockNumber, to: BlockPtr<'_>) -> anyhow::Result<()> {
+        let to = to.to_ref();
+        run(self, move |storage| storage.finalize(from, &to)).await
+    }
+}
+
+fn validate_chain_base(
+    storage: &LocalStorage,
+    parent_hash: &str,
+    first_block: BlockNumber,
+    last_block: BlockNumber
+) -> anyhow::Result<()> {
+    for header_result in storage.list_blocks(first_block, last_block) {
+        let b = header_result?;
+        if b.parent_number >= first_block {
+            break;
+        }
+        if b.parent_hash == parent_hash {
+            return Ok(());
+        }
+    }
+    bail!(
+        "blocks above {} are already present in the database, but none of those is directly based on {}",
+        first_block,
+        parent_hash
+    );
+}
+
+fn build_chain(
+    storage: &LocalStorage,
+    first_block: BlockNumber,
+    parent_hash: Option<&str>
+) -> anyhow::Result<HeadChain> {
+    let Some(head) = storage.get_head()? else {
+        return Ok(HeadChain::empty());
+    };
+
+    if head.number < first_block {
+        return Ok(HeadChain::empty());
+    }
+
+    if let Some(parent_hash) = parent_hash {
+        validate_chain_base(storage, parent_hash, first_block, head.number)?;
+    }
+
+    let mut chain = HeadChain::empty();
+    let mut expected = head.clone();
+
+    for header_result in storage.list_blocks_in_reversed_order(first_block, head.number) {
+        let b = header_result?;
+        if b.number < expected.number {
+            break;
+        }
+        if b.ptr() == expected.ptr() {
+            expected.set_ptr(b.parent_ptr());
+            chain.blocks.push(b.ptr().to_ref());
+            if b.is_final {
+                chain.first_finalized = true;
+                break;
+            }
+        }
+    }
+
+    ensure!(
+        !chain.blocks.is_empty(),
+        "head block {} is missing in the database",
+        head
+    );
+
+    chain.blocks.reverse();
+
+    ensure!(
+        chain.first_finalized || expected.number < first_block || first_block == 0,
+        "block {} is missing in the database, while the above block is present",
+        expected
+    );
+
+    if let Some(parent_hash) = parent_hash {
+        if expected.number < first_block {
+            ensure!(
+                expected.hash == parent_hash,
+                "the highest available chain {} is not based on block with hash {}",
+                chain.blocks.last().unwrap(),
+                parent_hash
+            );
+        }
+    }
+
+    Ok(chain)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::Pin,
+        sync::{Arc, Mutex},
+        task::{Context, Poll},
+        time::Duration
+    };
+
+    use futures::{Stream, task::AtomicWaker};
+    use sqd_data_source::{DataEvent, DataSource};
+    use tempfile::TempDir;
+    use tokio::time::Instant;
+
+    use super::*;
+    use crate::{
+        block::{Block as StoredBlock, BlockHeader},
+        ingest::{Ingest, IngestHandle}
+    };
+
+    const FIRST_BLOCK: BlockNumber = 100;
+    const PARENT_HASH: &str = "99-0";
+
+    /// Canonical chain of a simulated node, blocks are named `{number}-{fork}`
+    #[derive(Default)]
+    struct TestChain {
+        state: Mutex<TestChainState>,
+        waker: AtomicWaker
+    }
+
+    #[derive(Default)]
+    struct TestChainState {
+        blocks: Vec<BlockArc>,
+        finalized: Option<BlockNumber>
+    }
+
+    impl TestChainState {
+        fn get(&self, number: BlockNumber) -> Option<&BlockArc> {
+            number
+                .checked_sub(FIRST_BLOCK)
+                .and_then(|idx| self.blocks.get(idx as usize))
+        }
+    }
+
+    impl TestChain {
+        fn produce(&self, n: usize) {
+            let fork = self.state.lock().unwrap().blocks.last().map_or(0, |b| fork_of(b));
+            self.extend(n, fork);
+        }
+
+        fn fork(&self, at: BlockNumber, n: usize, fork: u32) {
+            self.state.lock().unwrap().blocks.truncate((at - FIRST_BLOCK) as usize);
+            self.extend(n, fork);
+        }
+
+        fn extend(&self, n: usize, fork: u32) {
+            {
+                let mut state = self.state.lock().unwrap();
+                for _ in 0..n {
+                    let (number, parent_hash) = state.blocks.last().map_or_else(
+                        || (FIRST_BLOCK, PARENT_HASH.to_string()),
+                        |b| (b.header.number + 1, b.header.hash.to_string())
+                    );
+                    state.blocks.push(test_block(number, fork, parent_hash));
+                }
+            }
+            self.waker.wake();
+        }
+
+        fn finalize(&self, number: BlockNumber) {
+            self.state.lock().unwrap().finalized = Some(number);
+            self.waker.wake();
+        }
+    }
+
+    fn test_block(number: BlockNumber, fork: u32, parent_hash: String) -> BlockArc {
+        Arc::new(StoredBlock {
+            header: BlockHeader {
+                number,
+                hash: format!("{}-{}", number, fork).into(),
+                parent_number: number - 1,
+                parent_hash: parent_hash.into(),
+                timestamp: Some(number as i64 * 1000),
+                is_final: false
+            },
+            data: format!("data of {}-{}", number, fork).into_bytes().into()
+        })
+    }
+
+    fn fork_of(block: &BlockArc) -> u32 {
+        block.header.hash.split_once('-').unwrap().1.parse().unwrap()
+    }
+
+    fn block_ref(number: BlockNumber, fork: u32) -> BlockRef {
+        BlockRef {
+            number,
+            hash: format!("{}-{}", number, fork)
+        }
+    }
+
+    /// Serves blocks of a [`TestChain`] the way `StandardDataSource` does
+    struct TestSource {
+        chain: Arc<TestChain>,
+        next_block: BlockNumber,
+        parent_hash: Option<String>,
+        reported_finalized: Option<BlockNumber>
+    }
+
+    impl TestSource {
+        fn new(chain: Arc<TestChain>) -> Self {
+            Self {
+                chain,
+                next_block: FIRST_BLOCK,
+                parent_hash: None,
+                reported_finalized: None
+            }
+        }
+    }
+
+    impl DataSource for TestSource {
+        type Block = BlockArc;
+
+        fn set_position(&mut self, next_block: BlockNumber, parent_block_hash: Option<&str>) {
+            self.next_block = next_block;
+            self.parent_hash = parent_block_hash.map(|hash| hash.to_string());
+        }
+
+        fn get_next_block(&self) -> BlockNumber {
+            self.next_block
+        }
+
+        fn get_parent_block_hash(&self) -> Option<&str> {
+            self.parent_hash.as_deref()
+        }
+    }
+
+    impl Stream for TestSource {
+        type Item = DataEvent<BlockArc>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let this = self.get_mut();
+            let state = this.chain.state.lock().unwrap();
+
+            if let Some(block) = state.get(this.next_block) {
+                if this
+                    .parent_hash
+                    .as_deref()
+                    .map_or(false, |hash| hash != block.header.parent_hash)
+                {
+                    let idx = (this.next_block - FIRST_BLOCK) as usize;
+                    let prev = state.blocks[idx.saturating_sub(10)..idx]
+                        .iter()
+                        .map(|b| b.ptr().to_ref())
+                        .collect();
+                    return Poll::Ready(Some(DataEvent::Fork(prev)));
+                }
+                let is_final = state.finalized.map_or(false, |fin| block.header.number <= fin);
+                this.next_block = block.header.number + 1;
+                this.parent_hash = Some(block.header.hash.to_string());
+                return Poll::Ready(Some(DataEvent::Block {
+                    block: block.clone(),
+                    is_final
+                }));
+            }
+
+            if let Some(finalized) = state.finalized {
+                if this.reported_finalized != Some(finalized) {
+                    this.reported_finalized = Some(finalized);
+                    let head = state.get(finalized).unwrap().ptr().to_ref();
+                    return Poll::Ready(Some(DataEvent::FinalizedHead(head)));
+                }
+            }
+
+            // registered under the lock, so that no update is missed
+            this.chain.waker.register(cx.waker());
+            Poll::Pending
+        }
+    }
+
+    fn setup() -> (TempDir, LocalStorage, Arc<TestChain>) {
+        let dir = tempfile::tempdir().unwrap();
+        let storage = LocalStorage::open(dir.path()).unwrap();
+        (dir, storage, Arc::new(TestChain::default()))
+    }
+
+    async fn start(storage: &LocalStorage, chain: &Arc<TestChain>) -> anyhow::Result<IngestHandle> {
+        let (_, handle) = Ingest::new(storage.clone(), TestSource::new(chain.clone()))
+            .set_first_block(FIRST_BLOCK)
+            .set_parent_block_hash(Some(PARENT_HASH.to_string()))
+            .start()
+            .await?;
+        Ok(handle)
+    }
+
+    async fn wait_until(
+        handle: &mut IngestHandle,
+        mut condition: impl FnMut() -> anyhow::Result<bool>
+    ) -> anyhow::Result<()> {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while !condition()? {
+            ensure!(Instant::now() < deadline, "timed out waiting for the storage");
+            tokio::select! {
+                res = &mut *handle => {
+                    res?;
+                    bail!("ingest terminated")
+                },
+                _ = tokio::time::sleep(Duration::from_millis(10)) => {}
+            }
+        }
+        Ok(())
+    }
+
+    async fn wait_heads(
+        handle: &mut IngestHandle,
+        storage: &LocalStorage,
+        head: BlockRef,
+        finalized_head: Option<BlockRef>
+    ) -> anyhow::Result<()> {
+        wait_until(handle, || {
+            Ok(storage.get_head()? == Some(head.clone()) && storage.get_finalized_head()? == finalized_head)
+        })
+        .await
+    }
+
+    fn list_blocks(storage: &LocalStorage) -> anyhow::Result<Vec<BlockHeader<'static>>> {
+        storage.list_blocks(0, BlockNumber::MAX).collect()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ingested_chain_is_saved_and_finalized() -> anyhow::Result<()> {
+        let (_dir, storage, chain) = setup();
+        let mut handle = start(&storage, &chain).await?;
+
+        chain.produce(20);
+        chain.finalize(110);
+        wait_heads(&mut handle, &storage, block_ref(119, 0), Some(block_ref(110, 0))).await?;
+
+        let blocks = list_blocks(&storage)?;
+        assert_eq!(
+            blocks.iter().map(|b| b.number).collect::<Vec<_>>(),
+            (100..120).collect::<Vec<_>>()
+        );
+        assert!(blocks.iter().all(|b| b.is_final == (b.number <= 110)));
+
+        let block = storage.fetch_block(105, "105-0")?.unwrap();
+        assert_eq!(block.header.parent_hash, "104-0");
+        assert_eq!(block.data.as_ref(), b"data of 105-0");
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn forked_blocks_are_deleted_on_finalization() -> anyhow::Result<()> {
+        let (_dir, storage, chain) = setup();
+        let mut handle = start(&storage, &chain).await?;
+
+        chain.produce(11);
+        wait_heads(&mut handle, &storage, block_ref(110, 0), None).await?;
+
+        chain.fork(106, 6, 1);
+        wait_heads(&mut handle, &storage, block_ref(111, 1), None).await?;
+        assert_eq!(storage.list_blocks(106, 110).count(), 10, "both forks are kept");
+
+        chain.finalize(111);
+        wait_heads(&mut handle, &storage, block_ref(111, 1), Some(block_ref(111, 1))).await?;
+
+        let blocks = list_blocks(&storage)?;
+        let expected: Vec<_> = (100..112)
+            .map(|number| block_ref(number, if number < 106 { 0 } else { 1 }))
+            .collect();
+        assert_eq!(blocks.iter().map(|b| b.ptr().to_ref()).collect::<Vec<_>>(), expected);
+        assert!(blocks.iter().all(|b| b.is_final));
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn ingest_resumes_from_the_stored_chain() -> anyhow::Result<()> {
+        let (_dir, storage, chain) = setup();
+        let mut handle = start(&storage, &chain).await?;
+
+        chain.produce(10);
+        chain.finalize(104);
+        wait_heads(&mut handle, &storage, block_ref(109, 0), Some(block_ref(104, 0))).await?;
+        handle.abort();
+
+        let head_chain = storage.get_chain_head(FIRST_BLOCK, Some(PARENT_HASH)).await?;
+        assert!(head_chain.first_finalized);
+        assert_eq!(
+            head_chain.blocks,
+            (104..110).map(|n| block_ref(n, 0)).collect::<Vec<_>>()
+        );
+
+        let mut handle = start(&storage, &chain).await?;
+        chain.produce(5);
+        chain.finalize(112);
+        wait_heads(&mut handle, &storage, block_ref(114, 0), Some(block_ref(112, 0))).await?;
+        assert_eq!(list_blocks(&storage)?.len(), 15);
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stored_chain_must_be_based_on_the_parent_hash() -> anyhow::Result<()> {
+        let (_dir, storage, chain) = setup();
+        let mut handle = start(&storage, &chain).await?;
+
+        chain.produce(5);
+        wait_heads(&mut handle, &storage, block_ref(104, 0), None).await?;
+        handle.abort();
+
+        assert!(storage.get_chain_head(FIRST_BLOCK, Some("99-1")).await.is_err());
+
+        let head_chain = storage.get_chain_head(FIRST_BLOCK + 10, None).await?;
+        assert!(head_chain.blocks.is_empty());
+        Ok(())
+    }
+}
@@ -0,0 +1,14 @@
+mod ingest;
+mod serve;
+mod store;
+
+pub use store::*;
+
+/// Runs a storage operation on the blocking thread pool
+async fn run<R: Send + 'static>(
+    storage: &LocalStorage,
+    f: impl FnOnce(&LocalStorage) -> anyhow::Result<R> + Send + 'static
+) -> anyhow::Result<R> {
+    let storage = storage.clone();
+    tokio::task::spawn_blocking(move || f(&storage)).await?
+}
@@ -0,0 +1,41 @@
+use sqd_primitives::BlockNumber;
+
+use super::{run, store::LocalStorage};
+use crate::{
+    block::{Block, BlockHeader},
+    serve::{BlockReader, Heads}
+};
+
+impl BlockReader for LocalStorage {
+    async fn get_heads(&self) -> anyhow::Result<Heads> {
+        run(self, |storage| {
+            Ok(Heads {
+                head: storage.get_head()?,
+                finalized_head: storage.get_finalized_head()?
+            })
+        })
+        .await
+    }
+
+    async fn list_headers(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber
+    ) -> anyhow::Result<Vec<BlockHeader<'static>>> {
+        run(self, move |storage| {
+            storage.list_blocks(first_block, last_block).collect()
+        })
+        .await
+    }
+
+    async fn fetch_blocks(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber
+    ) -> anyhow::Result<Vec<Block<'static>>> {
+        run(self, move |storage| {
+            storage.fetch_blocks(first_block, last_block).collect()
+        })
+        .await
+    }
+}
@@ -0,0 +1,224 @@
+use std::{path::Path, sync::Arc};
+
+use anyhow::{Context, bail};
+use borsh::{BorshDeserialize, BorshSerialize};
+use rocksdb::{ColumnFamily, ColumnFamilyDescriptor, DB, Direction, IteratorMode, Options as RocksOptions, WriteBatch};
+use sqd_primitives::{Block as _, BlockNumber, BlockRef};
+
+use crate::block::{Block, BlockHeader};
+
+const CF_HEADERS: &str = "HEADERS";
+const CF_DATA: &str = "DATA";
+const CF_STATE: &str = "STATE";
+
+const HEAD_KEY: &[u8] = b"head";
+const FINALIZED_HEAD_KEY: &[u8] = b"finalized_head";
+
+/// Block storage in an embedded RocksDB database.
+///
+/// Blocks are keyed by number and hash, so that forks coexist until they are finalized.
+#[derive(Clone)]
+pub struct LocalStorage {
+    db: Arc<DB>
+}
+
+#[derive(BorshSerialize, BorshDeserialize)]
+struct StoredHeader {
+    parent_number: BlockNumber,
+    parent_hash: String,
+    timestamp: Option<i64>,
+    is_final: bool
+}
+
+impl From<&BlockHeader<'_>> for StoredHeader {
+    fn from(header: &BlockHeader<'_>) -> Self {
+        Self {
+            parent_number: header.parent_number,
+            parent_hash: header.parent_hash.to_string(),
+            timestamp: header.timestamp,
+            is_final: header.is_final
+        }
+    }
+}
+
+impl LocalStorage {
+    pub fn open(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let mut options = RocksOptions::default();
+        options.create_if_missing(true);
+        options.create_missing_column_families(true);
+
+        let db = DB::open_cf_descriptors(
+            &options,
+            path,
+            [
+                ColumnFamilyDescriptor::new(CF_HEADERS, RocksOptions::default()),
+                ColumnFamilyDescriptor::new(CF_DATA, RocksOptions::default()),
+                ColumnFamilyDescriptor::new(CF_STATE, RocksOptions::default())
+            ]
+        )?;
+
+        Ok(Self { db: Arc::new(db) })
+    }
+
+    fn cf(&self, name: &str) -> &ColumnFamily {
+        self.db.cf_handle(name).expect("column family opened at startup")
+    }
+
+    pub fn save_block(&self, block: &Block<'_>) -> anyhow::Result<()> {
+        let key = block_key(block.header.number, &block.header.hash);
+        let mut header = StoredHeader::from(&block.header);
+        header.is_final = false;
+        let mut batch = WriteBatch::default();
+        batch.put_cf(self.cf(CF_HEADERS), &key, borsh::to_vec(&header)?);
+        batch.put_cf(self.cf(CF_DATA), &key, block.data.as_ref());
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    pub fn set_head(&self, number: BlockNumber, hash: &str) -> anyhow::Result<()> {
+        let head = BlockRef {
+            number,
+            hash: hash.to_string()
+        };
+        self.db.put_cf(self.cf(CF_STATE), HEAD_KEY, borsh::to_vec(&head)?)?;
+        Ok(())
+    }
+
+    /// Returns the last written head, or the finalized head, if there is none
+    pub fn get_head(&self) -> anyhow::Result<Option<BlockRef>> {
+        match self.get_state(HEAD_KEY).context("invalid head")? {
+            Some(head) => Ok(Some(head)),
+            None => self.get_finalized_head()
+        }
+    }
+
+    pub fn get_finalized_head(&self) -> anyhow::Result<Option<BlockRef>> {
+        self.get_state(FINALIZED_HEAD_KEY).context("invalid finalized head")
+    }
+
+    fn get_state(&self, key: &[u8]) -> anyhow::Result<Option<BlockRef>> {
+        self.db
+            .get_pinned_cf(self.cf(CF_STATE), key)?
+            .map(|value| BlockRef::try_from_slice(&value))
+            .transpose()
+            .map_err(Into::into)
+    }
+
+    /// Marks the chain of blocks ending at `to` as final and deletes all other blocks
+    /// in the `[from, to.number]` range.
+    ///
+    /// The finalized head is updated in the same write.
+    pub fn finalize(&self, from: BlockNumber, to: &BlockRef) -> anyhow::Result<()> {
+        let mut batch = WriteBatch::default();
+        let mut expected = to.clone();
+
+        for header_result in self.list_blocks_in_reversed_order(from, to.number) {
+            let header = header_result?;
+            if header.number < expected.number {
+                bail!("block {} is missing", expected)
+            }
+            let key = block_key(header.number, &header.hash);
+            if header.ptr() == expected.ptr() {
+                expected.set_ptr(header.parent_ptr());
+                let mut stored = StoredHeader::from(&header);
+                stored.is_final = true;
+                batch.put_cf(self.cf(CF_HEADERS), &key, borsh::to_vec(&stored)?);
+            } else {
+                batch.delete_cf(self.cf(CF_HEADERS), &key);
+                batch.delete_cf(self.cf(CF_DATA), &key);
+            }
+        }
+
+        if expected.number >= from && from > 0 {
+            bail!("block {} is missing", expected)
+        }
+
+        batch.put_cf(self.cf(CF_STATE), FINALIZED_HEAD_KEY, borsh::to_vec(to)?);
+        self.db.write(batch)?;
+        Ok(())
+    }
+
+    pub fn fetch_block(&self, number: BlockNumber, hash: &str) -> anyhow::Result<Option<Block<'static>>> {
+        let key = block_key(number, hash);
+        let Some(header) = self.db.get_pinned_cf(self.cf(CF_HEADERS), &key)? else {
+            return Ok(None);
+        };
+        let header = decode_header(number, hash, &header)?;
+        self.read_data(header).map(Some)
+    }
+
+    pub fn fetch_blocks(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber
+    ) -> impl Iterator<Item = anyhow::Result<Block<'static>>> + '_ {
+        self.list_blocks(first_block, last_block)
+            .map(|header_result| self.read_data(header_result?))
+    }
+
+    fn read_data(&self, header: BlockHeader<'static>) -> anyhow::Result<Block<'static>> {
+        let data = self
+            .db
+            .get_cf(self.cf(CF_DATA), block_key(header.number, &header.hash))?
+            .with_context(|| format!("data of block {}#{} is missing", header.number, header.hash))?;
+        Ok(Block {
+            header,
+            data: data.into()
+        })
+    }
+
+    pub fn list_blocks(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber
+    ) -> impl Iterator<Item = anyhow::Result<BlockHeader<'static>>> + '_ {
+        let start = first_block.to_be_bytes();
+        self.db
+            .iterator_cf(self.cf(CF_HEADERS), IteratorMode::From(&start, Direction::Forward))
+            .map(read_header)
+            .take_while(move |res| res.as_ref().map_or(true, |b| b.number <= last_block))
+    }
+
+    pub fn list_blocks_in_reversed_order(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber
+    ) -> impl Iterator<Item = anyhow::Result<BlockHeader<'static>>> + '_ {
+        let start = last_block.saturating_add(1).to_be_bytes();
+        self.db
+            .iterator_cf(self.cf(CF_HEADERS), IteratorMode::From(&start, Direction::Reverse))
+            .map(read_header)
+            .skip_while(move |res| res.as_ref().map_or(false, |b| b.number > last_block))
+            .take_while(move |res| res.as_ref().map_or(true, |b| b.number >= first_block))
+    }
+}
+
+fn block_key(number: BlockNumber, hash: &str) -> Vec<u8> {
+    let mut key = Vec::with_capacity(8 + hash.len());
+    key.extend_from_slice(&number.to_be_bytes());
+    key.extend_from_slice(hash.as_bytes());
+    key
+}
+
+fn read_header(item: Result<(Box<[u8]>, Box<[u8]>), rocksdb::Error>) -> anyhow::Result<BlockHeader<'static>> {
+    let (key, value) = item?;
+    let Some((number, hash)) = key.split_first_chunk::<8>() else {
+        bail!("invalid block key")
+    };
+    let number = BlockNumber::from_be_bytes(*number);
+    let hash = std::str::from_utf8(hash).context("invalid block hash")?;
+    decode_header(number, hash, &value)
+}
+
+fn decode_header(number: BlockNumber, hash: &str, value: &[u8]) -> anyhow::Result<BlockHeader<'static>> {
+    let stored =
+        StoredHeader::try_from_slice(value).with_context(|| format!("invalid header of block {}#{}", number, hash))?;
+    Ok(BlockHeader {
+        number,
+        hash: hash.to_string().into(),
+        parent_number: stored.parent_number,
+        parent_hash: stored.parent_hash.into(),
+        timestamp: stored.timestamp,
+        is_final: stored.is_final
+    })
+}
@@ -6,6 +6,8 @@ mod chain_watch;
 mod cmd;
 mod data_source;
 mod ingest;
+mod local;
+mod serve;
 mod util;
 
 use std::io::IsTerminal;
@@ -24,7 +26,9 @@ struct CLI {
 #[derive(clap::Subcommand)]
 enum Command {
     /// Run data ingestion
-    Ingest(cmd::ingest::Args)
+    Ingest(cmd::ingest::Args),
+    /// Serve stored blocks over the data source API
+    Serve(cmd::serve::Args)
 }
 
 fn main() -> anyhow::Result<()> {
@@ -48,6 +52,7 @@ fn main() -> anyhow::Result<()> {
     }
 
     match cli.command {
-        Command::Ingest(args) => cmd::ingest::run(args)
+        Command::Ingest(args) => cmd::ingest::run(args),
+        Command::Serve(args) => cmd::serve::run(args)
     }
 }
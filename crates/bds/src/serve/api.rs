@@ -0,0 +1,569 @@
+use std::{
+    io::Read,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant}
+};
+
+use anyhow::ensure;
+use async_stream::stream;
+use axum::{
+    Extension, Json, Router,
+    body::{Body, Bytes},
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    routing::{get, post}
+};
+use flate2::read::GzDecoder;
+use serde::{Deserialize, Serialize};
+use sqd_primitives::{Block as _, BlockNumber, BlockRef};
+use tracing::error;
+
+use super::reader::{BlockReader, Heads};
+use crate::block::{Block, BlockHeader};
+
+/// Max number of blocks fetched from the storage at once
+const PAGE_SIZE: BlockNumber = 100;
+
+/// Number of finalized blocks included into a base block conflict response
+const FORK_FINALIZED_BLOCKS: BlockNumber = 10;
+
+/// New pages are not fetched once the response took that long
+const RESPONSE_TIME_LIMIT: Duration = Duration::from_secs(10);
+
+/// Builds the router of the data source API, that is consumed by `ReqwestDataClient`
+pub fn build_router<R: BlockReader>(reader: R) -> Router {
+    Router::new()
+        .route("/stream", post(stream::<R>))
+        .route("/head", get(get_head::<R>))
+        .route("/finalized-head", get(get_finalized_head::<R>))
+        .layer(Extension(reader))
+        .layer(Extension(ChainCache::default()))
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct StreamRequest {
+    from_block: BlockNumber,
+    parent_block_hash: Option<String>
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BaseBlockConflict {
+    previous_blocks: Vec<BlockRef>
+}
+
+async fn get_head<R: BlockReader>(Extension(reader): Extension<R>) -> Response {
+    match reader.get_heads().await {
+        Ok(heads) => Json(heads.head).into_response(),
+        Err(err) => internal_error(err)
+    }
+}
+
+async fn get_finalized_head<R: BlockReader>(Extension(reader): Extension<R>) -> Response {
+    match reader.get_heads().await {
+        Ok(heads) => Json(heads.finalized_head).into_response(),
+        Err(err) => internal_error(err)
+    }
+}
+
+async fn stream<R: BlockReader>(
+    Extension(reader): Extension<R>,
+    Extension(cache): Extension<ChainCache>,
+    Json(req): Json<StreamRequest>
+) -> Response {
+    match stream_internal(reader, cache, req).await {
+        Ok(res) => res,
+        Err(err) => internal_error(err)
+    }
+}
+
+async fn stream_internal<R: BlockReader>(reader: R, cache: ChainCache, req: StreamRequest) -> anyhow::Result<Response> {
+    let heads = reader.get_heads().await?;
+
+    let Some(chain) = cache.load(&reader, &heads).await? else {
+        return Ok(response(StatusCode::NO_CONTENT, &heads).body(Body::empty()).unwrap());
+    };
+
+    if req.from_block > chain.head.number {
+        return Ok(response(StatusCode::NO_CONTENT, &heads).body(Body::empty()).unwrap());
+    }
+
+    let started = Instant::now();
+
+    let mut next_block = chain
+        .first_block()
+        .map_or(req.from_block, |first| first.max(req.from_block));
+    let first_page = loop {
+        let page = chain.fetch_page(&reader, next_block).await?;
+        next_block = std::cmp::min(next_block + PAGE_SIZE, chain.head.number + 1);
+        if !page.is_empty() {
+            break page;
+        }
+        if next_block > chain.head.number || started.elapsed() >= RESPONSE_TIME_LIMIT {
+            return Ok(response(StatusCode::NO_CONTENT, &heads).body(Body::empty()).unwrap());
+        }
+    };
+
+    let first = &first_page[0].header;
+    if let Some(parent_hash) = req.parent_block_hash.as_deref() {
+        if first.parent_hash != parent_hash {
+            let previous_blocks = chain.get_previous_blocks(&reader, req.from_block).await?;
+            return Ok((StatusCode::CONFLICT, Json(BaseBlockConflict { previous_blocks })).into_response());
+        }
+    }
+
+    let mut parent = BlockRef {
+        number: first.parent_number,
+        hash: first.parent_hash.to_string()
+    };
+    let mut first_pack = Vec::new();
+    let mut complete = write_blocks(&mut first_pack, &mut parent, &first_page)?;
+
+    let body = stream! {
+        yield Ok::<_, std::io::Error>(Bytes::from(first_pack));
+
+        while complete && next_block <= chain.head.number && started.elapsed() < RESPONSE_TIME_LIMIT {
+            let page = match chain.fetch_page(&reader, next_block).await {
+                Ok(page) => page,
+                Err(err) => {
+                    error!(err =? err, "terminating response stream due to storage error");
+                    break
+                }
+            };
+            next_block = std::cmp::min(next_block + PAGE_SIZE, chain.head.number + 1);
+
+            let mut pack = Vec::new();
+            match write_blocks(&mut pack, &mut parent, &page) {
+                Ok(is_complete) => complete = is_complete,
+                Err(err) => {
+                    error!(err =? err, "terminating response stream due to invalid block data");
+                    break
+                }
+            }
+            if !pack.is_empty() {
+                yield Ok(Bytes::from(pack));
+            }
+        }
+    };
+
+    Ok(response(StatusCode::OK, &heads)
+        .header("content-type", "text/plain")
+        .body(Body::from_stream(body))
+        .unwrap())
+}
+
+fn response(status: StatusCode, heads: &Heads) -> axum::http::response::Builder {
+    let mut builder = Response::builder().status(status);
+    if let Some(head) = heads.finalized_head.as_ref() {
+        builder = builder
+            .header("x-sqd-finalized-head-number", head.number)
+            .header("x-sqd-finalized-head-hash", head.hash.as_str());
+    }
+    builder
+}
+
+/// Appends JSON lines of the blocks continuing the `parent`.
+///
+/// Returns `false`, when a block is not based on the previous one,
+/// which happens when the served part of the chain was finalized differently.
+fn write_blocks(buf: &mut Vec<u8>, parent: &mut BlockRef, blocks: &[Block<'_>]) -> anyhow::Result<bool> {
+    for block in blocks {
+        if block.header.parent_hash != parent.hash {
+            return Ok(false);
+        }
+        GzDecoder::new(block.data.as_ref()).read_to_end(buf)?;
+        buf.push(b'\n');
+        parent.set_ptr(block.ptr());
+    }
+    Ok(true)
+}
+
+fn internal_error(err: anyhow::Error) -> Response {
+    error!(err =? err, "unhandled error, returning 500");
+    (StatusCode::INTERNAL_SERVER_ERROR, format!("{:?}", err)).into_response()
+}
+
+/// The last loaded [`ServedChain`].
+///
+/// Requests between head changes share it, and a moved head is only walked back
+/// until it joins the cached chain.
+#[derive(Clone, Default)]
+struct ChainCache(Arc<Mutex<Option<Arc<ServedChain>>>>);
+
+impl ChainCache {
+    async fn load<R: BlockReader>(&self, reader: &R, heads: &Heads) -> anyhow::Result<Option<Arc<ServedChain>>> {
+        let cached = self.0.lock().unwrap().clone();
+
+        if let Some(chain) = cached.as_ref() {
+            if heads.head.as_ref() == Some(&chain.head) && heads.finalized_head == chain.finalized_head {
+                return Ok(cached);
+            }
+        }
+
+        let Some(chain) = ServedChain::load(reader, heads, cached.as_deref()).await? else {
+            return Ok(None);
+        };
+        let chain = Arc::new(chain);
+        *self.0.lock().unwrap() = Some(chain.clone());
+        Ok(Some(chain))
+    }
+}
+
+/// The chain, that is served from a particular state of the storage.
+///
+/// Finalized blocks are unique by height, above the finalized head
+/// the chain is the one leading to the head.
+struct ServedChain {
+    head: BlockRef,
+    finalized_head: Option<BlockRef>,
+    /// blocks above the finalized head in ascending order
+    unfinalized: Vec<BlockRef>
+}
+
+impl ServedChain {
+    /// Walks the chain from the head down to the finalized head.
+    ///
+    /// The walk stops early, once it reaches a block of the `known` chain.
+    async fn load<R: BlockReader>(
+        reader: &R,
+        heads: &Heads,
+        known: Option<&ServedChain>
+    ) -> anyhow::Result<Option<Self>> {
+        let Some(head) = heads.head.clone() else {
+            return Ok(None);
+        };
+
+        let lowest = heads.finalized_head.as_ref().map_or(0, |h| h.number + 1);
+        let mut unfinalized = Vec::new();
+        let mut expected = head.clone();
+
+        'walk: while expected.number >= lowest {
+            if let Some(known) = known.and_then(|c| c.chain_to(&expected, lowest)) {
+                unfinalized.extend(known.iter().rev().cloned());
+                break;
+            }
+            let first = expected.number.saturating_sub(PAGE_SIZE - 1).max(lowest);
+            for b in reader.list_headers(first, expected.number).await?.iter().rev() {
+                if b.ptr() == expected.ptr() {
+                    unfinalized.push(b.ptr().to_ref());
+                    expected.set_ptr(b.parent_ptr());
+                    if let Some(known) = known.and_then(|c| c.chain_to(&expected, lowest)) {
+                        unfinalized.extend(known.iter().rev().cloned());
+                        break 'walk;
+                    }
+                }
+            }
+            if expected.number >= first {
+                // without finalization the chain simply starts with the first stored block
+                ensure!(
+                    heads.finalized_head.is_none(),
+                    "block {} is missing in the storage",
+                    expected
+                );
+                break;
+            }
+        }
+
+        unfinalized.reverse();
+
+        Ok(Some(Self {
+            head,
+            finalized_head: heads.finalized_head.clone(),
+            unfinalized
+        }))
+    }
+
+    /// Unfinalized blocks from `lowest` up to `tip`, if `tip` is one of them
+    fn chain_to(&self, tip: &BlockRef, lowest: BlockNumber) -> Option<&[BlockRef]> {
+        let own_lowest = self.finalized_head.as_ref().map_or(0, |h| h.number + 1);
+        if own_lowest > lowest || tip.number < lowest {
+            return None;
+        }
+        let end = self.unfinalized.binary_search_by_key(&tip.number, |b| b.number).ok()?;
+        if self.unfinalized[end].hash != tip.hash {
+            return None;
+        }
+        let start = self.unfinalized.partition_point(|b| b.number < lowest);
+        Some(&self.unfinalized[start..=end])
+    }
+
+    /// The lowest block of the chain, if it is known without scanning the storage.
+    ///
+    /// Without finalization the walk from the head reaches the first stored block.
+    fn first_block(&self) -> Option<BlockNumber> {
+        if self.finalized_head.is_some() {
+            return None;
+        }
+        self.unfinalized.first().map(|b| b.number)
+    }
+
+    fn contains(&self, header: &BlockHeader<'_>) -> bool {
+        if header.number > self.head.number {
+            return false;
+        }
+        if self
+            .finalized_head
+            .as_ref()
+            .map_or(false, |h| header.number <= h.number)
+        {
+            return header.is_final;
+        }
+        self.unfinalized
+            .binary_search_by_key(&header.number, |b| b.number)
+            .map_or(false, |i| self.unfinalized[i].hash == header.hash)
+    }
+
+    /// Fetches blocks of the chain, that are within a page starting at `first_block`
+    async fn fetch_page<R: BlockReader>(
+        &self,
+        reader: &R,
+        first_block: BlockNumber
+    ) -> anyhow::Result<Vec<Block<'static>>> {
+        let last_block = std::cmp::min(first_block + PAGE_SIZE - 1, self.head.number);
+        let mut blocks = reader.fetch_blocks(first_block, last_block).await?;
+        blocks.retain(|b| self.contains(&b.header));
+        Ok(blocks)
+    }
+
+    /// Lists blocks below `block_number`, that allow the client to find a common base
+    async fn get_previous_blocks<R: BlockReader>(
+        &self,
+        reader: &R,
+        block_number: BlockNumber
+    ) -> anyhow::Result<Vec<BlockRef>> {
+        let Some(last_block) = block_number.checked_sub(1) else {
+            return Ok(Vec::new());
+        };
+
+        let mut blocks = Vec::new();
+
+        if let Some(finalized_head) = self.finalized_head.as_ref() {
+            let top = std::cmp::min(last_block, finalized_head.number);
+            let first = top.saturating_sub(FORK_FINALIZED_BLOCKS - 1);
+            blocks.extend(
+                reader
+                    .list_headers(first, top)
+                    .await?
+                    .iter()
+                    .filter(|b| b.is_final)
+                    .map(|b| b.ptr().to_ref())
+            );
+        }
+
+        blocks.extend(self.unfinalized.iter().take_while(|b| b.number <= last_block).cloned());
+
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        borrow::Cow,
+        io::Write,
+        sync::atomic::{AtomicUsize, Ordering}
+    };
+
+    use flate2::{Compression, write::GzEncoder};
+    use futures::TryStreamExt;
+    use sqd_data_client::{BlockStreamRequest, BlockStreamResponse, reqwest::ReqwestDataClient};
+
+    use super::*;
+    use crate::local::LocalStorage;
+
+    fn save(storage: &LocalStorage, number: BlockNumber, hash: &str, parent_hash: &str) {
+        let json = serde_json::json!({
+            "header": {
+                "number": number,
+                "hash": hash,
+                "parentHash": parent_hash
+            }
+        });
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::fast());
+        encoder.write_all(json.to_string().as_bytes()).unwrap();
+        let block = Block {
+            header: BlockHeader {
+                number,
+                hash: Cow::Borrowed(hash),
+                parent_number: number - 1,
+                parent_hash: Cow::Borrowed(parent_hash),
+                timestamp: None,
+                is_final: false
+            },
+            data: encoder.finish().unwrap().into()
+        };
+        storage.save_block(&block).unwrap();
+    }
+
+    fn block_ref(number: BlockNumber, hash: &str) -> BlockRef {
+        BlockRef {
+            number,
+            hash: hash.to_string()
+        }
+    }
+
+    /// Counts listed headers and fetch calls, to see how far the chain is walked and scanned
+    #[derive(Clone)]
+    struct CountingReader {
+        storage: LocalStorage,
+        listed: Arc<AtomicUsize>,
+        fetched: Arc<AtomicUsize>
+    }
+
+    impl CountingReader {
+        fn new(storage: LocalStorage) -> Self {
+            Self {
+                storage,
+                listed: Arc::new(AtomicUsize::new(0)),
+                fetched: Arc::new(AtomicUsize::new(0))
+            }
+        }
+    }
+
+    impl BlockReader for CountingReader {
+        async fn get_heads(&self) -> anyhow::Result<Heads> {
+            self.storage.get_heads().await
+        }
+
+        async fn list_headers(
+            &self,
+            first_block: BlockNumber,
+            last_block: BlockNumber
+        ) -> anyhow::Result<Vec<BlockHeader<'static>>> {
+            let headers = self.storage.list_headers(first_block, last_block).await?;
+            self.listed.fetch_add(headers.len(), Ordering::SeqCst);
+            Ok(headers)
+        }
+
+        async fn fetch_blocks(
+            &self,
+            first_block: BlockNumber,
+            last_block: BlockNumber
+        ) -> anyhow::Result<Vec<Block<'static>>> {
+            self.fetched.fetch_add(1, Ordering::SeqCst);
+            self.storage.fetch_blocks(first_block, last_block).await
+        }
+    }
+
+    fn hash(number: BlockNumber, fork: &str) -> String {
+        format!("{number}-{fork}")
+    }
+
+    #[tokio::test]
+    async fn cached_chain_is_walked_only_down_to_the_known_part() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let storage = LocalStorage::open(dir.path())?;
+        for number in 1..=300 {
+            save(&storage, number, &hash(number, "a"), &hash(number - 1, "a"));
+        }
+        storage.set_head(300, &hash(300, "a"))?;
+
+        let reader = CountingReader::new(storage.clone());
+        let cache = ChainCache::default();
+
+        let chain = cache.load(&reader, &reader.get_heads().await?).await?.unwrap();
+        assert_eq!(chain.unfinalized.len(), 300);
+        let walked = reader.listed.swap(0, Ordering::SeqCst);
+        assert!(walked >= 300);
+
+        // same heads, no walk at all
+        cache.load(&reader, &reader.get_heads().await?).await?.unwrap();
+        assert_eq!(reader.listed.load(Ordering::SeqCst), 0);
+
+        // 300-a is replaced by 300-b <- 301-b
+        save(&storage, 300, &hash(300, "b"), &hash(299, "a"));
+        save(&storage, 301, &hash(301, "b"), &hash(300, "b"));
+        storage.set_head(301, &hash(301, "b"))?;
+
+        let chain = cache.load(&reader, &reader.get_heads().await?).await?.unwrap();
+        assert!(reader.listed.load(Ordering::SeqCst) <= PAGE_SIZE as usize + 1);
+        let expected: Vec<BlockRef> = (1..=301)
+            .map(|number| block_ref(number, &hash(number, if number < 300 { "a" } else { "b" })))
+            .collect();
+        assert_eq!(chain.unfinalized, expected);
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn stream_starts_at_the_first_stored_block() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let storage = LocalStorage::open(dir.path())?;
+        for number in 1_000_000..1_000_003 {
+            save(&storage, number, &hash(number, "a"), &hash(number - 1, "a"));
+        }
+        storage.set_head(1_000_002, &hash(1_000_002, "a"))?;
+
+        let reader = CountingReader::new(storage);
+        let req = StreamRequest {
+            from_block: 0,
+            parent_block_hash: None
+        };
+        let res = stream_internal(reader.clone(), ChainCache::default(), req).await?;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        // the empty range below the first stored block is not scanned page by page
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await?;
+        assert_eq!(body.split(|b| *b == b'\n').filter(|line| !line.is_empty()).count(), 3);
+        assert_eq!(reader.fetched.load(Ordering::SeqCst), 1);
+
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn stream_serves_the_chain_leading_to_the_head() -> anyhow::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let storage = LocalStorage::open(dir.path())?;
+
+        // 100-a .. 103-a, 104-a is orphaned by 104-b <- 105-b
+        save(&storage, 100, "100-a", "99-a");
+        save(&storage, 101, "101-a", "100-a");
+        save(&storage, 102, "102-a", "101-a");
+        save(&storage, 103, "103-a", "102-a");
+        save(&storage, 104, "104-a", "103-a");
+        save(&storage, 104, "104-b", "103-a");
+        save(&storage, 105, "105-b", "104-b");
+        storage.set_head(105, "105-b")?;
+        storage.finalize(100, &block_ref(102, "102-a"))?;
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        tokio::spawn(axum::serve(listener, build_router(storage)).into_future());
+
+        let client = ReqwestDataClient::from_url(url.as_str());
+
+        assert_eq!(client.get_head().await?, Some(block_ref(105, "105-b")));
+        assert_eq!(client.get_finalized_head().await?, Some(block_ref(102, "102-a")));
+
+        let BlockStreamResponse::Stream { blocks, finalized_head } =
+            client.stream(BlockStreamRequest::new(101)).await?
+        else {
+            panic!("unexpected fork")
+        };
+        assert_eq!(finalized_head, Some(block_ref(102, "102-a")));
+        let hashes: Vec<String> = blocks
+            .map_ok(|line| {
+                let json: serde_json::Value = serde_json::from_slice(&line).unwrap();
+                json["header"]["hash"].as_str().unwrap().to_string()
+            })
+            .try_collect()
+            .await?;
+        assert_eq!(hashes, ["101-a", "102-a", "103-a", "104-b", "105-b"]);
+
+        let mut req = BlockStreamRequest::new(105);
+        req.set_parent_block_hash(Some("104-a"));
+        let BlockStreamResponse::Fork(prev) = client.stream(req).await? else {
+            panic!("expected a fork")
+        };
+        assert_eq!(prev.last(), Some(&block_ref(104, "104-b")));
+        assert!(prev.contains(&block_ref(102, "102-a")));
+
+        let BlockStreamResponse::Stream { blocks, .. } = client.stream(BlockStreamRequest::new(106)).await? else {
+            panic!("unexpected fork")
+        };
+        assert_eq!(blocks.try_collect::<Vec<_>>().await?.len(), 0);
+
+        Ok(())
+    }
+}
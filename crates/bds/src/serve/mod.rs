@@ -0,0 +1,5 @@
+mod api;
+mod reader;
+
+pub use api::*;
+pub use reader::*;
@@ -0,0 +1,30 @@
+use std::future::Future;
+
+use sqd_primitives::{BlockNumber, BlockRef};
+
+use crate::block::{Block, BlockHeader};
+
+#[derive(Clone, Debug, Default)]
+pub struct Heads {
+    pub head: Option<BlockRef>,
+    pub finalized_head: Option<BlockRef>
+}
+
+/// Read access to the stored blocks.
+///
+/// Ranges are inclusive and blocks are returned in ascending order, including the forked ones.
+pub trait BlockReader: Clone + Send + Sync + 'static {
+    fn get_heads(&self) -> impl Future<Output = anyhow::Result<Heads>> + Send;
+
+    fn list_headers(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber
+    ) -> impl Future<Output = anyhow::Result<Vec<BlockHeader<'static>>>> + Send;
+
+    fn fetch_blocks(
+        &self,
+        first_block: BlockNumber,
+        last_block: BlockNumber
+    ) -> impl Future<Output = anyhow::Result<Vec<Block<'static>>>> + Send;
+}
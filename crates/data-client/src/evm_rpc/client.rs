@@ -0,0 +1,554 @@
+use std::fmt::{Debug, Formatter};
+
+use anyhow::{ensure, Context};
+use futures::{future::BoxFuture, FutureExt, StreamExt, TryStreamExt};
+use reqwest::{Client, IntoUrl};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use sqd_data::evm::model::Block;
+use sqd_primitives::{BlockNumber, BlockRef};
+
+use super::{
+    mapping::map_block,
+    types::{parse_quantity, RpcBlock, RpcBlockRef, RpcReceipt, RpcTransactionTrace}
+};
+use crate::{
+    json_rpc::{has_rpc_error_code, rpc_error_kind, JsonRpcClient, RpcCall},
+    reqwest::{default_http_client, is_retryable_http_error, url_source_label},
+    types::{BlockStreamRequest, BlockStreamResponse},
+    DataClient
+};
+
+const DEFAULT_BATCH_SIZE: usize = 10;
+
+/// Max number of blocks served by a single stream
+const MAX_STREAM_BLOCKS: u64 = 1000;
+
+/// Number of canonical blocks reported back on a parent hash mismatch
+const FORK_HINTS: u64 = 50;
+
+/// Data client for a standard Ethereum JSON-RPC node.
+///
+/// Blocks are fetched in batches of `eth_getBlockByNumber`, `eth_getBlockReceipts`
+/// and, when enabled, `debug_traceBlockByNumber` with the `callTracer`.
+#[derive(Clone)]
+pub struct EvmRpcDataClient {
+    rpc: JsonRpcClient,
+    batch_size: usize,
+    with_traces: bool,
+    finality_confirmations: Option<u64>
+}
+
+impl Debug for EvmRpcDataClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvmRpcDataClient")
+            .field("url", &self.rpc.url().as_str())
+            .field("batch_size", &self.batch_size)
+            .field("with_traces", &self.with_traces)
+            .field("finality_confirmations", &self.finality_confirmations)
+            .finish()
+    }
+}
+
+impl EvmRpcDataClient {
+    pub fn from_url(url: impl IntoUrl) -> Self {
+        let http = default_http_client();
+        Self::new(http, url)
+    }
+
+    pub fn new(http: Client, url: impl IntoUrl) -> Self {
+        Self {
+            rpc: JsonRpcClient::new(http, url),
+            batch_size: DEFAULT_BATCH_SIZE,
+            with_traces: false,
+            finality_confirmations: None
+        }
+    }
+
+    /// Number of blocks requested in a single JSON-RPC batch
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be positive");
+        self.batch_size = batch_size;
+        self
+    }
+
+    /// Fetch call traces via `debug_traceBlockByNumber`
+    pub fn with_traces(mut self, with_traces: bool) -> Self {
+        self.with_traces = with_traces;
+        self
+    }
+
+    /// Treat blocks as final after the given number of confirmations
+    /// instead of relying on the node's `finalized` tag.
+    pub fn with_finality_confirmations(mut self, confirmations: Option<u64>) -> Self {
+        self.finality_confirmations = confirmations;
+        self
+    }
+
+    pub async fn stream(&self, req: BlockStreamRequest) -> anyhow::Result<BlockStreamResponse<Block>> {
+        let head = self.get_head().await?;
+
+        if let Some(parent_hash) = req.parent_block_hash.as_ref().filter(|_| req.first_block > 0) {
+            match self.get_block_ref(req.first_block - 1).await? {
+                Some(parent) if &parent.hash != parent_hash => {
+                    let prev_blocks = self.get_fork_hints(req.first_block).await?;
+                    return Ok(BlockStreamResponse::Fork(prev_blocks));
+                }
+                Some(_) => {}
+                // the node is behind the requested position
+                None => {
+                    return Ok(BlockStreamResponse::Stream {
+                        blocks: futures::stream::empty().boxed(),
+                        finalized_head: None
+                    })
+                }
+            }
+        }
+
+        let finalized_head = self.get_finalized_head().await?;
+
+        if req.first_block > head {
+            return Ok(BlockStreamResponse::Stream {
+                blocks: futures::stream::empty().boxed(),
+                finalized_head
+            });
+        }
+
+        let state = StreamState {
+            client: self.clone(),
+            next_block: req.first_block,
+            last_block: std::cmp::min(head, req.first_block + MAX_STREAM_BLOCKS - 1),
+            parent_hash: req.parent_block_hash,
+            finished: false
+        };
+
+        let blocks = futures::stream::try_unfold(state, |mut state| async move {
+            let blocks = state.next_batch().await?;
+            Ok::<_, anyhow::Error>(if blocks.is_empty() {
+                None
+            } else {
+                Some((
+                    futures::stream::iter(blocks.into_iter().map(Ok::<_, anyhow::Error>)),
+                    state
+                ))
+            })
+        })
+        .try_flatten();
+
+        Ok(BlockStreamResponse::Stream {
+            blocks: blocks.boxed(),
+            finalized_head
+        })
+    }
+
+    pub async fn get_head(&self) -> anyhow::Result<BlockNumber> {
+        let head: String = self.rpc.call("eth_blockNumber", json!([])).await?;
+        parse_quantity(&head).context("invalid eth_blockNumber result")
+    }
+
+    pub async fn get_finalized_head(&self) -> anyhow::Result<Option<BlockRef>> {
+        let block = match self.finality_confirmations {
+            None => {
+                self.rpc
+                    .call::<Option<RpcBlockRef>>("eth_getBlockByNumber", json!(["finalized", false]))
+                    .await?
+            }
+            Some(confirmations) => {
+                let head = self.get_head().await?;
+                if head < confirmations {
+                    return Ok(None);
+                }
+                self.get_block_ref(head - confirmations).await?
+            }
+        };
+        Ok(block.map(|b| BlockRef {
+            number: b.number,
+            hash: b.hash
+        }))
+    }
+
+    async fn get_block_ref(&self, number: BlockNumber) -> anyhow::Result<Option<RpcBlockRef>> {
+        self.rpc
+            .call("eth_getBlockByNumber", json!([quantity(number), false]))
+            .await
+    }
+
+    /// Canonical blocks preceding `first_block` in ascending order
+    async fn get_fork_hints(&self, first_block: BlockNumber) -> anyhow::Result<Vec<BlockRef>> {
+        let calls = (first_block.saturating_sub(FORK_HINTS)..first_block)
+            .map(|n| RpcCall::new("eth_getBlockByNumber", json!([quantity(n), false])))
+            .collect();
+
+        let mut prev_blocks = Vec::new();
+        for value in self.rpc.batch(calls).await? {
+            if let Some(block) = decode::<Option<RpcBlockRef>>("eth_getBlockByNumber", value)? {
+                prev_blocks.push(BlockRef {
+                    number: block.number,
+                    hash: block.hash
+                })
+            }
+        }
+        ensure!(!prev_blocks.is_empty(), "got an empty list of prev blocks");
+        Ok(prev_blocks)
+    }
+
+    pub fn is_retryable(&self, err: &anyhow::Error) -> bool {
+        is_retryable_http_error(err) || has_rpc_error_code(err, &[-32005, 429])
+    }
+}
+
+struct StreamState {
+    client: EvmRpcDataClient,
+    next_block: BlockNumber,
+    last_block: BlockNumber,
+    parent_hash: Option<String>,
+    finished: bool
+}
+
+impl StreamState {
+    /// Fetches the next batch of blocks.
+    ///
+    /// The batch is cut short at the first block, that is not yet available
+    /// or doesn't extend the previous one. In the latter case the next stream request
+    /// will find out the fork via the parent hash check.
+    async fn next_batch(&mut self) -> anyhow::Result<Vec<Block>> {
+        if self.finished || self.next_block > self.last_block {
+            return Ok(Vec::new());
+        }
+
+        let batch_end = std::cmp::min(self.last_block, self.next_block + self.client.batch_size as u64 - 1);
+        let mut calls = Vec::new();
+        for n in self.next_block..=batch_end {
+            calls.push(RpcCall::new("eth_getBlockByNumber", json!([quantity(n), true])));
+            calls.push(RpcCall::new("eth_getBlockReceipts", json!([quantity(n)])));
+            if self.client.with_traces {
+                calls.push(RpcCall::new(
+                    "debug_traceBlockByNumber",
+                    json!([quantity(n), {"tracer": "callTracer"}])
+                ));
+            }
+        }
+
+        let mut results = self.client.rpc.batch(calls).await?.into_iter();
+        let mut blocks = Vec::new();
+
+        for n in self.next_block..=batch_end {
+            let block = decode::<Option<RpcBlock>>("eth_getBlockByNumber", results.next().unwrap())?;
+            let receipts = decode::<Option<Vec<RpcReceipt>>>("eth_getBlockReceipts", results.next().unwrap())?;
+            let traces = if self.client.with_traces {
+                decode::<Option<Vec<RpcTransactionTrace>>>("debug_traceBlockByNumber", results.next().unwrap())?
+            } else {
+                None
+            };
+
+            let (Some(block), Some(receipts)) = (block, receipts) else {
+                break;
+            };
+            if self.client.with_traces && traces.is_none() {
+                break;
+            }
+            ensure!(
+                block.number == n,
+                "eth_getBlockByNumber returned block {} instead of {}",
+                block.number,
+                n
+            );
+            if self.parent_hash.as_ref().is_some_and(|hash| hash != &block.parent_hash) {
+                break;
+            }
+
+            let block = map_block(block, receipts, traces).with_context(|| format!("failed to map block {}", n))?;
+            self.parent_hash = Some(block.header.hash.clone());
+            blocks.push(block);
+        }
+
+        self.next_block += blocks.len() as u64;
+        self.finished = self.next_block <= batch_end;
+
+        Ok(blocks)
+    }
+}
+
+fn quantity(value: u64) -> String {
+    format!("0x{:x}", value)
+}
+
+fn decode<T: DeserializeOwned>(method: &str, value: Value) -> anyhow::Result<T> {
+    serde_json::from_value(value).with_context(|| format!("failed to decode {} result", method))
+}
+
+impl DataClient for EvmRpcDataClient {
+    type Block = Block;
+
+    fn stream(&self, req: BlockStreamRequest) -> BoxFuture<'static, anyhow::Result<BlockStreamResponse<Self::Block>>> {
+        let this = self.clone();
+        async move { this.stream(req).await }.boxed()
+    }
+
+    fn get_finalized_head(&self) -> BoxFuture<'static, anyhow::Result<Option<BlockRef>>> {
+        let this = self.clone();
+        async move { this.get_finalized_head().await }.boxed()
+    }
+
+    fn is_retryable(&self, err: &anyhow::Error) -> bool {
+        self.is_retryable(err)
+    }
+
+    fn error_kind(&self, err: &anyhow::Error) -> &'static str {
+        rpc_error_kind(err)
+    }
+
+    fn source_label(&self) -> String {
+        url_source_label(self.rpc.url())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::{routing::post, Json, Router};
+    use futures::TryStreamExt;
+    use serde_json::{json, Value};
+    use sqd_data::evm::model::{Block, TraceOp};
+    use sqd_primitives::BlockRef;
+
+    use super::EvmRpcDataClient;
+    use crate::{BlockStreamRequest, BlockStreamResponse};
+
+    const HEAD: u64 = 5;
+    const FINALIZED: u64 = 3;
+
+    fn hash(n: u64) -> String {
+        format!("0x{:064x}", n + 0x100)
+    }
+
+    fn tx_hash(n: u64) -> String {
+        format!("0x{:064x}", n + 0x200)
+    }
+
+    fn rpc_block(n: u64, full: bool) -> Value {
+        let tx = json!({
+            "hash": tx_hash(n),
+            "transactionIndex": "0x0",
+            "nonce": format!("0x{:x}", n),
+            "from": "0x01",
+            "to": "0x02",
+            "input": "0x",
+            "value": "0x0",
+            "type": "0x2",
+            "gas": "0x5208"
+        });
+        json!({
+            "number": format!("0x{:x}", n),
+            "hash": hash(n),
+            "parentHash": if n == 0 { format!("0x{:064x}", 0) } else { hash(n - 1) },
+            "timestamp": format!("0x{:x}", 1_700_000_000 + n * 12),
+            "transactionsRoot": "0x",
+            "receiptsRoot": "0x",
+            "stateRoot": "0x",
+            "logsBloom": "0x",
+            "sha3Uncles": "0x",
+            "extraData": "0x",
+            "miner": "0x03",
+            "size": "0x100",
+            "gasLimit": "0x1c9c380",
+            "gasUsed": "0x5208",
+            "uncles": [],
+            "transactions": [if full { tx } else { json!(tx_hash(n)) }]
+        })
+    }
+
+    fn rpc_receipts(n: u64) -> Value {
+        json!([{
+            "transactionHash": tx_hash(n),
+            "transactionIndex": "0x0",
+            "blockHash": hash(n),
+            "cumulativeGasUsed": "0x5208",
+            "effectiveGasPrice": "0x1",
+            "gasUsed": "0x5208",
+            "logsBloom": "0x",
+            "status": "0x1",
+            "logs": [{
+                "address": "0x02",
+                "topics": ["0x04"],
+                "data": "0x",
+                "transactionHash": tx_hash(n),
+                "transactionIndex": "0x0",
+                "logIndex": "0x0"
+            }]
+        }])
+    }
+
+    fn rpc_traces(n: u64) -> Value {
+        json!([{
+            "txHash": tx_hash(n),
+            "result": {
+                "type": "CALL",
+                "from": "0x01",
+                "to": "0x02",
+                "value": "0x0",
+                "gas": "0x5208",
+                "gasUsed": "0x5208",
+                "input": "0x",
+                "output": "0x",
+                "calls": [
+                    {
+                        "type": "STATICCALL",
+                        "from": "0x02",
+                        "to": "0x05",
+                        "gas": "0x100",
+                        "gasUsed": "0x10",
+                        "input": "0x",
+                        "output": "0x"
+                    },
+                    {
+                        "type": "CREATE2",
+                        "from": "0x02",
+                        "to": "0x06",
+                        "value": "0x0",
+                        "gas": "0x100",
+                        "input": "0x60",
+                        "error": "out of gas"
+                    }
+                ]
+            }
+        }])
+    }
+
+    fn block_number(param: &Value) -> Option<u64> {
+        let number = match param.as_str()? {
+            "finalized" => FINALIZED,
+            "latest" => HEAD,
+            n => super::parse_quantity(n).ok()?
+        };
+        Some(number).filter(|n| *n <= HEAD)
+    }
+
+    fn handle(call: &Value) -> Value {
+        let params = &call["params"];
+        let result = match call["method"].as_str().unwrap() {
+            "eth_blockNumber" => json!(format!("0x{:x}", HEAD)),
+            "eth_getBlockByNumber" => block_number(&params[0])
+                .map(|n| rpc_block(n, params[1].as_bool().unwrap()))
+                .unwrap_or(Value::Null),
+            "eth_getBlockReceipts" => block_number(&params[0]).map(rpc_receipts).unwrap_or(Value::Null),
+            "debug_traceBlockByNumber" => block_number(&params[0]).map(rpc_traces).unwrap_or(Value::Null),
+            method => {
+                return json!({
+                    "jsonrpc": "2.0",
+                    "id": call["id"],
+                    "error": {"code": -32601, "message": format!("method {} not found", method)}
+                })
+            }
+        };
+        json!({"jsonrpc": "2.0", "id": call["id"], "result": result})
+    }
+
+    async fn start_mock_node() -> String {
+        let app = Router::new().route(
+            "/",
+            post(|Json(batch): Json<Vec<Value>>| async move {
+                // answer in reverse order to make sure responses are matched by id
+                Json(batch.iter().rev().map(handle).collect::<Vec<_>>())
+            })
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        format!("http://{}/", addr)
+    }
+
+    async fn collect(client: &EvmRpcDataClient, req: BlockStreamRequest) -> (Vec<Block>, Option<BlockRef>) {
+        match client.stream(req).await.unwrap() {
+            BlockStreamResponse::Stream { blocks, finalized_head } => {
+                (blocks.try_collect().await.unwrap(), finalized_head)
+            }
+            BlockStreamResponse::Fork(_) => panic!("unexpected fork")
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_blocks_with_receipts_and_traces() {
+        let url = start_mock_node().await;
+        let client = EvmRpcDataClient::from_url(url).with_batch_size(4).with_traces(true);
+
+        let mut req = BlockStreamRequest::new(1);
+        req.set_parent_block_hash(Some(&hash(0)));
+        let (blocks, finalized_head) = collect(&client, req).await;
+
+        assert_eq!(
+            finalized_head,
+            Some(BlockRef {
+                number: FINALIZED,
+                hash: hash(FINALIZED)
+            })
+        );
+        assert_eq!(
+            blocks.iter().map(|b| b.header.number).collect::<Vec<_>>(),
+            vec![1, 2, 3, 4, 5]
+        );
+
+        let block = &blocks[1];
+        assert_eq!(block.header.parent_hash, hash(1));
+        assert_eq!(block.header.timestamp, 1_700_000_024);
+        assert_eq!(block.transactions[0].status, Some(1));
+        assert_eq!(block.transactions[0].r#type, Some(2));
+        assert_eq!(block.logs.as_ref().unwrap()[0].transaction_hash, tx_hash(2));
+
+        let traces = block.traces.as_ref().unwrap();
+        assert_eq!(
+            traces.iter().map(|t| t.trace_address.clone()).collect::<Vec<_>>(),
+            vec![vec![], vec![0], vec![1]]
+        );
+        assert_eq!(traces[0].subtraces, 2);
+        match &traces[1].op {
+            TraceOp::Call { action, .. } => assert_eq!(action.call_type, "staticcall"),
+            _ => panic!("expected a call trace")
+        }
+        match &traces[2].op {
+            TraceOp::Create { action, result } => {
+                assert_eq!(action.creation_method.as_deref(), Some("create2"));
+                assert!(result.is_none());
+            }
+            _ => panic!("expected a create trace")
+        }
+    }
+
+    #[tokio::test]
+    async fn reports_fork_when_parent_hash_differs() {
+        let url = start_mock_node().await;
+        let client = EvmRpcDataClient::from_url(url);
+
+        let mut req = BlockStreamRequest::new(3);
+        req.set_parent_block_hash(Some("0xbad"));
+
+        match client.stream(req).await.unwrap() {
+            BlockStreamResponse::Fork(prev_blocks) => assert_eq!(
+                prev_blocks,
+                (0..3)
+                    .map(|n| BlockRef {
+                        number: n,
+                        hash: hash(n)
+                    })
+                    .collect::<Vec<_>>()
+            ),
+            BlockStreamResponse::Stream { .. } => panic!("expected a fork")
+        }
+    }
+
+    #[tokio::test]
+    async fn request_above_head_gives_empty_stream() {
+        let url = start_mock_node().await;
+        let client = EvmRpcDataClient::from_url(url).with_finality_confirmations(Some(1));
+
+        let (blocks, finalized_head) = collect(&client, BlockStreamRequest::new(HEAD + 1)).await;
+
+        assert!(blocks.is_empty());
+        assert_eq!(
+            finalized_head,
+            Some(BlockRef {
+                number: HEAD - 1,
+                hash: hash(HEAD - 1)
+            })
+        );
+    }
+}
@@ -0,0 +1,279 @@
+use anyhow::{anyhow, ensure, Context};
+use sqd_data::evm::model::{
+    AccessListItem, Block, BlockHeader, EIP7702Authorization, Log, Trace, TraceActionCall, TraceActionCreate,
+    TraceActionSelfDestruct, TraceOp, TraceResultCall, TraceResultCreate, Transaction, Withdrawal
+};
+
+use super::types::{RpcBlock, RpcCallFrame, RpcReceipt, RpcTransaction, RpcTransactionTrace};
+
+/// Builds a block from the JSON-RPC responses.
+///
+/// Fails when receipts or traces were taken from a different block,
+/// which happens when the chain reorganizes while the block is being fetched.
+pub(crate) fn map_block(
+    block: RpcBlock,
+    receipts: Vec<RpcReceipt>,
+    traces: Option<Vec<RpcTransactionTrace>>
+) -> anyhow::Result<Block> {
+    ensure!(
+        receipts.len() == block.transactions.len(),
+        "block {} has {} transactions, but {} receipts",
+        block.number,
+        block.transactions.len(),
+        receipts.len()
+    );
+
+    let mut logs = Vec::new();
+    let mut transactions = Vec::with_capacity(block.transactions.len());
+    for (tx, receipt) in block.transactions.into_iter().zip(receipts) {
+        ensure!(
+            receipt.block_hash == block.hash && receipt.transaction_hash == tx.hash,
+            "receipt of transaction {} doesn't belong to block {}",
+            tx.hash,
+            block.hash
+        );
+        for log in receipt.logs.iter() {
+            logs.push(Log {
+                log_index: log.log_index.try_into()?,
+                transaction_index: log.transaction_index.try_into()?,
+                transaction_hash: log.transaction_hash.clone(),
+                address: log.address.clone(),
+                data: log.data.clone(),
+                topics: log.topics.clone()
+            })
+        }
+        transactions.push(map_transaction(tx, receipt)?);
+    }
+
+    let traces = traces
+        .map(|traces| {
+            ensure!(
+                traces.len() == transactions.len(),
+                "block {} has {} transactions, but {} traces",
+                block.number,
+                transactions.len(),
+                traces.len()
+            );
+            let mut flat = Vec::new();
+            for (tx, trace) in transactions.iter().zip(traces) {
+                if let Some(hash) = trace.tx_hash.as_ref() {
+                    ensure!(hash == &tx.hash, "trace of transaction {} is out of order", hash);
+                }
+                flatten_call_frame(tx.transaction_index, trace.result, Vec::new(), &mut flat)?;
+            }
+            Ok(flat)
+        })
+        .transpose()?;
+
+    let header = BlockHeader {
+        number: block.number,
+        hash: block.hash,
+        parent_hash: block.parent_hash,
+        timestamp: block.timestamp.try_into()?,
+        transactions_root: block.transactions_root,
+        receipts_root: block.receipts_root,
+        state_root: block.state_root,
+        logs_bloom: block.logs_bloom,
+        sha3_uncles: block.sha3_uncles,
+        extra_data: block.extra_data,
+        miner: block.miner,
+        nonce: block.nonce,
+        mix_hash: block.mix_hash,
+        size: block.size,
+        gas_limit: block.gas_limit,
+        gas_used: block.gas_used,
+        difficulty: block.difficulty,
+        total_difficulty: block.total_difficulty,
+        base_fee_per_gas: block.base_fee_per_gas,
+        uncles: block.uncles,
+        withdrawals: block.withdrawals.map(|withdrawals| {
+            withdrawals
+                .into_iter()
+                .map(|w| Withdrawal {
+                    address: w.address,
+                    amount: w.amount,
+                    index: w.index,
+                    validator_index: w.validator_index
+                })
+                .collect()
+        }),
+        withdrawals_root: block.withdrawals_root,
+        blob_gas_used: block.blob_gas_used,
+        excess_blob_gas: block.excess_blob_gas,
+        parent_beacon_block_root: block.parent_beacon_block_root,
+        requests_hash: block.requests_hash,
+        l1_block_number: block.l1_block_number,
+        main_block_general_gas_limit: None,
+        shared_gas_limit: None,
+        timestamp_millis_part: None
+    };
+
+    Ok(Block {
+        header,
+        transactions,
+        logs: Some(logs),
+        traces,
+        state_diffs: None
+    })
+}
+
+fn map_transaction(tx: RpcTransaction, receipt: RpcReceipt) -> anyhow::Result<Transaction> {
+    let y_parity = tx.y_parity.map(u8::try_from).transpose().context("invalid yParity")?;
+
+    let status = receipt
+        .status
+        .map(u8::try_from)
+        .transpose()
+        .context("invalid receipt status")?;
+
+    let l1_fee_scalar = receipt
+        .l1_fee_scalar
+        .as_deref()
+        .map(str::parse::<f64>)
+        .transpose()
+        .context("invalid l1FeeScalar")?;
+
+    let authorization_list = tx
+        .authorization_list
+        .map(|list| {
+            list.into_iter()
+                .map(|auth| {
+                    Ok(EIP7702Authorization {
+                        chain_id: auth.chain_id,
+                        address: auth.address,
+                        nonce: auth.nonce,
+                        y_parity: u8::try_from(auth.y_parity).context("invalid authorization yParity")?,
+                        r: auth.r,
+                        s: auth.s
+                    })
+                })
+                .collect::<anyhow::Result<Vec<_>>>()
+        })
+        .transpose()?;
+
+    Ok(Transaction {
+        transaction_index: tx.transaction_index.try_into()?,
+        hash: tx.hash,
+        nonce: tx.nonce,
+        from: tx.from,
+        to: tx.to,
+        input: tx.input,
+        value: tx.value,
+        r#type: tx.r#type,
+        gas: tx.gas,
+        gas_price: tx.gas_price,
+        max_fee_per_gas: tx.max_fee_per_gas,
+        max_priority_fee_per_gas: tx.max_priority_fee_per_gas,
+        v: tx.v,
+        r: tx.r,
+        s: tx.s,
+        y_parity,
+        access_list: tx.access_list.map(|list| {
+            list.into_iter()
+                .map(|item| AccessListItem {
+                    address: item.address,
+                    storage_keys: item.storage_keys
+                })
+                .collect()
+        }),
+        chain_id: tx.chain_id,
+        max_fee_per_blob_gas: tx.max_fee_per_blob_gas,
+        blob_versioned_hashes: tx.blob_versioned_hashes,
+        authorization_list,
+        calls: None,
+        nonce_key: None,
+        fee_token: None,
+        fee_payer_signature: None,
+        signature: None,
+        valid_before: None,
+        valid_after: None,
+        aa_authorization_list: None,
+        key_authorization: None,
+        contract_address: receipt.contract_address,
+        cumulative_gas_used: receipt.cumulative_gas_used,
+        effective_gas_price: receipt.effective_gas_price,
+        gas_used: receipt.gas_used,
+        logs_bloom: receipt.logs_bloom,
+        status,
+        blob_gas_used: receipt.blob_gas_used,
+        blob_gas_price: receipt.blob_gas_price,
+        l1_base_fee_scalar: receipt.l1_base_fee_scalar,
+        l1_blob_base_fee: receipt.l1_blob_base_fee,
+        l1_blob_base_fee_scalar: receipt.l1_blob_base_fee_scalar,
+        l1_fee: receipt.l1_fee,
+        l1_fee_scalar,
+        l1_gas_price: receipt.l1_gas_price,
+        l1_gas_used: receipt.l1_gas_used
+    })
+}
+
+/// Converts the nested `callTracer` output into flat traces in depth-first order
+fn flatten_call_frame(
+    transaction_index: u32,
+    frame: RpcCallFrame,
+    trace_address: Vec<u32>,
+    out: &mut Vec<Trace>
+) -> anyhow::Result<()> {
+    let succeeded = frame.error.is_none();
+    let gas = frame.gas.unwrap_or_else(|| "0x0".to_string());
+
+    let op = match frame.r#type.as_str() {
+        "CREATE" | "CREATE2" => TraceOp::Create {
+            action: TraceActionCreate {
+                from: frame.from,
+                value: frame.value,
+                gas,
+                init: frame.input.unwrap_or_else(|| "0x".to_string()),
+                creation_method: Some(frame.r#type.to_lowercase())
+            },
+            result: succeeded.then(|| TraceResultCreate {
+                gas_used: frame.gas_used.unwrap_or_else(|| "0x0".to_string()),
+                code: frame.output,
+                address: frame.to
+            })
+        },
+        "CALL" | "CALLCODE" | "DELEGATECALL" | "STATICCALL" => TraceOp::Call {
+            action: TraceActionCall {
+                from: frame.from,
+                to: frame
+                    .to
+                    .ok_or_else(|| anyhow!("{} frame has no recipient", frame.r#type))?,
+                value: frame.value,
+                gas,
+                input: frame.input.unwrap_or_else(|| "0x".to_string()),
+                call_type: frame.r#type.to_lowercase()
+            },
+            result: succeeded.then(|| TraceResultCall {
+                gas_used: frame.gas_used,
+                output: frame.output
+            })
+        },
+        "SELFDESTRUCT" => TraceOp::SelfDestruct {
+            action: TraceActionSelfDestruct {
+                address: Some(frame.from),
+                refund_address: frame
+                    .to
+                    .ok_or_else(|| anyhow!("SELFDESTRUCT frame has no refund address"))?,
+                balance: frame.value
+            }
+        },
+        other => return Err(anyhow!("unknown call frame type '{}'", other))
+    };
+
+    out.push(Trace {
+        transaction_index,
+        trace_address: trace_address.clone(),
+        subtraces: frame.calls.len().try_into()?,
+        error: frame.error,
+        revert_reason: frame.revert_reason,
+        op
+    });
+
+    for (idx, call) in frame.calls.into_iter().enumerate() {
+        let mut address = trace_address.clone();
+        address.push(idx.try_into()?);
+        flatten_call_frame(transaction_index, call, address, out)?;
+    }
+
+    Ok(())
+}
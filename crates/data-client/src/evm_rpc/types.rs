@@ -0,0 +1,194 @@
+use serde::{de::Error, Deserialize, Deserializer};
+
+/// Parses a JSON-RPC quantity, i.e. a `0x`-prefixed hex number
+pub(crate) fn parse_quantity(value: &str) -> anyhow::Result<u64> {
+    let digits = value
+        .strip_prefix("0x")
+        .ok_or_else(|| anyhow::anyhow!("quantity '{}' is not 0x-prefixed", value))?;
+    Ok(u64::from_str_radix(digits, 16)?)
+}
+
+fn quantity<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let value = String::deserialize(deserializer)?;
+    parse_quantity(&value).map_err(D::Error::custom)
+}
+
+fn quantity_option<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| parse_quantity(&value).map_err(D::Error::custom))
+        .transpose()
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcBlockRef {
+    #[serde(deserialize_with = "quantity")]
+    pub number: u64,
+    pub hash: String
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcWithdrawal {
+    pub index: String,
+    pub validator_index: String,
+    pub address: String,
+    pub amount: String
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcBlock {
+    #[serde(deserialize_with = "quantity")]
+    pub number: u64,
+    pub hash: String,
+    pub parent_hash: String,
+    #[serde(deserialize_with = "quantity")]
+    pub timestamp: u64,
+    pub transactions_root: String,
+    pub receipts_root: String,
+    pub state_root: String,
+    pub logs_bloom: String,
+    pub sha3_uncles: String,
+    pub extra_data: String,
+    pub miner: String,
+    pub nonce: Option<String>,
+    pub mix_hash: Option<String>,
+    #[serde(deserialize_with = "quantity")]
+    pub size: u64,
+    pub gas_limit: String,
+    pub gas_used: String,
+    pub difficulty: Option<String>,
+    pub total_difficulty: Option<String>,
+    pub base_fee_per_gas: Option<String>,
+    pub uncles: Option<Vec<String>>,
+    pub withdrawals: Option<Vec<RpcWithdrawal>>,
+    pub withdrawals_root: Option<String>,
+    pub blob_gas_used: Option<String>,
+    pub excess_blob_gas: Option<String>,
+    pub parent_beacon_block_root: Option<String>,
+    pub requests_hash: Option<String>,
+    #[serde(default, deserialize_with = "quantity_option")]
+    pub l1_block_number: Option<u64>,
+    pub transactions: Vec<RpcTransaction>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcAccessListItem {
+    pub address: String,
+    pub storage_keys: Vec<String>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcAuthorization {
+    pub chain_id: String,
+    pub address: String,
+    #[serde(deserialize_with = "quantity")]
+    pub nonce: u64,
+    #[serde(deserialize_with = "quantity")]
+    pub y_parity: u64,
+    pub r: String,
+    pub s: String
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcTransaction {
+    pub hash: String,
+    #[serde(deserialize_with = "quantity")]
+    pub transaction_index: u64,
+    #[serde(deserialize_with = "quantity")]
+    pub nonce: u64,
+    pub from: String,
+    pub to: Option<String>,
+    pub input: Option<String>,
+    pub value: Option<String>,
+    #[serde(rename = "type", default, deserialize_with = "quantity_option")]
+    pub r#type: Option<u64>,
+    pub gas: String,
+    pub gas_price: Option<String>,
+    pub max_fee_per_gas: Option<String>,
+    pub max_priority_fee_per_gas: Option<String>,
+    pub v: Option<String>,
+    pub r: Option<String>,
+    pub s: Option<String>,
+    #[serde(default, deserialize_with = "quantity_option")]
+    pub y_parity: Option<u64>,
+    pub access_list: Option<Vec<RpcAccessListItem>>,
+    #[serde(default, deserialize_with = "quantity_option")]
+    pub chain_id: Option<u64>,
+    pub max_fee_per_blob_gas: Option<String>,
+    pub blob_versioned_hashes: Option<Vec<String>>,
+    pub authorization_list: Option<Vec<RpcAuthorization>>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcLog {
+    pub address: String,
+    pub topics: Vec<String>,
+    pub data: String,
+    pub transaction_hash: String,
+    #[serde(deserialize_with = "quantity")]
+    pub transaction_index: u64,
+    #[serde(deserialize_with = "quantity")]
+    pub log_index: u64
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcReceipt {
+    pub transaction_hash: String,
+    #[serde(deserialize_with = "quantity")]
+    pub transaction_index: u64,
+    pub block_hash: String,
+    pub contract_address: Option<String>,
+    pub cumulative_gas_used: String,
+    pub effective_gas_price: Option<String>,
+    pub gas_used: String,
+    pub logs_bloom: String,
+    #[serde(default, deserialize_with = "quantity_option")]
+    pub status: Option<u64>,
+    pub blob_gas_used: Option<String>,
+    pub blob_gas_price: Option<String>,
+    pub logs: Vec<RpcLog>,
+    // L2 fee fields of OP stack chains
+    #[serde(default, deserialize_with = "quantity_option")]
+    pub l1_base_fee_scalar: Option<u64>,
+    pub l1_blob_base_fee: Option<String>,
+    #[serde(default, deserialize_with = "quantity_option")]
+    pub l1_blob_base_fee_scalar: Option<u64>,
+    pub l1_fee: Option<String>,
+    pub l1_fee_scalar: Option<String>,
+    pub l1_gas_price: Option<String>,
+    pub l1_gas_used: Option<String>
+}
+
+/// Frame of the `callTracer`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcCallFrame {
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub from: String,
+    pub to: Option<String>,
+    pub value: Option<String>,
+    pub gas: Option<String>,
+    pub gas_used: Option<String>,
+    pub input: Option<String>,
+    pub output: Option<String>,
+    pub error: Option<String>,
+    pub revert_reason: Option<String>,
+    #[serde(default)]
+    pub calls: Vec<RpcCallFrame>
+}
+
+/// Trace of a single transaction returned by `debug_traceBlockByNumber`
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcTransactionTrace {
+    pub tx_hash: Option<String>,
+    pub result: RpcCallFrame
+}
@@ -0,0 +1,152 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    sync::Arc
+};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use reqwest::{Client, IntoUrl, Url};
+use serde::{de::DeserializeOwned, Deserialize};
+use serde_json::{json, Value};
+
+use crate::reqwest::{http_error_kind, UnexpectedHttpStatus};
+
+/// Minimal JSON-RPC 2.0 client over HTTP
+#[derive(Clone)]
+pub struct JsonRpcClient {
+    http: Client,
+    url: Arc<Url>
+}
+
+impl Debug for JsonRpcClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("JsonRpcClient")
+            .field("url", &self.url.as_str())
+            .finish()
+    }
+}
+
+pub struct RpcCall {
+    pub method: &'static str,
+    pub params: Value
+}
+
+impl RpcCall {
+    pub fn new(method: &'static str, params: Value) -> Self {
+        Self { method, params }
+    }
+}
+
+#[derive(Deserialize)]
+struct RpcResponse {
+    id: u64,
+    result: Option<Value>,
+    error: Option<RpcError>
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String
+}
+
+impl Display for RpcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "rpc error {}: {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for RpcError {}
+
+impl JsonRpcClient {
+    pub fn new(http: Client, url: impl IntoUrl) -> Self {
+        Self {
+            http,
+            url: Arc::new(url.into_url().unwrap())
+        }
+    }
+
+    pub fn url(&self) -> &Url {
+        self.url.as_ref()
+    }
+
+    pub async fn call<T: DeserializeOwned>(&self, method: &'static str, params: Value) -> anyhow::Result<T> {
+        let mut results = self.batch(vec![RpcCall::new(method, params)]).await?;
+        let result = results.pop().unwrap();
+        serde_json::from_value(result).with_context(|| format!("failed to decode {} result", method))
+    }
+
+    /// Sends all calls in a single batch request and returns their results in the order of `calls`
+    pub async fn batch(&self, calls: Vec<RpcCall>) -> anyhow::Result<Vec<Value>> {
+        self.batch_results(&calls)
+            .await?
+            .into_iter()
+            .enumerate()
+            .map(|(idx, res)| res.map_err(|err| anyhow!(err).context(format!("{} call failed", calls[idx].method))))
+            .collect()
+    }
+
+    /// Like [`JsonRpcClient::batch`], but leaves it to the caller to handle errors of individual calls
+    pub async fn batch_results(&self, calls: &[RpcCall]) -> anyhow::Result<Vec<Result<Value, RpcError>>> {
+        if calls.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let body: Vec<Value> = calls
+            .iter()
+            .enumerate()
+            .map(|(id, call)| {
+                json!({
+                    "jsonrpc": "2.0",
+                    "id": id,
+                    "method": call.method,
+                    "params": call.params
+                })
+            })
+            .collect();
+
+        let res = self.http.post(self.url.as_ref().clone()).json(&body).send().await?;
+
+        if !res.status().is_success() {
+            let status = res.status();
+            let text = res.text().await.unwrap_or_default();
+            bail!(UnexpectedHttpStatus { status, text })
+        }
+
+        let mut responses: Vec<RpcResponse> = res.json().await.context("failed to decode rpc batch response")?;
+        ensure!(
+            responses.len() == calls.len(),
+            "expected {} responses in rpc batch, but got {}",
+            calls.len(),
+            responses.len()
+        );
+        responses.sort_by_key(|r| r.id);
+
+        responses
+            .into_iter()
+            .enumerate()
+            .map(|(idx, res)| {
+                ensure!(res.id == idx as u64, "rpc batch response has unexpected ids");
+                Ok(match res.error {
+                    Some(err) => Err(err),
+                    None => Ok(res.result.unwrap_or(Value::Null))
+                })
+            })
+            .collect()
+    }
+}
+
+/// Error class for the `ingest_source_errors` metric label
+pub(crate) fn rpc_error_kind(err: &anyhow::Error) -> &'static str {
+    if err.chain().any(|cause| cause.downcast_ref::<RpcError>().is_some()) {
+        "rpc"
+    } else {
+        http_error_kind(err)
+    }
+}
+
+/// Whether any error in the chain is an rpc error with one of the given codes
+pub(crate) fn has_rpc_error_code(err: &anyhow::Error, codes: &[i64]) -> bool {
+    err.chain()
+        .filter_map(|cause| cause.downcast_ref::<RpcError>())
+        .any(|rpc_error| codes.contains(&rpc_error.code))
+}
@@ -1,4 +1,8 @@
+pub mod evm_rpc;
+pub mod json_rpc;
+pub mod replay;
 pub mod reqwest;
+pub mod solana_rpc;
 mod types;
 
 pub use types::*;
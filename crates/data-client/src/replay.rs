@@ -0,0 +1,490 @@
+use std::{
+    fmt::{Debug, Formatter},
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Instant
+};
+
+use anyhow::{bail, ensure, Context};
+use bytes::Bytes;
+use futures::{future::BoxFuture, FutureExt, StreamExt};
+use serde::Deserialize;
+use sqd_primitives::{BlockNumber, BlockRef};
+
+use crate::{
+    types::{BlockStreamRequest, BlockStreamResponse},
+    DataClient
+};
+
+/// Number of preceding blocks reported back on a parent hash mismatch
+const FORK_HINTS: usize = 50;
+
+/// Data client, that replays newline-delimited JSON blocks recorded on disk.
+///
+/// Files ending with `.zst`/`.zstd` or `.gz` are decompressed on the fly.
+/// Recorded blocks must form a single chain, the files are ordered by their first block.
+///
+/// All recorded blocks are available at once, unless a moving head is simulated
+/// with [`ReplayDataClient::with_head_rate`].
+#[derive(Clone)]
+pub struct ReplayDataClient {
+    recording: Arc<Recording>,
+    head: HeadSimulation,
+    finality_lag: usize
+}
+
+#[derive(Clone)]
+struct HeadSimulation {
+    started_at: Instant,
+    /// Number of blocks visible at the start
+    initial: usize,
+    /// Blocks per second, `None` when all blocks are visible
+    rate: Option<f64>
+}
+
+struct Recording {
+    files: Vec<PathBuf>,
+    blocks: Vec<IndexedBlock>,
+    first_parent_hash: String
+}
+
+struct IndexedBlock {
+    number: BlockNumber,
+    hash: String,
+    file: usize,
+    line: usize
+}
+
+#[derive(Deserialize)]
+struct BlockLine {
+    header: BlockLineHeader
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BlockLineHeader {
+    #[serde(alias = "height")]
+    number: BlockNumber,
+    hash: String,
+    parent_hash: String
+}
+
+impl Debug for ReplayDataClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ReplayDataClient")
+            .field("files", &self.recording.files.len())
+            .field("blocks", &self.recording.blocks.len())
+            .field("head_rate", &self.head.rate)
+            .finish()
+    }
+}
+
+impl ReplayDataClient {
+    /// Indexes the given files and all files of the given directories
+    pub fn open<P: AsRef<Path>>(paths: impl IntoIterator<Item = P>) -> anyhow::Result<Self> {
+        let mut files = Vec::new();
+        for path in paths {
+            let path = path.as_ref();
+            if path.is_dir() {
+                let mut dir_files = Vec::new();
+                for entry in std::fs::read_dir(path).with_context(|| format!("failed to list {}", path.display()))? {
+                    let entry = entry?;
+                    let hidden = entry.file_name().to_string_lossy().starts_with('.');
+                    if entry.file_type()?.is_file() && !hidden {
+                        dir_files.push(entry.path());
+                    }
+                }
+                dir_files.sort();
+                files.extend(dir_files);
+            } else {
+                files.push(path.to_path_buf());
+            }
+        }
+        let recording = Recording::index(files)?;
+        let initial = recording.blocks.len();
+        Ok(Self {
+            recording: Arc::new(recording),
+            head: HeadSimulation {
+                started_at: Instant::now(),
+                initial,
+                rate: None
+            },
+            finality_lag: 0
+        })
+    }
+
+    /// Reveal recorded blocks at the given rate, starting with the blocks up to `initial_head`
+    pub fn with_head_rate(mut self, initial_head: BlockNumber, blocks_per_second: f64) -> Self {
+        assert!(blocks_per_second > 0.0, "head rate must be positive");
+        self.head = HeadSimulation {
+            started_at: Instant::now(),
+            initial: self.recording.blocks.partition_point(|b| b.number <= initial_head),
+            rate: Some(blocks_per_second)
+        };
+        self
+    }
+
+    /// Number of blocks between the visible head and the finalized one
+    pub fn with_finality_lag(mut self, blocks: usize) -> Self {
+        self.finality_lag = blocks;
+        self
+    }
+
+    /// Number of currently visible blocks
+    fn visible_blocks(&self) -> usize {
+        let len = self.recording.blocks.len();
+        match self.head.rate {
+            None => len,
+            Some(rate) => {
+                let revealed = (self.head.started_at.elapsed().as_secs_f64() * rate) as usize;
+                std::cmp::min(len, self.head.initial.saturating_add(revealed))
+            }
+        }
+    }
+
+    pub fn get_head(&self) -> Option<BlockRef> {
+        self.visible_blocks()
+            .checked_sub(1)
+            .map(|idx| self.recording.block_ref(idx))
+    }
+
+    pub fn get_finalized_head(&self) -> Option<BlockRef> {
+        self.visible_blocks()
+            .checked_sub(1 + self.finality_lag)
+            .map(|idx| self.recording.block_ref(idx))
+    }
+
+    /// Blocks are read on the blocking pool, so this must be called within a tokio runtime
+    pub fn stream(&self, req: BlockStreamRequest) -> anyhow::Result<BlockStreamResponse<Bytes>> {
+        let blocks = &self.recording.blocks;
+        let start = blocks.partition_point(|b| b.number < req.first_block);
+
+        // the parent can only be checked, when the recording has a block to continue with
+        if let Some(parent_hash) = req.parent_block_hash.as_ref().filter(|_| start < blocks.len()) {
+            let expected = match start.checked_sub(1) {
+                Some(idx) => &blocks[idx].hash,
+                None => &self.recording.first_parent_hash
+            };
+            if expected != parent_hash {
+                ensure!(
+                    start > 0,
+                    "parent hash {} of block {} doesn't match the first recorded block",
+                    parent_hash,
+                    req.first_block
+                );
+                let prev_blocks = (start.saturating_sub(FORK_HINTS)..start)
+                    .map(|idx| self.recording.block_ref(idx))
+                    .collect();
+                return Ok(BlockStreamResponse::Fork(prev_blocks));
+            }
+        }
+
+        let finalized_head = self.get_finalized_head();
+
+        if start >= self.visible_blocks() {
+            return Ok(BlockStreamResponse::Stream {
+                blocks: futures::stream::empty().boxed(),
+                finalized_head
+            });
+        }
+
+        let (sender, receiver) = tokio::sync::mpsc::channel(100);
+        let this = self.clone();
+        tokio::task::spawn_blocking(move || {
+            let result = this.recording.read_blocks(start, |idx, line| {
+                if idx >= this.visible_blocks() {
+                    return false;
+                }
+                sender.blocking_send(Ok(line)).is_ok()
+            });
+            if let Err(err) = result {
+                let _ = sender.blocking_send(Err(err));
+            }
+        });
+
+        let blocks = futures::stream::unfold(receiver, |mut receiver| async move {
+            receiver.recv().await.map(|item| (item, receiver))
+        });
+
+        Ok(BlockStreamResponse::Stream {
+            blocks: blocks.boxed(),
+            finalized_head
+        })
+    }
+}
+
+impl Recording {
+    fn index(files: Vec<PathBuf>) -> anyhow::Result<Self> {
+        ensure!(!files.is_empty(), "no files to replay");
+
+        let mut indexed_files = Vec::with_capacity(files.len());
+        for path in files {
+            let mut headers = Vec::new();
+            for_each_line(&path, |_, line| {
+                let block: BlockLine = serde_json::from_slice(&line)?;
+                headers.push(block.header);
+                Ok(true)
+            })
+            .with_context(|| format!("failed to index {}", path.display()))?;
+            if !headers.is_empty() {
+                indexed_files.push((path, headers));
+            }
+        }
+        ensure!(!indexed_files.is_empty(), "no blocks to replay");
+        indexed_files.sort_by_key(|(_, headers)| headers[0].number);
+
+        let first_parent_hash = indexed_files[0].1[0].parent_hash.clone();
+        let mut files = Vec::with_capacity(indexed_files.len());
+        let mut blocks: Vec<IndexedBlock> = Vec::new();
+
+        for (file, (path, headers)) in indexed_files.into_iter().enumerate() {
+            for (line, header) in headers.into_iter().enumerate() {
+                if let Some(prev) = blocks.last() {
+                    if header.number <= prev.number || header.parent_hash != prev.hash {
+                        bail!(
+                            "block {}#{} in {} doesn't extend the previous block {}#{}",
+                            header.number,
+                            header.hash,
+                            path.display(),
+                            prev.number,
+                            prev.hash
+                        )
+                    }
+                }
+                blocks.push(IndexedBlock {
+                    number: header.number,
+                    hash: header.hash,
+                    file,
+                    line
+                })
+            }
+            files.push(path);
+        }
+
+        Ok(Self {
+            files,
+            blocks,
+            first_parent_hash
+        })
+    }
+
+    fn block_ref(&self, idx: usize) -> BlockRef {
+        let block = &self.blocks[idx];
+        BlockRef {
+            number: block.number,
+            hash: block.hash.clone()
+        }
+    }
+
+    /// Passes recorded blocks starting from the `start`-th one to `cb`, until it returns `false`
+    fn read_blocks(&self, start: usize, mut cb: impl FnMut(usize, Bytes) -> bool) -> anyhow::Result<()> {
+        let mut idx = start;
+        while idx < self.blocks.len() {
+            let file = self.blocks[idx].file;
+            let first_line = self.blocks[idx].line;
+            let path = &self.files[file];
+            let mut stop = false;
+            for_each_line(path, |line_idx, line| {
+                if line_idx < first_line {
+                    return Ok(true);
+                }
+                if !cb(idx, line) {
+                    stop = true;
+                    return Ok(false);
+                }
+                idx += 1;
+                Ok(idx < self.blocks.len() && self.blocks[idx].file == file)
+            })
+            .with_context(|| format!("failed to read {}", path.display()))?;
+            if stop {
+                break;
+            }
+            ensure!(
+                idx == self.blocks.len() || self.blocks[idx].file != file,
+                "{} was truncated after indexing",
+                path.display()
+            );
+        }
+        Ok(())
+    }
+}
+
+fn open_file(path: &Path) -> anyhow::Result<Box<dyn Read>> {
+    let file = File::open(path)?;
+    let reader: Box<dyn Read> = match path.extension().and_then(|ext| ext.to_str()) {
+        Some("zst" | "zstd") => Box::new(zstd::stream::read::Decoder::new(file)?),
+        Some("gz") => Box::new(flate2::read::GzDecoder::new(file)),
+        _ => Box::new(file)
+    };
+    Ok(reader)
+}
+
+/// Calls `cb` with every non-empty line of the file, until it returns `false`
+fn for_each_line(path: &Path, mut cb: impl FnMut(usize, Bytes) -> anyhow::Result<bool>) -> anyhow::Result<()> {
+    let mut reader = BufReader::new(open_file(path)?);
+    let mut buf = Vec::new();
+    let mut line_idx = 0;
+    loop {
+        buf.clear();
+        if reader.read_until(b'\n', &mut buf)? == 0 {
+            return Ok(());
+        }
+        let line = buf.trim_ascii();
+        if line.is_empty() {
+            continue;
+        }
+        if !cb(line_idx, Bytes::copy_from_slice(line))
+            .with_context(|| format!("failed to process record {}", line_idx + 1))?
+        {
+            return Ok(());
+        }
+        line_idx += 1;
+    }
+}
+
+impl DataClient for ReplayDataClient {
+    type Block = Bytes;
+
+    fn stream(&self, req: BlockStreamRequest) -> BoxFuture<'static, anyhow::Result<BlockStreamResponse<Self::Block>>> {
+        let this = self.clone();
+        async move { this.stream(req) }.boxed()
+    }
+
+    fn get_finalized_head(&self) -> BoxFuture<'static, anyhow::Result<Option<BlockRef>>> {
+        futures::future::ready(Ok(self.get_finalized_head())).boxed()
+    }
+
+    fn is_retryable(&self, _err: &anyhow::Error) -> bool {
+        false
+    }
+
+    fn error_kind(&self, _err: &anyhow::Error) -> &'static str {
+        "io"
+    }
+
+    fn source_label(&self) -> String {
+        "replay".to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{io::Write, path::Path};
+
+    use futures::TryStreamExt;
+    use serde_json::json;
+    use sqd_primitives::BlockRef;
+
+    use super::ReplayDataClient;
+    use crate::{BlockStreamRequest, BlockStreamResponse};
+
+    fn hash(n: u64) -> String {
+        format!("0x{:064x}", n + 0x100)
+    }
+
+    fn block_lines(blocks: std::ops::Range<u64>) -> Vec<u8> {
+        let mut out = Vec::new();
+        for n in blocks {
+            let parent_hash = if n == 0 { format!("0x{:064x}", 0) } else { hash(n - 1) };
+            let block = json!({"header": {"number": n, "hash": hash(n), "parentHash": parent_hash}});
+            writeln!(out, "{}", block).unwrap();
+        }
+        out
+    }
+
+    /// Writes blocks 0..10 split into a plain, a gzip and a zstd file
+    fn write_recording(dir: &Path) {
+        std::fs::write(dir.join("a.jsonl"), block_lines(0..5)).unwrap();
+
+        let mut gz = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        gz.write_all(&block_lines(5..8)).unwrap();
+        std::fs::write(dir.join("b.jsonl.gz"), gz.finish().unwrap()).unwrap();
+
+        let zst = zstd::stream::encode_all(&block_lines(8..10)[..], 0).unwrap();
+        std::fs::write(dir.join("c.jsonl.zst"), zst).unwrap();
+    }
+
+    async fn collect_numbers(client: &ReplayDataClient, req: BlockStreamRequest) -> Vec<u64> {
+        match client.stream(req).unwrap() {
+            BlockStreamResponse::Stream { blocks, .. } => {
+                let lines: Vec<_> = blocks.try_collect().await.unwrap();
+                lines
+                    .iter()
+                    .map(|line| {
+                        let block: serde_json::Value = serde_json::from_slice(line).unwrap();
+                        block["header"]["number"].as_u64().unwrap()
+                    })
+                    .collect()
+            }
+            BlockStreamResponse::Fork(_) => panic!("unexpected fork")
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_from_requested_block_across_compressed_files() {
+        let dir = tempfile::tempdir().unwrap();
+        write_recording(dir.path());
+        let client = ReplayDataClient::open([dir.path()]).unwrap().with_finality_lag(2);
+
+        let mut req = BlockStreamRequest::new(3);
+        req.set_parent_block_hash(Some(&hash(2)));
+
+        assert_eq!(collect_numbers(&client, req).await, (3..10).collect::<Vec<_>>());
+        assert_eq!(
+            client.get_finalized_head(),
+            Some(BlockRef {
+                number: 7,
+                hash: hash(7)
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_fork_when_parent_hash_differs() {
+        let dir = tempfile::tempdir().unwrap();
+        write_recording(dir.path());
+        let client = ReplayDataClient::open([dir.path()]).unwrap();
+
+        let mut req = BlockStreamRequest::new(5);
+        req.set_parent_block_hash(Some("0xbad"));
+
+        match client.stream(req).unwrap() {
+            BlockStreamResponse::Fork(prev_blocks) => assert_eq!(
+                prev_blocks,
+                (0..5)
+                    .map(|n| BlockRef {
+                        number: n,
+                        hash: hash(n)
+                    })
+                    .collect::<Vec<_>>()
+            ),
+            BlockStreamResponse::Stream { .. } => panic!("expected a fork")
+        }
+    }
+
+    #[tokio::test]
+    async fn simulated_head_limits_visible_blocks() {
+        let dir = tempfile::tempdir().unwrap();
+        write_recording(dir.path());
+        let client = ReplayDataClient::open([dir.path()]).unwrap().with_head_rate(4, 0.001);
+
+        assert_eq!(client.get_head().map(|h| h.number), Some(4));
+        assert_eq!(
+            collect_numbers(&client, BlockStreamRequest::new(2)).await,
+            vec![2, 3, 4]
+        );
+        assert!(collect_numbers(&client, BlockStreamRequest::new(5)).await.is_empty());
+    }
+
+    #[test]
+    fn rejects_recording_with_gaps_in_the_chain() {
+        let dir = tempfile::tempdir().unwrap();
+        let mut lines = block_lines(0..3);
+        lines.extend(block_lines(4..6));
+        std::fs::write(dir.path().join("blocks.jsonl"), lines).unwrap();
+
+        assert!(ReplayDataClient::open([dir.path()]).is_err());
+    }
+}
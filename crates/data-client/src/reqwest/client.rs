@@ -129,47 +129,50 @@ impl ReqwestDataClient {
     }
 
     pub fn is_retryable(&self, err: &anyhow::Error) -> bool {
-        for cause in err.chain() {
-            if let Some(unexpected_status) = cause.downcast_ref::<UnexpectedHttpStatus>() {
-                return match unexpected_status.status.as_u16() {
-                    429 | 502 | 503 | 504 | 524 => true,
-                    _ => false
-                };
-            }
+        is_retryable_http_error(err)
+    }
 
-            if let Some(reqwest_error) = cause.downcast_ref::<reqwest::Error>() {
-                match reqwest_error.status().unwrap_or_default().as_u16() {
-                    429 | 502 | 503 | 504 | 524 => return true,
-                    _ => {}
-                }
-                if reqwest_error.is_timeout() {
-                    return true;
-                }
-                if reqwest_error.is_request()
-                    && reqwest_error.to_string() == "connection closed before message completed"
-                {
-                    return true;
-                }
-            }
+    pub fn url(&self) -> &Url {
+        self.url.as_ref()
+    }
+}
 
-            if let Some(io_error) = cause.downcast_ref::<std::io::Error>() {
-                match io_error.kind() {
-                    ErrorKind::ConnectionAborted => return true,
-                    ErrorKind::ConnectionRefused => return true,
-                    ErrorKind::ConnectionReset => return true,
-                    ErrorKind::HostUnreachable => return true,
-                    ErrorKind::NetworkUnreachable => return true,
-                    ErrorKind::TimedOut => return true,
-                    _ => {}
-                }
+/// Whether a failed HTTP exchange is worth repeating
+pub(crate) fn is_retryable_http_error(err: &anyhow::Error) -> bool {
+    for cause in err.chain() {
+        if let Some(unexpected_status) = cause.downcast_ref::<UnexpectedHttpStatus>() {
+            return match unexpected_status.status.as_u16() {
+                429 | 502 | 503 | 504 | 524 => true,
+                _ => false
+            };
+        }
+
+        if let Some(reqwest_error) = cause.downcast_ref::<reqwest::Error>() {
+            match reqwest_error.status().unwrap_or_default().as_u16() {
+                429 | 502 | 503 | 504 | 524 => return true,
+                _ => {}
+            }
+            if reqwest_error.is_timeout() {
+                return true;
+            }
+            if reqwest_error.is_request() && reqwest_error.to_string() == "connection closed before message completed" {
+                return true;
             }
         }
-        false
-    }
 
-    pub fn url(&self) -> &Url {
-        self.url.as_ref()
+        if let Some(io_error) = cause.downcast_ref::<std::io::Error>() {
+            match io_error.kind() {
+                ErrorKind::ConnectionAborted => return true,
+                ErrorKind::ConnectionRefused => return true,
+                ErrorKind::ConnectionReset => return true,
+                ErrorKind::HostUnreachable => return true,
+                ErrorKind::NetworkUnreachable => return true,
+                ErrorKind::TimedOut => return true,
+                _ => {}
+            }
+        }
     }
+    false
 }
 
 fn extract_finalized_head(res: &Response) -> anyhow::Result<Option<BlockRef>> {
@@ -228,43 +231,52 @@ impl DataClient for ReqwestDataClient {
     }
 
     fn error_kind(&self, err: &anyhow::Error) -> &'static str {
-        for cause in err.chain() {
-            if cause.downcast_ref::<UnexpectedHttpStatus>().is_some() {
+        http_error_kind(err)
+    }
+
+    fn source_label(&self) -> String {
+        url_source_label(&self.url)
+    }
+}
+
+/// Error class of a failed HTTP exchange for the `ingest_source_errors` metric label
+pub(crate) fn http_error_kind(err: &anyhow::Error) -> &'static str {
+    for cause in err.chain() {
+        if cause.downcast_ref::<UnexpectedHttpStatus>().is_some() {
+            return "http";
+        }
+        if let Some(reqwest_error) = cause.downcast_ref::<reqwest::Error>() {
+            if reqwest_error.is_timeout() {
+                return "timeout";
+            }
+            if reqwest_error.is_connect() {
+                return "connect";
+            }
+            if reqwest_error.is_status() {
                 return "http";
             }
-            if let Some(reqwest_error) = cause.downcast_ref::<reqwest::Error>() {
-                if reqwest_error.is_timeout() {
-                    return "timeout";
-                }
-                if reqwest_error.is_connect() {
-                    return "connect";
-                }
-                if reqwest_error.is_status() {
-                    return "http";
-                }
-                if reqwest_error.is_body() || reqwest_error.is_decode() {
-                    return "decode";
-                }
-                if reqwest_error.is_request() {
-                    return "request";
-                }
+            if reqwest_error.is_body() || reqwest_error.is_decode() {
+                return "decode";
             }
-            if cause.downcast_ref::<std::io::Error>().is_some() {
-                return "io";
+            if reqwest_error.is_request() {
+                return "request";
             }
         }
-        "other"
+        if cause.downcast_ref::<std::io::Error>().is_some() {
+            return "io";
+        }
     }
+    "other"
+}
 
-    // Host alone collapses endpoints that differ only by port or dataset path. Built by hand
-    // rather than from `Url::authority()`, which carries userinfo straight into the label.
-    fn source_label(&self) -> String {
-        let host = self.url.host_str().unwrap_or("unknown");
-        let path = self.url.path().trim_end_matches('/');
-        match self.url.port() {
-            Some(port) => format!("{host}:{port}{path}"),
-            None => format!("{host}{path}")
-        }
+// Host alone collapses endpoints that differ only by port or dataset path. Built by hand
+// rather than from `Url::authority()`, which carries userinfo straight into the label.
+pub(crate) fn url_source_label(url: &Url) -> String {
+    let host = url.host_str().unwrap_or("unknown");
+    let path = url.path().trim_end_matches('/');
+    match url.port() {
+        Some(port) => format!("{host}:{port}{path}"),
+        None => format!("{host}{path}")
     }
 }
 
@@ -0,0 +1,592 @@
+use std::{
+    collections::VecDeque,
+    fmt::{Debug, Formatter}
+};
+
+use anyhow::{ensure, Context};
+use futures::{future::BoxFuture, FutureExt, StreamExt, TryStreamExt};
+use reqwest::{Client, IntoUrl};
+use serde_json::{json, Value};
+use sqd_data::solana::model::Block;
+use sqd_primitives::{BlockNumber, BlockRef};
+
+use super::{mapping::map_block, types::RpcBlock};
+use crate::{
+    json_rpc::{has_rpc_error_code, rpc_error_kind, JsonRpcClient, RpcCall},
+    reqwest::{default_http_client, is_retryable_http_error, url_source_label},
+    types::{BlockStreamRequest, BlockStreamResponse},
+    DataClient
+};
+
+const DEFAULT_BATCH_SIZE: usize = 5;
+
+/// Max number of slots served by a single stream
+const MAX_STREAM_SLOTS: u64 = 500;
+
+/// Number of slots preceding the requested one, that are reported back on a parent hash mismatch
+const FORK_HINT_SLOTS: u64 = 64;
+
+/// The slot was skipped or its block is missing in the node's ledger
+const SLOT_SKIPPED: &[i64] = &[-32007, -32009];
+
+/// The block is not yet available or confirmed
+const BLOCK_NOT_AVAILABLE: &[i64] = &[-32004, -32014];
+
+/// The node is behind or rate limited
+const NODE_UNAVAILABLE: &[i64] = &[-32005, 429];
+
+/// Data client for a Solana JSON-RPC node.
+///
+/// Confirmed blocks are listed with `getBlocks` and fetched in batches of `getBlock`.
+/// Block numbers are slots, so skipped slots appear as gaps between a block and its parent.
+#[derive(Clone)]
+pub struct SolanaRpcDataClient {
+    rpc: JsonRpcClient,
+    batch_size: usize
+}
+
+impl Debug for SolanaRpcDataClient {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolanaRpcDataClient")
+            .field("url", &self.rpc.url().as_str())
+            .field("batch_size", &self.batch_size)
+            .finish()
+    }
+}
+
+impl SolanaRpcDataClient {
+    pub fn from_url(url: impl IntoUrl) -> Self {
+        let http = default_http_client();
+        Self::new(http, url)
+    }
+
+    pub fn new(http: Client, url: impl IntoUrl) -> Self {
+        Self {
+            rpc: JsonRpcClient::new(http, url),
+            batch_size: DEFAULT_BATCH_SIZE
+        }
+    }
+
+    /// Number of blocks requested in a single JSON-RPC batch
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        assert!(batch_size > 0, "batch size must be positive");
+        self.batch_size = batch_size;
+        self
+    }
+
+    pub async fn stream(&self, req: BlockStreamRequest) -> anyhow::Result<BlockStreamResponse<Block>> {
+        let head = self.get_head().await?;
+        let finalized_head = self.get_finalized_head().await?;
+
+        if req.first_block > head {
+            return Ok(BlockStreamResponse::Stream {
+                blocks: futures::stream::empty().boxed(),
+                finalized_head
+            });
+        }
+
+        let last_slot = std::cmp::min(head, req.first_block + MAX_STREAM_SLOTS - 1);
+        let slots = self.get_block_slots(req.first_block, last_slot, "confirmed").await?;
+
+        let mut state = StreamState {
+            client: self.clone(),
+            slots: slots.into(),
+            parent_hash: req.parent_block_hash.clone(),
+            finished: false
+        };
+
+        let first_batch = state.fetch_batch().await?;
+        if let (Some(parent_hash), Some(first)) = (req.parent_block_hash.as_ref(), first_batch.first()) {
+            if &first.header.parent_hash != parent_hash {
+                let prev_blocks = self.get_fork_hints(req.first_block).await?;
+                return Ok(BlockStreamResponse::Fork(prev_blocks));
+            }
+        }
+        let first_batch = state.link(first_batch);
+
+        let rest = futures::stream::try_unfold(state, |mut state| async move {
+            let blocks = state.next_batch().await?;
+            Ok::<_, anyhow::Error>(if blocks.is_empty() {
+                None
+            } else {
+                Some((
+                    futures::stream::iter(blocks.into_iter().map(Ok::<_, anyhow::Error>)),
+                    state
+                ))
+            })
+        })
+        .try_flatten();
+
+        let blocks = futures::stream::iter(first_batch.into_iter().map(Ok)).chain(rest);
+
+        Ok(BlockStreamResponse::Stream {
+            blocks: blocks.boxed(),
+            finalized_head
+        })
+    }
+
+    pub async fn get_head(&self) -> anyhow::Result<BlockNumber> {
+        self.rpc.call("getSlot", json!([{"commitment": "confirmed"}])).await
+    }
+
+    pub async fn get_finalized_head(&self) -> anyhow::Result<Option<BlockRef>> {
+        let slot: BlockNumber = self.rpc.call("getSlot", json!([{"commitment": "finalized"}])).await?;
+        let block: Option<RpcBlock> = self
+            .rpc
+            .call("getBlock", json!([slot, header_request_config("finalized")]))
+            .await?;
+        Ok(block.map(|block| BlockRef {
+            number: slot,
+            hash: block.blockhash
+        }))
+    }
+
+    async fn get_block_slots(
+        &self,
+        first_slot: BlockNumber,
+        last_slot: BlockNumber,
+        commitment: &str
+    ) -> anyhow::Result<Vec<BlockNumber>> {
+        self.rpc
+            .call("getBlocks", json!([first_slot, last_slot, {"commitment": commitment}]))
+            .await
+    }
+
+    /// Canonical blocks preceding `first_block` in ascending order
+    async fn get_fork_hints(&self, first_block: BlockNumber) -> anyhow::Result<Vec<BlockRef>> {
+        ensure!(first_block > 0, "got a parent hash mismatch at the genesis block");
+
+        let slots = self
+            .get_block_slots(
+                first_block.saturating_sub(FORK_HINT_SLOTS),
+                first_block - 1,
+                "confirmed"
+            )
+            .await?;
+
+        let calls: Vec<_> = slots
+            .iter()
+            .map(|slot| RpcCall::new("getBlock", json!([slot, header_request_config("confirmed")])))
+            .collect();
+
+        let mut prev_blocks = Vec::new();
+        for (slot, result) in slots.iter().zip(self.rpc.batch_results(&calls).await?) {
+            match result {
+                Ok(value) => {
+                    if let Some(block) = decode::<Option<RpcBlock>>(value)? {
+                        prev_blocks.push(BlockRef {
+                            number: *slot,
+                            hash: block.blockhash
+                        })
+                    }
+                }
+                Err(err) if SLOT_SKIPPED.contains(&err.code) => {}
+                Err(err) => return Err(anyhow::Error::new(err).context("getBlock call failed"))
+            }
+        }
+        ensure!(!prev_blocks.is_empty(), "got an empty list of prev blocks");
+        Ok(prev_blocks)
+    }
+
+    pub fn is_retryable(&self, err: &anyhow::Error) -> bool {
+        is_retryable_http_error(err)
+            || has_rpc_error_code(err, BLOCK_NOT_AVAILABLE)
+            || has_rpc_error_code(err, NODE_UNAVAILABLE)
+    }
+}
+
+struct StreamState {
+    client: SolanaRpcDataClient,
+    slots: VecDeque<BlockNumber>,
+    parent_hash: Option<String>,
+    finished: bool
+}
+
+impl StreamState {
+    async fn next_batch(&mut self) -> anyhow::Result<Vec<Block>> {
+        while !self.finished && !self.slots.is_empty() {
+            let blocks = self.fetch_batch().await?;
+            let blocks = self.link(blocks);
+            if !blocks.is_empty() {
+                return Ok(blocks);
+            }
+        }
+        Ok(Vec::new())
+    }
+
+    /// Fetches blocks of the next listed slots.
+    ///
+    /// Skipped slots are passed over, while the first block, that is not available yet,
+    /// ends the stream.
+    async fn fetch_batch(&mut self) -> anyhow::Result<Vec<Block>> {
+        let len = std::cmp::min(self.client.batch_size, self.slots.len());
+        let slots: Vec<BlockNumber> = self.slots.drain(..len).collect();
+
+        let calls: Vec<_> = slots
+            .iter()
+            .map(|slot| RpcCall::new("getBlock", json!([slot, block_request_config()])))
+            .collect();
+
+        let results = self.client.rpc.batch_results(&calls).await?;
+        let mut blocks = Vec::with_capacity(slots.len());
+
+        for (slot, result) in slots.into_iter().zip(results) {
+            let block = match result {
+                Ok(value) => decode::<Option<RpcBlock>>(value)?,
+                Err(err) if SLOT_SKIPPED.contains(&err.code) => continue,
+                Err(err) if BLOCK_NOT_AVAILABLE.contains(&err.code) => None,
+                Err(err) => {
+                    return Err(anyhow::Error::new(err).context(format!("getBlock call for slot {} failed", slot)))
+                }
+            };
+            let Some(block) = block else {
+                self.finished = true;
+                break;
+            };
+            blocks.push(map_block(slot, block).with_context(|| format!("failed to map block {}", slot))?);
+        }
+
+        Ok(blocks)
+    }
+
+    /// Keeps the blocks, that extend the chain received so far.
+    ///
+    /// A parent hash mismatch ends the stream, the next stream request will report the fork.
+    fn link(&mut self, mut blocks: Vec<Block>) -> Vec<Block> {
+        for (idx, block) in blocks.iter().enumerate() {
+            if self
+                .parent_hash
+                .as_ref()
+                .is_some_and(|hash| hash != &block.header.parent_hash)
+            {
+                self.finished = true;
+                blocks.truncate(idx);
+                break;
+            }
+            self.parent_hash = Some(block.header.hash.clone());
+        }
+        blocks
+    }
+}
+
+fn block_request_config() -> Value {
+    json!({
+        "commitment": "confirmed",
+        "encoding": "json",
+        "transactionDetails": "full",
+        "rewards": true,
+        "maxSupportedTransactionVersion": 0
+    })
+}
+
+fn header_request_config(commitment: &str) -> Value {
+    json!({
+        "commitment": commitment,
+        "transactionDetails": "none",
+        "rewards": false,
+        "maxSupportedTransactionVersion": 0
+    })
+}
+
+fn decode<T: serde::de::DeserializeOwned>(value: Value) -> anyhow::Result<T> {
+    serde_json::from_value(value).context("failed to decode getBlock result")
+}
+
+impl DataClient for SolanaRpcDataClient {
+    type Block = Block;
+
+    fn stream(&self, req: BlockStreamRequest) -> BoxFuture<'static, anyhow::Result<BlockStreamResponse<Self::Block>>> {
+        let this = self.clone();
+        async move { this.stream(req).await }.boxed()
+    }
+
+    fn get_finalized_head(&self) -> BoxFuture<'static, anyhow::Result<Option<BlockRef>>> {
+        let this = self.clone();
+        async move { this.get_finalized_head().await }.boxed()
+    }
+
+    fn is_retryable(&self, err: &anyhow::Error) -> bool {
+        self.is_retryable(err)
+    }
+
+    fn error_kind(&self, err: &anyhow::Error) -> &'static str {
+        rpc_error_kind(err)
+    }
+
+    fn source_label(&self) -> String {
+        url_source_label(self.rpc.url())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::BTreeMap, path::Path, sync::Arc};
+
+    use axum::{extract::State, routing::post, Json, Router};
+    use futures::TryStreamExt;
+    use serde_json::{json, Value};
+    use sqd_data::solana::model::{Block, TransactionVersion};
+    use sqd_primitives::BlockRef;
+
+    use super::SolanaRpcDataClient;
+    use crate::{BlockStreamRequest, BlockStreamResponse};
+
+    const HEAD: u64 = 103;
+    const FINALIZED: u64 = 100;
+
+    type Fixtures = Arc<BTreeMap<u64, Value>>;
+
+    /// `getBlock` results stored as `fixtures/solana-blocks/<slot>.json`
+    fn load_fixtures() -> Fixtures {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .join("fixtures")
+            .join("solana-blocks");
+        let mut blocks = BTreeMap::new();
+        for entry in std::fs::read_dir(dir).unwrap() {
+            let path = entry.unwrap().path();
+            let slot = path.file_stem().unwrap().to_str().unwrap().parse().unwrap();
+            let block = serde_json::from_slice(&std::fs::read(&path).unwrap()).unwrap();
+            blocks.insert(slot, block);
+        }
+        Arc::new(blocks)
+    }
+
+    fn get_block(fixtures: &Fixtures, params: &Value) -> Result<Value, Value> {
+        let slot = params[0].as_u64().unwrap();
+        let config = &params[1];
+        match fixtures.get(&slot) {
+            Some(block) => {
+                let mut block = block.clone();
+                let block_obj = block.as_object_mut().unwrap();
+                if config["transactionDetails"] == "none" {
+                    block_obj.remove("transactions");
+                }
+                if config["rewards"] == false {
+                    block_obj.remove("rewards");
+                }
+                Ok(block)
+            }
+            None if slot <= HEAD => Err(json!({
+                "code": -32007,
+                "message": format!("Slot {} was skipped, or missing due to ledger jump to recent snapshot", slot)
+            })),
+            None => Err(json!({
+                "code": -32004,
+                "message": format!("Block not available for slot {}", slot)
+            }))
+        }
+    }
+
+    fn handle(fixtures: &Fixtures, call: &Value) -> Value {
+        let params = &call["params"];
+        let result = match call["method"].as_str().unwrap() {
+            "getSlot" if params[0]["commitment"] == "finalized" => Ok(json!(FINALIZED)),
+            "getSlot" => Ok(json!(HEAD)),
+            "getBlocks" => {
+                let first = params[0].as_u64().unwrap();
+                let last = params[1].as_u64().unwrap();
+                Ok(json!(fixtures
+                    .range(first..=last)
+                    .map(|(slot, _)| *slot)
+                    .collect::<Vec<_>>()))
+            }
+            "getBlock" => get_block(fixtures, params),
+            method => Err(json!({"code": -32601, "message": format!("method {} not found", method)}))
+        };
+        match result {
+            Ok(result) => json!({"jsonrpc": "2.0", "id": call["id"], "result": result}),
+            Err(error) => json!({"jsonrpc": "2.0", "id": call["id"], "error": error})
+        }
+    }
+
+    async fn start_mock_node() -> (String, Fixtures) {
+        let fixtures = load_fixtures();
+        let app = Router::new()
+            .route(
+                "/",
+                post(
+                    |State(fixtures): State<Fixtures>, Json(batch): Json<Vec<Value>>| async move {
+                        Json(batch.iter().map(|call| handle(&fixtures, call)).collect::<Vec<_>>())
+                    }
+                )
+            )
+            .with_state(fixtures.clone());
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+        (format!("http://{}/", addr), fixtures)
+    }
+
+    fn blockhash(fixtures: &Fixtures, slot: u64) -> String {
+        fixtures[&slot]["blockhash"].as_str().unwrap().to_string()
+    }
+
+    async fn collect(client: &SolanaRpcDataClient, req: BlockStreamRequest) -> (Vec<Block>, Option<BlockRef>) {
+        match client.stream(req).await.unwrap() {
+            BlockStreamResponse::Stream { blocks, finalized_head } => {
+                (blocks.try_collect().await.unwrap(), finalized_head)
+            }
+            BlockStreamResponse::Fork(_) => panic!("unexpected fork")
+        }
+    }
+
+    #[tokio::test]
+    async fn streams_blocks_over_skipped_slots() {
+        let (url, fixtures) = start_mock_node().await;
+        let client = SolanaRpcDataClient::from_url(url).with_batch_size(2);
+
+        let (blocks, finalized_head) = collect(&client, BlockStreamRequest::new(100)).await;
+
+        assert_eq!(
+            finalized_head,
+            Some(BlockRef {
+                number: FINALIZED,
+                hash: blockhash(&fixtures, FINALIZED)
+            })
+        );
+        assert_eq!(
+            blocks
+                .iter()
+                .map(|b| (b.header.number, b.header.parent_number))
+                .collect::<Vec<_>>(),
+            vec![(100, 99), (102, 100), (103, 102)]
+        );
+        assert_eq!(blocks[1].header.parent_hash, blockhash(&fixtures, 100));
+    }
+
+    #[tokio::test]
+    async fn maps_instructions_logs_and_balances() {
+        let (url, _fixtures) = start_mock_node().await;
+        let client = SolanaRpcDataClient::from_url(url);
+
+        let (blocks, _) = collect(&client, BlockStreamRequest::new(100)).await;
+        let block = &blocks[0];
+
+        assert_eq!(block.header.height, 90);
+        assert_eq!(block.rewards.len(), 1);
+        assert!(matches!(block.transactions[0].version, TransactionVersion::Legacy));
+
+        assert_eq!(
+            block
+                .instructions
+                .iter()
+                .map(|i| i.instruction_address.clone())
+                .collect::<Vec<_>>(),
+            vec![vec![0], vec![1], vec![1, 0], vec![1, 0, 0], vec![1, 1]]
+        );
+        assert_eq!(
+            block
+                .instructions
+                .iter()
+                .map(|i| i.compute_units_consumed)
+                .collect::<Vec<_>>(),
+            vec![None, Some(20000), Some(4645), None, Some(4736)]
+        );
+        assert_eq!(
+            block.get_account(block.instructions[2].program_id).unwrap(),
+            "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA"
+        );
+        assert!(block.instructions.iter().all(|i| i.is_committed));
+
+        assert_eq!(
+            block
+                .logs
+                .iter()
+                .map(|l| (
+                    l.log_index,
+                    l.instruction_address.clone(),
+                    l.kind.to_str(),
+                    l.message.as_str()
+                ))
+                .collect::<Vec<_>>(),
+            vec![
+                (3, vec![1], "log", "Instruction: Swap"),
+                (5, vec![1, 0], "log", "Instruction: Transfer"),
+                (11, vec![1, 1], "log", "Instruction: Transfer"),
+                (14, vec![1], "data", "AQID")
+            ]
+        );
+
+        assert_eq!(block.balances.len(), 1);
+        assert_eq!(block.balances[0].pre - block.balances[0].post, 5000);
+
+        assert_eq!(
+            block
+                .token_balances
+                .iter()
+                .map(|b| (b.pre_amount, b.post_amount, b.post_decimals))
+                .collect::<Vec<_>>(),
+            vec![(Some(1000), Some(900), Some(6)), (Some(0), Some(100), Some(6))]
+        );
+    }
+
+    #[tokio::test]
+    async fn maps_failed_and_truncated_transactions() {
+        let (url, _fixtures) = start_mock_node().await;
+        let client = SolanaRpcDataClient::from_url(url);
+
+        let (blocks, _) = collect(&client, BlockStreamRequest::new(102)).await;
+        let block = &blocks[0];
+
+        let failed = &block.transactions[0];
+        assert!(matches!(failed.version, TransactionVersion::Other(0)));
+        assert!(failed.err.is_some());
+        assert_eq!(
+            failed
+                .loaded_addresses
+                .writable
+                .iter()
+                .chain(failed.loaded_addresses.readonly.iter())
+                .map(|a| block.get_account(*a).unwrap())
+                .collect::<Vec<_>>(),
+            vec![
+                "8BnEgHoWFysVcuFFX7QztDmzuH8r5ZFvyP3sYwn1XTh6",
+                "SysvarC1ock11111111111111111111111111111111"
+            ]
+        );
+        assert_eq!(block.instructions[0].accounts.len(), 3);
+        assert_eq!(block.instructions[0].error, Some(json!("custom program error: 0x1")));
+        assert!(!block.instructions[0].is_committed);
+
+        let truncated = &block.transactions[1];
+        assert!(truncated.has_dropped_log_messages);
+        assert_eq!(
+            block.instructions[1..]
+                .iter()
+                .map(|i| i.has_dropped_log_messages)
+                .collect::<Vec<_>>(),
+            vec![false, true]
+        );
+    }
+
+    #[tokio::test]
+    async fn reports_fork_when_parent_hash_differs() {
+        let (url, fixtures) = start_mock_node().await;
+        let client = SolanaRpcDataClient::from_url(url);
+
+        let mut req = BlockStreamRequest::new(101);
+        req.set_parent_block_hash(Some("11111111111111111111111111111111"));
+
+        match client.stream(req).await.unwrap() {
+            BlockStreamResponse::Fork(prev_blocks) => assert_eq!(
+                prev_blocks,
+                vec![BlockRef {
+                    number: 100,
+                    hash: blockhash(&fixtures, 100)
+                }]
+            ),
+            BlockStreamResponse::Stream { .. } => panic!("expected a fork")
+        }
+    }
+
+    #[tokio::test]
+    async fn request_above_head_gives_empty_stream() {
+        let (url, _fixtures) = start_mock_node().await;
+        let client = SolanaRpcDataClient::from_url(url);
+
+        let (blocks, finalized_head) = collect(&client, BlockStreamRequest::new(HEAD + 1)).await;
+
+        assert!(blocks.is_empty());
+        assert_eq!(finalized_head.map(|h| h.number), Some(FINALIZED));
+    }
+}
@@ -0,0 +1,386 @@
+use std::collections::{BTreeMap, HashMap};
+
+use anyhow::{anyhow, bail, ensure, Context};
+use serde_json::Value;
+use sqd_data::solana::model::{
+    AccountIndex, AddressTableLookup, Balance, Block, BlockHeader, Instruction, LoadedAddresses, LogMessage,
+    LogMessageKind, Reward, TokenBalance, Transaction, TransactionVersion
+};
+use sqd_primitives::{BlockNumber, ItemIndex};
+
+use super::types::{RpcBlock, RpcInstruction, RpcTokenBalance, RpcTransactionWithMeta};
+
+/// Block-wide table of accounts, which all account references point into
+#[derive(Default)]
+struct Accounts {
+    index: HashMap<String, AccountIndex>,
+    list: Vec<String>
+}
+
+impl Accounts {
+    fn add(&mut self, account: &str) -> AccountIndex {
+        if let Some(idx) = self.index.get(account) {
+            return *idx;
+        }
+        let idx = self.list.len() as AccountIndex;
+        self.index.insert(account.to_string(), idx);
+        self.list.push(account.to_string());
+        idx
+    }
+}
+
+struct BlockBuilder {
+    accounts: Accounts,
+    transactions: Vec<Transaction>,
+    instructions: Vec<Instruction>,
+    logs: Vec<LogMessage>,
+    balances: Vec<Balance>,
+    token_balances: Vec<TokenBalance>
+}
+
+/// Converts the `getBlock` result of the given slot.
+///
+/// Only balances and token balances, that were changed by a transaction, are kept.
+pub(crate) fn map_block(slot: BlockNumber, block: RpcBlock) -> anyhow::Result<Block> {
+    let mut builder = BlockBuilder {
+        accounts: Accounts::default(),
+        transactions: Vec::with_capacity(block.transactions.len()),
+        instructions: Vec::new(),
+        logs: Vec::new(),
+        balances: Vec::new(),
+        token_balances: Vec::new()
+    };
+
+    for (idx, tx) in block.transactions.into_iter().enumerate() {
+        let signature = tx.transaction.signatures.first().cloned().unwrap_or_default();
+        builder
+            .push_transaction(idx as ItemIndex, tx)
+            .with_context(|| format!("failed to map transaction {}", signature))?;
+    }
+
+    let rewards = block
+        .rewards
+        .into_iter()
+        .map(|reward| Reward {
+            pubkey: builder.accounts.add(&reward.pubkey),
+            lamports: reward.lamports,
+            post_balance: reward.post_balance,
+            reward_type: reward.reward_type,
+            commission: reward.commission
+        })
+        .collect();
+
+    let header = BlockHeader {
+        number: slot,
+        hash: block.blockhash,
+        parent_number: block.parent_slot,
+        parent_hash: block.previous_blockhash,
+        height: block
+            .block_height
+            .ok_or_else(|| anyhow!("block height of slot {} is not known", slot))?,
+        timestamp: block.block_time
+    };
+
+    Ok(Block {
+        header,
+        transactions: builder.transactions,
+        instructions: builder.instructions,
+        logs: builder.logs,
+        balances: builder.balances,
+        token_balances: builder.token_balances,
+        rewards,
+        accounts: builder.accounts.list
+    })
+}
+
+impl BlockBuilder {
+    fn push_transaction(&mut self, transaction_index: ItemIndex, tx: RpcTransactionWithMeta) -> anyhow::Result<()> {
+        let meta = tx.meta.ok_or_else(|| anyhow!("transaction status meta is missing"))?;
+        let message = tx.transaction.message;
+
+        let version = match tx.version {
+            None => TransactionVersion::Legacy,
+            Some(Value::String(s)) if s == "legacy" => TransactionVersion::Legacy,
+            Some(Value::Number(n)) => TransactionVersion::Other(
+                n.as_u64()
+                    .and_then(|v| u8::try_from(v).ok())
+                    .ok_or_else(|| anyhow!("invalid transaction version {}", n))?
+            ),
+            Some(other) => bail!("invalid transaction version {}", other)
+        };
+
+        let (loaded_writable, loaded_readonly) = meta
+            .loaded_addresses
+            .map(|loaded| (loaded.writable, loaded.readonly))
+            .unwrap_or_default();
+
+        // instructions and balances refer to static keys followed by the ones loaded from lookup tables
+        let keys: Vec<AccountIndex> = message
+            .account_keys
+            .iter()
+            .chain(loaded_writable.iter())
+            .chain(loaded_readonly.iter())
+            .map(|key| self.accounts.add(key))
+            .collect();
+
+        let account = |idx: u32| -> anyhow::Result<AccountIndex> {
+            keys.get(idx as usize)
+                .copied()
+                .ok_or_else(|| anyhow!("account index {} is out of range", idx))
+        };
+
+        let is_committed = meta.err.is_none();
+        let first_instruction = self.instructions.len();
+        let mut inner_instructions: HashMap<u32, Vec<RpcInstruction>> = meta
+            .inner_instructions
+            .unwrap_or_default()
+            .into_iter()
+            .map(|inner| (inner.index, inner.instructions))
+            .collect();
+
+        for (idx, ins) in message.instructions.iter().enumerate() {
+            let mut address = vec![idx as ItemIndex];
+            self.instructions.push(map_instruction(
+                transaction_index,
+                address.clone(),
+                ins,
+                is_committed,
+                &account
+            )?);
+
+            for inner in inner_instructions.remove(&(idx as u32)).unwrap_or_default() {
+                let height = inner.stack_height.unwrap_or(2) as usize;
+                ensure!(
+                    height >= 2 && height <= address.len() + 1,
+                    "unexpected stack height {} of inner instruction after {:?}",
+                    height,
+                    address
+                );
+                if height <= address.len() {
+                    address.truncate(height);
+                    *address.last_mut().unwrap() += 1;
+                } else {
+                    address.push(0);
+                }
+                self.instructions.push(map_instruction(
+                    transaction_index,
+                    address.clone(),
+                    &inner,
+                    is_committed,
+                    &account
+                )?);
+            }
+        }
+
+        let logs_complete = self.push_logs(
+            transaction_index,
+            meta.log_messages.as_deref().unwrap_or_default(),
+            first_instruction
+        );
+
+        for (idx, (pre, post)) in meta.pre_balances.iter().zip(meta.post_balances.iter()).enumerate() {
+            if pre != post {
+                self.balances.push(Balance {
+                    transaction_index,
+                    account: account(idx as u32)?,
+                    pre: *pre,
+                    post: *post
+                })
+            }
+        }
+
+        let mut token_balances: BTreeMap<u32, (Option<RpcTokenBalance>, Option<RpcTokenBalance>)> = BTreeMap::new();
+        for balance in meta.pre_token_balances.unwrap_or_default() {
+            token_balances.entry(balance.account_index).or_default().0 = Some(balance);
+        }
+        for balance in meta.post_token_balances.unwrap_or_default() {
+            token_balances.entry(balance.account_index).or_default().1 = Some(balance);
+        }
+        for (idx, (pre, post)) in token_balances {
+            if let (Some(pre), Some(post)) = (pre.as_ref(), post.as_ref()) {
+                if pre.mint == post.mint
+                    && pre.owner == post.owner
+                    && pre.ui_token_amount.amount == post.ui_token_amount.amount
+                {
+                    continue;
+                }
+            }
+            let pre = pre.map(|b| self.map_token_balance(b)).transpose()?;
+            let post = post.map(|b| self.map_token_balance(b)).transpose()?;
+            self.token_balances.push(TokenBalance {
+                transaction_index,
+                account: account(idx)?,
+                pre_mint: pre.as_ref().map(|b| b.mint),
+                post_mint: post.as_ref().map(|b| b.mint),
+                pre_decimals: pre.as_ref().map(|b| b.decimals),
+                post_decimals: post.as_ref().map(|b| b.decimals),
+                pre_program_id: pre.as_ref().and_then(|b| b.program_id),
+                post_program_id: post.as_ref().and_then(|b| b.program_id),
+                pre_owner: pre.as_ref().and_then(|b| b.owner),
+                post_owner: post.as_ref().and_then(|b| b.owner),
+                pre_amount: pre.as_ref().map(|b| b.amount),
+                post_amount: post.as_ref().map(|b| b.amount)
+            })
+        }
+
+        let address_table_lookups = message
+            .address_table_lookups
+            .unwrap_or_default()
+            .into_iter()
+            .map(|lookup| AddressTableLookup {
+                account_key: self.accounts.add(&lookup.account_key),
+                readonly_indexes: lookup.readonly_indexes,
+                writable_indexes: lookup.writable_indexes
+            })
+            .collect();
+
+        let static_keys = message.account_keys.len();
+        self.transactions.push(Transaction {
+            transaction_index,
+            version,
+            account_keys: keys[..static_keys].to_vec(),
+            address_table_lookups,
+            num_readonly_signed_accounts: message.header.num_readonly_signed_accounts,
+            num_readonly_unsigned_accounts: message.header.num_readonly_unsigned_accounts,
+            num_required_signatures: message.header.num_required_signatures,
+            recent_blockhash: message.recent_blockhash,
+            signatures: tx.transaction.signatures,
+            err: meta.err,
+            compute_units_consumed: meta.compute_units_consumed,
+            cost_units: meta.cost_units,
+            fee: meta.fee,
+            loaded_addresses: LoadedAddresses {
+                readonly: keys[static_keys + loaded_writable.len()..].to_vec(),
+                writable: keys[static_keys..static_keys + loaded_writable.len()].to_vec()
+            },
+            has_dropped_log_messages: !logs_complete
+        });
+
+        Ok(())
+    }
+
+    fn map_token_balance(&mut self, balance: RpcTokenBalance) -> anyhow::Result<TokenBalanceSide> {
+        Ok(TokenBalanceSide {
+            mint: self.accounts.add(&balance.mint),
+            decimals: balance.ui_token_amount.decimals,
+            program_id: balance.program_id.map(|id| self.accounts.add(&id)),
+            owner: balance.owner.map(|owner| self.accounts.add(&owner)),
+            amount: balance
+                .ui_token_amount
+                .amount
+                .parse::<u64>()
+                .with_context(|| format!("invalid token amount '{}'", balance.ui_token_amount.amount))?
+        })
+    }
+
+    /// Assigns log messages to the instructions of the transaction, that starts at `first_instruction`.
+    ///
+    /// Returns `false`, when the logs were truncated by the node or can't be matched against
+    /// the instructions. In that case all instructions without complete logs are marked.
+    fn push_logs(&mut self, transaction_index: ItemIndex, messages: &[String], first_instruction: usize) -> bool {
+        let mut stack: Vec<usize> = Vec::new();
+        let mut next = first_instruction;
+
+        let complete = 'parse: {
+            for (log_index, message) in messages.iter().enumerate() {
+                let (kind, text) = if let Some(text) = message.strip_prefix("Program log: ") {
+                    (LogMessageKind::Log, text)
+                } else if let Some(text) = message.strip_prefix("Program data: ") {
+                    (LogMessageKind::Data, text)
+                } else if message.starts_with("Program return: ") {
+                    continue;
+                } else if message == "Log truncated" {
+                    break 'parse false;
+                } else if let Some((program, event)) = message.strip_prefix("Program ").and_then(|s| s.split_once(' '))
+                {
+                    if let Some(depth) = event.strip_prefix("invoke [").and_then(|s| s.strip_suffix(']')) {
+                        let Some(ins) = self.instructions.get(next) else {
+                            break 'parse false;
+                        };
+                        if self.accounts.list[ins.program_id as usize] != program
+                            || depth.parse::<usize>().ok() != Some(ins.instruction_address.len())
+                        {
+                            break 'parse false;
+                        }
+                        stack.push(next);
+                        next += 1;
+                        continue;
+                    }
+                    if event == "success" {
+                        stack.pop();
+                        continue;
+                    }
+                    if let Some(error) = event.strip_prefix("failed: ") {
+                        if let Some(top) = stack.pop() {
+                            self.instructions[top].error = Some(Value::String(error.to_string()));
+                        }
+                        continue;
+                    }
+                    if let Some(consumed) = event.strip_prefix("consumed ") {
+                        let units = consumed.split_once(' ').and_then(|(units, _)| units.parse().ok());
+                        if let Some(top) = stack.last() {
+                            self.instructions[*top].compute_units_consumed = units;
+                        }
+                        continue;
+                    }
+                    (LogMessageKind::Other, message.as_str())
+                } else {
+                    (LogMessageKind::Other, message.as_str())
+                };
+
+                if let Some(top) = stack.last() {
+                    let ins = &self.instructions[*top];
+                    self.logs.push(LogMessage {
+                        transaction_index,
+                        log_index: log_index as ItemIndex,
+                        instruction_address: ins.instruction_address.clone(),
+                        program_id: ins.program_id,
+                        kind,
+                        message: text.to_string()
+                    })
+                }
+            }
+            true
+        };
+
+        if !complete {
+            for idx in stack.into_iter().chain(next..self.instructions.len()) {
+                self.instructions[idx].has_dropped_log_messages = true;
+            }
+        }
+
+        complete
+    }
+}
+
+struct TokenBalanceSide {
+    mint: AccountIndex,
+    decimals: u16,
+    program_id: Option<AccountIndex>,
+    owner: Option<AccountIndex>,
+    amount: u64
+}
+
+fn map_instruction(
+    transaction_index: ItemIndex,
+    instruction_address: Vec<ItemIndex>,
+    ins: &RpcInstruction,
+    is_committed: bool,
+    account: &impl Fn(u32) -> anyhow::Result<AccountIndex>
+) -> anyhow::Result<Instruction> {
+    Ok(Instruction {
+        transaction_index,
+        instruction_address,
+        program_id: account(ins.program_id_index)?,
+        accounts: ins
+            .accounts
+            .iter()
+            .map(|idx| account(*idx))
+            .collect::<anyhow::Result<_>>()?,
+        data: ins.data.clone(),
+        compute_units_consumed: None,
+        error: None,
+        is_committed,
+        has_dropped_log_messages: false
+    })
+}
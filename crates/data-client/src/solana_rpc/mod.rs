@@ -0,0 +1,5 @@
+mod client;
+mod mapping;
+mod types;
+
+pub use client::*;
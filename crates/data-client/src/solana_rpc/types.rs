@@ -0,0 +1,122 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+/// `getBlock` result in the `json` encoding
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcBlock {
+    pub blockhash: String,
+    pub previous_blockhash: String,
+    pub parent_slot: u64,
+    pub block_height: Option<u64>,
+    pub block_time: Option<i64>,
+    #[serde(default)]
+    pub transactions: Vec<RpcTransactionWithMeta>,
+    #[serde(default)]
+    pub rewards: Vec<RpcReward>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcTransactionWithMeta {
+    pub transaction: RpcTransaction,
+    pub meta: Option<RpcTransactionMeta>,
+    /// Either `"legacy"` or a version number, absent when versioned transactions are not requested
+    pub version: Option<Value>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcTransaction {
+    pub signatures: Vec<String>,
+    pub message: RpcMessage
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcMessage {
+    pub account_keys: Vec<String>,
+    pub header: RpcMessageHeader,
+    pub recent_blockhash: String,
+    pub instructions: Vec<RpcInstruction>,
+    pub address_table_lookups: Option<Vec<RpcAddressTableLookup>>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcMessageHeader {
+    pub num_required_signatures: u8,
+    pub num_readonly_signed_accounts: u8,
+    pub num_readonly_unsigned_accounts: u8
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcInstruction {
+    pub program_id_index: u32,
+    pub accounts: Vec<u32>,
+    pub data: String,
+    pub stack_height: Option<u32>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcAddressTableLookup {
+    pub account_key: String,
+    pub writable_indexes: Vec<u8>,
+    pub readonly_indexes: Vec<u8>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcTransactionMeta {
+    pub err: Option<Value>,
+    pub fee: u64,
+    pub pre_balances: Vec<u64>,
+    pub post_balances: Vec<u64>,
+    pub inner_instructions: Option<Vec<RpcInnerInstructions>>,
+    pub log_messages: Option<Vec<String>>,
+    pub pre_token_balances: Option<Vec<RpcTokenBalance>>,
+    pub post_token_balances: Option<Vec<RpcTokenBalance>>,
+    pub loaded_addresses: Option<RpcLoadedAddresses>,
+    pub compute_units_consumed: Option<u64>,
+    pub cost_units: Option<u64>
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RpcInnerInstructions {
+    pub index: u32,
+    pub instructions: Vec<RpcInstruction>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcTokenBalance {
+    pub account_index: u32,
+    pub mint: String,
+    pub owner: Option<String>,
+    pub program_id: Option<String>,
+    pub ui_token_amount: RpcTokenAmount
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RpcTokenAmount {
+    pub amount: String,
+    pub decimals: u16
+}
+
+#[derive(Deserialize)]
+pub(crate) struct RpcLoadedAddresses {
+    pub writable: Vec<String>,
+    pub readonly: Vec<String>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct RpcReward {
+    pub pubkey: String,
+    pub lamports: i64,
+    pub post_balance: u64,
+    pub reward_type: Option<String>,
+    pub commission: Option<u8>
+}
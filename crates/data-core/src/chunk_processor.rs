@@ -1,6 +1,7 @@
 use std::collections::BTreeMap;
 
 use anyhow::anyhow;
+use arrow::datatypes::SchemaRef;
 use sqd_array::slice::AnyTableSlice;
 
 use crate::{PreparedTable, TableProcessor};
@@ -24,6 +25,11 @@ impl ChunkProcessor {
         processor.push_batch(records)
     }
 
+    /// Schema of the records, that `push_table()` expects for the given table
+    pub fn table_schema(&self, name: &str) -> Option<SchemaRef> {
+        self.tables.get(name).map(|t| t.schema())
+    }
+
     pub fn byte_size(&self) -> usize {
         self.tables.values().map(|t| t.byte_size()).sum()
     }
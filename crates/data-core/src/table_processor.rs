@@ -127,6 +127,10 @@ impl TableProcessor {
         })
     }
 
+    pub fn schema(&self) -> SchemaRef {
+        self.schema.clone()
+    }
+
     pub fn num_rows(&self) -> usize {
         self.num_rows
     }
@@ -0,0 +1,155 @@
+use std::time::Instant;
+
+use sqd_primitives::BlockNumber;
+
+/// Weight of the latest observation in the smoothed latency and error rate
+const EWMA_ALPHA: f64 = 0.2;
+
+/// Score penalty for each block an endpoint is behind the committed position, in milliseconds
+const LAG_PENALTY_MS: f64 = 1000.0;
+
+/// Score penalty of an endpoint, that fails every request, in milliseconds
+const ERROR_PENALTY_MS: f64 = 10_000.0;
+
+/// Health of a data source endpoint.
+///
+/// Endpoints race for each block, and the one polled first wins the ties,
+/// so the data source polls them in the order of their scores.
+#[derive(Default)]
+pub(crate) struct EndpointHealth {
+    /// Smoothed time to the response of block stream requests, in milliseconds
+    latency_ms: Option<f64>,
+    /// Smoothed share of failed requests
+    error_rate: f64,
+    /// Highest block received from the endpoint, whether it was accepted or not
+    last_seen_block: Option<BlockNumber>,
+    request_started: Option<Instant>,
+    /// Whether the score might have changed since the last [`EndpointHealth::take_changed`]
+    changed: bool
+}
+
+impl EndpointHealth {
+    pub fn on_request(&mut self) {
+        self.request_started = Some(Instant::now())
+    }
+
+    pub fn on_response(&mut self) {
+        if let Some(started) = self.request_started.take() {
+            let ms = started.elapsed().as_secs_f64() * 1000.0;
+            self.latency_ms = Some(self.latency_ms.map_or(ms, |avg| ewma(avg, ms)));
+        }
+        self.error_rate = ewma(self.error_rate, 0.0);
+        self.changed = true;
+    }
+
+    pub fn on_error(&mut self) {
+        self.request_started = None;
+        self.error_rate = ewma(self.error_rate, 1.0);
+        self.changed = true;
+    }
+
+    pub fn on_block(&mut self, number: BlockNumber) {
+        if self.last_seen_block.map_or(true, |n| n < number) {
+            self.last_seen_block = Some(number);
+            self.changed = true;
+        }
+    }
+
+    /// Forget the seen blocks, they might be unrelated to the new position
+    pub fn reset_position(&mut self) {
+        self.last_seen_block = None;
+        self.changed = true;
+    }
+
+    /// Whether the score might have changed since the previous call
+    pub fn take_changed(&mut self) -> bool {
+        std::mem::take(&mut self.changed)
+    }
+
+    /// Number of blocks the endpoint is behind the `committed` one.
+    ///
+    /// Endpoints, that haven't sent anything since the last reset, are not considered lagging.
+    pub fn lag(&self, committed: Option<BlockNumber>) -> BlockNumber {
+        match (committed, self.last_seen_block) {
+            (Some(committed), Some(seen)) => committed.saturating_sub(seen),
+            _ => 0
+        }
+    }
+
+    pub fn latency_secs(&self) -> f64 {
+        self.latency_ms.unwrap_or(0.0) / 1000.0
+    }
+
+    pub fn error_rate(&self) -> f64 {
+        self.error_rate
+    }
+
+    /// Lower is better
+    pub fn score(&self, committed: Option<BlockNumber>) -> f64 {
+        self.lag(committed) as f64 * LAG_PENALTY_MS
+            + self.latency_ms.unwrap_or(0.0)
+            + self.error_rate * ERROR_PENALTY_MS
+    }
+}
+
+fn ewma(avg: f64, value: f64) -> f64 {
+    avg + EWMA_ALPHA * (value - avg)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EndpointHealth;
+
+    fn seen(block: u64) -> EndpointHealth {
+        let mut health = EndpointHealth::default();
+        health.on_block(block);
+        health
+    }
+
+    #[test]
+    fn lag() {
+        assert_eq!(EndpointHealth::default().lag(Some(10)), 0);
+        assert_eq!(seen(5).lag(None), 0);
+        assert_eq!(seen(5).lag(Some(10)), 5);
+        assert_eq!(seen(12).lag(Some(10)), 0);
+
+        let mut health = seen(5);
+        health.reset_position();
+        assert_eq!(health.lag(Some(10)), 0);
+    }
+
+    #[test]
+    fn score() {
+        let committed = Some(10);
+        assert_eq!(seen(10).score(committed), 0.0);
+        assert_eq!(seen(8).score(committed), 2000.0);
+
+        let mut failing = seen(10);
+        failing.on_error();
+        assert!((failing.score(committed) - 2000.0).abs() < 1e-6);
+
+        let mut slow = seen(10);
+        slow.latency_ms = Some(150.0);
+        assert_eq!(slow.score(committed), 150.0);
+        assert!(slow.score(committed) < seen(9).score(committed));
+    }
+
+    #[test]
+    fn changes_are_taken_once() {
+        let mut health = EndpointHealth::default();
+        assert!(!health.take_changed());
+
+        health.on_request();
+        assert!(!health.take_changed());
+
+        health.on_block(5);
+        assert!(health.take_changed());
+        assert!(!health.take_changed());
+
+        health.on_block(4);
+        assert!(!health.take_changed());
+
+        health.on_error();
+        assert!(health.take_changed());
+    }
+}
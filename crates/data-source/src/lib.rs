@@ -1,5 +1,7 @@
+mod health;
 mod map;
 pub mod metrics;
+mod quorum;
 mod standard;
 mod types;
 
@@ -1,6 +1,6 @@
-use std::sync::LazyLock;
+use std::sync::{atomic::AtomicU64, LazyLock};
 
-use prometheus_client::metrics::{counter::Counter, family::Family};
+use prometheus_client::metrics::{counter::Counter, family::Family, gauge::Gauge};
 
 type Labels = Vec<(&'static str, String)>;
 
@@ -11,6 +11,24 @@ pub static INGEST_SOURCE_ERRORS: LazyLock<Family<Labels, Counter>> = LazyLock::n
 /// Fork signals by `source` and whether it held the contested position (`at_tip`/`above_tip`).
 pub static INGEST_FORK_SIGNALS: LazyLock<Family<Labels, Counter>> = LazyLock::new(Default::default);
 
+/// Blocks the `source` endpoint is behind the committed position, as last seen by the data source.
+pub static INGEST_SOURCE_LAG: LazyLock<Family<Labels, Gauge>> = LazyLock::new(Default::default);
+
+/// Smoothed time to the response of block stream requests to the `source` endpoint, in seconds.
+pub static INGEST_SOURCE_LATENCY: LazyLock<Family<Labels, Gauge<f64, AtomicU64>>> = LazyLock::new(Default::default);
+
+/// Smoothed share of failed requests to the `source` endpoint.
+pub static INGEST_SOURCE_ERROR_RATE: LazyLock<Family<Labels, Gauge<f64, AtomicU64>>> = LazyLock::new(Default::default);
+
+/// Place of the `source` endpoint in the poll order, 0 is the preferred endpoint.
+pub static INGEST_SOURCE_RANK: LazyLock<Family<Labels, Gauge>> = LazyLock::new(Default::default);
+
+/// Blocks proposed by the `source` endpoint, that lost the block hash quorum.
+pub static INGEST_HASH_DISAGREEMENTS: LazyLock<Family<Labels, Counter>> = LazyLock::new(Default::default);
+
+/// Blocks accepted without reaching the block hash quorum.
+pub static INGEST_HASH_QUORUM_TIMEOUTS: LazyLock<Counter> = LazyLock::new(Default::default);
+
 /// Public because the pre-ingest head probe lives in `hotblocks` and must feed the same counter:
 /// it runs before this crate's stream loop, so a total outage never reaches `on_error`.
 pub fn record_ingest_source_error(source: &str, kind: &'static str) {
@@ -27,3 +45,21 @@ pub(crate) fn record_ingest_fork_signal(source: &str, standing: &'static str) {
         ])
         .inc();
 }
+
+pub(crate) fn record_ingest_source_health(source: &str, rank: usize, lag: u64, latency_secs: f64, error_rate: f64) {
+    let labels = vec![("source", source.to_string())];
+    INGEST_SOURCE_RANK.get_or_create(&labels).set(rank as i64);
+    INGEST_SOURCE_LAG.get_or_create(&labels).set(lag as i64);
+    INGEST_SOURCE_LATENCY.get_or_create(&labels).set(latency_secs);
+    INGEST_SOURCE_ERROR_RATE.get_or_create(&labels).set(error_rate);
+}
+
+pub(crate) fn record_ingest_hash_disagreement(source: &str) {
+    INGEST_HASH_DISAGREEMENTS
+        .get_or_create(&vec![("source", source.to_string())])
+        .inc();
+}
+
+pub(crate) fn record_ingest_hash_quorum_timeout() {
+    INGEST_HASH_QUORUM_TIMEOUTS.inc();
+}
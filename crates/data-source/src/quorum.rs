@@ -0,0 +1,121 @@
+use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+
+use sqd_primitives::Block;
+use tokio::time::Sleep;
+use tracing::warn;
+
+/// Blocks proposed by endpoints for the current position.
+///
+/// A block is accepted once enough endpoints sent the same one.
+/// When that doesn't happen within the timeout, the block with the majority of the votes cast wins.
+pub(crate) struct HashQuorum<B> {
+    quorum: usize,
+    timeout: Duration,
+    candidates: Vec<Candidate<B>>,
+    deadline: Option<Pin<Box<Sleep>>>
+}
+
+struct Candidate<B> {
+    block: B,
+    is_final: bool,
+    votes: usize
+}
+
+pub(crate) struct Decision<B> {
+    pub block: B,
+    pub is_final: bool,
+    pub votes: usize,
+    pub timed_out: bool
+}
+
+impl<B: Block> HashQuorum<B> {
+    pub fn new(quorum: usize, timeout: Duration) -> Self {
+        assert!(quorum > 0, "hash quorum must be positive");
+        Self {
+            quorum,
+            timeout,
+            candidates: Vec::new(),
+            deadline: None
+        }
+    }
+
+    /// Quorum, that can be reached by `active_endpoints`
+    pub fn effective_quorum(&self, active_endpoints: usize) -> usize {
+        std::cmp::max(1, std::cmp::min(self.quorum, active_endpoints))
+    }
+
+    pub fn vote(&mut self, block: B, is_final: bool, active_endpoints: usize) -> Option<Decision<B>> {
+        let idx = match self
+            .candidates
+            .iter()
+            .position(|c| c.block.number() == block.number() && c.block.hash() == block.hash())
+        {
+            Some(idx) => {
+                let candidate = &mut self.candidates[idx];
+                candidate.votes += 1;
+                candidate.is_final |= is_final;
+                idx
+            }
+            None => {
+                self.candidates.push(Candidate {
+                    block,
+                    is_final,
+                    votes: 1
+                });
+                if self.deadline.is_none() {
+                    self.deadline = Some(Box::pin(tokio::time::sleep(self.timeout)));
+                }
+                self.candidates.len() - 1
+            }
+        };
+
+        if self.candidates[idx].votes >= self.effective_quorum(active_endpoints) {
+            Some(self.decide(idx, false))
+        } else {
+            None
+        }
+    }
+
+    /// Picks the block with the majority of the votes cast, once the timeout is over.
+    ///
+    /// Without a majority the timeout starts again, and the quorum waits for more votes.
+    pub fn poll_timeout(&mut self, cx: &mut std::task::Context<'_>) -> Option<Decision<B>> {
+        let deadline = self.deadline.as_mut()?;
+        if deadline.as_mut().poll(cx) == Poll::Pending {
+            return None;
+        }
+
+        let votes: usize = self.candidates.iter().map(|c| c.votes).sum();
+        if let Some(idx) = self.candidates.iter().position(|c| c.votes * 2 > votes) {
+            return Some(self.decide(idx, true));
+        }
+
+        warn!(
+            block_number = self.candidates[0].block.number(),
+            candidates = self.candidates.len(),
+            votes,
+            "no block has the majority of the votes, waiting for more data sources"
+        );
+        let mut deadline = Box::pin(tokio::time::sleep(self.timeout));
+        let _ = deadline.as_mut().poll(cx);
+        self.deadline = Some(deadline);
+        None
+    }
+
+    fn decide(&mut self, idx: usize, timed_out: bool) -> Decision<B> {
+        self.deadline = None;
+        let winner = self.candidates.swap_remove(idx);
+        self.candidates.clear();
+        Decision {
+            block: winner.block,
+            is_final: winner.is_final,
+            votes: winner.votes,
+            timed_out
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.candidates.clear();
+        self.deadline = None;
+    }
+}
@@ -1,4 +1,9 @@
-use std::{future::Future, pin::Pin, task::Poll, time::Duration};
+use std::{
+    future::Future,
+    pin::Pin,
+    task::Poll,
+    time::{Duration, Instant}
+};
 
 use anyhow::Context;
 use futures::{future::BoxFuture, stream::BoxStream, FutureExt, Stream, StreamExt};
@@ -7,13 +12,24 @@ use sqd_primitives::{Block, BlockNumber, BlockRef};
 use tokio::time::Sleep;
 use tracing::warn;
 
-use crate::types::{DataEvent, DataSource};
+use crate::{
+    health::EndpointHealth,
+    quorum::{Decision, HashQuorum},
+    types::{DataEvent, DataSource}
+};
+
+/// How often endpoint health is exported to metrics
+const HEALTH_REPORT_INTERVAL: Duration = Duration::from_secs(5);
 
 struct Endpoint<C: DataClient> {
     client: C,
+    source: String,
     state: EndpointState<C::Block>,
     error_counter: usize,
-    last_committed_block: Option<BlockNumber>
+    last_committed_block: Option<BlockNumber>,
+    health: EndpointHealth,
+    /// Block proposed to the hash quorum. The stream is paused, until the quorum decides.
+    vote: Option<BlockRef>
 }
 
 enum EndpointState<B> {
@@ -33,30 +49,40 @@ enum EndpointState<B> {
     Backoff(Pin<Box<Sleep>>)
 }
 
-pub struct StandardDataSource<C: DataClient, F> {
+pub struct StandardDataSource<C: DataClient, F, B> {
     endpoints: Vec<Endpoint<C>>,
-    state: DataSourceState<F>
+    /// Indexes of `endpoints` from the best to the worst
+    poll_order: Vec<usize>,
+    /// Committed block, `poll_order` was sorted for
+    poll_order_committed: Option<BlockNumber>,
+    health_reported: Option<Instant>,
+    state: DataSourceState<F, B>
 }
 
-struct DataSourceState<F> {
+struct DataSourceState<F, B> {
     parse: F,
     finalized_head: Option<BlockRef>,
     position: BlockStreamRequest,
     position_is_canonical: bool,
     max_seen_finalized_block: BlockNumber,
-    fork_consensus_timeout: Option<Pin<Box<Sleep>>>
+    fork_consensus_timeout: Option<Pin<Box<Sleep>>>,
+    quorum: Option<HashQuorum<B>>,
+    /// Whether the quorum accepted a block, and the votes of endpoints must be settled
+    votes_decided: bool,
+    /// Number of endpoints, that are not in backoff
+    active_endpoints: usize
 }
 
-impl<F> DataSourceState<F> {
-    fn poll_endpoint<B, C>(&mut self, ep: &mut Endpoint<C>, cx: &mut std::task::Context<'_>) -> Poll<DataEvent<B>>
+impl<F, B: Block> DataSourceState<F, B> {
+    fn poll_endpoint<C>(&mut self, ep: &mut Endpoint<C>, cx: &mut std::task::Context<'_>) -> Poll<DataEvent<B>>
     where
-        B: Block,
         C: DataClient,
         F: Fn(C::Block) -> anyhow::Result<B>
     {
         loop {
             match &mut ep.state {
                 EndpointState::Ready => {
+                    ep.health.on_request();
                     ep.state = EndpointState::Req {
                         req: self.position.clone(),
                         future: ep.client.stream(self.position.clone())
@@ -66,6 +92,7 @@ impl<F> DataSourceState<F> {
                     Poll::Ready(Ok(BlockStreamResponse::Stream { finalized_head, blocks })) => {
                         let finalized_head_updated = self.on_new_finalized_head(finalized_head.as_ref());
 
+                        ep.health.on_response();
                         ep.error_counter = 0;
                         ep.state = EndpointState::Stream {
                             finalized_head: finalized_head.as_ref().map_or(0, |b| b.number),
@@ -79,12 +106,14 @@ impl<F> DataSourceState<F> {
                     Poll::Ready(Ok(BlockStreamResponse::Fork(prev_blocks))) => {
                         let req = req.clone();
                         ep.on_fork_signal(req.first_block, &prev_blocks);
+                        ep.health.on_response();
                         ep.error_counter = 0;
                         ep.state = EndpointState::Fork { req, prev_blocks };
                     }
                     Poll::Ready(Err(err)) => ep.on_error(err),
                     Poll::Pending => return Poll::Pending
                 },
+                EndpointState::Stream { .. } if ep.vote.is_some() => return Poll::Pending,
                 EndpointState::Stream { finalized_head, blocks } => match blocks.poll_next_unpin(cx) {
                     Poll::Ready(None) => {
                         ep.error_counter = 0;
@@ -98,9 +127,22 @@ impl<F> DataSourceState<F> {
                         match (self.parse)(new_block).context("failed to parse a block") {
                             Ok(block) => {
                                 ep.error_counter = 0;
-                                if block.number() >= self.position.first_block {
+                                ep.health.on_block(block.number());
+                                if self.quorum.is_some() && self.disagrees_with_position(&block) {
+                                    // a late vote or a block on top of a rejected one
+                                    let accepted_number = self.position.first_block.checked_sub(1);
+                                    let accepted_hash = self.position.parent_block_hash.as_deref();
+                                    ep.on_disagreement(block.number(), block.hash(), accepted_number, accepted_hash);
+                                    ep.state = EndpointState::Ready;
+                                } else if block.number() >= self.position.first_block {
                                     let is_final = *finalized_head >= block.number();
-                                    if self.accept_new_block(&block, is_final) {
+                                    if self.quorum.is_some() {
+                                        ep.vote = Some(BlockRef {
+                                            number: block.number(),
+                                            hash: block.hash().to_string()
+                                        });
+                                        return self.vote(block, is_final);
+                                    } else if self.accept_new_block(&block, is_final) {
                                         ep.last_committed_block = Some(block.number());
                                         return Poll::Ready(DataEvent::Block { block, is_final });
                                     } else {
@@ -129,6 +171,52 @@ impl<F> DataSourceState<F> {
         }
     }
 
+    /// Whether `block` contradicts the last accepted block
+    fn disagrees_with_position(&self, block: &impl Block) -> bool {
+        let Some(accepted_hash) = self.position.parent_block_hash.as_deref() else {
+            return false;
+        };
+        if block.number() >= self.position.first_block {
+            block.parent_hash() != accepted_hash
+        } else if block.number() + 1 == self.position.first_block {
+            block.hash() != accepted_hash
+        } else {
+            false
+        }
+    }
+
+    fn vote(&mut self, block: B, is_final: bool) -> Poll<DataEvent<B>> {
+        let quorum = self.quorum.as_mut().expect("hash quorum must be enabled");
+        match quorum.vote(block, is_final, self.active_endpoints) {
+            Some(decision) => self.on_quorum_decision(decision),
+            None => Poll::Pending
+        }
+    }
+
+    fn on_quorum_decision(&mut self, decision: Decision<B>) -> Poll<DataEvent<B>> {
+        self.votes_decided = true;
+
+        if decision.timed_out {
+            warn!(
+                block_number = decision.block.number(),
+                block_hash = decision.block.hash(),
+                votes = decision.votes,
+                "block hash quorum was not reached in time, accepting the block with the most votes"
+            );
+            crate::metrics::record_ingest_hash_quorum_timeout();
+        }
+
+        // Candidates were checked against the current position, when they were proposed
+        if self.accept_new_block(&decision.block, decision.is_final) {
+            Poll::Ready(DataEvent::Block {
+                block: decision.block,
+                is_final: decision.is_final
+            })
+        } else {
+            Poll::Pending
+        }
+    }
+
     fn accept_new_block(&mut self, block: &impl Block, is_final: bool) -> bool {
         assert!(self.position.first_block <= block.number());
 
@@ -227,11 +315,30 @@ impl<C: DataClient> Endpoint<C> {
             // as `at_tip` keeps the defect bucket clean of shapes it cannot speak to.
             _ => "at_tip"
         };
-        crate::metrics::record_ingest_fork_signal(&self.client.source_label(), standing);
+        crate::metrics::record_ingest_fork_signal(&self.source, standing);
+    }
+
+    fn on_disagreement(
+        &self,
+        number: BlockNumber,
+        hash: &str,
+        accepted_number: Option<BlockNumber>,
+        accepted_hash: Option<&str>
+    ) {
+        warn!(
+            data_source =? self.client,
+            block_number = number,
+            block_hash = hash,
+            accepted_block_number =? accepted_number,
+            accepted_block_hash =? accepted_hash,
+            "data source disagrees with the accepted block"
+        );
+        crate::metrics::record_ingest_hash_disagreement(&self.source);
     }
 
     fn on_error(&mut self, error: anyhow::Error) {
-        crate::metrics::record_ingest_source_error(&self.client.source_label(), self.client.error_kind(&error));
+        crate::metrics::record_ingest_source_error(&self.source, self.client.error_kind(&error));
+        self.health.on_error();
 
         let backoff = [0, 100, 200, 500, 1000, 2000, 5000, 10000];
         let pause = backoff[std::cmp::min(self.error_counter, backoff.len() - 1)];
@@ -259,7 +366,7 @@ impl<C: DataClient> Endpoint<C> {
     }
 }
 
-impl<B, C, F> StandardDataSource<C, F>
+impl<B, C, F> StandardDataSource<C, F, B>
 where
     B: Block,
     C: DataClient,
@@ -269,12 +376,17 @@ where
         let endpoints = clients
             .into_iter()
             .map(|client| Endpoint {
+                source: client.source_label(),
                 client,
                 error_counter: 0,
                 state: EndpointState::Ready,
-                last_committed_block: None
+                last_committed_block: None,
+                health: EndpointHealth::default(),
+                vote: None
             })
-            .collect();
+            .collect::<Vec<_>>();
+
+        let poll_order = (0..endpoints.len()).collect();
 
         let state = DataSourceState {
             parse,
@@ -285,15 +397,51 @@ where
             },
             position_is_canonical: false,
             max_seen_finalized_block: 0,
-            fork_consensus_timeout: None
+            fork_consensus_timeout: None,
+            quorum: None,
+            votes_decided: false,
+            active_endpoints: 0
         };
 
-        Self { endpoints, state }
+        Self {
+            endpoints,
+            poll_order,
+            poll_order_committed: None,
+            health_reported: None,
+            state
+        }
+    }
+
+    /// Accept a block only after `quorum` endpoints sent the same one.
+    ///
+    /// When fewer endpoints are active, all of them must agree.
+    /// When the quorum isn't reached within `timeout`, the block with the majority of the votes cast is accepted.
+    /// Forks are also accepted only when signalled by the quorum, or by the majority of active endpoints
+    /// after the fork consensus timeout.
+    pub fn with_hash_quorum(mut self, quorum: usize, timeout: Duration) -> Self {
+        self.state.quorum = Some(HashQuorum::new(quorum, timeout));
+        self
     }
 
     fn poll_next_event(&mut self, cx: &mut std::task::Context<'_>) -> Poll<DataEvent<B>> {
-        for ep in self.endpoints.iter_mut() {
-            let event = self.state.poll_endpoint(ep, cx);
+        self.update_poll_order();
+        self.state.active_endpoints = self.endpoints.iter().filter(|ep| ep.is_active()).count();
+
+        let mut event = Poll::Pending;
+        for &idx in self.poll_order.iter() {
+            event = self.state.poll_endpoint(&mut self.endpoints[idx], cx);
+            if event.is_ready() {
+                break;
+            }
+        }
+        if event.is_ready() {
+            self.settle_votes();
+            return event;
+        }
+
+        if let Some(decision) = self.state.quorum.as_mut().and_then(|q| q.poll_timeout(cx)) {
+            let event = self.state.on_quorum_decision(decision);
+            self.settle_votes();
             if event.is_ready() {
                 return event;
             }
@@ -301,9 +449,15 @@ where
 
         let forks = self.endpoints.iter().filter(|ep| ep.is_on_fork()).count();
         if forks > 0 {
-            if forks > self.endpoints.len() / 2
-                || forks == self.endpoints.iter().filter(|ep| ep.is_active()).count()
-                || self.fork_consensus_timeout(cx)
+            let (consensus, timeout_consensus) = match self.state.quorum.as_ref() {
+                // Short of the quorum, the majority of active endpoints confirms the fork after the timeout
+                Some(quorum) => (
+                    forks >= quorum.effective_quorum(self.state.active_endpoints),
+                    forks * 2 > self.state.active_endpoints
+                ),
+                None => (forks > self.endpoints.len() / 2, true)
+            };
+            if consensus || forks == self.state.active_endpoints || timeout_consensus && self.fork_consensus_timeout(cx)
             {
                 return Poll::Ready(DataEvent::Fork(self.extract_fork()));
             }
@@ -314,6 +468,76 @@ where
         Poll::Pending
     }
 
+    /// Resumes endpoints, that voted for the accepted block, and restarts the others
+    fn settle_votes(&mut self) {
+        if !std::mem::take(&mut self.state.votes_decided) {
+            return;
+        }
+        let accepted_number = self.state.position.first_block.checked_sub(1);
+        let accepted_hash = self.state.position.parent_block_hash.as_deref();
+        for ep in self.endpoints.iter_mut() {
+            let Some(vote) = ep.vote.take() else { continue };
+            if Some(vote.number) == accepted_number && Some(vote.hash.as_str()) == accepted_hash {
+                ep.last_committed_block = Some(vote.number);
+            } else {
+                ep.on_disagreement(vote.number, &vote.hash, accepted_number, accepted_hash);
+                ep.state = EndpointState::Ready;
+            }
+        }
+    }
+
+    fn clear_votes(&mut self) {
+        self.state.votes_decided = false;
+        if let Some(quorum) = self.state.quorum.as_mut() {
+            quorum.clear();
+        }
+        for ep in self.endpoints.iter_mut() {
+            ep.vote = None;
+        }
+    }
+
+    /// Puts endpoints with the best health first, so that they win the races for new blocks.
+    /// The sort is stable, hence equally healthy endpoints keep the configured order.
+    ///
+    /// Scores change only with the health of an endpoint or with the committed position,
+    /// so the endpoints are re-sorted only then.
+    fn update_poll_order(&mut self) {
+        let committed = self.state.position.first_block.checked_sub(1);
+        let mut changed = committed != self.poll_order_committed;
+        for ep in self.endpoints.iter_mut() {
+            changed |= ep.health.take_changed();
+        }
+        if changed {
+            self.poll_order_committed = committed;
+            let endpoints = &self.endpoints;
+            self.poll_order.clear();
+            self.poll_order.extend(0..endpoints.len());
+            self.poll_order.sort_by(|&a, &b| {
+                endpoints[a]
+                    .health
+                    .score(committed)
+                    .total_cmp(&endpoints[b].health.score(committed))
+            });
+        }
+
+        if self
+            .health_reported
+            .map_or(true, |at| at.elapsed() >= HEALTH_REPORT_INTERVAL)
+        {
+            self.health_reported = Some(Instant::now());
+            for (rank, &idx) in self.poll_order.iter().enumerate() {
+                let ep = &self.endpoints[idx];
+                crate::metrics::record_ingest_source_health(
+                    &ep.source,
+                    rank,
+                    ep.health.lag(committed),
+                    ep.health.latency_secs(),
+                    ep.health.error_rate()
+                );
+            }
+        }
+    }
+
     fn fork_consensus_timeout(&mut self, cx: &mut std::task::Context<'_>) -> bool {
         let mut timeout = self
             .state
@@ -331,6 +555,7 @@ where
 
     fn extract_fork(&mut self) -> Vec<BlockRef> {
         self.state.fork_consensus_timeout = None;
+        self.clear_votes();
         let mut chain = Vec::new();
         for ep in self.endpoints.iter_mut() {
             match std::mem::replace(&mut ep.state, EndpointState::Ready) {
@@ -347,7 +572,7 @@ where
     }
 }
 
-impl<B, C, F> Stream for StandardDataSource<C, F>
+impl<B, C, F> Stream for StandardDataSource<C, F, B>
 where
     B: Block,
     C: DataClient,
@@ -360,7 +585,7 @@ where
     }
 }
 
-impl<B, C, F> DataSource for StandardDataSource<C, F>
+impl<B, C, F> DataSource for StandardDataSource<C, F, B>
 where
     B: Block,
     C: DataClient,
@@ -373,9 +598,11 @@ where
         self.state.position.set_parent_block_hash(parent_block_hash);
         self.state.position_is_canonical = false;
         self.state.finalized_head = None;
+        self.clear_votes();
         for ep in self.endpoints.iter_mut() {
             ep.state = EndpointState::Ready;
             ep.last_committed_block = None;
+            ep.health.reset_position();
         }
     }
 
@@ -387,3 +614,87 @@ where
         self.state.position.parent_block_hash.as_deref()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use futures::{future::BoxFuture, FutureExt, StreamExt};
+    use sqd_data_client::{BlockStreamRequest, BlockStreamResponse, DataClient};
+    use sqd_primitives::{Block, BlockNumber, BlockRef};
+
+    use super::StandardDataSource;
+    use crate::DataEvent;
+
+    #[derive(Debug, Clone)]
+    struct TestBlock {
+        number: BlockNumber
+    }
+
+    impl Block for TestBlock {
+        fn number(&self) -> BlockNumber {
+            self.number
+        }
+
+        fn hash(&self) -> &str {
+            "hash"
+        }
+
+        fn parent_number(&self) -> BlockNumber {
+            self.number.saturating_sub(1)
+        }
+
+        fn parent_hash(&self) -> &str {
+            "hash"
+        }
+    }
+
+    /// Serves blocks up to `head`, and waits for new ones forever after that
+    #[derive(Debug)]
+    struct TestClient {
+        head: BlockNumber
+    }
+
+    impl DataClient for TestClient {
+        type Block = TestBlock;
+
+        fn stream(
+            &self,
+            req: BlockStreamRequest
+        ) -> BoxFuture<'static, anyhow::Result<BlockStreamResponse<TestBlock>>> {
+            if req.first_block > self.head {
+                return futures::future::pending().boxed();
+            }
+            let blocks = (req.first_block..=self.head).map(|number| Ok(TestBlock { number }));
+            futures::future::ready(Ok(BlockStreamResponse::Stream {
+                finalized_head: None,
+                blocks: futures::stream::iter(blocks).boxed()
+            }))
+            .boxed()
+        }
+
+        fn get_finalized_head(&self) -> BoxFuture<'static, anyhow::Result<Option<BlockRef>>> {
+            futures::future::ready(Ok(None)).boxed()
+        }
+
+        fn is_retryable(&self, _err: &anyhow::Error) -> bool {
+            false
+        }
+    }
+
+    #[tokio::test]
+    async fn lagging_endpoint_is_demoted() {
+        let clients = vec![TestClient { head: 4 }, TestClient { head: 9 }];
+        let mut source = StandardDataSource::new(clients, Ok);
+        assert_eq!(source.poll_order, vec![0, 1]);
+
+        loop {
+            match source.next().await {
+                Some(DataEvent::Block { block, .. }) if block.number == 9 => break,
+                Some(_) => {}
+                None => panic!("the data source ended")
+            }
+        }
+
+        source.update_poll_order();
+        assert_eq!(source.poll_order, vec![1, 0]);
+    }
+}
@@ -28,6 +28,9 @@ table_builder! {
         d.options.add_stats("transaction_index");
         d.options.add_stats("address");
         d.options.add_stats("topic0");
+        d.options.add_index("address");
+        d.options.add_bloom_filter("address");
+        d.options.add_bloom_filter("topic0");
         d.options.use_dictionary("address");
         d.options.use_dictionary("topic0");
         d.options.row_group_size = 10_000;
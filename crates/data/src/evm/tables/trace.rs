@@ -1,5 +1,6 @@
 use sqd_array::builder::{StringBuilder, UInt32Builder, UInt64Builder};
 use sqd_data_core::table_builder;
+use sqd_dataset::Codec;
 
 use super::common::{sighash, HexBytesBuilder, TraceAddressListBuilder};
 use crate::evm::model::{Block, Trace, TraceOp};
@@ -62,6 +63,7 @@ table_builder! {
         d.options.use_dictionary("call_to");
         d.options.use_dictionary("call_sighash");
         d.options.use_dictionary("call_type");
+        d.options.set_codec("call_input", Codec::Zstd);
         d.options.row_group_size = 10_000;
     }
 }
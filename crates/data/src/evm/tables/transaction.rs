@@ -2,6 +2,7 @@ use sqd_array::builder::{
     BooleanBuilder, Float64Builder, ListBuilder, StringBuilder, UInt32Builder, UInt64Builder, UInt8Builder
 };
 use sqd_data_core::{struct_builder, table_builder};
+use sqd_dataset::Codec;
 
 use crate::evm::{
     model::{Block, TempoKeychainSignature, TempoPrimitiveSignature, TempoSignature, Transaction},
@@ -161,9 +162,14 @@ table_builder! {
         d.options.add_stats("to");
         d.options.add_stats("from");
         d.options.add_stats("sighash");
+        d.options.add_index("to");
+        d.options.add_index("from");
+        d.options.add_bloom_filter("to");
+        d.options.add_bloom_filter("from");
         d.options.use_dictionary("to");
         d.options.use_dictionary("sighash");
         d.options.use_dictionary("access_list.list.element.address");
+        d.options.set_codec("input", Codec::Zstd);
         d.options.row_group_size = 10_000;
     }
 }
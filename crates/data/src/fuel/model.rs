@@ -0,0 +1,291 @@
+use serde::Deserialize;
+use sqd_primitives::{BlockNumber, ItemIndex};
+
+use crate::types::{HexBytes, JsonValue};
+
+/// TAI64 label of the unix epoch
+const TAI64_UNIX_EPOCH: u64 = (1 << 62) + 10;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHeader {
+    pub height: BlockNumber,
+    pub hash: HexBytes,
+    pub parent_hash: HexBytes,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string")]
+    pub da_height: u64,
+    pub consensus_parameters_version: u32,
+    pub state_transition_bytecode_version: u32,
+    pub transactions_count: u64,
+    pub message_receipt_count: u64,
+    pub transactions_root: HexBytes,
+    pub message_outbox_root: HexBytes,
+    pub event_inbox_root: HexBytes,
+    pub prev_root: HexBytes,
+    /// TAI64 timestamp
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string")]
+    pub time: u64,
+    pub application_hash: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInputContract {
+    pub utxo_id: HexBytes,
+    pub balance_root: HexBytes,
+    pub state_root: HexBytes,
+    pub tx_pointer: String,
+    pub contract_id: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionOutputContract {
+    pub input_index: u32,
+    pub balance_root: HexBytes,
+    pub state_root: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Policies {
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub tip: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub witness_limit: Option<u64>,
+    pub maturity: Option<u32>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub max_fee: Option<u64>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Transaction {
+    pub index: ItemIndex,
+    pub hash: HexBytes,
+    #[serde(rename = "type")]
+    pub r#type: String,
+    pub input_asset_ids: Option<Vec<HexBytes>>,
+    pub input_contracts: Option<Vec<HexBytes>>,
+    pub input_contract: Option<TransactionInputContract>,
+    pub output_contract: Option<TransactionOutputContract>,
+    pub policies: Option<Policies>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub script_gas_limit: Option<u64>,
+    pub maturity: Option<u32>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub mint_amount: Option<u64>,
+    pub mint_asset_id: Option<HexBytes>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub mint_gas_price: Option<u64>,
+    pub tx_pointer: Option<String>,
+    pub is_script: bool,
+    pub is_create: bool,
+    pub is_mint: bool,
+    pub is_upgrade: bool,
+    pub is_upload: bool,
+    pub witnesses: Option<Vec<HexBytes>>,
+    pub receipts_root: Option<HexBytes>,
+    pub status: JsonValue,
+    pub script: Option<HexBytes>,
+    pub script_data: Option<HexBytes>,
+    pub bytecode_witness_index: Option<u32>,
+    pub bytecode_root: Option<HexBytes>,
+    pub salt: Option<HexBytes>,
+    pub storage_slots: Option<Vec<HexBytes>>,
+    pub raw_payload: Option<HexBytes>,
+    pub subsection_index: Option<u32>,
+    pub subsections_number: Option<u32>,
+    pub proof_set: Option<Vec<HexBytes>>,
+    pub upgrade_purpose: Option<JsonValue>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+    pub index: ItemIndex,
+    pub transaction_index: ItemIndex,
+    pub receipt_type: String,
+    pub contract: Option<HexBytes>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub pc: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub is: Option<u64>,
+    pub to: Option<HexBytes>,
+    pub to_address: Option<HexBytes>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub amount: Option<u64>,
+    pub asset_id: Option<HexBytes>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub gas: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub param1: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub param2: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub val: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub ptr: Option<u64>,
+    pub digest: Option<HexBytes>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub reason: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub ra: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub rb: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub rc: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub rd: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub len: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub result: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub gas_used: Option<u64>,
+    pub data: Option<HexBytes>,
+    pub sender: Option<HexBytes>,
+    pub recipient: Option<HexBytes>,
+    pub nonce: Option<HexBytes>,
+    pub contract_id: Option<HexBytes>,
+    pub sub_id: Option<HexBytes>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputCoin {
+    pub utxo_id: HexBytes,
+    pub owner: HexBytes,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string")]
+    pub amount: u64,
+    pub asset_id: HexBytes,
+    pub tx_pointer: String,
+    pub witness_index: u32,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string")]
+    pub predicate_gas_used: u64,
+    pub predicate: HexBytes,
+    pub predicate_data: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputContract {
+    pub utxo_id: HexBytes,
+    pub balance_root: HexBytes,
+    pub state_root: HexBytes,
+    pub tx_pointer: String,
+    pub contract_id: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InputMessage {
+    pub sender: HexBytes,
+    pub recipient: HexBytes,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string")]
+    pub amount: u64,
+    pub nonce: HexBytes,
+    pub witness_index: u32,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string")]
+    pub predicate_gas_used: u64,
+    pub data: HexBytes,
+    pub predicate: HexBytes,
+    pub predicate_data: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+pub enum InputKind {
+    InputCoin(InputCoin),
+    InputContract(InputContract),
+    InputMessage(InputMessage)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionInput {
+    pub index: ItemIndex,
+    pub transaction_index: ItemIndex,
+    #[serde(flatten)]
+    pub kind: InputKind
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CoinOutput {
+    pub to: HexBytes,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string")]
+    pub amount: u64,
+    pub asset_id: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractOutput {
+    pub input_index: u32,
+    pub balance_root: HexBytes,
+    pub state_root: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContractCreated {
+    pub contract: HexBytes,
+    pub state_root: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(tag = "type")]
+pub enum OutputKind {
+    CoinOutput(CoinOutput),
+    ContractOutput(ContractOutput),
+    ChangeOutput(CoinOutput),
+    VariableOutput(CoinOutput),
+    ContractCreated(ContractCreated)
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionOutput {
+    pub index: ItemIndex,
+    pub transaction_index: ItemIndex,
+    #[serde(flatten)]
+    pub kind: OutputKind
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Block {
+    pub header: BlockHeader,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+    #[serde(default)]
+    pub receipts: Vec<Receipt>,
+    #[serde(default)]
+    pub inputs: Vec<TransactionInput>,
+    #[serde(default)]
+    pub outputs: Vec<TransactionOutput>
+}
+
+impl sqd_primitives::Block for Block {
+    fn number(&self) -> BlockNumber {
+        self.header.height
+    }
+
+    fn hash(&self) -> &str {
+        &self.header.hash
+    }
+
+    fn parent_number(&self) -> BlockNumber {
+        self.header.height.saturating_sub(1)
+    }
+
+    fn parent_hash(&self) -> &str {
+        &self.header.parent_hash
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        let unix_seconds = self.header.time.checked_sub(TAI64_UNIX_EPOCH)?;
+        i64::try_from(unix_seconds).ok()?.checked_mul(1000)
+    }
+}
@@ -0,0 +1,51 @@
+use sqd_array::builder::{UInt32Builder, UInt64Builder};
+use sqd_data_core::table_builder;
+
+use crate::fuel::{model::BlockHeader, tables::common::*};
+
+table_builder! {
+    BlockBuilder {
+        number: UInt64Builder,
+        hash: HexBytesBuilder,
+        parent_hash: HexBytesBuilder,
+        da_height: UInt64Builder,
+        consensus_parameters_version: UInt32Builder,
+        state_transition_bytecode_version: UInt32Builder,
+        transactions_count: UInt64Builder,
+        message_receipt_count: UInt64Builder,
+        transactions_root: HexBytesBuilder,
+        message_outbox_root: HexBytesBuilder,
+        event_inbox_root: HexBytesBuilder,
+        prev_root: HexBytesBuilder,
+        time: UInt64Builder,
+        application_hash: HexBytesBuilder,
+    }
+
+    description(d) {
+        d.downcast.block_number = vec!["number"];
+        d.sort_key = vec!["number"];
+        d.options.add_stats("number");
+        d.options.row_group_size = 5_000;
+    }
+}
+
+impl BlockBuilder {
+    pub fn push(&mut self, row: &BlockHeader) {
+        self.number.append(row.height);
+        self.hash.append(&row.hash);
+        self.parent_hash.append(&row.parent_hash);
+        self.da_height.append(row.da_height);
+        self.consensus_parameters_version
+            .append(row.consensus_parameters_version);
+        self.state_transition_bytecode_version
+            .append(row.state_transition_bytecode_version);
+        self.transactions_count.append(row.transactions_count);
+        self.message_receipt_count.append(row.message_receipt_count);
+        self.transactions_root.append(&row.transactions_root);
+        self.message_outbox_root.append(&row.message_outbox_root);
+        self.event_inbox_root.append(&row.event_inbox_root);
+        self.prev_root.append(&row.prev_root);
+        self.time.append(row.time);
+        self.application_hash.append(&row.application_hash);
+    }
+}
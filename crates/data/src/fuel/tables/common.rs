@@ -0,0 +1,5 @@
+use sqd_array::builder::{ListBuilder, StringBuilder};
+
+pub type HexBytesBuilder = StringBuilder;
+pub type JsonBuilder = StringBuilder;
+pub type HexBytesListBuilder = ListBuilder<HexBytesBuilder>;
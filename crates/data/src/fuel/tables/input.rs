@@ -0,0 +1,125 @@
+use sqd_array::builder::{StringBuilder, UInt32Builder, UInt64Builder};
+use sqd_data_core::table_builder;
+
+use crate::fuel::{
+    model::{Block, InputKind, TransactionInput},
+    tables::common::*
+};
+
+table_builder! {
+    InputBuilder {
+        block_number: UInt64Builder,
+        transaction_index: UInt32Builder,
+        index: UInt32Builder,
+        r#type: StringBuilder,
+        coin_utxo_id: HexBytesBuilder,
+        coin_owner: HexBytesBuilder,
+        coin_amount: UInt64Builder,
+        coin_asset_id: HexBytesBuilder,
+        coin_tx_pointer: StringBuilder,
+        coin_witness_index: UInt32Builder,
+        coin_predicate_gas_used: UInt64Builder,
+        coin_predicate: HexBytesBuilder,
+        coin_predicate_data: HexBytesBuilder,
+        contract_utxo_id: HexBytesBuilder,
+        contract_balance_root: HexBytesBuilder,
+        contract_state_root: HexBytesBuilder,
+        contract_tx_pointer: StringBuilder,
+        contract_contract_id: HexBytesBuilder,
+        message_sender: HexBytesBuilder,
+        message_recipient: HexBytesBuilder,
+        message_amount: UInt64Builder,
+        message_nonce: HexBytesBuilder,
+        message_witness_index: UInt32Builder,
+        message_predicate_gas_used: UInt64Builder,
+        message_data: HexBytesBuilder,
+        message_predicate: HexBytesBuilder,
+        message_predicate_data: HexBytesBuilder,
+
+        coin_predicate_size: UInt64Builder,
+        message_predicate_size: UInt64Builder,
+    }
+
+    description(d) {
+        d.downcast.block_number = vec!["block_number"];
+        d.downcast.item_index = vec!["transaction_index", "index"];
+        d.sort_key = vec!["type", "coin_owner", "coin_asset_id", "block_number", "transaction_index", "index"];
+        d.options.add_stats("block_number");
+        d.options.add_stats("transaction_index");
+        d.options.add_stats("type");
+        d.options.add_stats("coin_owner");
+        d.options.add_stats("coin_asset_id");
+        d.options.add_stats("contract_contract_id");
+        d.options.add_stats("message_sender");
+        d.options.add_stats("message_recipient");
+        d.options.use_dictionary("type");
+        d.options.use_dictionary("coin_asset_id");
+        d.options.row_group_size = 10_000;
+    }
+}
+
+impl InputBuilder {
+    pub fn push(&mut self, block: &Block, row: &TransactionInput) {
+        self.block_number.append(block.header.height);
+        self.transaction_index.append(row.transaction_index);
+        self.index.append(row.index);
+
+        let (coin, contract, message) = match &row.kind {
+            InputKind::InputCoin(coin) => {
+                self.r#type.append("InputCoin");
+                (Some(coin), None, None)
+            }
+            InputKind::InputContract(contract) => {
+                self.r#type.append("InputContract");
+                (None, Some(contract), None)
+            }
+            InputKind::InputMessage(message) => {
+                self.r#type.append("InputMessage");
+                (None, None, Some(message))
+            }
+        };
+
+        self.coin_utxo_id.append_option(coin.map(|c| c.utxo_id.as_str()));
+        self.coin_owner.append_option(coin.map(|c| c.owner.as_str()));
+        self.coin_amount.append_option(coin.map(|c| c.amount));
+        self.coin_asset_id.append_option(coin.map(|c| c.asset_id.as_str()));
+        self.coin_tx_pointer.append_option(coin.map(|c| c.tx_pointer.as_str()));
+        self.coin_witness_index.append_option(coin.map(|c| c.witness_index));
+        self.coin_predicate_gas_used
+            .append_option(coin.map(|c| c.predicate_gas_used));
+        self.coin_predicate.append_option(coin.map(|c| c.predicate.as_str()));
+        self.coin_predicate_data
+            .append_option(coin.map(|c| c.predicate_data.as_str()));
+
+        self.contract_utxo_id
+            .append_option(contract.map(|c| c.utxo_id.as_str()));
+        self.contract_balance_root
+            .append_option(contract.map(|c| c.balance_root.as_str()));
+        self.contract_state_root
+            .append_option(contract.map(|c| c.state_root.as_str()));
+        self.contract_tx_pointer
+            .append_option(contract.map(|c| c.tx_pointer.as_str()));
+        self.contract_contract_id
+            .append_option(contract.map(|c| c.contract_id.as_str()));
+
+        self.message_sender.append_option(message.map(|m| m.sender.as_str()));
+        self.message_recipient
+            .append_option(message.map(|m| m.recipient.as_str()));
+        self.message_amount.append_option(message.map(|m| m.amount));
+        self.message_nonce.append_option(message.map(|m| m.nonce.as_str()));
+        self.message_witness_index
+            .append_option(message.map(|m| m.witness_index));
+        self.message_predicate_gas_used
+            .append_option(message.map(|m| m.predicate_gas_used));
+        self.message_data.append_option(message.map(|m| m.data.as_str()));
+        self.message_predicate
+            .append_option(message.map(|m| m.predicate.as_str()));
+        self.message_predicate_data
+            .append_option(message.map(|m| m.predicate_data.as_str()));
+
+        self.coin_predicate_size
+            .append(coin.map_or(0, |c| c.predicate.len() as u64));
+        self.message_predicate_size
+            .append(message.map_or(0, |m| m.predicate.len() as u64));
+    }
+}
@@ -0,0 +1,51 @@
+mod block;
+mod common;
+mod input;
+mod output;
+mod receipt;
+mod transaction;
+
+pub use block::*;
+pub use input::*;
+pub use output::*;
+pub use receipt::*;
+use sqd_data_core::chunk_builder;
+pub use transaction::*;
+
+use super::model::Block;
+
+chunk_builder! {
+    FuelChunkBuilder {
+        blocks: BlockBuilder,
+        transactions: TransactionBuilder,
+        receipts: ReceiptBuilder,
+        inputs: InputBuilder,
+        outputs: OutputBuilder,
+    }
+}
+
+impl sqd_data_core::BlockChunkBuilder for FuelChunkBuilder {
+    type Block = Block;
+
+    fn push(&mut self, block: &Self::Block) -> anyhow::Result<()> {
+        self.blocks.push(&block.header);
+
+        for row in block.transactions.iter() {
+            self.transactions.push(block, row);
+        }
+
+        for row in block.receipts.iter() {
+            self.receipts.push(block, row);
+        }
+
+        for row in block.inputs.iter() {
+            self.inputs.push(block, row);
+        }
+
+        for row in block.outputs.iter() {
+            self.outputs.push(block, row);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,97 @@
+use sqd_array::builder::{StringBuilder, UInt32Builder, UInt64Builder};
+use sqd_data_core::table_builder;
+
+use crate::fuel::{
+    model::{Block, OutputKind, TransactionOutput},
+    tables::common::*
+};
+
+table_builder! {
+    OutputBuilder {
+        block_number: UInt64Builder,
+        transaction_index: UInt32Builder,
+        index: UInt32Builder,
+        r#type: StringBuilder,
+        coin_to: HexBytesBuilder,
+        coin_amount: UInt64Builder,
+        coin_asset_id: HexBytesBuilder,
+        contract_input_index: UInt32Builder,
+        contract_balance_root: HexBytesBuilder,
+        contract_state_root: HexBytesBuilder,
+        change_to: HexBytesBuilder,
+        change_amount: UInt64Builder,
+        change_asset_id: HexBytesBuilder,
+        variable_to: HexBytesBuilder,
+        variable_amount: UInt64Builder,
+        variable_asset_id: HexBytesBuilder,
+        contract_created_contract: HexBytesBuilder,
+        contract_created_state_root: HexBytesBuilder,
+    }
+
+    description(d) {
+        d.downcast.block_number = vec!["block_number"];
+        d.downcast.item_index = vec!["transaction_index", "index", "contract_input_index"];
+        d.sort_key = vec!["type", "block_number", "transaction_index", "index"];
+        d.options.add_stats("block_number");
+        d.options.add_stats("transaction_index");
+        d.options.add_stats("type");
+        d.options.use_dictionary("type");
+        d.options.row_group_size = 10_000;
+    }
+}
+
+impl OutputBuilder {
+    pub fn push(&mut self, block: &Block, row: &TransactionOutput) {
+        self.block_number.append(block.header.height);
+        self.transaction_index.append(row.transaction_index);
+        self.index.append(row.index);
+
+        let (mut coin, mut contract, mut change, mut variable, mut created) = (None, None, None, None, None);
+        match &row.kind {
+            OutputKind::CoinOutput(output) => {
+                self.r#type.append("CoinOutput");
+                coin = Some(output);
+            }
+            OutputKind::ContractOutput(output) => {
+                self.r#type.append("ContractOutput");
+                contract = Some(output);
+            }
+            OutputKind::ChangeOutput(output) => {
+                self.r#type.append("ChangeOutput");
+                change = Some(output);
+            }
+            OutputKind::VariableOutput(output) => {
+                self.r#type.append("VariableOutput");
+                variable = Some(output);
+            }
+            OutputKind::ContractCreated(output) => {
+                self.r#type.append("ContractCreated");
+                created = Some(output);
+            }
+        }
+
+        self.coin_to.append_option(coin.map(|o| o.to.as_str()));
+        self.coin_amount.append_option(coin.map(|o| o.amount));
+        self.coin_asset_id.append_option(coin.map(|o| o.asset_id.as_str()));
+
+        self.contract_input_index.append_option(contract.map(|o| o.input_index));
+        self.contract_balance_root
+            .append_option(contract.map(|o| o.balance_root.as_str()));
+        self.contract_state_root
+            .append_option(contract.map(|o| o.state_root.as_str()));
+
+        self.change_to.append_option(change.map(|o| o.to.as_str()));
+        self.change_amount.append_option(change.map(|o| o.amount));
+        self.change_asset_id.append_option(change.map(|o| o.asset_id.as_str()));
+
+        self.variable_to.append_option(variable.map(|o| o.to.as_str()));
+        self.variable_amount.append_option(variable.map(|o| o.amount));
+        self.variable_asset_id
+            .append_option(variable.map(|o| o.asset_id.as_str()));
+
+        self.contract_created_contract
+            .append_option(created.map(|o| o.contract.as_str()));
+        self.contract_created_state_root
+            .append_option(created.map(|o| o.state_root.as_str()));
+    }
+}
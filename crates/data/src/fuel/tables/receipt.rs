@@ -0,0 +1,94 @@
+use sqd_array::builder::{StringBuilder, UInt32Builder, UInt64Builder};
+use sqd_data_core::table_builder;
+
+use crate::fuel::{
+    model::{Block, Receipt},
+    tables::common::*
+};
+
+table_builder! {
+    ReceiptBuilder {
+        block_number: UInt64Builder,
+        transaction_index: UInt32Builder,
+        index: UInt32Builder,
+        receipt_type: StringBuilder,
+        contract: HexBytesBuilder,
+        pc: UInt64Builder,
+        is: UInt64Builder,
+        to: HexBytesBuilder,
+        to_address: HexBytesBuilder,
+        amount: UInt64Builder,
+        asset_id: HexBytesBuilder,
+        gas: UInt64Builder,
+        param1: UInt64Builder,
+        param2: UInt64Builder,
+        val: UInt64Builder,
+        ptr: UInt64Builder,
+        digest: HexBytesBuilder,
+        reason: UInt64Builder,
+        ra: UInt64Builder,
+        rb: UInt64Builder,
+        rc: UInt64Builder,
+        rd: UInt64Builder,
+        len: UInt64Builder,
+        result: UInt64Builder,
+        gas_used: UInt64Builder,
+        data: HexBytesBuilder,
+        sender: HexBytesBuilder,
+        recipient: HexBytesBuilder,
+        nonce: HexBytesBuilder,
+        contract_id: HexBytesBuilder,
+        sub_id: HexBytesBuilder,
+        data_size: UInt64Builder,
+    }
+
+    description(d) {
+        d.downcast.block_number = vec!["block_number"];
+        d.downcast.item_index = vec!["transaction_index", "index"];
+        d.sort_key = vec!["receipt_type", "contract", "block_number", "transaction_index", "index"];
+        d.options.add_stats("block_number");
+        d.options.add_stats("transaction_index");
+        d.options.add_stats("receipt_type");
+        d.options.add_stats("contract");
+        d.options.use_dictionary("receipt_type");
+        d.options.use_dictionary("contract");
+        d.options.row_group_size = 10_000;
+    }
+}
+
+impl ReceiptBuilder {
+    pub fn push(&mut self, block: &Block, row: &Receipt) {
+        self.block_number.append(block.header.height);
+        self.transaction_index.append(row.transaction_index);
+        self.index.append(row.index);
+        self.receipt_type.append(&row.receipt_type);
+        self.contract.append_option(row.contract.as_deref());
+        self.pc.append_option(row.pc);
+        self.is.append_option(row.is);
+        self.to.append_option(row.to.as_deref());
+        self.to_address.append_option(row.to_address.as_deref());
+        self.amount.append_option(row.amount);
+        self.asset_id.append_option(row.asset_id.as_deref());
+        self.gas.append_option(row.gas);
+        self.param1.append_option(row.param1);
+        self.param2.append_option(row.param2);
+        self.val.append_option(row.val);
+        self.ptr.append_option(row.ptr);
+        self.digest.append_option(row.digest.as_deref());
+        self.reason.append_option(row.reason);
+        self.ra.append_option(row.ra);
+        self.rb.append_option(row.rb);
+        self.rc.append_option(row.rc);
+        self.rd.append_option(row.rd);
+        self.len.append_option(row.len);
+        self.result.append_option(row.result);
+        self.gas_used.append_option(row.gas_used);
+        self.data.append_option(row.data.as_deref());
+        self.sender.append_option(row.sender.as_deref());
+        self.recipient.append_option(row.recipient.as_deref());
+        self.nonce.append_option(row.nonce.as_deref());
+        self.contract_id.append_option(row.contract_id.as_deref());
+        self.sub_id.append_option(row.sub_id.as_deref());
+        self.data_size.append(row.data.as_ref().map_or(0, |s| s.len() as u64));
+    }
+}
@@ -0,0 +1,181 @@
+use sqd_array::builder::{BooleanBuilder, StringBuilder, UInt32Builder, UInt64Builder};
+use sqd_data_core::table_builder;
+
+use crate::{
+    fuel::{
+        model::{Block, Transaction},
+        tables::common::*
+    },
+    types::HexBytes
+};
+
+table_builder! {
+    TransactionBuilder {
+        block_number: UInt64Builder,
+        index: UInt32Builder,
+        hash: HexBytesBuilder,
+        r#type: StringBuilder,
+        input_asset_ids: HexBytesListBuilder,
+        input_contracts: HexBytesListBuilder,
+        input_contract_utxo_id: HexBytesBuilder,
+        input_contract_balance_root: HexBytesBuilder,
+        input_contract_state_root: HexBytesBuilder,
+        input_contract_tx_pointer: StringBuilder,
+        input_contract_contract_id: HexBytesBuilder,
+        output_contract_input_index: UInt32Builder,
+        output_contract_balance_root: HexBytesBuilder,
+        output_contract_state_root: HexBytesBuilder,
+        policies_tip: UInt64Builder,
+        policies_witness_limit: UInt64Builder,
+        policies_maturity: UInt32Builder,
+        policies_max_fee: UInt64Builder,
+        script_gas_limit: UInt64Builder,
+        maturity: UInt32Builder,
+        mint_amount: UInt64Builder,
+        mint_asset_id: HexBytesBuilder,
+        mint_gas_price: UInt64Builder,
+        tx_pointer: StringBuilder,
+        is_script: BooleanBuilder,
+        is_create: BooleanBuilder,
+        is_mint: BooleanBuilder,
+        is_upgrade: BooleanBuilder,
+        is_upload: BooleanBuilder,
+        witnesses: HexBytesListBuilder,
+        receipts_root: HexBytesBuilder,
+        status: JsonBuilder,
+        script: HexBytesBuilder,
+        script_data: HexBytesBuilder,
+        bytecode_witness_index: UInt32Builder,
+        bytecode_root: HexBytesBuilder,
+        salt: HexBytesBuilder,
+        storage_slots: HexBytesListBuilder,
+        raw_payload: HexBytesBuilder,
+        subsection_index: UInt32Builder,
+        subsections_number: UInt32Builder,
+        proof_set: HexBytesListBuilder,
+        upgrade_purpose: JsonBuilder,
+
+        input_asset_ids_size: UInt64Builder,
+        input_contracts_size: UInt64Builder,
+        witnesses_size: UInt64Builder,
+        storage_slots_size: UInt64Builder,
+        proof_set_size: UInt64Builder,
+        script_data_size: UInt64Builder,
+        raw_payload_size: UInt64Builder,
+    }
+
+    description(d) {
+        d.downcast.block_number = vec!["block_number"];
+        d.downcast.item_index = vec!["index"];
+        d.sort_key = vec!["type", "block_number", "index"];
+        d.options.add_stats("block_number");
+        d.options.add_stats("index");
+        d.options.add_stats("type");
+        d.options.use_dictionary("type");
+        d.options.row_group_size = 10_000;
+    }
+}
+
+impl TransactionBuilder {
+    pub fn push(&mut self, block: &Block, row: &Transaction) {
+        self.block_number.append(block.header.height);
+        self.index.append(row.index);
+        self.hash.append(&row.hash);
+        self.r#type.append(&row.r#type);
+
+        let input_asset_ids_size = push_list(&mut self.input_asset_ids, row.input_asset_ids.as_deref());
+        let input_contracts_size = push_list(&mut self.input_contracts, row.input_contracts.as_deref());
+
+        let input_contract = row.input_contract.as_ref();
+        self.input_contract_utxo_id
+            .append_option(input_contract.map(|c| c.utxo_id.as_str()));
+        self.input_contract_balance_root
+            .append_option(input_contract.map(|c| c.balance_root.as_str()));
+        self.input_contract_state_root
+            .append_option(input_contract.map(|c| c.state_root.as_str()));
+        self.input_contract_tx_pointer
+            .append_option(input_contract.map(|c| c.tx_pointer.as_str()));
+        self.input_contract_contract_id
+            .append_option(input_contract.map(|c| c.contract_id.as_str()));
+
+        let output_contract = row.output_contract.as_ref();
+        self.output_contract_input_index
+            .append_option(output_contract.map(|c| c.input_index));
+        self.output_contract_balance_root
+            .append_option(output_contract.map(|c| c.balance_root.as_str()));
+        self.output_contract_state_root
+            .append_option(output_contract.map(|c| c.state_root.as_str()));
+
+        let policies = row.policies.as_ref();
+        self.policies_tip.append_option(policies.and_then(|p| p.tip));
+        self.policies_witness_limit
+            .append_option(policies.and_then(|p| p.witness_limit));
+        self.policies_maturity.append_option(policies.and_then(|p| p.maturity));
+        self.policies_max_fee.append_option(policies.and_then(|p| p.max_fee));
+
+        self.script_gas_limit.append_option(row.script_gas_limit);
+        self.maturity.append_option(row.maturity);
+        self.mint_amount.append_option(row.mint_amount);
+        self.mint_asset_id.append_option(row.mint_asset_id.as_deref());
+        self.mint_gas_price.append_option(row.mint_gas_price);
+        self.tx_pointer.append_option(row.tx_pointer.as_deref());
+        self.is_script.append(row.is_script);
+        self.is_create.append(row.is_create);
+        self.is_mint.append(row.is_mint);
+        self.is_upgrade.append(row.is_upgrade);
+        self.is_upload.append(row.is_upload);
+
+        let witnesses_size = push_list(&mut self.witnesses, row.witnesses.as_deref());
+
+        self.receipts_root.append_option(row.receipts_root.as_deref());
+        self.status.append(&serde_json::to_string(&row.status).unwrap());
+        self.script.append_option(row.script.as_deref());
+        self.script_data.append_option(row.script_data.as_deref());
+        self.bytecode_witness_index.append_option(row.bytecode_witness_index);
+        self.bytecode_root.append_option(row.bytecode_root.as_deref());
+        self.salt.append_option(row.salt.as_deref());
+
+        let storage_slots_size = push_list(&mut self.storage_slots, row.storage_slots.as_deref());
+
+        self.raw_payload.append_option(row.raw_payload.as_deref());
+        self.subsection_index.append_option(row.subsection_index);
+        self.subsections_number.append_option(row.subsections_number);
+
+        let proof_set_size = push_list(&mut self.proof_set, row.proof_set.as_deref());
+
+        let upgrade_purpose = row
+            .upgrade_purpose
+            .as_ref()
+            .map(|val| serde_json::to_string(val).unwrap());
+        self.upgrade_purpose.append_option(upgrade_purpose.as_deref());
+
+        self.input_asset_ids_size.append(input_asset_ids_size);
+        self.input_contracts_size.append(input_contracts_size);
+        self.witnesses_size.append(witnesses_size);
+        self.storage_slots_size.append(storage_slots_size);
+        self.proof_set_size.append(proof_set_size);
+        self.script_data_size
+            .append(row.script_data.as_ref().map_or(0, |s| s.len() as u64));
+        self.raw_payload_size
+            .append(row.raw_payload.as_ref().map_or(0, |s| s.len() as u64));
+    }
+}
+
+/// Appends an optional list and returns its total byte size
+fn push_list(builder: &mut HexBytesListBuilder, list: Option<&[HexBytes]>) -> u64 {
+    match list {
+        Some(list) => {
+            let mut size = 0;
+            for item in list.iter() {
+                builder.values().append(item);
+                size += item.len() as u64;
+            }
+            builder.append();
+            size
+        }
+        None => {
+            builder.append_null();
+            0
+        }
+    }
+}
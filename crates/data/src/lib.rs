@@ -1,7 +1,9 @@
 pub mod bitcoin;
 pub mod evm;
+pub mod fuel;
 pub mod hyperliquid_fills;
 pub mod hyperliquid_replica_cmds;
 pub mod solana;
+pub mod substrate;
 pub mod tron;
 mod types;
@@ -4,6 +4,7 @@ use sqd_array::builder::{
 };
 use sqd_bloom_filter::BloomFilter;
 use sqd_data_core::table_builder;
+use sqd_dataset::Codec;
 
 use crate::solana::{
     model::{AccountIndex, Block, Instruction},
@@ -95,6 +96,8 @@ table_builder! {
         d.options.add_stats("d16");
         d.options.add_stats("program_id");
         d.options.add_stats("block_number");
+        d.options.add_index("program_id");
+        d.options.add_bloom_filter("program_id");
         d.options.use_dictionary("program_id");
         d.options.use_dictionary("a0");
         d.options.use_dictionary("a1");
@@ -112,6 +115,7 @@ table_builder! {
         d.options.use_dictionary("a14");
         d.options.use_dictionary("a15");
         d.options.use_dictionary("rest_accounts.list.element");
+        d.options.set_codec("data", Codec::Zstd);
         d.options.row_group_size = 20_000;
     }
 }
@@ -0,0 +1,95 @@
+use serde::Deserialize;
+use sqd_primitives::{BlockNumber, ItemIndex};
+
+use crate::types::{HexBytes, JsonValue};
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockHeader {
+    pub number: BlockNumber,
+    pub hash: HexBytes,
+    pub parent_hash: HexBytes,
+    pub state_root: HexBytes,
+    pub extrinsics_root: HexBytes,
+    pub digest: JsonValue,
+    pub spec_name: String,
+    pub spec_version: u32,
+    pub impl_name: String,
+    pub impl_version: u32,
+    pub timestamp: Option<i64>,
+    pub validator: Option<HexBytes>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Extrinsic {
+    pub index: ItemIndex,
+    pub version: u32,
+    pub signature: Option<JsonValue>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub fee: Option<u64>,
+    #[serde(deserialize_with = "sqd_data_core::serde::decode_string_option", default)]
+    pub tip: Option<u64>,
+    pub error: Option<JsonValue>,
+    pub success: bool,
+    pub hash: HexBytes
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Call {
+    pub extrinsic_index: ItemIndex,
+    pub address: Vec<u32>,
+    pub name: String,
+    pub args: JsonValue,
+    pub origin: Option<JsonValue>,
+    pub error: Option<JsonValue>,
+    pub success: bool
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Event {
+    pub index: ItemIndex,
+    pub extrinsic_index: Option<ItemIndex>,
+    pub call_address: Option<Vec<u32>>,
+    pub name: String,
+    pub args: JsonValue,
+    pub phase: String,
+    #[serde(default)]
+    pub topics: Vec<HexBytes>
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Block {
+    pub header: BlockHeader,
+    #[serde(default)]
+    pub extrinsics: Vec<Extrinsic>,
+    #[serde(default)]
+    pub calls: Vec<Call>,
+    #[serde(default)]
+    pub events: Vec<Event>
+}
+
+impl sqd_primitives::Block for Block {
+    fn number(&self) -> BlockNumber {
+        self.header.number
+    }
+
+    fn hash(&self) -> &str {
+        &self.header.hash
+    }
+
+    fn parent_number(&self) -> BlockNumber {
+        self.header.number.saturating_sub(1)
+    }
+
+    fn parent_hash(&self) -> &str {
+        &self.header.parent_hash
+    }
+
+    fn timestamp(&self) -> Option<i64> {
+        self.header.timestamp
+    }
+}
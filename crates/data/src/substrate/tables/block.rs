@@ -0,0 +1,47 @@
+use sqd_array::builder::{StringBuilder, TimestampMillisecondBuilder, UInt32Builder, UInt64Builder};
+use sqd_data_core::table_builder;
+
+use crate::substrate::{model::BlockHeader, tables::common::*};
+
+table_builder! {
+    BlockBuilder {
+        number: UInt64Builder,
+        hash: HexBytesBuilder,
+        parent_hash: HexBytesBuilder,
+        state_root: HexBytesBuilder,
+        extrinsics_root: HexBytesBuilder,
+        digest: JsonBuilder,
+        spec_name: StringBuilder,
+        spec_version: UInt32Builder,
+        impl_name: StringBuilder,
+        impl_version: UInt32Builder,
+        timestamp: TimestampMillisecondBuilder,
+        validator: HexBytesBuilder,
+    }
+
+    description(d) {
+        d.downcast.block_number = vec!["number"];
+        d.sort_key = vec!["number"];
+        d.options.add_stats("number");
+        d.options.use_dictionary("spec_name");
+        d.options.use_dictionary("impl_name");
+        d.options.row_group_size = 5_000;
+    }
+}
+
+impl BlockBuilder {
+    pub fn push(&mut self, row: &BlockHeader) {
+        self.number.append(row.number);
+        self.hash.append(&row.hash);
+        self.parent_hash.append(&row.parent_hash);
+        self.state_root.append(&row.state_root);
+        self.extrinsics_root.append(&row.extrinsics_root);
+        self.digest.append(&to_json_string(&row.digest));
+        self.spec_name.append(&row.spec_name);
+        self.spec_version.append(row.spec_version);
+        self.impl_name.append(&row.impl_name);
+        self.impl_version.append(row.impl_version);
+        self.timestamp.append_option(row.timestamp);
+        self.validator.append_option(row.validator.as_deref());
+    }
+}
@@ -0,0 +1,102 @@
+use sqd_array::builder::{BooleanBuilder, StringBuilder, UInt32Builder, UInt64Builder};
+use sqd_data_core::table_builder;
+
+use crate::{
+    substrate::{
+        model::{Block, Call},
+        tables::common::*
+    },
+    types::JsonValue
+};
+
+table_builder! {
+    CallBuilder {
+        block_number: UInt64Builder,
+        extrinsic_index: UInt32Builder,
+        address: CallAddressListBuilder,
+        name: StringBuilder,
+        args: JsonBuilder,
+        origin: JsonBuilder,
+        error: JsonBuilder,
+        success: BooleanBuilder,
+        args_size: UInt64Builder,
+
+        _ethereum_transact_to: HexBytesBuilder,
+        _ethereum_transact_sighash: HexBytesBuilder,
+    }
+
+    description(d) {
+        d.downcast.block_number = vec!["block_number"];
+        d.downcast.item_index = vec!["extrinsic_index", "address"];
+        d.sort_key = vec!["name", "block_number", "extrinsic_index", "address"];
+        d.options.add_stats("block_number");
+        d.options.add_stats("name");
+        d.options.add_stats("_ethereum_transact_to");
+        d.options.add_stats("_ethereum_transact_sighash");
+        d.options.use_dictionary("name");
+        d.options.row_group_size = 10_000;
+    }
+}
+
+impl CallBuilder {
+    pub fn push(&mut self, block: &Block, row: &Call) {
+        self.block_number.append(block.header.number);
+        self.extrinsic_index.append(row.extrinsic_index);
+
+        for idx in row.address.iter() {
+            self.address.values().append(*idx);
+        }
+        self.address.append();
+
+        self.name.append(&row.name);
+
+        let args = to_json_string(&row.args);
+        self.args.append(&args);
+        self.origin
+            .append_option(row.origin.as_ref().map(to_json_string).as_deref());
+        self.error
+            .append_option(row.error.as_ref().map(to_json_string).as_deref());
+        self.success.append(row.success);
+        self.args_size.append(args.len() as u64);
+
+        let (to, sighash) = if row.name == "Ethereum.transact" {
+            ethereum_transact(&row.args)
+        } else {
+            (None, None)
+        };
+        self._ethereum_transact_to
+            .append_option(to.map(|s| s.to_ascii_lowercase()).as_deref());
+        self._ethereum_transact_sighash
+            .append_option(sighash.map(|s| s.to_ascii_lowercase()).as_deref());
+    }
+}
+
+/// Extracts the call target and the function selector from `Ethereum.transact` args.
+///
+/// Depending on the runtime version, the transaction is either a plain object
+/// or an enum of `Legacy`, `EIP2930` and `EIP1559` variants.
+fn ethereum_transact(args: &JsonValue) -> (Option<&str>, Option<&str>) {
+    let Some(tx) = args.get("transaction") else {
+        return (None, None);
+    };
+    let tx = if tx.get("__kind").is_some() {
+        match tx.get("value") {
+            Some(value) => value,
+            None => return (None, None)
+        }
+    } else {
+        tx
+    };
+
+    let to = match get_str(tx, &["action", "__kind"]) {
+        Some("Call") => get_str(tx, &["action", "value"]),
+        _ => None
+    };
+
+    let sighash = tx
+        .get("input")
+        .and_then(|input| input.as_str())
+        .and_then(|input| input.get(0..10));
+
+    (to, sighash)
+}
@@ -0,0 +1,16 @@
+use sqd_array::builder::{ListBuilder, StringBuilder, UInt32Builder};
+
+use crate::types::JsonValue;
+
+pub type HexBytesBuilder = StringBuilder;
+pub type JsonBuilder = StringBuilder;
+pub type CallAddressListBuilder = ListBuilder<UInt32Builder>;
+pub type TopicListBuilder = ListBuilder<HexBytesBuilder>;
+
+pub fn to_json_string(value: &JsonValue) -> String {
+    serde_json::to_string(value).unwrap()
+}
+
+pub fn get_str<'a>(value: &'a JsonValue, path: &[&str]) -> Option<&'a str> {
+    path.iter().try_fold(value, |val, key| val.get(key))?.as_str()
+}
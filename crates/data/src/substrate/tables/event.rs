@@ -0,0 +1,152 @@
+use sqd_array::builder::{StringBuilder, UInt32Builder, UInt64Builder};
+use sqd_data_core::table_builder;
+
+use crate::substrate::{
+    model::{Block, Event},
+    tables::common::*
+};
+
+table_builder! {
+    EventBuilder {
+        block_number: UInt64Builder,
+        index: UInt32Builder,
+        extrinsic_index: UInt32Builder,
+        call_address: CallAddressListBuilder,
+        name: StringBuilder,
+        args: JsonBuilder,
+        phase: StringBuilder,
+        topics: TopicListBuilder,
+        args_size: UInt64Builder,
+
+        _evm_log_address: HexBytesBuilder,
+        _evm_log_topic0: HexBytesBuilder,
+        _evm_log_topic1: HexBytesBuilder,
+        _evm_log_topic2: HexBytesBuilder,
+        _evm_log_topic3: HexBytesBuilder,
+        _contract_address: HexBytesBuilder,
+        _gear_program_id: HexBytesBuilder,
+        _revive_contract: HexBytesBuilder,
+        _revive_topic0: HexBytesBuilder,
+        _revive_topic1: HexBytesBuilder,
+        _revive_topic2: HexBytesBuilder,
+        _revive_topic3: HexBytesBuilder,
+    }
+
+    description(d) {
+        d.downcast.block_number = vec!["block_number"];
+        d.downcast.item_index = vec!["index", "extrinsic_index", "call_address"];
+        d.sort_key = vec!["name", "block_number", "index"];
+        d.options.add_stats("block_number");
+        d.options.add_stats("index");
+        d.options.add_stats("name");
+        d.options.add_stats("_evm_log_address");
+        d.options.add_stats("_evm_log_topic0");
+        d.options.add_stats("_contract_address");
+        d.options.add_stats("_gear_program_id");
+        d.options.add_stats("_revive_contract");
+        d.options.add_stats("_revive_topic0");
+        d.options.use_dictionary("name");
+        d.options.use_dictionary("phase");
+        d.options.row_group_size = 20_000;
+    }
+}
+
+impl EventBuilder {
+    pub fn push(&mut self, block: &Block, row: &Event) {
+        self.block_number.append(block.header.number);
+        self.index.append(row.index);
+        self.extrinsic_index.append_option(row.extrinsic_index);
+
+        if let Some(address) = &row.call_address {
+            for idx in address.iter() {
+                self.call_address.values().append(*idx);
+            }
+            self.call_address.append();
+        } else {
+            self.call_address.append_null();
+        }
+
+        self.name.append(&row.name);
+
+        let args = to_json_string(&row.args);
+        self.args.append(&args);
+        self.phase.append(&row.phase);
+
+        for topic in row.topics.iter() {
+            self.topics.values().append(topic);
+        }
+        self.topics.append();
+
+        self.args_size.append(args.len() as u64);
+
+        self.push_evm_log(row);
+        self.push_contracts_event(row);
+        self.push_gear_message(row);
+        self.push_revive_event(row);
+    }
+
+    fn push_evm_log(&mut self, row: &Event) {
+        let log = if row.name == "EVM.Log" {
+            // older runtimes emit the log itself, newer ones wrap it into `{log: ...}`
+            Some(row.args.get("log").unwrap_or(&row.args))
+        } else {
+            None
+        };
+
+        let address = log.and_then(|log| get_str(log, &["address"]));
+        self._evm_log_address
+            .append_option(address.map(|s| s.to_ascii_lowercase()).as_deref());
+
+        let topics = log.and_then(|log| log.get("topics"));
+        let topic = |idx: usize| {
+            topics
+                .and_then(|topics| topics.get(idx))
+                .and_then(|topic| topic.as_str())
+                .map(|topic| topic.to_ascii_lowercase())
+        };
+        self._evm_log_topic0.append_option(topic(0).as_deref());
+        self._evm_log_topic1.append_option(topic(1).as_deref());
+        self._evm_log_topic2.append_option(topic(2).as_deref());
+        self._evm_log_topic3.append_option(topic(3).as_deref());
+    }
+
+    fn push_contracts_event(&mut self, row: &Event) {
+        let contract = if row.name == "Contracts.ContractEmitted" {
+            get_str(&row.args, &["contract"])
+        } else {
+            None
+        };
+        self._contract_address.append_option(contract);
+    }
+
+    fn push_gear_message(&mut self, row: &Event) {
+        let program_id = match row.name.as_str() {
+            "Gear.UserMessageEnqueued" => get_str(&row.args, &["destination"]),
+            "Gear.UserMessageSent" => get_str(&row.args, &["message", "source"]),
+            _ => None
+        };
+        self._gear_program_id.append_option(program_id);
+    }
+
+    fn push_revive_event(&mut self, row: &Event) {
+        let is_revive = row.name == "Revive.ContractEmitted";
+
+        let contract = if is_revive {
+            get_str(&row.args, &["contract"])
+        } else {
+            None
+        };
+        self._revive_contract.append_option(contract);
+
+        let topics = if is_revive { row.args.get("topics") } else { None };
+        let topic = |idx: usize| {
+            topics
+                .and_then(|topics| topics.get(idx))
+                .and_then(|topic| topic.as_str())
+        };
+        self._revive_topic0.append_option(topic(0));
+        self._revive_topic1.append_option(topic(1));
+        self._revive_topic2.append_option(topic(2));
+        self._revive_topic3.append_option(topic(3));
+    }
+}
@@ -0,0 +1,46 @@
+use sqd_array::builder::{BooleanBuilder, UInt32Builder, UInt64Builder};
+use sqd_data_core::table_builder;
+
+use crate::substrate::{
+    model::{Block, Extrinsic},
+    tables::common::*
+};
+
+table_builder! {
+    ExtrinsicBuilder {
+        block_number: UInt64Builder,
+        index: UInt32Builder,
+        version: UInt32Builder,
+        signature: JsonBuilder,
+        fee: UInt64Builder,
+        tip: UInt64Builder,
+        error: JsonBuilder,
+        success: BooleanBuilder,
+        hash: HexBytesBuilder,
+    }
+
+    description(d) {
+        d.downcast.block_number = vec!["block_number"];
+        d.downcast.item_index = vec!["index"];
+        d.sort_key = vec!["block_number", "index"];
+        d.options.add_stats("block_number");
+        d.options.add_stats("index");
+        d.options.row_group_size = 10_000;
+    }
+}
+
+impl ExtrinsicBuilder {
+    pub fn push(&mut self, block: &Block, row: &Extrinsic) {
+        self.block_number.append(block.header.number);
+        self.index.append(row.index);
+        self.version.append(row.version);
+        self.signature
+            .append_option(row.signature.as_ref().map(to_json_string).as_deref());
+        self.fee.append_option(row.fee);
+        self.tip.append_option(row.tip);
+        self.error
+            .append_option(row.error.as_ref().map(to_json_string).as_deref());
+        self.success.append(row.success);
+        self.hash.append(&row.hash);
+    }
+}
@@ -11,9 +11,11 @@ use serde_json::{json, Value};
 use sqd_data::{
     bitcoin::{model as bitcoin, tables::BitcoinChunkBuilder},
     evm::{model as evm, tables::EvmChunkBuilder},
+    fuel::{model as fuel, tables::FuelChunkBuilder},
     hyperliquid_fills::{model as hyperliquid_fills, tables::HyperliquidFillsChunkBuilder},
     hyperliquid_replica_cmds::{model as hyperliquid_replica_cmds, tables::HyperliquidReplicaCmdsChunkBuilder},
     solana::{model as solana, tables::SolanaChunkBuilder},
+    substrate::{model as substrate, tables::SubstrateChunkBuilder},
     tron::{model as tron, tables::TronChunkBuilder}
 };
 use sqd_data_core::{BlockChunkBuilder, PreparedChunk};
@@ -661,3 +663,292 @@ fn hyperliquid_replica_commands_real_schema_is_lossless() {
         &[("blocks", 1), ("actions", 4)]
     );
 }
+
+#[test]
+fn substrate_real_schema_is_lossless() {
+    let block: substrate::Block = parse(json!({
+        "header": {
+            "number": 25_000_000,
+            "hash": hex(300),
+            "parentHash": hex(299),
+            "stateRoot": hex(301),
+            "extrinsicsRoot": hex(302),
+            "digest": {"logs": ["0x0642414245"]},
+            "specName": "polkadot",
+            "specVersion": 1_003_000,
+            "implName": "parity-polkadot",
+            "implVersion": 0,
+            "timestamp": 1_760_000_000_000i64,
+            "validator": hex(303)
+        },
+        "extrinsics": [
+            {
+                "index": 0,
+                "version": 4,
+                "success": true,
+                "hash": hex(310)
+            },
+            {
+                "index": 1,
+                "version": 4,
+                "signature": {
+                    "address": {"__kind": "Id", "value": hex(311)},
+                    "signature": {"__kind": "Sr25519", "value": "0x01"}
+                },
+                "fee": "1000",
+                "tip": "5",
+                "error": {"__kind": "Module", "value": {"index": 5, "error": "0x02000000"}},
+                "success": false,
+                "hash": hex(312)
+            }
+        ],
+        "calls": [
+            {
+                "extrinsicIndex": 0,
+                "address": [],
+                "name": "Timestamp.set",
+                "args": {"now": "1760000000000"},
+                "origin": {"__kind": "system", "value": {"__kind": "None"}},
+                "success": true
+            },
+            {
+                "extrinsicIndex": 1,
+                "address": [],
+                "name": "Utility.batch_all",
+                "args": {"calls": []},
+                "success": false,
+                "error": {"__kind": "Module", "value": {"index": 5, "error": "0x02000000"}}
+            },
+            {
+                "extrinsicIndex": 1,
+                "address": [0],
+                "name": "Balances.transfer_keep_alive",
+                "args": {"dest": hex(313), "value": "100"},
+                "success": false
+            }
+        ],
+        "events": [
+            {
+                "index": 0,
+                "extrinsicIndex": 0,
+                "callAddress": [],
+                "name": "System.ExtrinsicSuccess",
+                "args": {"dispatchInfo": {"weight": "1"}},
+                "phase": "ApplyExtrinsic"
+            },
+            {
+                "index": 1,
+                "name": "ParaInclusion.CandidateIncluded",
+                "args": ["0x03"],
+                "phase": "Finalization",
+                "topics": [hex(314)]
+            }
+        ]
+    }));
+
+    assert_lossless(
+        SubstrateChunkBuilder::new(),
+        block,
+        &[("blocks", 1), ("extrinsics", 2), ("calls", 3), ("events", 2)]
+    );
+}
+
+#[test]
+fn fuel_real_schema_is_lossless() {
+    let block: fuel::Block = parse(json!({
+        "header": {
+            "height": 12_000_000,
+            "hash": hex(400),
+            "parentHash": hex(399),
+            "daHeight": "21000000",
+            "consensusParametersVersion": 1,
+            "stateTransitionBytecodeVersion": 2,
+            "transactionsCount": "2",
+            "messageReceiptCount": "0",
+            "transactionsRoot": hex(401),
+            "messageOutboxRoot": hex(402),
+            "eventInboxRoot": hex(403),
+            "prevRoot": hex(404),
+            "time": "4611686020187387914",
+            "applicationHash": hex(405)
+        },
+        "transactions": [
+            {
+                "index": 0,
+                "hash": hex(410),
+                "type": "Script",
+                "inputAssetIds": [hex(411)],
+                "inputContracts": [hex(412)],
+                "policies": {"tip": "1", "witnessLimit": "1000", "maxFee": "50"},
+                "scriptGasLimit": "100000",
+                "isScript": true,
+                "isCreate": false,
+                "isMint": false,
+                "isUpgrade": false,
+                "isUpload": false,
+                "witnesses": ["0xaa"],
+                "receiptsRoot": hex(413),
+                "status": {"type": "SuccessStatus", "totalGas": "10", "totalFee": "2"},
+                "script": "0x24040000",
+                "scriptData": "0x00"
+            },
+            {
+                "index": 1,
+                "hash": hex(414),
+                "type": "Mint",
+                "inputContract": {
+                    "utxoId": hex(415),
+                    "balanceRoot": hex(416),
+                    "stateRoot": hex(417),
+                    "txPointer": "00b71b000000",
+                    "contractId": hex(412)
+                },
+                "outputContract": {
+                    "inputIndex": 0,
+                    "balanceRoot": hex(418),
+                    "stateRoot": hex(419)
+                },
+                "mintAmount": "2",
+                "mintAssetId": hex(411),
+                "mintGasPrice": "1",
+                "txPointer": "00b71b000001",
+                "isScript": false,
+                "isCreate": false,
+                "isMint": true,
+                "isUpgrade": false,
+                "isUpload": false,
+                "status": {"type": "SuccessStatus", "totalGas": "0", "totalFee": "0"}
+            }
+        ],
+        "receipts": [
+            {
+                "index": 0,
+                "transactionIndex": 0,
+                "receiptType": "CALL",
+                "contract": hex(420),
+                "to": hex(412),
+                "amount": "0",
+                "assetId": hex(411),
+                "gas": "90000",
+                "param1": "10480",
+                "param2": "10505",
+                "pc": "11008",
+                "is": "11008"
+            },
+            {
+                "index": 1,
+                "transactionIndex": 0,
+                "receiptType": "LOG_DATA",
+                "contract": hex(412),
+                "ra": "0",
+                "rb": "1",
+                "ptr": "67107840",
+                "len": "8",
+                "digest": hex(421),
+                "data": "0x0000000000000001",
+                "pc": "11100",
+                "is": "11008"
+            },
+            {
+                "index": 2,
+                "transactionIndex": 0,
+                "receiptType": "SCRIPT_RESULT",
+                "result": "0",
+                "gasUsed": "9000"
+            }
+        ],
+        "inputs": [
+            {
+                "index": 0,
+                "transactionIndex": 0,
+                "type": "InputCoin",
+                "utxoId": hex(430),
+                "owner": hex(431),
+                "amount": "100",
+                "assetId": hex(411),
+                "txPointer": "00b71a000000",
+                "witnessIndex": 0,
+                "predicateGasUsed": "0",
+                "predicate": "0x",
+                "predicateData": "0x"
+            },
+            {
+                "index": 1,
+                "transactionIndex": 0,
+                "type": "InputContract",
+                "utxoId": hex(432),
+                "balanceRoot": hex(433),
+                "stateRoot": hex(434),
+                "txPointer": "00b71a000001",
+                "contractId": hex(412)
+            },
+            {
+                "index": 2,
+                "transactionIndex": 0,
+                "type": "InputMessage",
+                "sender": hex(435),
+                "recipient": hex(431),
+                "amount": "7",
+                "nonce": hex(436),
+                "witnessIndex": 0,
+                "predicateGasUsed": "0",
+                "data": "0x01",
+                "predicate": "0x",
+                "predicateData": "0x"
+            }
+        ],
+        "outputs": [
+            {
+                "index": 0,
+                "transactionIndex": 0,
+                "type": "ContractOutput",
+                "inputIndex": 1,
+                "balanceRoot": hex(440),
+                "stateRoot": hex(441)
+            },
+            {
+                "index": 1,
+                "transactionIndex": 0,
+                "type": "ChangeOutput",
+                "to": hex(431),
+                "amount": "93",
+                "assetId": hex(411)
+            },
+            {
+                "index": 2,
+                "transactionIndex": 0,
+                "type": "VariableOutput",
+                "to": hex(431),
+                "amount": "0",
+                "assetId": hex(411)
+            },
+            {
+                "index": 3,
+                "transactionIndex": 0,
+                "type": "CoinOutput",
+                "to": hex(442),
+                "amount": "7",
+                "assetId": hex(411)
+            },
+            {
+                "index": 4,
+                "transactionIndex": 1,
+                "type": "ContractCreated",
+                "contract": hex(443),
+                "stateRoot": hex(444)
+            }
+        ]
+    }));
+
+    assert_lossless(
+        FuelChunkBuilder::new(),
+        block,
+        &[
+            ("blocks", 1),
+            ("transactions", 2),
+            ("receipts", 3),
+            ("inputs", 3),
+            ("outputs", 5)
+        ]
+    );
+}
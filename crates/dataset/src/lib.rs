@@ -43,10 +43,42 @@ impl TableOptions {
         options.stats_enable = true
     }
 
+    pub fn has_index(&self, name: &str) -> bool {
+        self.column_options.get(name).map_or(false, |c| c.index_enable)
+    }
+
+    /// Marks the column as a candidate for the value index (e.g. addresses),
+    /// which storage builds only for datasets, that have it enabled.
+    pub fn add_index(&mut self, name: Name) {
+        let options = self.column_options.entry(name).or_default();
+        options.index_enable = true
+    }
+
+    pub fn has_bloom_filter(&self, name: &str) -> bool {
+        self.column_options.get(name).map_or(false, |c| c.bloom_filter_enable)
+    }
+
+    /// Requests parquet bloom filters for the column, so that readers can skip
+    /// row groups not containing the looked up values.
+    pub fn add_bloom_filter(&mut self, name: Name) {
+        let options = self.column_options.entry(name).or_default();
+        options.bloom_filter_enable = true
+    }
+
     pub fn use_dictionary(&mut self, name: Name) {
         let options = self.column_options.entry(name).or_default();
         options.dictionary_encoding = true
     }
+
+    pub fn get_codec(&self, name: &str) -> Codec {
+        self.column_options.get(name).map_or(Codec::None, |c| c.codec)
+    }
+
+    /// Sets the codec for pages of the column in the storage tables
+    pub fn set_codec(&mut self, name: Name, codec: Codec) {
+        let options = self.column_options.entry(name).or_default();
+        options.codec = codec
+    }
 }
 
 impl Default for TableOptions {
@@ -63,7 +95,12 @@ impl Default for TableOptions {
 pub struct ColumnOptions {
     pub stats_enable: bool,
     pub stats_partition: usize,
-    pub dictionary_encoding: bool
+    pub index_enable: bool,
+    pub bloom_filter_enable: bool,
+    /// Target false positive probability of the bloom filter
+    pub bloom_filter_fpp: f64,
+    pub dictionary_encoding: bool,
+    pub codec: Codec
 }
 
 impl Default for ColumnOptions {
@@ -71,7 +108,22 @@ impl Default for ColumnOptions {
         Self {
             stats_enable: false,
             stats_partition: 4096,
-            dictionary_encoding: false
+            index_enable: false,
+            bloom_filter_enable: false,
+            bloom_filter_fpp: 0.01,
+            dictionary_encoding: false,
+            codec: Codec::None
         }
     }
 }
+
+/// Compression of the column pages in the storage tables.
+///
+/// Codecs are selected per column and apply to all buffers of the column.
+/// Tables without codecs store raw Arrow buffers, as before codecs were introduced.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum Codec {
+    #[default]
+    None,
+    Zstd
+}
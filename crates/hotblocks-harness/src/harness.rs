@@ -41,6 +41,9 @@ pub struct HarnessConfig {
     pub sources: usize,
     pub rust_log: String,
     pub quiescence: Quiescence,
+    pub disable_compaction: bool,
+    /// Sources that must send the same block before the service accepts it, see `block_hash_quorum`.
+    pub block_hash_quorum: Option<usize>,
     pub sut_args: Vec<String>
 }
 
@@ -64,6 +67,8 @@ impl HarnessConfig {
             sources: 1,
             rust_log: "info".to_string(),
             quiescence: Quiescence::default(),
+            disable_compaction: false,
+            block_hash_quorum: None,
             sut_args: sut.args
         }
     }
@@ -122,7 +127,9 @@ impl Harness {
                 sources: std::iter::once(&sim)
                     .chain(peers.iter())
                     .map(|s| s.base_url(&cfg.dataset))
-                    .collect()
+                    .collect(),
+                disable_compaction: cfg.disable_compaction,
+                block_hash_quorum: cfg.block_hash_quorum
             }]
         );
         sut_cfg.args = cfg.sut_args;
@@ -39,7 +39,10 @@ pub struct DatasetSpec {
     pub id: String,
     pub kind: String,
     pub retention: Retention,
-    pub sources: Vec<String>
+    pub sources: Vec<String>,
+    /// Keeps every ingested batch in its own chunk, so that a script controls the chunk layout.
+    pub disable_compaction: bool,
+    pub block_hash_quorum: Option<usize>
 }
 
 #[derive(Clone, Debug)]
@@ -262,6 +265,12 @@ impl Sut {
                 Retention::Api => yaml.push_str("  retention_strategy: Api\n"),
                 Retention::None => yaml.push_str("  retention_strategy: None\n")
             }
+            if ds.disable_compaction {
+                yaml.push_str("  disable_compaction: true\n");
+            }
+            if let Some(quorum) = ds.block_hash_quorum {
+                yaml.push_str(&format!("  block_hash_quorum: {quorum}\n"));
+            }
             yaml.push_str("  data_sources:\n");
             for src in &ds.sources {
                 yaml.push_str(&format!("    - \"{src}\"\n"));
@@ -12,28 +12,34 @@ use async_stream::try_stream;
 use axum::{
     BoxError, Extension, Json, Router,
     body::{Body, Bytes},
-    extract::{Path, Request},
+    extract::{Path, Query as UrlQuery, Request},
     http::{HeaderMap, HeaderName, HeaderValue, StatusCode, Uri},
-    response::{IntoResponse, Response},
+    response::{
+        IntoResponse, Response,
+        sse::{Event, KeepAlive, Sse}
+    },
     routing::{get, post}
 };
 use futures::Stream;
-use serde::Serialize;
-use sqd_primitives::BlockRef;
+use serde::{Deserialize, Serialize};
+use sqd_primitives::{BlockNumber, BlockRef};
 use sqd_query::{Query, UnexpectedBaseBlock};
 use sqd_storage::db::DatasetId;
 use tower_http::request_id::{MakeRequestUuid, RequestId, SetRequestIdLayer};
-use tracing::{Instrument, error};
+use tracing::{Instrument, error, info};
 
 use crate::{
     cli::App,
-    dataset_controller::DatasetController,
-    encoding::ContentEncoding,
+    data_service::PutDatasetOutcome,
+    dataset_config::DatasetConfig,
+    dataset_controller::{DatasetController, HeadSubscription},
+    encoding::{ContentEncoding, ResponseFormat},
     errors::{
-        BlockItemIsNotAvailable, BlockRangeMissing, Busy, QueryIsAboveTheHead, QueryKindMismatch, QueryTaskPanicked,
-        UnknownDataset, UnsupportedQuery
+        BlockItemIsNotAvailable, BlockRangeMissing, Busy, DatasetKindChange, QueryIsAboveTheHead, QueryKindMismatch,
+        QueryTaskPanicked, UnknownDataset
     },
-    query::QueryResponse,
+    export::{list_finalized_chunks, read_finalized_table},
+    query::{LIVE_STREAM_CHAIN_LIMIT, QueryResponse, Rollback, StreamError, find_fork_base, orphaned_blocks},
     types::{ClientId, RetentionStrategy}
 };
 
@@ -42,7 +48,6 @@ const DEFAULT_CLIENT_ID: &str = "unknown";
 #[derive(Clone, Copy, Debug)]
 enum ErrorCode {
     MalformedRequest,
-    UnsupportedQuery,
     KindMismatch,
     UnknownDataset,
     RangeUnavailable,
@@ -60,7 +65,6 @@ impl ErrorCode {
     const fn as_str(self) -> &'static str {
         match self {
             Self::MalformedRequest => "MALFORMED_REQUEST",
-            Self::UnsupportedQuery => "UNSUPPORTED_QUERY",
             Self::KindMismatch => "KIND_MISMATCH",
             Self::UnknownDataset => "UNKNOWN_DATASET",
             Self::RangeUnavailable => "RANGE_UNAVAILABLE",
@@ -101,15 +105,27 @@ type AppRef = Arc<App>;
 pub fn build_api(app: App, shutting_down: Arc<AtomicBool>) -> Router {
     Router::new()
         .route("/", get(|| async { "Welcome to SQD hot block data service!" }))
+        .route(
+            "/datasets/{id}",
+            get(get_dataset_config).put(put_dataset).delete(delete_dataset)
+        )
         .route("/datasets/{id}/stream", post(stream))
         .route("/datasets/{id}/finalized-stream", post(finalized_stream))
         .route("/datasets/{id}/head", get(get_head))
         .route("/datasets/{id}/finalized-head", get(get_finalized_head))
+        .route("/datasets/{id}/finalized-chunks", get(get_finalized_chunks))
+        .route(
+            "/datasets/{id}/finalized-chunks/{first_block}/{table}",
+            get(get_finalized_chunk_table)
+        )
+        .route("/datasets/{id}/head-events", get(get_head_events))
         .route("/datasets/{id}/hashes/{hash}/block", get(get_block_by_hash))
         .route("/datasets/{id}/hashes/{hash}/transaction", get(get_transaction_by_hash))
         .route("/datasets/{id}/retention", get(get_retention).post(set_retention))
         .route("/datasets/{id}/status", get(get_status))
         .route("/datasets/{id}/metadata", get(get_metadata))
+        .route("/datasets/{id}/backups/{name}", post(create_dataset_backup))
+        .route("/backups/{name}", post(create_backup))
         .route("/metrics", get(get_metrics))
         .route("/rocksdb/stats", get(get_rocks_stats))
         .route("/rocksdb/prop/{cf}/{name}", get(get_rocks_prop))
@@ -298,15 +314,29 @@ impl IntoResponse for ResponseWithMetadata {
     }
 }
 
+#[derive(Deserialize)]
+struct StreamParams {
+    /// Keep the response open across the head and report forks inline
+    #[serde(default)]
+    follow: bool
+}
+
 async fn stream(
     Extension(app): Extension<AppRef>,
     Extension(client_id): Extension<ClientId>,
     Path(dataset_id): Path<DatasetId>,
+    UrlQuery(params): UrlQuery<StreamParams>,
     headers: HeaderMap,
     body: Bytes
 ) -> impl IntoResponse {
     let encoding = ContentEncoding::from_headers(&headers);
-    let response = stream_internal(app, dataset_id, body, false, client_id.clone(), encoding).await;
+    let response = match ResponseFormat::from_headers(&headers) {
+        None => not_acceptable(),
+        Some(format) if params.follow => {
+            follow_internal(app, dataset_id, body, client_id.clone(), encoding, format).await
+        }
+        Some(format) => stream_internal(app, dataset_id, body, false, client_id.clone(), encoding, format).await
+    };
     ResponseWithMetadata::new()
         .with_client_id(&client_id)
         .with_dataset_id(dataset_id)
@@ -322,7 +352,10 @@ async fn finalized_stream(
     body: Bytes
 ) -> impl IntoResponse {
     let encoding = ContentEncoding::from_headers(&headers);
-    let response = stream_internal(app, dataset_id, body, true, client_id.clone(), encoding).await;
+    let response = match ResponseFormat::from_headers(&headers) {
+        None => not_acceptable(),
+        Some(format) => stream_internal(app, dataset_id, body, true, client_id.clone(), encoding, format).await
+    };
     ResponseWithMetadata::new()
         .with_client_id(&client_id)
         .with_dataset_id(dataset_id)
@@ -330,13 +363,25 @@ async fn finalized_stream(
         .with_response(|| response)
 }
 
+fn not_acceptable() -> Response {
+    error_response(
+        StatusCode::NOT_ACCEPTABLE,
+        ErrorCode::MalformedRequest,
+        format!(
+            "query responses are served as text/plain or {}",
+            ResponseFormat::ARROW_STREAMS_MIME
+        )
+    )
+}
+
 async fn stream_internal(
     app: AppRef,
     dataset_id: DatasetId,
     body: Bytes,
     finalized: bool,
     client_id: ClientId,
-    encoding: ContentEncoding
+    encoding: ContentEncoding,
+    format: ResponseFormat
 ) -> Response {
     let dataset = get_dataset!(app, dataset_id);
 
@@ -351,42 +396,99 @@ async fn stream_internal(
 
     let query_result = if finalized {
         app.query_service
-            .query_finalized(&dataset, query, client_id, encoding)
+            .query_finalized(&dataset, query, client_id, encoding, format)
             .await
     } else {
-        app.query_service.query(&dataset, query, client_id, encoding).await
+        app.query_service
+            .query(&dataset, query, client_id, encoding, format)
+            .await
     };
 
     match query_result {
         Ok(stream) => {
-            let mut res = Response::builder()
-                .status(200)
-                .header("content-type", "text/plain")
-                .header("content-encoding", encoding.as_str())
-                .header("vary", "Accept-Encoding");
-
-            if let Some(finalized_head) = stream.finalized_head() {
-                if finalized {
-                    // For finalized stream, use the finalized head as the head
-                    res = res.header("x-sqd-head-number", finalized_head.number);
-                } else {
-                    let head_block = finalized_head.number.max(dataset.get_head_block_number().unwrap_or(0));
-                    res = res.header("x-sqd-head-number", head_block);
-                }
-                res = res.header("x-sqd-finalized-head-number", finalized_head.number);
-                res = res.header("x-sqd-finalized-head-hash", finalized_head.hash.as_str());
-            } else if let Some(head_block) = dataset.get_head_block_number() {
-                res = res.header("x-sqd-head-number", head_block);
-            }
-
+            let res = stream_response_builder(&dataset, Some(&stream), finalized, encoding, format);
             let body = Body::from_stream(stream_query_response(stream));
-
             res.body(body).unwrap()
         }
         Err(err) => error_to_response(err, &body)
     }
 }
 
+async fn follow_internal(
+    app: AppRef,
+    dataset_id: DatasetId,
+    body: Bytes,
+    client_id: ClientId,
+    encoding: ContentEncoding,
+    format: ResponseFormat
+) -> Response {
+    let dataset = get_dataset!(app, dataset_id);
+
+    if format != ResponseFormat::JsonLines {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::MalformedRequest,
+            "follow mode is only supported for JSON lines responses".to_string()
+        );
+    }
+
+    let query: Query = match Json::<Query>::from_bytes(&body) {
+        Ok(Json(q)) => q,
+        Err(rejection) => return error_response(rejection.status(), ErrorCode::MalformedRequest, rejection.body_text())
+    };
+
+    if let Err(err) = query.validate() {
+        return error_response(StatusCode::BAD_REQUEST, ErrorCode::MalformedRequest, err.to_string());
+    }
+
+    let first = match app
+        .query_service
+        .query_live(&dataset, query.clone(), client_id.clone(), encoding)
+        .await
+    {
+        Ok(stream) => Some(stream),
+        // Nothing to return yet, but that is exactly what the follow mode waits for
+        Err(err) if err.is::<QueryIsAboveTheHead>() => None,
+        Err(err) => return error_to_response(err, &body)
+    };
+
+    let res = stream_response_builder(&dataset, first.as_ref(), false, encoding, format);
+    let body = Body::from_stream(follow_query_response(
+        app, dataset_id, query, first, client_id, encoding
+    ));
+    res.body(body).unwrap()
+}
+
+fn stream_response_builder(
+    dataset: &DatasetController,
+    stream: Option<&QueryResponse>,
+    finalized: bool,
+    encoding: ContentEncoding,
+    format: ResponseFormat
+) -> axum::http::response::Builder {
+    let mut res = Response::builder()
+        .status(200)
+        .header("content-type", format.content_type())
+        .header("content-encoding", encoding.as_str())
+        .header("vary", "Accept, Accept-Encoding");
+
+    if let Some(finalized_head) = stream.and_then(|s| s.finalized_head()) {
+        if finalized {
+            // For finalized stream, use the finalized head as the head
+            res = res.header("x-sqd-head-number", finalized_head.number);
+        } else {
+            let head_block = finalized_head.number.max(dataset.get_head_block_number().unwrap_or(0));
+            res = res.header("x-sqd-head-number", head_block);
+        }
+        res = res.header("x-sqd-finalized-head-number", finalized_head.number);
+        res = res.header("x-sqd-finalized-head-hash", finalized_head.hash.as_str());
+    } else if let Some(head_block) = dataset.get_head_block_number() {
+        res = res.header("x-sqd-head-number", head_block);
+    }
+
+    res
+}
+
 /// Pack source for [`stream_query_response`]; a trait so tests can script the panic
 /// path without a live database.
 trait DataPackSource: Send + 'static {
@@ -431,6 +533,104 @@ fn stream_query_response<S: DataPackSource>(mut stream: S) -> impl Stream<Item =
     }
 }
 
+/// Streams query results across the head, until the requested range is exhausted.
+///
+/// Every continuation query is bound to the head of the last returned chunk,
+/// so a fork surfaces as [`UnexpectedBaseBlock`] and is reported to the client
+/// as an inline [`Rollback`] line, after which the stream resumes from the fork base.
+fn follow_query_response(
+    app: AppRef,
+    dataset_id: DatasetId,
+    mut query: Query,
+    first: Option<QueryResponse>,
+    client_id: ClientId,
+    encoding: ContentEncoding
+) -> impl Stream<Item = Result<Bytes, BoxError>> {
+    try_stream! {
+        let mut next = first;
+        // Heads of the chunks passed to the client
+        let mut chain: Vec<BlockRef> = Vec::new();
+
+        loop {
+            if let Some(mut stream) = next.take() {
+                while let Some(pack_result) = stream.next_data_pack().await.transpose() {
+                    match pack_result {
+                        Ok(bytes) => {
+                            yield bytes;
+                        },
+                        Err(err) if stream_can_finish_cleanly(&err) => {
+                            if !err.is::<Busy>() {
+                                error!(err =? err, "terminating live stream due to query error");
+                            }
+                            // The client resumes from the last block it has received
+                            yield stream.finish();
+                            yield stream_error_line(&err, encoding)?;
+                            return
+                        }
+                        Err(err) => {
+                            crate::metrics::report_query_worker_panic();
+                            error!(err =? err, "aborting live stream after query worker panic");
+                            Err::<(), _>(err)?;
+                        }
+                    }
+                }
+
+                chain.extend(stream.take_chunk_heads());
+                if stream.reached_last_block() || stream.limit_reached() {
+                    return
+                }
+                if chain.len() > LIVE_STREAM_CHAIN_LIMIT {
+                    chain.drain(..chain.len() - LIVE_STREAM_CHAIN_LIMIT);
+                }
+                if let Some(head) = chain.last() {
+                    query.set_first_block(head.number + 1);
+                    query.set_parent_block_hash(head.hash.clone());
+                }
+            }
+
+            let dataset = match app.data_service.get_dataset(dataset_id) {
+                Ok(dataset) => dataset,
+                Err(err) => {
+                    yield stream_error_line(&anyhow::Error::from(err), encoding)?;
+                    return
+                }
+            };
+
+            match app.query_service.query_live(&dataset, query.clone(), client_id.clone(), encoding).await {
+                Ok(stream) => {
+                    next = Some(stream);
+                }
+                Err(err) if err.is::<QueryIsAboveTheHead>() => {}
+                Err(err) if err.is::<UnexpectedBaseBlock>() => {
+                    let Some(base) = find_fork_base(&app.db, dataset_id, &chain)? else {
+                        // The fork point is unknown, the client has to resolve it via a regular query
+                        yield StreamError {
+                            code: ErrorCode::Conflict.as_str(),
+                            message: err.to_string()
+                        }
+                        .to_json_line(encoding)?;
+                        return
+                    };
+                    let orphaned = orphaned_blocks(&mut chain, &base);
+                    if chain.last() != Some(&base) {
+                        chain.push(base.clone());
+                    }
+                    query.set_first_block(base.number + 1);
+                    query.set_parent_block_hash(base.hash.clone());
+                    yield Rollback { base, orphaned }.to_json_line(encoding)?;
+                }
+                Err(err) => {
+                    if !err.is::<Busy>() {
+                        error!(err =? err, "terminating live stream due to query error");
+                    }
+                    yield stream_error_line(&err, encoding)?;
+                    return
+                }
+            }
+        }
+    }
+}
+
 /// A failed pack finishes as a valid partial 200, except a worker panic: that drops
 /// the runner mid-stream (no trailer to emit), so such a stream must abort instead.
 fn stream_can_finish_cleanly(err: &anyhow::Error) -> bool {
@@ -535,19 +735,7 @@ fn error_to_response(err: anyhow::Error, body: &Bytes) -> Response {
         return with_error_code(response, ErrorCode::Conflict);
     }
 
-    let (status_code, error_code) = if err.is::<UnknownDataset>() {
-        (StatusCode::NOT_FOUND, ErrorCode::UnknownDataset)
-    } else if err.is::<UnsupportedQuery>() {
-        (StatusCode::BAD_REQUEST, ErrorCode::UnsupportedQuery)
-    } else if err.is::<QueryKindMismatch>() {
-        (StatusCode::BAD_REQUEST, ErrorCode::KindMismatch)
-    } else if err.is::<BlockRangeMissing>() {
-        (StatusCode::BAD_REQUEST, ErrorCode::RangeUnavailable)
-    } else if err.is::<BlockItemIsNotAvailable>() {
-        (StatusCode::BAD_REQUEST, ErrorCode::ItemUnavailable)
-    } else if err.is::<Busy>() {
-        (StatusCode::SERVICE_UNAVAILABLE, ErrorCode::Overloaded)
-    } else {
+    let Some((status_code, error_code)) = classify_error(&err) else {
         error!(
             err = ?err,
             query = %String::from_utf8_lossy(body),
@@ -563,6 +751,33 @@ fn error_to_response(err: anyhow::Error, body: &Bytes) -> Response {
     error_response(status_code, error_code, err.to_string())
 }
 
+/// Status and code of the errors, that are expected to reach clients
+fn classify_error(err: &anyhow::Error) -> Option<(StatusCode, ErrorCode)> {
+    if err.is::<UnknownDataset>() {
+        Some((StatusCode::NOT_FOUND, ErrorCode::UnknownDataset))
+    } else if err.is::<QueryKindMismatch>() {
+        Some((StatusCode::BAD_REQUEST, ErrorCode::KindMismatch))
+    } else if err.is::<BlockRangeMissing>() {
+        Some((StatusCode::BAD_REQUEST, ErrorCode::RangeUnavailable))
+    } else if err.is::<BlockItemIsNotAvailable>() {
+        Some((StatusCode::BAD_REQUEST, ErrorCode::ItemUnavailable))
+    } else if err.is::<Busy>() {
+        Some((StatusCode::SERVICE_UNAVAILABLE, ErrorCode::Overloaded))
+    } else {
+        None
+    }
+}
+
+/// Final line of a live stream, that ends because of `err`
+fn stream_error_line(err: &anyhow::Error, encoding: ContentEncoding) -> anyhow::Result<Bytes> {
+    let code = classify_error(err).map_or(ErrorCode::Internal, |(_, code)| code);
+    StreamError {
+        code: code.as_str(),
+        message: err.to_string()
+    }
+    .to_json_line(encoding)
+}
+
 fn error_response(status: StatusCode, code: ErrorCode, message: impl Into<String>) -> Response {
     // FIXME(GAP-36): expose `code` on the HTTP binding once a backwards-compatible
     // representation is chosen. sqd-portal forwards this body verbatim, so keep the
@@ -584,8 +799,8 @@ mod error_response_tests {
     async fn classified_errors_keep_the_plain_text_wire_format() {
         let response = error_response(
             StatusCode::BAD_REQUEST,
-            ErrorCode::UnsupportedQuery,
-            "substrate queries are not supported"
+            ErrorCode::KindMismatch,
+            "substrate query was issued against evm dataset"
         );
 
         assert_eq!(response.status(), StatusCode::BAD_REQUEST);
@@ -595,10 +810,10 @@ mod error_response_tests {
         );
         assert_eq!(
             response.extensions().get::<ErrorCode>().map(|code| code.as_str()),
-            Some("UNSUPPORTED_QUERY")
+            Some("KIND_MISMATCH")
         );
         let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
-        assert_eq!(body, "substrate queries are not supported");
+        assert_eq!(body, "substrate query was issued against evm dataset");
     }
 }
 
@@ -624,6 +839,72 @@ async fn get_finalized_head(
         })
 }
 
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FinalizedChunksParams {
+    #[serde(default)]
+    from_block: BlockNumber
+}
+
+async fn get_finalized_chunks(
+    Extension(app): Extension<AppRef>,
+    Extension(client_id): Extension<ClientId>,
+    Path(dataset_id): Path<DatasetId>,
+    UrlQuery(params): UrlQuery<FinalizedChunksParams>
+) -> impl IntoResponse {
+    ResponseWithMetadata::new()
+        .with_client_id(&client_id)
+        .with_dataset_id(dataset_id)
+        .with_endpoint("/finalized_chunks")
+        .with_response(|| {
+            get_dataset!(app, dataset_id);
+            match list_finalized_chunks(&app.db.snapshot(), dataset_id, params.from_block) {
+                Ok(chunks) => json_ok!(chunks),
+                Err(err) => text!(StatusCode::INTERNAL_SERVER_ERROR, "{:?}", err)
+            }
+        })
+}
+
+async fn get_finalized_chunk_table(
+    Extension(app): Extension<AppRef>,
+    Extension(client_id): Extension<ClientId>,
+    Path((dataset_id, first_block, table)): Path<(DatasetId, BlockNumber, String)>
+) -> impl IntoResponse {
+    let response = match app.data_service.get_dataset(dataset_id) {
+        Ok(_) => {
+            let db = app.db.clone();
+            let read_result = tokio::task::spawn_blocking(move || {
+                read_finalized_table(&db.snapshot(), dataset_id, first_block, &table)
+            })
+            .await;
+
+            match read_result {
+                Ok(Ok(Some(bytes))) => Response::builder()
+                    .status(200)
+                    .header("content-type", ResponseFormat::ARROW_STREAM_MIME)
+                    .body(Body::from(bytes))
+                    .unwrap(),
+                Ok(Ok(None)) => error_response(StatusCode::NOT_FOUND, ErrorCode::NotFound, "finalized chunk not found"),
+                Ok(Err(err)) => {
+                    error!(error = ?err, dataset_id = %dataset_id, "get_finalized_chunk_table failed");
+                    error_response(StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::Internal, "internal error")
+                }
+                Err(err) => {
+                    error!(error = ?err, dataset_id = %dataset_id, "finalized table read task panicked");
+                    error_response(StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::Internal, "internal error")
+                }
+            }
+        }
+        Err(err) => error_response(StatusCode::NOT_FOUND, ErrorCode::UnknownDataset, err.to_string())
+    };
+
+    ResponseWithMetadata::new()
+        .with_client_id(&client_id)
+        .with_dataset_id(dataset_id)
+        .with_endpoint("/finalized_chunks/{first_block}/{table}")
+        .with_response(|| response)
+}
+
 async fn get_head(
     Extension(app): Extension<AppRef>,
     Extension(client_id): Extension<ClientId>,
@@ -640,6 +921,62 @@ async fn get_head(
         })
 }
 
+async fn get_head_events(
+    Extension(app): Extension<AppRef>,
+    Extension(client_id): Extension<ClientId>,
+    Path(dataset_id): Path<DatasetId>
+) -> impl IntoResponse {
+    ResponseWithMetadata::new()
+        .with_client_id(&client_id)
+        .with_dataset_id(dataset_id.clone())
+        .with_endpoint("/head-events")
+        .with_response(|| {
+            let subscription = get_dataset!(app, dataset_id).subscribe();
+            Sse::new(head_events(subscription))
+                .keep_alive(KeepAlive::default())
+                .into_response()
+        })
+}
+
+/// Emits the current heads, then every head change and fork until the dataset goes away.
+///
+/// A subscriber that falls behind on fork events is disconnected
+/// rather than served a stream with silent gaps.
+fn head_events(mut sub: HeadSubscription) -> impl Stream<Item = anyhow::Result<Event>> {
+    try_stream! {
+        let head = sub.head.borrow_and_update().clone();
+        yield Event::default().event("head").json_data(head)?;
+
+        let finalized_head = sub.finalized_head.borrow_and_update().clone();
+        yield Event::default().event("finalized-head").json_data(finalized_head)?;
+
+        loop {
+            let event = tokio::select! {
+                biased;
+                fork = sub.forks.recv() => match fork {
+                    Ok(fork) => Event::default().event("fork").json_data(fork)?,
+                    Err(_) => break
+                },
+                changed = sub.head.changed() => {
+                    if changed.is_err() {
+                        break
+                    }
+                    let head = sub.head.borrow_and_update().clone();
+                    Event::default().event("head").json_data(head)?
+                },
+                changed = sub.finalized_head.changed() => {
+                    if changed.is_err() {
+                        break
+                    }
+                    let head = sub.finalized_head.borrow_and_update().clone();
+                    Event::default().event("finalized-head").json_data(head)?
+                }
+            };
+            yield event;
+        }
+    }
+}
+
 async fn get_block_by_hash(
     Extension(app): Extension<AppRef>,
     Extension(client_id): Extension<ClientId>,
@@ -762,7 +1099,7 @@ async fn set_retention(
         .with_endpoint("/retention")
         .with_response(|| {
             let ds = get_dataset!(app, dataset_id);
-            if app.api_controlled_datasets.contains(&dataset_id) {
+            if app.data_service.is_api_controlled(dataset_id) {
                 ds.retain(strategy);
                 text!(StatusCode::OK, "OK")
             } else {
@@ -775,6 +1112,203 @@ async fn set_retention(
         })
 }
 
+async fn get_dataset_config(
+    Extension(app): Extension<AppRef>,
+    Extension(client_id): Extension<ClientId>,
+    Path(dataset_id): Path<DatasetId>
+) -> impl IntoResponse {
+    ResponseWithMetadata::new()
+        .with_client_id(&client_id)
+        .with_dataset_id(dataset_id.clone())
+        .with_endpoint("/dataset")
+        .with_response(|| match app.data_service.get_dataset_config(dataset_id) {
+            Ok(config) => json_ok!(config),
+            Err(err) => error_response(StatusCode::NOT_FOUND, ErrorCode::UnknownDataset, err.to_string())
+        })
+}
+
+async fn put_dataset(
+    Extension(app): Extension<AppRef>,
+    Extension(client_id): Extension<ClientId>,
+    Path(dataset_id): Path<DatasetId>,
+    body: Bytes
+) -> impl IntoResponse {
+    let response = match serde_json::from_slice::<DatasetConfig>(&body) {
+        Err(err) => error_response(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::MalformedRequest,
+            format!("invalid dataset config: {err}")
+        ),
+        Ok(config) => match config.validate() {
+            Err(err) => error_response(StatusCode::BAD_REQUEST, ErrorCode::MalformedRequest, err.to_string()),
+            Ok(()) => match app.data_service.put_dataset(dataset_id, config).await {
+                Ok(PutDatasetOutcome::Created) => text!(StatusCode::CREATED, "Created"),
+                Ok(PutDatasetOutcome::Updated | PutDatasetOutcome::Unchanged) => text!(StatusCode::OK, "OK"),
+                Err(err) => dataset_management_error(err)
+            }
+        }
+    };
+    ResponseWithMetadata::new()
+        .with_client_id(&client_id)
+        .with_dataset_id(dataset_id)
+        .with_endpoint("/dataset")
+        .with_response(|| response)
+}
+
+async fn delete_dataset(
+    Extension(app): Extension<AppRef>,
+    Extension(client_id): Extension<ClientId>,
+    Path(dataset_id): Path<DatasetId>
+) -> impl IntoResponse {
+    let response = match app.data_service.delete_dataset(dataset_id).await {
+        Ok(()) => text!(StatusCode::OK, "OK"),
+        Err(err) => dataset_management_error(err)
+    };
+    ResponseWithMetadata::new()
+        .with_client_id(&client_id)
+        .with_dataset_id(dataset_id)
+        .with_endpoint("/dataset")
+        .with_response(|| response)
+}
+
+async fn create_backup(
+    Extension(app): Extension<AppRef>,
+    Extension(client_id): Extension<ClientId>,
+    Path(name): Path<String>
+) -> impl IntoResponse {
+    let response = run_backup(&app, name, None).await;
+    ResponseWithMetadata::new()
+        .with_client_id(&client_id)
+        .with_endpoint("/backups")
+        .with_response(|| response)
+}
+
+async fn create_dataset_backup(
+    Extension(app): Extension<AppRef>,
+    Extension(client_id): Extension<ClientId>,
+    Path((dataset_id, name)): Path<(DatasetId, String)>
+) -> impl IntoResponse {
+    let response = match app.data_service.get_dataset(dataset_id) {
+        Ok(_) => run_backup(&app, name, Some(dataset_id)).await,
+        Err(err) => error_response(StatusCode::NOT_FOUND, ErrorCode::UnknownDataset, err.to_string())
+    };
+    ResponseWithMetadata::new()
+        .with_client_id(&client_id)
+        .with_dataset_id(dataset_id)
+        .with_endpoint("/backups")
+        .with_response(|| response)
+}
+
+#[derive(Serialize)]
+struct BackupCreated {
+    path: String
+}
+
+/// Creates a backup of the whole database or of a single dataset under `--backup-dir`
+async fn run_backup(app: &AppRef, name: String, dataset_id: Option<DatasetId>) -> Response {
+    let Some(backup_dir) = app.backup_dir.clone() else {
+        return error_response(
+            StatusCode::NOT_FOUND,
+            ErrorCode::NotFound,
+            "backups are not enabled, see --backup-dir"
+        );
+    };
+
+    if !is_valid_backup_name(&name) {
+        return error_response(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::MalformedRequest,
+            format!("invalid backup name '{}'", name)
+        );
+    }
+
+    let path = backup_dir.join(&name);
+    if path.exists() {
+        return error_response(
+            StatusCode::CONFLICT,
+            ErrorCode::Conflict,
+            format!("backup '{}' already exists", name)
+        );
+    }
+
+    let db = app.db.clone();
+    let backup_path = path.clone();
+    let started = Instant::now();
+    let result = tokio::task::spawn_blocking(move || {
+        std::fs::create_dir_all(&backup_dir)?;
+        match dataset_id {
+            Some(dataset_id) => db.create_dataset_backup(&backup_path, &[dataset_id]),
+            None => db.create_backup(&backup_path)
+        }
+    })
+    .await;
+
+    match result {
+        Ok(Ok(())) => {
+            info!(
+                backup = %path.display(),
+                elapsed_ms = started.elapsed().as_millis() as u64,
+                "backup created"
+            );
+            json_ok!(BackupCreated {
+                path: path.display().to_string()
+            })
+        }
+        Ok(Err(err)) => {
+            error!(error = ?err, backup = %path.display(), "failed to create backup");
+            error_response(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorCode::Internal,
+                format!("{:?}", err)
+            )
+        }
+        Err(err) => {
+            error!(error = ?err, backup = %path.display(), "backup task panicked");
+            error_response(StatusCode::INTERNAL_SERVER_ERROR, ErrorCode::Internal, "internal error")
+        }
+    }
+}
+
+/// Backup names become directory names, so they can't contain path separators or start with a dot
+fn is_valid_backup_name(name: &str) -> bool {
+    !name.is_empty()
+        && !name.starts_with('.')
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '.')
+}
+
+#[cfg(test)]
+mod backup_name_tests {
+    use super::is_valid_backup_name;
+
+    #[test]
+    fn backup_names_stay_inside_the_backup_dir() {
+        assert!(is_valid_backup_name("2026-10-18"));
+        assert!(is_valid_backup_name("ethereum_v1.2"));
+        assert!(!is_valid_backup_name(""));
+        assert!(!is_valid_backup_name(".."));
+        assert!(!is_valid_backup_name(".hidden"));
+        assert!(!is_valid_backup_name("a/b"));
+        assert!(!is_valid_backup_name("a\\b"));
+    }
+}
+
+fn dataset_management_error(err: anyhow::Error) -> Response {
+    if err.is::<UnknownDataset>() {
+        error_response(StatusCode::NOT_FOUND, ErrorCode::UnknownDataset, err.to_string())
+    } else if err.is::<DatasetKindChange>() {
+        error_response(StatusCode::CONFLICT, ErrorCode::Conflict, err.to_string())
+    } else {
+        error!(err = ?err, "dataset management request failed");
+        error_response(
+            StatusCode::INTERNAL_SERVER_ERROR,
+            ErrorCode::Internal,
+            format!("{:?}", err)
+        )
+    }
+}
+
 async fn get_status(
     Extension(app): Extension<AppRef>,
     Extension(client_id): Extension<ClientId>,
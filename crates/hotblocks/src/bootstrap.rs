@@ -0,0 +1,288 @@
+use std::{collections::BTreeMap, future::Future, time::Duration};
+
+use anyhow::Context;
+use arrow::{array::RecordBatch, datatypes::SchemaRef, ipc::reader::StreamReader};
+use reqwest::StatusCode;
+use sqd_primitives::{BlockNumber, BlockRef};
+use sqd_storage::db::{Chunk, DatasetId};
+use tokio::time::Instant;
+use tracing::{info, warn};
+use url::Url;
+
+use crate::{
+    dataset_config::{DatasetConfig, RetentionConfig},
+    dataset_controller::apply_table_options,
+    export::FinalizedChunk,
+    types::{DBRef, DatasetKind}
+};
+
+/// Delays between listings, when the peer removes a chunk while it is being copied
+const MIN_RETRY_DELAY: Duration = Duration::from_millis(200);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(10);
+
+/// A peer hotblocks instance to copy finalized chunks from, see `--bootstrap-from`
+#[derive(Clone)]
+pub struct Bootstrap {
+    pub peer: Url,
+    /// Time limit of the copy of a single dataset
+    pub timeout: Duration
+}
+
+/// Client of the finalized chunk export of a peer hotblocks instance
+struct PeerClient {
+    http: reqwest::Client,
+    dataset_url: String
+}
+
+impl PeerClient {
+    fn new(http: reqwest::Client, peer: &Url, dataset_id: DatasetId) -> Self {
+        Self {
+            http,
+            dataset_url: format!("{}/datasets/{}", peer.as_str().trim_end_matches('/'), dataset_id)
+        }
+    }
+
+    async fn list_chunks(&self, from_block: BlockNumber) -> anyhow::Result<Vec<FinalizedChunk>> {
+        let chunks = self
+            .http
+            .get(format!("{}/finalized-chunks", self.dataset_url))
+            .query(&[("fromBlock", from_block)])
+            .send()
+            .await?
+            .error_for_status()?
+            .json()
+            .await?;
+        Ok(chunks)
+    }
+
+    /// Returns `None`, if the chunk no longer exists
+    async fn get_table(
+        &self,
+        first_block: BlockNumber,
+        table: &str
+    ) -> anyhow::Result<Option<(SchemaRef, Vec<RecordBatch>)>> {
+        let res = self
+            .http
+            .get(format!(
+                "{}/finalized-chunks/{}/{}",
+                self.dataset_url, first_block, table
+            ))
+            .send()
+            .await?;
+
+        if res.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+
+        let bytes = res.error_for_status()?.bytes().await?;
+        let reader = StreamReader::try_new(bytes.as_ref(), None)?;
+        let schema = reader.schema();
+        let batches = reader.collect::<Result<Vec<_>, _>>()?;
+        Ok(Some((schema, batches)))
+    }
+
+    async fn get_tables(
+        &self,
+        chunk: &FinalizedChunk
+    ) -> anyhow::Result<Option<BTreeMap<String, (SchemaRef, Vec<RecordBatch>)>>> {
+        let mut tables = BTreeMap::new();
+        for table in &chunk.tables {
+            let Some(data) = self
+                .get_table(chunk.first_block, table)
+                .await
+                .with_context(|| format!("failed to fetch table '{}' of chunk {}", table, chunk.first_block))?
+            else {
+                return Ok(None);
+            };
+            tables.insert(table.clone(), data);
+        }
+        Ok(Some(tables))
+    }
+}
+
+/// Copies finalized chunks of an empty dataset from a peer hotblocks instance.
+///
+/// The copy starts at the `FromBlock` retention floor or, for other retention strategies,
+/// at the peer's first chunk. It stops at the peer's finalized head, at the first chunk, that doesn't
+/// extend the already copied ones, or at the deadline. After that the dataset is ingested
+/// from its data sources as usual, continuing from the last copied block.
+///
+/// Does nothing, when the dataset already has data.
+pub async fn bootstrap_dataset(
+    db: DBRef,
+    http: reqwest::Client,
+    bootstrap: &Bootstrap,
+    dataset_id: DatasetId,
+    cfg: &DatasetConfig
+) -> anyhow::Result<()> {
+    let is_empty = {
+        let db = db.clone();
+        let kind = cfg.kind;
+        tokio::task::spawn_blocking(move || -> anyhow::Result<bool> {
+            db.create_dataset_if_not_exists(dataset_id, kind.storage_kind())?;
+            Ok(db.snapshot().get_last_chunk(dataset_id)?.is_none())
+        })
+        .await??
+    };
+    if !is_empty {
+        info!(dataset_id = %dataset_id, "dataset already has data, skipping bootstrap");
+        return Ok(());
+    }
+
+    // A fixed floor must be covered, otherwise the controller would drop the copy right away.
+    // Other strategies keep whatever the peer has, so its first chunk becomes the floor.
+    let floor = match &cfg.retention_strategy {
+        RetentionConfig::FromBlock { number, parent_hash } => Some((*number, parent_hash.as_deref())),
+        RetentionConfig::Head(_) | RetentionConfig::Api { .. } | RetentionConfig::None => None
+    };
+
+    let client = PeerClient::new(http, &bootstrap.peer, dataset_id);
+    let started = Instant::now();
+    let deadline = started + bootstrap.timeout;
+    let mut head: Option<BlockRef> = None;
+    let mut copied_chunks = 0;
+    let mut retry_delay = MIN_RETRY_DELAY;
+
+    info!(dataset_id = %dataset_id, peer = %bootstrap.peer, "bootstrap from peer started");
+
+    'copy: loop {
+        let from_block = head
+            .as_ref()
+            .map_or(floor.map_or(0, |(number, _)| number), |h| h.number + 1);
+        let Some(chunks) = before_deadline(deadline, client.list_chunks(from_block)).await? else {
+            warn!(dataset_id = %dataset_id, "bootstrap deadline reached");
+            break;
+        };
+        if chunks.is_empty() {
+            break;
+        }
+
+        for chunk in chunks {
+            match head.as_ref() {
+                Some(head) => {
+                    if chunk.first_block != head.number + 1 || chunk.parent_block_hash != head.hash {
+                        warn!(
+                            dataset_id = %dataset_id,
+                            "peer chunk {}-{} doesn't extend the copied head {}#{}, stopping bootstrap",
+                            chunk.first_block,
+                            chunk.last_block,
+                            head.number,
+                            head.hash
+                        );
+                        break 'copy;
+                    }
+                }
+                None => {
+                    let covers_floor = floor.is_none_or(|(first_block, first_parent_hash)| {
+                        if chunk.first_block == first_block {
+                            first_parent_hash.is_none_or(|hash| hash == chunk.parent_block_hash)
+                        } else {
+                            chunk.first_block < first_block && first_parent_hash.is_none()
+                        }
+                    });
+                    if !covers_floor {
+                        warn!(
+                            dataset_id = %dataset_id,
+                            "peer data starts at block {}, which doesn't match the retention floor {}, skipping bootstrap",
+                            chunk.first_block,
+                            from_block
+                        );
+                        break 'copy;
+                    }
+                }
+            }
+
+            let Some(tables) = before_deadline(deadline, client.get_tables(&chunk)).await? else {
+                warn!(dataset_id = %dataset_id, "bootstrap deadline reached");
+                break 'copy;
+            };
+            let Some(tables) = tables else {
+                // the peer merged or removed the chunk, list the chunks again
+                tokio::time::sleep_until(deadline.min(Instant::now() + retry_delay)).await;
+                retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                continue 'copy;
+            };
+            retry_delay = MIN_RETRY_DELAY;
+
+            let chunk_head = BlockRef {
+                number: chunk.last_block,
+                hash: chunk.last_block_hash.clone()
+            };
+            insert_chunk(db.clone(), dataset_id, cfg.kind, cfg.address_index, chunk, tables).await?;
+            head = Some(chunk_head);
+            copied_chunks += 1;
+        }
+    }
+
+    info!(
+        dataset_id = %dataset_id,
+        copied_chunks,
+        head = head.as_ref().map(|h| h.number),
+        elapsed_ms = started.elapsed().as_millis() as u64,
+        "bootstrap from peer finished"
+    );
+    Ok(())
+}
+
+/// Returns `None`, if `request` doesn't complete before the deadline
+async fn before_deadline<T>(
+    deadline: Instant,
+    request: impl Future<Output = anyhow::Result<T>>
+) -> anyhow::Result<Option<T>> {
+    match tokio::time::timeout_at(deadline, request).await {
+        Ok(res) => res.map(Some),
+        Err(_) => Ok(None)
+    }
+}
+
+/// Writes the tables of a peer chunk and commits it as finalized
+async fn insert_chunk(
+    db: DBRef,
+    dataset_id: DatasetId,
+    kind: DatasetKind,
+    address_index: bool,
+    chunk: FinalizedChunk,
+    tables: BTreeMap<String, (SchemaRef, Vec<RecordBatch>)>
+) -> anyhow::Result<()> {
+    tokio::task::spawn_blocking(move || {
+        let desc = kind.dataset_description();
+
+        let mut table_ids = BTreeMap::new();
+        for (name, (schema, batches)) in tables {
+            let mut builder = db.new_table_builder(schema);
+            if let Some(table_desc) = desc.tables.get(name.as_str()) {
+                apply_table_options(&mut builder, table_desc, address_index)?;
+            }
+            for batch in batches.iter() {
+                builder.write_record_batch(batch)?;
+            }
+            let table_id = builder
+                .finish()
+                .with_context(|| format!("failed to write table '{}'", name))?;
+            table_ids.insert(name, table_id);
+        }
+
+        let finalized_head = BlockRef {
+            number: chunk.last_block,
+            hash: chunk.last_block_hash.clone()
+        };
+
+        let chunk = Chunk::V1 {
+            first_block: chunk.first_block,
+            last_block: chunk.last_block,
+            last_block_hash: chunk.last_block_hash,
+            parent_block_hash: chunk.parent_block_hash,
+            first_block_time: chunk.first_block_time,
+            last_block_time: chunk.last_block_time,
+            tables: table_ids
+        };
+
+        db.update_dataset(dataset_id, |tx| {
+            tx.insert_chunk(&chunk)?;
+            tx.set_finalized_head(finalized_head.clone());
+            Ok(())
+        })
+        .with_context(|| format!("failed to insert chunk {}-{}", chunk.first_block(), chunk.last_block()))
+    })
+    .await?
+}
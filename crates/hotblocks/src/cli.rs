@@ -1,17 +1,20 @@
 use std::{
-    collections::{BTreeSet, HashSet},
+    collections::HashSet,
+    path::{Path, PathBuf},
     sync::Arc,
-    time::Instant
+    time::{Duration, Instant}
 };
 
 use anyhow::Context;
 use clap::Parser;
-use sqd_storage::db::{DatabaseSettings, DatasetId};
+use sqd_storage::db::{DatabaseSettings, restore_backup};
 use tracing::info;
+use url::Url;
 
 use crate::{
+    bootstrap::Bootstrap,
     data_service::{DataService, DataServiceRef},
-    dataset_config::{DatasetConfig, RetentionConfig},
+    dataset_config::{DatasetConfig, DatasetOverrides},
     metrics::{DatasetMetricsCollector, RocksDbCollector},
     query::{QueryService, QueryServiceRef},
     types::DBRef
@@ -28,6 +31,12 @@ pub struct CLI {
     #[arg(long = "db")]
     pub database_dir: String,
 
+    /// File to persist datasets added, changed or removed via the admin API.
+    /// Its entries override the datasets config on boot.
+    /// Defaults to `dataset-overrides.yaml` inside the database directory.
+    #[arg(long, value_name = "FILE")]
+    pub dataset_overrides: Option<String>,
+
     #[arg(long, value_name = "MB", default_value = "256")]
     pub data_cache_size: usize,
 
@@ -97,22 +106,36 @@ pub struct CLI {
     pub startup_disk_reclaim: bool,
 
     /// Index block hashes of newly ingested chunks, enabling
-    /// `GET /datasets/{id}/hashes/{hash}/block`. EVM datasets only.
+    /// `GET /datasets/{id}/hashes/{hash}/block`. EVM, Solana, Tron and Bitcoin datasets only.
     ///
-    /// No backfill: pre-existing chunks stay unresolvable until they roll off
-    /// via retention. Entries drain as chunks are pruned after switching off.
+    /// Pre-existing chunks stay unresolvable until they roll off via retention,
+    /// unless `--hash-index-backfill` is given. Entries drain as chunks are pruned
+    /// after switching off.
     #[arg(long)]
     pub block_hash_index: bool,
 
     /// Index transaction hashes of newly ingested chunks, enabling
-    /// `GET /datasets/{id}/hashes/{hash}/transaction`. EVM datasets only.
+    /// `GET /datasets/{id}/hashes/{hash}/transaction`. EVM, Solana, Tron and Bitcoin
+    /// datasets only; Solana transactions are keyed by their first signature, Bitcoin ones by `txid`.
     ///
     /// Independent of `--block-hash-index` and off by default because this
-    /// index has one entry per transaction. No backfill; entries drain through
-    /// retention after switching it off.
+    /// index has one entry per transaction. Backfilled only with
+    /// `--hash-index-backfill`; entries drain through retention after switching it off.
     #[arg(long)]
     pub transaction_hash_index: bool,
 
+    /// Index hashes of chunks ingested before the enabled hash indexes were turned on.
+    ///
+    /// Runs in the background after startup, one dataset at a time, while the service
+    /// is already serving. Lookups are complete once the backfill reports completion.
+    /// Repeated runs are harmless, but each one walks all existing chunks.
+    #[arg(long)]
+    pub hash_index_backfill: bool,
+
+    /// Upper bound on the backfill indexing rate, to leave disk bandwidth to ingestion
+    #[arg(long, value_name = "BLOCKS_PER_SEC")]
+    pub hash_index_backfill_rate: Option<u64>,
+
     /// Chunks that never grew past this many buffered bytes are prepared in
     /// memory at flush; larger ones spill to temp files while accumulating.
     /// 0 forces the temp-file path for every chunk.
@@ -124,6 +147,35 @@ pub struct CLI {
     )]
     pub spill_bound_bytes: usize,
 
+    /// Directory for backups created via `POST /backups/{name}` and
+    /// `POST /datasets/{id}/backups/{name}`. The backup endpoints are disabled without it.
+    ///
+    /// Backups hard-link the database files, so the directory should be on the same
+    /// filesystem as the database.
+    #[arg(long, value_name = "DIR")]
+    pub backup_dir: Option<String>,
+
+    /// Bootstrap the database from a backup, when the database directory is missing or empty.
+    ///
+    /// Lets a new replica start from a recent copy instead of re-ingesting hot blocks from upstream.
+    /// Ignored once the database exists, so it is safe to leave on across restarts.
+    #[arg(long, value_name = "DIR")]
+    pub restore_from: Option<String>,
+
+    /// Base URL of a healthy hotblocks peer to copy finalized chunks from.
+    ///
+    /// Applies at startup to configured datasets, that have no data yet.
+    /// Ingestion from the data sources continues from the last copied block.
+    /// A failed or partial copy is not fatal, the rest is ingested as usual.
+    #[arg(long, value_name = "URL")]
+    pub bootstrap_from: Option<Url>,
+
+    /// Time limit of the copy from `--bootstrap-from` per dataset.
+    ///
+    /// Whatever was not copied by then is ingested from the data sources.
+    #[arg(long, value_name = "SECS", default_value = "600")]
+    pub bootstrap_timeout_secs: u64,
+
     /// Known client IDs for metrics labeling. Client IDs not in this list
     /// will be reported as "unknown" to prevent metrics cardinality abuse.
     #[arg(long = "known-client", value_name = "ID")]
@@ -134,14 +186,19 @@ pub struct App {
     pub db: DBRef,
     pub data_service: DataServiceRef,
     pub query_service: QueryServiceRef,
-    pub api_controlled_datasets: BTreeSet<DatasetId>,
     pub metrics_registry: prometheus_client::registry::Registry,
-    pub known_clients: HashSet<String>
+    pub known_clients: HashSet<String>,
+    pub backup_dir: Option<PathBuf>
 }
 
 impl CLI {
     pub async fn build_app(&self) -> anyhow::Result<App> {
         let datasets = DatasetConfig::read_config_file(&self.datasets).context("failed to read datasets config")?;
+        let overrides_file = self
+            .dataset_overrides
+            .as_ref()
+            .map(PathBuf::from)
+            .unwrap_or_else(|| Path::new(&self.database_dir).join("dataset-overrides.yaml"));
 
         let mut settings = DatabaseSettings::default()
             .with_data_cache_size(self.data_cache_size)
@@ -157,6 +214,10 @@ impl CLI {
             settings = settings.with_max_background_jobs(jobs);
         }
 
+        if let Some(backup_dir) = self.restore_from.as_ref() {
+            self.restore_database(backup_dir)?;
+        }
+
         let db_open_started = Instant::now();
         info!("opening RocksDB");
         let db = settings
@@ -168,22 +229,29 @@ impl CLI {
             "RocksDB opened"
         );
 
+        // The database directory must exist before the overrides file can be placed there
+        let overrides = DatasetOverrides::load(overrides_file, datasets)?;
+
+        let data_service = DataService::start(
+            db.clone(),
+            overrides,
+            self.startup_disk_reclaim,
+            self.spill_bound_bytes,
+            self.bootstrap_from.clone().map(|peer| Bootstrap {
+                peer,
+                timeout: Duration::from_secs(self.bootstrap_timeout_secs)
+            })
+        )
+        .await
+        .map(Arc::new)?;
+
         let mut metrics_registry = crate::metrics::build_metrics_registry();
         metrics_registry.register_collector(Box::new(DatasetMetricsCollector {
             db: db.clone(),
-            datasets: datasets.keys().copied().collect()
+            data_service: data_service.clone()
         }));
         metrics_registry.register_collector(Box::new(RocksDbCollector { db: db.clone() }));
 
-        let api_controlled_datasets = datasets
-            .iter()
-            .filter_map(|(id, cfg)| matches!(cfg.retention_strategy, RetentionConfig::Api { .. }).then_some(*id))
-            .collect();
-
-        let data_service = DataService::start(db.clone(), datasets, self.startup_disk_reclaim, self.spill_bound_bytes)
-            .await
-            .map(Arc::new)?;
-
         let query_service = {
             let mut builder = QueryService::builder(db.clone());
             builder.set_max_data_waiters(self.query_max_data_waiters);
@@ -207,9 +275,28 @@ impl CLI {
             db,
             data_service,
             query_service,
-            api_controlled_datasets,
             metrics_registry,
-            known_clients
+            known_clients,
+            backup_dir: self.backup_dir.as_ref().map(PathBuf::from)
         })
     }
+
+    fn restore_database(&self, backup_dir: &str) -> anyhow::Result<()> {
+        let db_dir = Path::new(&self.database_dir);
+        let db_exists = db_dir.exists()
+            && std::fs::read_dir(db_dir)
+                .context("failed to list the database directory")?
+                .next()
+                .is_some();
+        if db_exists {
+            info!("database already exists, skipping restore from {}", backup_dir);
+            return Ok(());
+        }
+
+        let started = Instant::now();
+        info!("restoring database from {}", backup_dir);
+        restore_backup(backup_dir, db_dir).context("failed to restore database from backup")?;
+        info!(elapsed_ms = started.elapsed().as_millis() as u64, "database restored");
+        Ok(())
+    }
 }
@@ -1,35 +1,60 @@
-use std::{
-    collections::{BTreeMap, HashMap},
-    sync::Arc,
-    time::Instant
-};
+use std::{collections::HashMap, sync::Arc, time::Instant};
 
-use anyhow::{Context, anyhow};
-use futures::{FutureExt, StreamExt, TryStreamExt};
-use sqd_data_client::reqwest::ReqwestDataClient;
+use anyhow::{Context, anyhow, bail, ensure};
+use futures::{StreamExt, TryStreamExt};
+use parking_lot::RwLock;
 use sqd_storage::db::{CF_TABLES, DatasetId};
 use tracing::{error, info, warn};
 
 use crate::{
-    dataset_config::{DatasetConfig, RetentionConfig},
-    dataset_controller::DatasetController,
-    errors::UnknownDataset,
+    bootstrap::{Bootstrap, bootstrap_dataset},
+    dataset_config::{DatasetConfig, DatasetOverrides, RetentionConfig},
+    dataset_controller::{DatasetController, SourceClient},
+    errors::{DatasetKindChange, UnknownDataset},
     types::{DBRef, RetentionStrategy}
 };
 
 pub type DataServiceRef = Arc<DataService>;
 
+struct Dataset {
+    controller: Arc<DatasetController>,
+    config: DatasetConfig
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PutDatasetOutcome {
+    Created,
+    Updated,
+    Unchanged
+}
+
 pub struct DataService {
-    datasets: HashMap<DatasetId, Arc<DatasetController>>
+    db: DBRef,
+    spill_bound_bytes: usize,
+    datasets: RwLock<HashMap<DatasetId, Dataset>>,
+    // Serializes dataset additions and removals, so that controllers and
+    // persisted overrides never diverge.
+    overrides: tokio::sync::Mutex<DatasetOverrides>
+}
+
+impl std::fmt::Debug for DataService {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DataService")
+            .field("datasets", &self.dataset_ids())
+            .finish_non_exhaustive()
+    }
 }
 
 impl DataService {
     pub async fn start(
         db: DBRef,
-        datasets: BTreeMap<DatasetId, DatasetConfig>,
+        overrides: DatasetOverrides,
         disk_reclaim: bool,
-        spill_bound_bytes: usize
+        spill_bound_bytes: usize,
+        bootstrap: Option<Bootstrap>
     ) -> anyhow::Result<Self> {
+        let datasets = overrides.effective();
+
         let unconfigured: Vec<DatasetId> = db
             .get_all_datasets()?
             .into_iter()
@@ -50,54 +75,35 @@ impl DataService {
         let controller_init_started = Instant::now();
         info!(configured_datasets, "dataset controller initialization started");
 
+        let http_client = sqd_data_client::reqwest::default_http_client();
+
         let mut controllers = futures::stream::iter(datasets.into_iter())
             .map(|(dataset_id, cfg)| {
                 let db = db.clone();
-
-                let http_client = sqd_data_client::reqwest::default_http_client();
-
-                let data_sources = cfg
-                    .data_sources
-                    .into_iter()
-                    .map(|url| ReqwestDataClient::new(http_client.clone(), url))
-                    .collect();
-
-                let (retention, max_blocks) = match &cfg.retention_strategy {
-                    RetentionConfig::FromBlock { number, parent_hash } => (
-                        RetentionStrategy::FromBlock {
-                            number: *number,
-                            parent_hash: parent_hash.clone()
-                        },
-                        None
-                    ),
-                    RetentionConfig::Head(n) => (RetentionStrategy::Head(*n), None),
-                    RetentionConfig::Api { max_blocks } => (RetentionStrategy::None, *max_blocks),
-                    RetentionConfig::None => (RetentionStrategy::None, None)
-                };
-
-                tokio::task::spawn_blocking(move || {
-                    DatasetController::new(
-                        db,
-                        dataset_id,
-                        cfg.kind,
-                        retention,
-                        max_blocks,
-                        data_sources,
-                        spill_bound_bytes
-                    )
-                    .map(|c| {
-                        c.enable_compaction(!cfg.disable_compaction);
-                        Arc::new(c)
-                    })
-                })
-                .map(move |res| res.with_context(|| anyhow!("failed to initialize dataset {}", dataset_id)))
+                let http_client = http_client.clone();
+                let bootstrap = bootstrap.clone();
+                async move {
+                    // Best-effort: whatever was not copied gets ingested from the data sources
+                    if let Some(bootstrap) = bootstrap.as_ref() {
+                        if let Err(err) = bootstrap_dataset(db.clone(), http_client, bootstrap, dataset_id, &cfg).await
+                        {
+                            warn!(dataset_id = %dataset_id, error =? err, "bootstrap from peer failed");
+                        }
+                    }
+                    start_controller(db, dataset_id, cfg.clone(), spill_bound_bytes)
+                        .await
+                        .map(|controller| Dataset {
+                            controller,
+                            config: cfg
+                        })
+                }
             })
             .buffered(5);
 
         let mut datasets = HashMap::new();
 
-        while let Some(ctl) = controllers.try_next().await?.transpose()? {
-            datasets.insert(ctl.dataset_id(), ctl);
+        while let Some(ds) = controllers.try_next().await? {
+            datasets.insert(ds.controller.dataset_id(), ds);
         }
 
         info!(
@@ -107,15 +113,195 @@ impl DataService {
             "dataset controller initialization complete"
         );
 
-        Ok(Self { datasets })
+        Ok(Self {
+            db,
+            spill_bound_bytes,
+            datasets: RwLock::new(datasets),
+            overrides: tokio::sync::Mutex::new(overrides)
+        })
     }
 
     pub fn get_dataset(&self, dataset_id: DatasetId) -> Result<Arc<DatasetController>, UnknownDataset> {
         self.datasets
+            .read()
+            .get(&dataset_id)
+            .map(|ds| ds.controller.clone())
+            .ok_or(UnknownDataset { dataset_id })
+    }
+
+    pub fn get_dataset_config(&self, dataset_id: DatasetId) -> Result<DatasetConfig, UnknownDataset> {
+        self.datasets
+            .read()
             .get(&dataset_id)
-            .map(Arc::clone)
+            .map(|ds| ds.config.clone())
             .ok_or(UnknownDataset { dataset_id })
     }
+
+    pub fn dataset_ids(&self) -> Vec<DatasetId> {
+        let mut ids: Vec<_> = self.datasets.read().keys().copied().collect();
+        ids.sort();
+        ids
+    }
+
+    /// Whether the retention of the dataset is managed through the API
+    pub fn is_api_controlled(&self, dataset_id: DatasetId) -> bool {
+        self.datasets
+            .read()
+            .get(&dataset_id)
+            .is_some_and(|ds| matches!(ds.config.retention_strategy, RetentionConfig::Api { .. }))
+    }
+
+    /// Start serving a new dataset or apply a new config to an existing one.
+    ///
+    /// An existing dataset keeps its data, but its controller is restarted.
+    /// The kind of an existing dataset can't be changed.
+    pub async fn put_dataset(&self, dataset_id: DatasetId, config: DatasetConfig) -> anyhow::Result<PutDatasetOutcome> {
+        config.validate()?;
+
+        let mut overrides = self.overrides.lock().await;
+
+        let current = self.datasets.read().get(&dataset_id).map(|ds| ds.config.clone());
+        if let Some(current) = current.as_ref() {
+            if current == &config {
+                return Ok(PutDatasetOutcome::Unchanged);
+            }
+            ensure!(
+                current.kind == config.kind,
+                DatasetKindChange {
+                    dataset_id,
+                    current_kind: current.kind.as_str(),
+                    requested_kind: config.kind.as_str()
+                }
+            );
+        }
+
+        // Only one controller may drive the dataset at a time
+        let prev = self.datasets.write().remove(&dataset_id);
+        if let Some(prev) = prev {
+            prev.controller.stop().await
+        }
+
+        let started = start_controller(self.db.clone(), dataset_id, config.clone(), self.spill_bound_bytes).await;
+
+        let controller = match started.and_then(|ctl| overrides.set(dataset_id, Some(config.clone())).map(|_| ctl)) {
+            Ok(ctl) => ctl,
+            Err(err) => {
+                if let Some(current) = current {
+                    // bring the previous setup back, it is still what is persisted
+                    match start_controller(self.db.clone(), dataset_id, current.clone(), self.spill_bound_bytes).await {
+                        Ok(controller) => {
+                            self.datasets.write().insert(
+                                dataset_id,
+                                Dataset {
+                                    controller,
+                                    config: current
+                                }
+                            );
+                        }
+                        Err(restore_err) => {
+                            error!(
+                                dataset_id = %dataset_id,
+                                error =? restore_err,
+                                "failed to restore the previous dataset controller"
+                            )
+                        }
+                    }
+                }
+                return Err(err);
+            }
+        };
+
+        self.datasets.write().insert(dataset_id, Dataset { controller, config });
+
+        Ok(if current.is_some() {
+            info!(dataset_id = %dataset_id, "dataset updated via API");
+            PutDatasetOutcome::Updated
+        } else {
+            info!(dataset_id = %dataset_id, "dataset created via API");
+            PutDatasetOutcome::Created
+        })
+    }
+
+    /// Stop serving the dataset and delete all its data
+    pub async fn delete_dataset(&self, dataset_id: DatasetId) -> anyhow::Result<()> {
+        let mut overrides = self.overrides.lock().await;
+
+        if !self.datasets.read().contains_key(&dataset_id) {
+            bail!(UnknownDataset { dataset_id });
+        }
+
+        overrides.set(dataset_id, None)?;
+
+        // Stops ingestion; queries still holding the controller finish on their snapshots
+        let removed = self.datasets.write().remove(&dataset_id);
+        if let Some(removed) = removed {
+            removed.controller.stop().await
+        }
+
+        let db = self.db.clone();
+        tokio::task::spawn_blocking(move || db.delete_dataset(dataset_id))
+            .await
+            .context("dataset deletion task panicked")?
+            .with_context(|| {
+                format!(
+                    "dataset {} was removed, but its data was not fully deleted, the next boot will retry",
+                    dataset_id
+                )
+            })?;
+
+        info!(dataset_id = %dataset_id, "dataset deleted via API");
+        Ok(())
+    }
+}
+
+async fn start_controller(
+    db: DBRef,
+    dataset_id: DatasetId,
+    cfg: DatasetConfig,
+    spill_bound_bytes: usize
+) -> anyhow::Result<Arc<DatasetController>> {
+    let http_client = sqd_data_client::reqwest::default_http_client();
+
+    let (retention, max_blocks) = match &cfg.retention_strategy {
+        RetentionConfig::FromBlock { number, parent_hash } => (
+            RetentionStrategy::FromBlock {
+                number: *number,
+                parent_hash: parent_hash.clone()
+            },
+            None
+        ),
+        RetentionConfig::Head(n) => (RetentionStrategy::Head(*n), None),
+        RetentionConfig::Api { max_blocks } => (RetentionStrategy::None, *max_blocks),
+        RetentionConfig::None => (RetentionStrategy::None, None)
+    };
+
+    tokio::task::spawn_blocking(move || {
+        let data_sources = cfg
+            .data_sources
+            .iter()
+            .map(|source| SourceClient::new(&http_client, source.clone(), cfg.kind))
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        DatasetController::new(
+            db,
+            dataset_id,
+            cfg.kind,
+            retention,
+            max_blocks,
+            data_sources,
+            spill_bound_bytes,
+            cfg.address_index,
+            cfg.block_hash_quorum
+        )
+        .map(|c| {
+            c.enable_compaction(!cfg.disable_compaction);
+            Arc::new(c)
+        })
+    })
+    .await
+    .map_err(anyhow::Error::from)
+    .and_then(|res| res)
+    .with_context(|| anyhow!("failed to initialize dataset {}", dataset_id))
 }
 
 /// Startup-only disk recovery; must run before any ingest or query exists (the file
@@ -1,8 +1,13 @@
-use std::{collections::BTreeMap, fmt};
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf}
+};
 
+use anyhow::{Context, bail, ensure};
 use serde::{
     Deserialize, Deserializer, Serialize,
-    de::{self, IgnoredAny, MapAccess, Visitor}
+    de::{self, DeserializeOwned, IgnoredAny, MapAccess, Visitor}
 };
 use sqd_query::BlockNumber;
 use sqd_storage::db::DatasetId;
@@ -106,25 +111,195 @@ impl<'de> Deserialize<'de> for RetentionConfig {
     }
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(deny_unknown_fields)]
 pub struct DatasetConfig {
     pub kind: DatasetKind,
     pub retention_strategy: RetentionConfig,
     #[serde(default)]
     pub disable_compaction: bool,
-    pub data_sources: Vec<Url>
+    /// Build per-chunk value indexes of address columns (EVM `logs.address`,
+    /// `transactions.from`/`to`, Solana `instructions.program_id`),
+    /// so that address-filtered queries skip pages without the address.
+    #[serde(default)]
+    pub address_index: bool,
+    /// Accept a block only after this many data sources sent the same one,
+    /// to not follow a single faulty source onto a bad fork.
+    #[serde(default)]
+    pub block_hash_quorum: Option<usize>,
+    pub data_sources: Vec<DataSourceConfig>
+}
+
+/// Where the blocks of a dataset come from.
+///
+/// A bare url is either a data service speaking the portal stream protocol (`http(s)://`)
+/// or a directory or file with recorded blocks to replay (`file://`).
+/// A node is given as `rpc: <url>`, only EVM and Solana nodes are supported.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum DataSourceConfig {
+    Url(Url),
+    Rpc { rpc: Url }
+}
+
+impl DataSourceConfig {
+    fn validate(&self, kind: DatasetKind) -> anyhow::Result<()> {
+        match self {
+            DataSourceConfig::Url(url) => match url.scheme() {
+                "http" | "https" => {}
+                "file" => ensure!(
+                    url.to_file_path().is_ok(),
+                    "data source url '{}' is not a local path",
+                    url
+                ),
+                _ => bail!(
+                    "unsupported data source url '{}', only http(s) and file urls are allowed",
+                    url
+                )
+            },
+            DataSourceConfig::Rpc { rpc } => {
+                ensure!(
+                    matches!(rpc.scheme(), "http" | "https"),
+                    "unsupported rpc url '{}', only http(s) is allowed",
+                    rpc
+                );
+                ensure!(
+                    matches!(kind, DatasetKind::Evm | DatasetKind::Solana),
+                    "rpc data sources are not supported for {} datasets",
+                    kind.as_str()
+                );
+            }
+        }
+        Ok(())
+    }
 }
 
 impl DatasetConfig {
     pub fn read_config_file(file: &str) -> anyhow::Result<BTreeMap<DatasetId, DatasetConfig>> {
-        let reader = std::io::BufReader::new(std::fs::File::open(file)?);
-        let deser = serde_yaml::Deserializer::from_reader(reader);
-        let config = serde_yaml::with::singleton_map_recursive::deserialize(deser)?;
-        Ok(config)
+        read_yaml_file(file.as_ref())
+    }
+
+    /// Checks what serde can't: the config must describe a dataset we are able to ingest.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        ensure!(!self.data_sources.is_empty(), "at least one data source is required");
+        for source in self.data_sources.iter() {
+            source.validate(self.kind)?;
+        }
+        if let RetentionConfig::Head(n) = self.retention_strategy {
+            ensure!(n > 0, "Head retention must keep at least one block");
+        }
+        if let Some(quorum) = self.block_hash_quorum {
+            ensure!(
+                quorum > 0 && quorum <= self.data_sources.len(),
+                "block hash quorum must be between 1 and the number of data sources"
+            );
+        }
+        Ok(())
+    }
+}
+
+fn read_yaml_file<T: DeserializeOwned>(file: &Path) -> anyhow::Result<T> {
+    let reader = std::io::BufReader::new(std::fs::File::open(file)?);
+    let deser = serde_yaml::Deserializer::from_reader(reader);
+    let value = serde_yaml::with::singleton_map_recursive::deserialize(deser)?;
+    Ok(value)
+}
+
+/// Dataset changes made through the admin API.
+///
+/// They are persisted in a separate file (same format as the datasets config,
+/// with `null` marking a removed dataset) and applied on top of the config file on boot.
+/// An override the config file has already caught up with is dropped, so folding
+/// API changes into the config file makes the override file shrink back to nothing.
+pub struct DatasetOverrides {
+    path: PathBuf,
+    config_file: BTreeMap<DatasetId, DatasetConfig>,
+    entries: BTreeMap<DatasetId, Option<DatasetConfig>>
+}
+
+impl DatasetOverrides {
+    pub fn load(path: PathBuf, config_file: BTreeMap<DatasetId, DatasetConfig>) -> anyhow::Result<Self> {
+        let entries = if path.exists() {
+            read_yaml_file(&path)
+                .with_context(|| format!("failed to read dataset overrides from {}", path.display()))?
+        } else {
+            BTreeMap::new()
+        };
+
+        let mut overrides = Self {
+            path,
+            config_file,
+            entries
+        };
+
+        let before = overrides.entries.len();
+        overrides.prune();
+        if overrides.entries.len() != before {
+            overrides.persist()?;
+        }
+
+        Ok(overrides)
+    }
+
+    /// Config file with all overrides applied
+    pub fn effective(&self) -> BTreeMap<DatasetId, DatasetConfig> {
+        let mut datasets = self.config_file.clone();
+        for (id, cfg) in self.entries.iter() {
+            match cfg {
+                Some(cfg) => datasets.insert(*id, cfg.clone()),
+                None => datasets.remove(id)
+            };
+        }
+        datasets
+    }
+
+    /// Record a new config (`None` for removal) of a dataset and persist the change
+    pub fn set(&mut self, dataset_id: DatasetId, config: Option<DatasetConfig>) -> anyhow::Result<()> {
+        let prev = self.entries.insert(dataset_id, config);
+        self.prune();
+        self.persist().inspect_err(|_| {
+            match prev {
+                Some(prev) => self.entries.insert(dataset_id, prev),
+                None => self.entries.remove(&dataset_id)
+            };
+        })
+    }
+
+    fn prune(&mut self) {
+        let config_file = &self.config_file;
+        self.entries.retain(|id, cfg| config_file.get(id) != cfg.as_ref())
+    }
+
+    fn persist(&self) -> anyhow::Result<()> {
+        let mut yaml = Vec::new();
+        let mut ser = serde_yaml::Serializer::new(&mut yaml);
+        serde_yaml::with::singleton_map_recursive::serialize(&self.entries, &mut ser)?;
+
+        // write + sync + rename, so that a crash never leaves a truncated file behind,
+        // and an acknowledged change survives a power loss
+        write_durably(&self.path, &yaml)
+            .with_context(|| format!("failed to write dataset overrides to {}", self.path.display()))
     }
 }
 
+fn write_durably(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let tmp = path.with_extension("tmp");
+    let mut file = std::fs::File::create(&tmp)?;
+    file.write_all(data)?;
+    file.sync_all()?;
+    drop(file);
+    std::fs::rename(&tmp, path)?;
+
+    // the rename itself is durable only once the directory entry is synced
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new(".")
+    };
+    std::fs::File::open(dir)?.sync_all()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -161,4 +336,110 @@ mod tests {
         );
         assert!(parse("Bogus").is_err());
     }
+
+    #[test]
+    fn data_sources_parse() {
+        let deser = serde_yaml::Deserializer::from_str(
+            "- http://localhost:7373\n- file:///data/blocks\n- rpc: http://localhost:8545\n"
+        );
+        let sources: Vec<DataSourceConfig> = serde_yaml::with::singleton_map_recursive::deserialize(deser).unwrap();
+        assert_eq!(
+            sources,
+            vec![
+                DataSourceConfig::Url(Url::parse("http://localhost:7373").unwrap()),
+                DataSourceConfig::Url(Url::parse("file:///data/blocks").unwrap()),
+                DataSourceConfig::Rpc {
+                    rpc: Url::parse("http://localhost:8545").unwrap()
+                }
+            ]
+        );
+    }
+
+    fn dataset(kind: DatasetKind, head: u64) -> DatasetConfig {
+        DatasetConfig {
+            kind,
+            retention_strategy: RetentionConfig::Head(head),
+            disable_compaction: false,
+            address_index: false,
+            block_hash_quorum: None,
+            data_sources: vec![DataSourceConfig::Url(Url::parse("http://localhost:7373").unwrap())]
+        }
+    }
+
+    #[test]
+    fn validation() {
+        assert!(dataset(DatasetKind::Evm, 10).validate().is_ok());
+        assert!(dataset(DatasetKind::Evm, 0).validate().is_err());
+
+        let mut cfg = dataset(DatasetKind::Evm, 10);
+        cfg.data_sources.clear();
+        assert!(cfg.validate().is_err());
+
+        cfg.data_sources
+            .push(DataSourceConfig::Url(Url::parse("ftp://localhost/blocks").unwrap()));
+        assert!(cfg.validate().is_err());
+
+        cfg.data_sources[0] = DataSourceConfig::Url(Url::parse("file:///tmp/blocks").unwrap());
+        assert!(cfg.validate().is_ok());
+
+        let rpc = DataSourceConfig::Rpc {
+            rpc: Url::parse("http://localhost:8545").unwrap()
+        };
+        let mut cfg = dataset(DatasetKind::Evm, 10);
+        cfg.data_sources = vec![rpc.clone()];
+        assert!(cfg.validate().is_ok());
+        cfg.kind = DatasetKind::Solana;
+        assert!(cfg.validate().is_ok());
+        cfg.kind = DatasetKind::Bitcoin;
+        assert!(cfg.validate().is_err());
+
+        let mut cfg = dataset(DatasetKind::Evm, 10);
+        cfg.block_hash_quorum = Some(1);
+        assert!(cfg.validate().is_ok());
+        cfg.block_hash_quorum = Some(2);
+        assert!(cfg.validate().is_err());
+        cfg.block_hash_quorum = Some(0);
+        assert!(cfg.validate().is_err());
+    }
+
+    #[test]
+    fn overrides_survive_reload() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("overrides.yaml");
+        let eth = DatasetId::from_str("eth");
+        let sol = DatasetId::from_str("sol");
+        let config_file = BTreeMap::from([(eth, dataset(DatasetKind::Evm, 10))]);
+
+        let mut overrides = DatasetOverrides::load(path.clone(), config_file.clone()).unwrap();
+        overrides.set(sol, Some(dataset(DatasetKind::Solana, 5))).unwrap();
+        overrides.set(eth, None).unwrap();
+
+        let reloaded = DatasetOverrides::load(path, config_file).unwrap();
+        assert_eq!(
+            reloaded.effective(),
+            BTreeMap::from([(sol, dataset(DatasetKind::Solana, 5))])
+        );
+    }
+
+    #[test]
+    fn overrides_matching_the_config_file_are_dropped() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("overrides.yaml");
+        let eth = DatasetId::from_str("eth");
+
+        let mut overrides = DatasetOverrides::load(path.clone(), BTreeMap::new()).unwrap();
+        overrides.set(eth, Some(dataset(DatasetKind::Evm, 10))).unwrap();
+        assert_eq!(overrides.entries.len(), 1);
+
+        // the operator has added the same dataset to the config file
+        let config_file = BTreeMap::from([(eth, dataset(DatasetKind::Evm, 10))]);
+        let reloaded = DatasetOverrides::load(path.clone(), config_file.clone()).unwrap();
+        assert!(reloaded.entries.is_empty());
+        assert_eq!(reloaded.effective(), config_file);
+
+        // removing a dataset absent from the config file leaves nothing to remember
+        let mut overrides = DatasetOverrides::load(path, BTreeMap::new()).unwrap();
+        overrides.set(eth, None).unwrap();
+        assert!(overrides.entries.is_empty());
+    }
 }
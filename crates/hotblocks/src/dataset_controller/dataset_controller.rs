@@ -2,17 +2,33 @@ use std::{ops::Add, time::Duration};
 
 use anyhow::{Context, anyhow};
 use futures::{FutureExt, StreamExt, future::BoxFuture, stream::FuturesUnordered};
-use sqd_data_client::{DataClient, reqwest::ReqwestDataClient};
+use sqd_data_client::DataClient;
 use sqd_primitives::{BlockNumber, BlockRef, TransactionRef};
 use sqd_storage::db::{CompactionStatus, DatasetId};
 use tokio::{select, task::JoinHandle, time::Instant};
+use tokio_util::task::TaskTracker;
 use tracing::{Instrument, debug, error, info, info_span, instrument, warn};
 
 use crate::{
-    dataset_controller::{ingest::ingest, ingest_generic::IngestMessage, write_controller::WriteController},
-    types::{DBRef, DatasetKind, RetentionStrategy}
+    dataset_controller::{
+        ingest::ingest, ingest_generic::IngestMessage, source_client::SourceClient, write_controller::WriteController
+    },
+    types::{DBRef, DatasetKind, ForkEvent, RetentionStrategy}
 };
 
+/// Capacity of the fork event queue of a single subscriber
+const FORK_EVENTS_CAPACITY: usize = 16;
+
+/// Live view of dataset heads.
+///
+/// Does not keep the dataset controller alive, so all channels close
+/// once the dataset is removed.
+pub struct HeadSubscription {
+    pub head: tokio::sync::watch::Receiver<Option<BlockRef>>,
+    pub finalized_head: tokio::sync::watch::Receiver<Option<BlockRef>>,
+    pub forks: tokio::sync::broadcast::Receiver<ForkEvent>
+}
+
 pub struct DatasetController {
     db: DBRef,
     dataset_id: DatasetId,
@@ -20,7 +36,11 @@ pub struct DatasetController {
     retention_sender: tokio::sync::watch::Sender<RetentionStrategy>,
     head_receiver: tokio::sync::watch::Receiver<Option<BlockRef>>,
     finalized_head_receiver: tokio::sync::watch::Receiver<Option<BlockRef>>,
+    fork_sender: tokio::sync::broadcast::Sender<ForkEvent>,
     compaction_enabled_sender: tokio::sync::watch::Sender<bool>,
+    // Tracks both tasks and the storage writes they run on the blocking pool,
+    // which outlive an aborted task
+    tasks: TaskTracker,
     task: JoinHandle<()>,
     compaction_task: JoinHandle<()>
 }
@@ -40,11 +60,14 @@ impl DatasetController {
         dataset_kind: DatasetKind,
         retention: RetentionStrategy,
         max_blocks: Option<u64>,
-        data_sources: Vec<ReqwestDataClient>,
-        spill_bound_bytes: usize
+        data_sources: Vec<SourceClient>,
+        spill_bound_bytes: usize,
+        address_index: bool,
+        block_hash_quorum: Option<usize>
     ) -> anyhow::Result<Self> {
         let (head_sender, head_receiver) = tokio::sync::watch::channel(None);
         let (finalized_head_sender, finalized_head_receiver) = tokio::sync::watch::channel(None);
+        let (fork_sender, _) = tokio::sync::broadcast::channel(FORK_EVENTS_CAPACITY);
 
         // Channels live on the controller so they outlive writer restarts; each
         // rebuilt writer gets a sender clone and seeds it from storage.
@@ -53,8 +76,10 @@ impl DatasetController {
             dataset_id,
             dataset_kind,
             head_sender.clone(),
-            finalized_head_sender.clone()
-        )?;
+            finalized_head_sender.clone(),
+            fork_sender.clone()
+        )?
+        .with_address_index(address_index);
 
         if let RetentionStrategy::FromBlock { number, parent_hash } = &retention {
             write.init_retention(*number, parent_hash.clone())?;
@@ -63,6 +88,8 @@ impl DatasetController {
         let (retention_sender, retention_recv) = tokio::sync::watch::channel(retention);
         let (compaction_enabled_sender, compaction_enabled_receiver) = tokio::sync::watch::channel(false);
 
+        let tasks = TaskTracker::new();
+
         let ctl = Ctl {
             db: db.clone(),
             dataset_id,
@@ -72,13 +99,19 @@ impl DatasetController {
             retention_recv,
             head_sender,
             finalized_head_sender,
-            spill_bound_bytes
+            fork_sender: fork_sender.clone(),
+            spill_bound_bytes,
+            address_index,
+            block_hash_quorum,
+            tasks: tasks.clone()
         };
 
-        let task = tokio::spawn(ctl.run(write).in_current_span());
+        let task = tasks.spawn(ctl.run(write).in_current_span());
 
-        let compaction_task =
-            tokio::spawn(compaction_loop(db.clone(), dataset_id, compaction_enabled_receiver).in_current_span());
+        let compaction_task = tasks.spawn(
+            compaction_loop(db.clone(), dataset_id, compaction_enabled_receiver, tasks.clone()).in_current_span()
+        );
+        tasks.close();
 
         Ok(Self {
             db,
@@ -87,12 +120,24 @@ impl DatasetController {
             retention_sender,
             head_receiver,
             finalized_head_receiver,
+            fork_sender,
             compaction_enabled_sender,
+            tasks,
             task,
             compaction_task
         })
     }
 
+    /// Stops ingestion and compaction and waits until no write to the dataset is in flight.
+    ///
+    /// Must be awaited before the dataset is replaced or deleted: queries may still hold
+    /// the controller, so dropping it does not stop anything.
+    pub async fn stop(&self) {
+        self.task.abort();
+        self.compaction_task.abort();
+        self.tasks.wait().await
+    }
+
     pub fn dataset_id(&self) -> DatasetId {
         self.dataset_id
     }
@@ -113,6 +158,14 @@ impl DatasetController {
         self.head_receiver.borrow().as_ref().map(|h| h.number)
     }
 
+    pub fn subscribe(&self) -> HeadSubscription {
+        HeadSubscription {
+            head: self.head_receiver.clone(),
+            finalized_head: self.finalized_head_receiver.clone(),
+            forks: self.fork_sender.subscribe()
+        }
+    }
+
     /// Resolves a block hash to its `BlockRef` via the storage index.
     /// `Ok(None)` means the hash is not in the index.
     pub async fn get_block_by_hash(&self, hash: String) -> anyhow::Result<Option<BlockRef>> {
@@ -205,7 +258,7 @@ struct Ctl {
     db: DBRef,
     dataset_id: DatasetId,
     dataset_kind: DatasetKind,
-    data_sources: Vec<ReqwestDataClient>,
+    data_sources: Vec<SourceClient>,
     // Safety cap on retained blocks for Api-controlled datasets: even if the portal
     // stops advancing the floor, the tail is trimmed to keep roughly this many blocks
     // behind the tip. `None` means grow indefinitely.
@@ -213,7 +266,11 @@ struct Ctl {
     retention_recv: tokio::sync::watch::Receiver<RetentionStrategy>,
     head_sender: tokio::sync::watch::Sender<Option<BlockRef>>,
     finalized_head_sender: tokio::sync::watch::Sender<Option<BlockRef>>,
-    spill_bound_bytes: usize
+    fork_sender: tokio::sync::broadcast::Sender<ForkEvent>,
+    spill_bound_bytes: usize,
+    address_index: bool,
+    block_hash_quorum: Option<usize>,
+    tasks: TaskTracker
 }
 
 macro_rules! warn_on_tx_restart {
@@ -230,15 +287,16 @@ macro_rules! warn_on_tx_restart {
 }
 
 macro_rules! blocking_write {
-    ($write:ident, $body:expr) => {{
+    ($tasks:expr, $write:ident, $body:expr) => {{
         let span = tracing::Span::current();
-        let res = tokio::task::spawn_blocking(move || {
-            let _enter = span.enter();
-            let result = warn_on_tx_restart!($body);
-            (result, $write)
-        })
-        .await
-        .context("write panicked")?;
+        let res = $tasks
+            .spawn_blocking(move || {
+                let _enter = span.enter();
+                let result = warn_on_tx_restart!($body);
+                (result, $write)
+            })
+            .await
+            .context("write panicked")?;
         $write = res.1;
         res.0
     }};
@@ -264,7 +322,7 @@ impl Ctl {
 
         macro_rules! blocking {
             ($body:expr) => {
-                blocking_write!(write, $body)
+                blocking_write!(self.tasks, write, $body)
             };
         }
 
@@ -412,7 +470,7 @@ impl Ctl {
                 let (number, parent_hash) = self.clamp_floor(&write, number, parent_hash);
                 let will_erase_head = write.head().map_or(false, |h| h.number < number) || // FromBlock is greater than current head, so everything is cleared
                     write.start_block() > number; // FromBlock is less than current front, dropping everything by design
-                blocking_write!(write, write.retain(number, parent_hash))?;
+                blocking_write!(self.tasks, write, write.retain(number, parent_hash))?;
                 match state {
                     State::Ingest { .. } if !will_erase_head => {} // Keep ingesting, head is valid
                     _ => *state = State::Init { head: self.max_blocks } // New ingest needed
@@ -441,7 +499,8 @@ impl Ctl {
                 self.dataset_kind,
                 write.next_block(),
                 write.head_hash(),
-                self.spill_bound_bytes
+                self.spill_bound_bytes,
+                self.block_hash_quorum
             )
             .instrument(ingest_span)
         );
@@ -459,18 +518,29 @@ impl Ctl {
         let dataset_kind = self.dataset_kind;
         let head_sender = self.head_sender.clone();
         let finalized_head_sender = self.finalized_head_sender.clone();
+        let fork_sender = self.fork_sender.clone();
+        let address_index = self.address_index;
 
         let span = tracing::Span::current();
-        tokio::task::spawn_blocking(move || {
-            let _entered = span.enter();
-            WriteController::new(db, dataset_id, dataset_kind, head_sender, finalized_head_sender)
-        })
-        .await
-        .context("write init task panicked")?
+        self.tasks
+            .spawn_blocking(move || {
+                let _entered = span.enter();
+                WriteController::new(
+                    db,
+                    dataset_id,
+                    dataset_kind,
+                    head_sender,
+                    finalized_head_sender,
+                    fork_sender
+                )
+                .map(|write| write.with_address_index(address_index))
+            })
+            .await
+            .context("write init task panicked")?
     }
 }
 
-async fn fetch_chain_top(clients: Vec<ReqwestDataClient>) -> BlockNumber {
+async fn fetch_chain_top(clients: Vec<SourceClient>) -> BlockNumber {
     let mut calls: FuturesUnordered<_> = (0..clients.len()).map(|i| call_client(&clients, i, false)).collect();
 
     let mut completed = 0;
@@ -527,18 +597,14 @@ async fn fetch_chain_top(clients: Vec<ReqwestDataClient>) -> BlockNumber {
         }
     }
 
-    async fn call_client(
-        clients: &[ReqwestDataClient],
-        idx: usize,
-        backoff: bool
-    ) -> (anyhow::Result<BlockNumber>, usize) {
+    async fn call_client(clients: &[SourceClient], idx: usize, backoff: bool) -> (anyhow::Result<BlockNumber>, usize) {
         if backoff {
             tokio::time::sleep(Duration::from_secs(5)).await;
         }
         clients[idx]
             .get_head()
             .map(move |res| {
-                let res = res.map(|maybe_head| maybe_head.map_or(0, |h| h.number));
+                let res = res.map(|maybe_head| maybe_head.unwrap_or(0));
                 (res, idx)
             })
             .await
@@ -546,21 +612,27 @@ async fn fetch_chain_top(clients: Vec<ReqwestDataClient>) -> BlockNumber {
 }
 
 #[instrument(name = "compaction", skip_all)]
-async fn compaction_loop(db: DBRef, dataset_id: DatasetId, mut enabled: tokio::sync::watch::Receiver<bool>) {
+async fn compaction_loop(
+    db: DBRef,
+    dataset_id: DatasetId,
+    mut enabled: tokio::sync::watch::Receiver<bool>,
+    tasks: TaskTracker
+) {
     let mut skips = 0;
     let skip_pause = [1, 2, 3, 4, 5, 10, 20, 30, 60];
     loop {
         if enabled.borrow_and_update().clone() {
             let db = db.clone();
             let span = tracing::Span::current();
-            let result = match tokio::task::spawn_blocking(move || {
-                let _s = span.enter();
-                debug!("compaction started");
-                warn_on_tx_restart! {
-                    db.perform_dataset_compaction(dataset_id, None, None, None)
-                }
-            })
-            .await
+            let result = match tasks
+                .spawn_blocking(move || {
+                    let _s = span.enter();
+                    debug!("compaction started");
+                    warn_on_tx_restart! {
+                        db.perform_dataset_compaction(dataset_id, None, None, None)
+                    }
+                })
+                .await
             {
                 Ok(res) => res,
                 Err(err) => Err(anyhow!("failed to await compaction task - {}", err))
@@ -1,28 +1,37 @@
-use bytes::Bytes;
+use std::time::Duration;
+
 use futures::{FutureExt, future::BoxFuture};
-use serde::de::DeserializeOwned;
-use sqd_data_client::reqwest::ReqwestDataClient;
 use sqd_data_source::{DataSource, StandardDataSource};
 use sqd_primitives::BlockNumber;
 use sqd_storage::db::DatasetId;
 
 use crate::{
-    dataset_controller::ingest_generic::{IngestGeneric, IngestMessage},
+    dataset_controller::{
+        ingest_generic::{IngestGeneric, IngestMessage},
+        source_client::{SourceClient, parse_source_block}
+    },
     types::DatasetKind
 };
 
+/// How long to wait for the data sources to agree on a block, before accepting the one with the most votes
+const HASH_QUORUM_TIMEOUT: Duration = Duration::from_secs(2);
+
 pub fn ingest<'a, 'b>(
     dataset_id: DatasetId,
     message_sender: tokio::sync::mpsc::Sender<IngestMessage>,
-    sources: Vec<ReqwestDataClient>,
+    sources: Vec<SourceClient>,
     dataset_kind: DatasetKind,
     first_block: BlockNumber,
     parent_block_hash: Option<&'a str>,
-    spill_bound_bytes: usize
+    spill_bound_bytes: usize,
+    block_hash_quorum: Option<usize>
 ) -> BoxFuture<'b, anyhow::Result<()>> {
     macro_rules! run {
         ($builder:expr) => {{
-            let mut data_source = StandardDataSource::new(sources, from_json_bytes);
+            let mut data_source = StandardDataSource::new(sources, parse_source_block);
+            if let Some(quorum) = block_hash_quorum {
+                data_source = data_source.with_hash_quorum(quorum, HASH_QUORUM_TIMEOUT);
+            }
             data_source.set_position(first_block, parent_block_hash);
             IngestGeneric::new(dataset_id, data_source, $builder, message_sender, spill_bound_bytes)
                 .run()
@@ -49,9 +58,11 @@ pub fn ingest<'a, 'b>(
         DatasetKind::Tron => {
             run!(sqd_data::tron::tables::TronChunkBuilder::new())
         }
+        DatasetKind::Substrate => {
+            run!(sqd_data::substrate::tables::SubstrateChunkBuilder::new())
+        }
+        DatasetKind::Fuel => {
+            run!(sqd_data::fuel::tables::FuelChunkBuilder::new())
+        }
     }
 }
-
-fn from_json_bytes<T: DeserializeOwned>(bytes: Bytes) -> anyhow::Result<T> {
-    serde_json::from_slice(&bytes).map_err(|err| err.into())
-}
@@ -1,7 +1,10 @@
 mod dataset_controller;
 mod ingest;
 mod ingest_generic;
+mod source_client;
 mod write_controller;
 
-pub use dataset_controller::DatasetController;
+pub use dataset_controller::{DatasetController, HeadSubscription};
 pub(crate) use ingest_generic::DEFAULT_SPILL_BOUND_BYTES;
+pub(crate) use source_client::SourceClient;
+pub(crate) use write_controller::apply_table_options;
@@ -0,0 +1,163 @@
+use std::any::{Any, type_name};
+
+use anyhow::{Context, anyhow, bail};
+use bytes::Bytes;
+use futures::{FutureExt, StreamExt, TryStreamExt, future::BoxFuture};
+use serde::de::DeserializeOwned;
+use sqd_data_client::{
+    BlockStreamRequest, BlockStreamResponse, DataClient, evm_rpc::EvmRpcDataClient, replay::ReplayDataClient,
+    reqwest::ReqwestDataClient, solana_rpc::SolanaRpcDataClient
+};
+use sqd_primitives::{BlockNumber, BlockRef};
+use url::Url;
+
+use crate::{dataset_config::DataSourceConfig, types::DatasetKind};
+
+/// Data source of a dataset, whatever protocol it speaks
+#[derive(Debug, Clone)]
+pub struct SourceClient {
+    url: Url,
+    client: Client
+}
+
+#[derive(Debug, Clone)]
+enum Client {
+    Portal(ReqwestDataClient),
+    Replay(ReplayDataClient),
+    EvmRpc(EvmRpcDataClient),
+    SolanaRpc(SolanaRpcDataClient)
+}
+
+/// Block as received from a [`SourceClient`].
+///
+/// Portal and replay sources send blocks as json, nodes are already mapped to the data model.
+pub enum SourceBlock {
+    Json(Bytes),
+    Evm(Box<sqd_data::evm::model::Block>),
+    Solana(Box<sqd_data::solana::model::Block>)
+}
+
+impl SourceClient {
+    /// Replay sources index their recording here, so this may block for a while
+    pub fn new(http: &reqwest::Client, config: DataSourceConfig, kind: DatasetKind) -> anyhow::Result<Self> {
+        let (url, client) = match config {
+            DataSourceConfig::Url(url) if url.scheme() == "file" => {
+                let path = url
+                    .to_file_path()
+                    .map_err(|_| anyhow!("data source url '{}' is not a local path", url))?;
+                let client = ReplayDataClient::open([&path])
+                    .with_context(|| format!("failed to open recording at {}", path.display()))?;
+                (url, Client::Replay(client))
+            }
+            DataSourceConfig::Url(url) => {
+                let client = ReqwestDataClient::new(http.clone(), url.clone());
+                (url, Client::Portal(client))
+            }
+            DataSourceConfig::Rpc { rpc } => {
+                let client = match kind {
+                    DatasetKind::Evm => Client::EvmRpc(EvmRpcDataClient::new(http.clone(), rpc.clone())),
+                    DatasetKind::Solana => Client::SolanaRpc(SolanaRpcDataClient::new(http.clone(), rpc.clone())),
+                    kind => bail!("rpc data sources are not supported for {} datasets", kind.as_str())
+                };
+                (rpc, client)
+            }
+        };
+        Ok(Self { url, client })
+    }
+
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    pub async fn get_head(&self) -> anyhow::Result<Option<BlockNumber>> {
+        match &self.client {
+            Client::Portal(c) => c.get_head().await.map(|head| head.map(|h| h.number)),
+            Client::Replay(c) => Ok(c.get_head().map(|h| h.number)),
+            Client::EvmRpc(c) => c.get_head().await.map(Some),
+            Client::SolanaRpc(c) => c.get_head().await.map(Some)
+        }
+    }
+}
+
+impl DataClient for SourceClient {
+    type Block = SourceBlock;
+
+    fn stream(&self, req: BlockStreamRequest) -> BoxFuture<'static, anyhow::Result<BlockStreamResponse<Self::Block>>> {
+        match &self.client {
+            Client::Portal(c) => map_stream(DataClient::stream(c, req), SourceBlock::Json),
+            Client::Replay(c) => map_stream(DataClient::stream(c, req), SourceBlock::Json),
+            Client::EvmRpc(c) => map_stream(DataClient::stream(c, req), |b| SourceBlock::Evm(Box::new(b))),
+            Client::SolanaRpc(c) => map_stream(DataClient::stream(c, req), |b| SourceBlock::Solana(Box::new(b)))
+        }
+    }
+
+    fn get_finalized_head(&self) -> BoxFuture<'static, anyhow::Result<Option<BlockRef>>> {
+        match &self.client {
+            Client::Portal(c) => DataClient::get_finalized_head(c),
+            Client::Replay(c) => DataClient::get_finalized_head(c),
+            Client::EvmRpc(c) => DataClient::get_finalized_head(c),
+            Client::SolanaRpc(c) => DataClient::get_finalized_head(c)
+        }
+    }
+
+    fn is_retryable(&self, err: &anyhow::Error) -> bool {
+        match &self.client {
+            Client::Portal(c) => c.is_retryable(err),
+            Client::Replay(c) => DataClient::is_retryable(c, err),
+            Client::EvmRpc(c) => c.is_retryable(err),
+            Client::SolanaRpc(c) => c.is_retryable(err)
+        }
+    }
+
+    fn error_kind(&self, err: &anyhow::Error) -> &'static str {
+        match &self.client {
+            Client::Portal(c) => c.error_kind(err),
+            Client::Replay(c) => c.error_kind(err),
+            Client::EvmRpc(c) => c.error_kind(err),
+            Client::SolanaRpc(c) => c.error_kind(err)
+        }
+    }
+
+    fn source_label(&self) -> String {
+        match &self.client {
+            Client::Portal(c) => c.source_label(),
+            Client::Replay(c) => c.source_label(),
+            Client::EvmRpc(c) => c.source_label(),
+            Client::SolanaRpc(c) => c.source_label()
+        }
+    }
+}
+
+fn map_stream<B: Send + 'static>(
+    response: BoxFuture<'static, anyhow::Result<BlockStreamResponse<B>>>,
+    map: fn(B) -> SourceBlock
+) -> BoxFuture<'static, anyhow::Result<BlockStreamResponse<SourceBlock>>> {
+    response
+        .map(move |res| {
+            res.map(|res| match res {
+                BlockStreamResponse::Stream { blocks, finalized_head } => BlockStreamResponse::Stream {
+                    blocks: blocks.map_ok(map).boxed(),
+                    finalized_head
+                },
+                BlockStreamResponse::Fork(prev_blocks) => BlockStreamResponse::Fork(prev_blocks)
+            })
+        })
+        .boxed()
+}
+
+/// Turns a received block into the block of the dataset's chunk builder
+pub fn parse_source_block<T: DeserializeOwned + 'static>(block: SourceBlock) -> anyhow::Result<T> {
+    match block {
+        SourceBlock::Json(bytes) => serde_json::from_slice(&bytes).map_err(|err| err.into()),
+        SourceBlock::Evm(block) => downcast(block),
+        SourceBlock::Solana(block) => downcast(block)
+    }
+}
+
+fn downcast<B: Any, T: Any>(block: Box<B>) -> anyhow::Result<T> {
+    let block: Box<dyn Any> = block;
+    block
+        .downcast::<T>()
+        .map(|block| *block)
+        .map_err(|_| anyhow!("{} can't be ingested as {}", type_name::<B>(), type_name::<T>()))
+}
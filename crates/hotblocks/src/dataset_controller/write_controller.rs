@@ -1,16 +1,17 @@
 use std::{collections::BTreeMap, time::Instant as StdInstant};
 
 use anyhow::{Context, anyhow, bail, ensure};
+use sqd_dataset::{Codec, TableDescription};
 use sqd_primitives::{BlockNumber, BlockRef};
-use sqd_storage::db::{Chunk as StorageChunk, Chunk, DatasetId, HashIndexWriteMetrics};
-use tokio::sync::watch;
+use sqd_storage::db::{Chunk as StorageChunk, Chunk, DatasetId, HashIndexWriteMetrics, TableBuilder};
+use tokio::sync::{broadcast, watch};
 use tracing::{debug, field::valuable, info, instrument, warn};
 
 use crate::{
     dataset_controller::ingest_generic::{IngestMessage, NewChunk},
     errors::UnapplicableFork,
     metrics::{WriteStage, report_hash_index_write_metrics, report_write_duration},
-    types::{DBRef, DatasetKind}
+    types::{DBRef, DatasetKind, ForkEvent}
 };
 
 #[derive(Debug)]
@@ -34,7 +35,9 @@ pub struct WriteController {
     head: Option<BlockRef>,
     finalized_head: Option<BlockRef>,
     head_sender: watch::Sender<Option<BlockRef>>,
-    finalized_head_sender: watch::Sender<Option<BlockRef>>
+    finalized_head_sender: watch::Sender<Option<BlockRef>>,
+    fork_sender: broadcast::Sender<ForkEvent>,
+    address_index: bool
 }
 
 impl WriteController {
@@ -43,7 +46,8 @@ impl WriteController {
         dataset_id: DatasetId,
         dataset_kind: DatasetKind,
         head_sender: watch::Sender<Option<BlockRef>>,
-        finalized_head_sender: watch::Sender<Option<BlockRef>>
+        finalized_head_sender: watch::Sender<Option<BlockRef>>,
+        fork_sender: broadcast::Sender<ForkEvent>
     ) -> anyhow::Result<Self> {
         db.create_dataset_if_not_exists(dataset_id, dataset_kind.storage_kind())?;
 
@@ -62,7 +66,9 @@ impl WriteController {
             head: last_chunk.as_ref().map(get_chunk_head),
             finalized_head: label.and_then(|l| l.finalized_head().cloned()),
             head_sender,
-            finalized_head_sender
+            finalized_head_sender,
+            fork_sender,
+            address_index: false
         };
 
         // Reseed subscribers to committed state (CN-9: recovery on writer rebuild).
@@ -72,6 +78,12 @@ impl WriteController {
         Ok(this)
     }
 
+    /// Also build value indexes for the columns the dataset description marks as indexable.
+    pub fn with_address_index(mut self, yes: bool) -> Self {
+        self.address_index = yes;
+        self
+    }
+
     pub fn dataset_kind(&self) -> DatasetKind {
         self.dataset_kind
     }
@@ -436,6 +448,17 @@ impl WriteController {
 
         debug!(finalized_head = valuable(&finalized_head), "saved new chunk");
 
+        if let Some(previous_head) = self.head.as_ref().filter(|h| chunk.first_block() <= h.number) {
+            // nobody listening is fine
+            let _ = self.fork_sender.send(ForkEvent {
+                base: BlockRef {
+                    number: chunk.first_block().saturating_sub(1),
+                    hash: chunk.parent_block_hash().to_string()
+                },
+                previous_head: previous_head.clone()
+            });
+        }
+
         // Head before finalized, so a subscriber never observes finalized > head (INV-5).
         self.set_head(Some(get_chunk_head(&chunk)));
         self.set_finalized_head(finalized_head);
@@ -489,11 +512,7 @@ impl WriteController {
                 let mut builder = self.db.new_table_builder(prepared.schema());
 
                 if let Some(table_desc) = desc.tables.get(name) {
-                    for (&col, opts) in table_desc.options.column_options.iter() {
-                        if opts.stats_enable {
-                            builder.add_stat_by_name(col)?;
-                        }
-                    }
+                    apply_table_options(&mut builder, table_desc, self.address_index)?;
                 }
 
                 prepared.read(&mut builder, 0, prepared.num_rows())?;
@@ -524,6 +543,28 @@ impl WriteController {
     }
 }
 
+/// Requests stats, indexes and codecs of the table description from the builder.
+///
+/// Must be called before any data is written.
+pub(crate) fn apply_table_options(
+    builder: &mut TableBuilder<'_>,
+    table_desc: &TableDescription,
+    address_index: bool
+) -> anyhow::Result<()> {
+    for (&col, opts) in table_desc.options.column_options.iter() {
+        if opts.stats_enable {
+            builder.add_stat_by_name(col)?;
+        }
+        if address_index && opts.index_enable {
+            builder.add_index_by_name(col)?;
+        }
+        if opts.codec != Codec::None {
+            builder.set_codec_by_name(col, opts.codec)?;
+        }
+    }
+    Ok(())
+}
+
 fn get_chunk_head(chunk: &Chunk) -> BlockRef {
     BlockRef {
         number: chunk.last_block(),
@@ -576,10 +617,10 @@ mod tests {
 
     use sqd_primitives::BlockRef;
     use sqd_storage::db::{Chunk, DatabaseSettings, DatasetId};
-    use tokio::sync::watch;
+    use tokio::sync::{broadcast, watch};
 
     use super::{WriteController, trim_floor};
-    use crate::types::{DBRef, DatasetKind};
+    use crate::types::{DBRef, DatasetKind, ForkEvent};
 
     #[test]
     fn nothing_is_trimmed_while_the_window_fits() {
@@ -625,6 +666,7 @@ mod tests {
         dataset_id: DatasetId,
         head_rx: watch::Receiver<Option<BlockRef>>,
         fin_rx: watch::Receiver<Option<BlockRef>>,
+        fork_rx: broadcast::Receiver<ForkEvent>,
         wc: WriteController,
         // Dropped last so RocksDB closes before the directory is removed.
         _dir: tempfile::TempDir
@@ -636,12 +678,14 @@ mod tests {
         let dataset_id = DatasetId::from_str("evm-test");
         let (head_tx, head_rx) = watch::channel(None);
         let (fin_tx, fin_rx) = watch::channel(None);
-        let wc = WriteController::new(db.clone(), dataset_id, DatasetKind::Evm, head_tx, fin_tx).unwrap();
+        let (fork_tx, fork_rx) = broadcast::channel(16);
+        let wc = WriteController::new(db.clone(), dataset_id, DatasetKind::Evm, head_tx, fin_tx, fork_tx).unwrap();
         Fixture {
             db,
             dataset_id,
             head_rx,
             fin_rx,
+            fork_rx,
             wc,
             _dir: dir
         }
@@ -675,6 +719,26 @@ mod tests {
         assert_eq!(label.finalized_head(), Some(&block(5, "h5")));
     }
 
+    // A chunk replacing committed blocks announces the fork, extending the head does not.
+    #[test]
+    fn overlapping_chunk_publishes_fork() {
+        let mut f = fixture();
+        f.wc.new_chunk(None, &chunk(1, 10, "h10", "h0")).unwrap();
+        f.wc.new_chunk(None, &chunk(11, 20, "h20", "h10")).unwrap();
+        assert!(f.fork_rx.try_recv().is_err());
+
+        f.wc.new_chunk(None, &chunk(11, 21, "h21b", "h10")).unwrap();
+
+        assert_eq!(
+            f.fork_rx.try_recv().unwrap(),
+            ForkEvent {
+                base: block(10, "h10"),
+                previous_head: block(20, "h20")
+            }
+        );
+        assert_eq!(*f.head_rx.borrow(), Some(block(21, "h21b")));
+    }
+
     // INV-40/CN-9: a rebuilt writer reseeds subscribers from committed storage.
     #[test]
     fn rebuilt_writer_reseeds_watermarks_from_storage() {
@@ -685,7 +749,8 @@ mod tests {
 
         let (head_tx, head_rx) = watch::channel(None);
         let (fin_tx, fin_rx) = watch::channel(None);
-        let _wc = WriteController::new(f.db.clone(), f.dataset_id, DatasetKind::Evm, head_tx, fin_tx).unwrap();
+        let (fork_tx, _) = broadcast::channel(16);
+        let _wc = WriteController::new(f.db.clone(), f.dataset_id, DatasetKind::Evm, head_tx, fin_tx, fork_tx).unwrap();
 
         assert_eq!(*head_rx.borrow(), Some(block(10, "h10")));
         assert_eq!(*fin_rx.borrow(), Some(block(5, "h5")));
@@ -1,4 +1,7 @@
-use axum::http::{HeaderMap, header::ACCEPT_ENCODING};
+use axum::http::{
+    HeaderMap,
+    header::{ACCEPT, ACCEPT_ENCODING}
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ContentEncoding {
@@ -28,6 +31,63 @@ impl ContentEncoding {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    JsonLines,
+    ArrowIpc
+}
+
+impl ResponseFormat {
+    /// A single Arrow IPC stream
+    pub const ARROW_STREAM_MIME: &'static str = "application/vnd.apache.arrow.stream";
+
+    /// Back-to-back Arrow IPC streams, framed as described in [`sqd_query::ArrowIpcWriter`]
+    pub const ARROW_STREAMS_MIME: &'static str = "application/vnd.sqd.arrow-streams";
+
+    pub fn content_type(&self) -> &'static str {
+        match self {
+            ResponseFormat::JsonLines => "text/plain",
+            ResponseFormat::ArrowIpc => Self::ARROW_STREAMS_MIME
+        }
+    }
+
+    /// Respond with Arrow IPC streams if the client accepts them, with JSON lines if it accepts
+    /// `text/plain` or sends no `Accept` at all.
+    ///
+    /// Returns `None` if none of the accepted media types can be served, e.g. for
+    /// [`Self::ARROW_STREAM_MIME`] alone: items differ in schema, so the payload is never
+    /// a single Arrow IPC stream.
+    pub fn from_headers(headers: &HeaderMap) -> Option<Self> {
+        let Some(accept) = headers.get(ACCEPT) else {
+            return Some(ResponseFormat::JsonLines);
+        };
+        let accept = accept.to_str().ok()?;
+
+        let mut accepts_json_lines = false;
+        for media_range in accept.split(',') {
+            let mut params = media_range.split(';').map(str::trim);
+            let media_type = params.next().unwrap_or_default();
+            let rejected = params.any(|param| {
+                param
+                    .strip_prefix("q=")
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .is_some_and(|q| q <= 0.0)
+            });
+            if rejected {
+                continue;
+            }
+            if media_type.eq_ignore_ascii_case(Self::ARROW_STREAMS_MIME) {
+                return Some(ResponseFormat::ArrowIpc);
+            }
+            accepts_json_lines |= ["", "*/*", "text/*", "text/plain"]
+                .iter()
+                .any(|m| media_type.eq_ignore_ascii_case(m));
+        }
+
+        accepts_json_lines.then_some(ResponseFormat::JsonLines)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -56,4 +116,51 @@ mod tests {
             ContentEncoding::Zstd
         );
     }
+
+    fn accept(value: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, value.parse().unwrap());
+        headers
+    }
+
+    #[test]
+    fn json_lines_by_default() {
+        assert_eq!(
+            ResponseFormat::from_headers(&HeaderMap::new()),
+            Some(ResponseFormat::JsonLines)
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("*/*")),
+            Some(ResponseFormat::JsonLines)
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/json, text/plain;q=0.1")),
+            Some(ResponseFormat::JsonLines)
+        );
+    }
+
+    #[test]
+    fn arrow_ipc_when_accepted() {
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/vnd.sqd.arrow-streams, text/plain;q=0.5")),
+            Some(ResponseFormat::ArrowIpc)
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/vnd.sqd.arrow-streams;q=0, */*")),
+            Some(ResponseFormat::JsonLines)
+        );
+    }
+
+    #[test]
+    fn single_arrow_stream_is_not_acceptable() {
+        // a single stream can't hold items of different schemas
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/vnd.apache.arrow.stream")),
+            None
+        );
+        assert_eq!(
+            ResponseFormat::from_headers(&accept("application/vnd.apache.arrow.stream, text/plain;q=0")),
+            None
+        );
+    }
 }
@@ -14,19 +14,6 @@ impl Display for Busy {
 
 impl std::error::Error for Busy {}
 
-#[derive(Debug)]
-pub struct UnsupportedQuery {
-    pub query_kind: &'static str
-}
-
-impl Display for UnsupportedQuery {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{} queries are not supported", self.query_kind)
-    }
-}
-
-impl std::error::Error for UnsupportedQuery {}
-
 #[derive(Debug)]
 pub struct QueryTaskPanicked;
 
@@ -65,6 +52,25 @@ impl Display for UnknownDataset {
 
 impl std::error::Error for UnknownDataset {}
 
+#[derive(Debug)]
+pub struct DatasetKindChange {
+    pub dataset_id: DatasetId,
+    pub current_kind: &'static str,
+    pub requested_kind: &'static str
+}
+
+impl Display for DatasetKindChange {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "dataset {} is of kind {} and can't be changed to {}, delete it first",
+            self.dataset_id, self.current_kind, self.requested_kind
+        )
+    }
+}
+
+impl std::error::Error for DatasetKindChange {}
+
 #[derive(Debug)]
 pub struct BlockRangeMissing {
     pub first_block: BlockNumber,
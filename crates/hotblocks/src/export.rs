@@ -0,0 +1,102 @@
+use arrow::ipc::writer::StreamWriter;
+use serde::{Deserialize, Serialize};
+use sqd_primitives::BlockNumber;
+use sqd_storage::db::{Chunk, DatasetId, ReadSnapshot};
+
+/// Max number of chunks returned by a single listing
+pub const FINALIZED_CHUNKS_LIMIT: usize = 100;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FinalizedChunk {
+    pub first_block: BlockNumber,
+    pub last_block: BlockNumber,
+    pub last_block_hash: String,
+    pub parent_block_hash: String,
+    pub first_block_time: Option<i64>,
+    pub last_block_time: Option<i64>,
+    pub tables: Vec<String>
+}
+
+impl From<&Chunk> for FinalizedChunk {
+    fn from(chunk: &Chunk) -> Self {
+        Self {
+            first_block: chunk.first_block(),
+            last_block: chunk.last_block(),
+            last_block_hash: chunk.last_block_hash().to_string(),
+            parent_block_hash: chunk.parent_block_hash().to_string(),
+            first_block_time: chunk.first_block_time(),
+            last_block_time: chunk.last_block_time(),
+            tables: chunk.tables().keys().cloned().collect()
+        }
+    }
+}
+
+/// Lists chunks, that end at or above `from_block` and lie entirely below the finalized head.
+///
+/// The finalized head is taken from the same snapshot, so a chunk listed here
+/// never changes, although it may be removed by retention later.
+///
+/// The listing stops at the first chunk crossing the finalized head. Chunks are not split,
+/// so finalized blocks of that chunk are listed only once the finalized head reaches its end.
+pub fn list_finalized_chunks(
+    db: &ReadSnapshot<'_>,
+    dataset_id: DatasetId,
+    from_block: BlockNumber
+) -> anyhow::Result<Vec<FinalizedChunk>> {
+    let Some(finalized_head) = db
+        .get_label(dataset_id)?
+        .and_then(|label| label.finalized_head().cloned())
+    else {
+        return Ok(Vec::new());
+    };
+
+    let mut chunks = Vec::new();
+    for chunk_result in db.list_chunks(dataset_id, from_block, None) {
+        let chunk = chunk_result?;
+        if chunk.last_block() > finalized_head.number || chunks.len() == FINALIZED_CHUNKS_LIMIT {
+            break;
+        }
+        chunks.push(FinalizedChunk::from(&chunk));
+    }
+    Ok(chunks)
+}
+
+/// Reads a table of the finalized chunk starting at `first_block` as an Arrow IPC stream.
+///
+/// Returns `None` when there is no such chunk or table.
+pub fn read_finalized_table(
+    db: &ReadSnapshot<'_>,
+    dataset_id: DatasetId,
+    first_block: BlockNumber,
+    table: &str
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let Some(finalized_head) = db
+        .get_label(dataset_id)?
+        .and_then(|label| label.finalized_head().cloned())
+    else {
+        return Ok(None);
+    };
+
+    let Some(chunk) = db.list_chunks(dataset_id, first_block, None).next().transpose()? else {
+        return Ok(None);
+    };
+
+    if chunk.first_block() != first_block || chunk.last_block() > finalized_head.number {
+        return Ok(None);
+    }
+
+    let Some(table_id) = chunk.tables().get(table) else {
+        return Ok(None);
+    };
+
+    let batch = db.create_table_reader(*table_id)?.read_table(None, None)?;
+
+    let mut buf = Vec::new();
+    let mut writer = StreamWriter::try_new(&mut buf, batch.schema_ref())?;
+    writer.write(&batch)?;
+    writer.finish()?;
+    drop(writer);
+
+    Ok(Some(buf))
+}
@@ -1,10 +1,12 @@
 mod api;
+mod bootstrap;
 mod cli;
 mod data_service;
 mod dataset_config;
 mod dataset_controller;
 mod encoding;
 mod errors;
+mod export;
 mod metrics;
 mod query;
 mod types;
@@ -22,6 +24,7 @@ use anyhow::Context;
 use api::build_api;
 use clap::Parser;
 use cli::CLI;
+use sqd_storage::db::{DatasetId, HashIndexBackfill};
 use tokio::signal::unix::{Signal, SignalKind, signal};
 use tokio_util::sync::CancellationToken;
 use tracing::{debug, error, info, instrument, warn};
@@ -55,6 +58,14 @@ fn main() -> anyhow::Result<()> {
         // before any controller spawned.
         tokio::spawn(db_cleanup_task(app.db.clone()));
 
+        if args.hash_index_backfill {
+            tokio::spawn(hash_index_backfill_task(
+                app.db.clone(),
+                app.data_service.dataset_ids(),
+                args.hash_index_backfill_rate
+            ));
+        }
+
         let shutting_down = Arc::new(AtomicBool::new(false));
         let drain = CancellationToken::new();
 
@@ -312,3 +323,53 @@ async fn db_cleanup_task(db: DBRef) {
         .await;
     }
 }
+
+const BACKFILL_PROGRESS_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Populates the enabled hash indexes with entries of chunks ingested before they were
+/// turned on, one dataset after another. Runs once; a restart with the flag starts over.
+#[instrument(name = "hash_index_backfill", skip_all)]
+async fn hash_index_backfill_task(db: DBRef, datasets: Vec<DatasetId>, max_blocks_per_second: Option<u64>) {
+    if !db.is_hash_index_enabled() {
+        warn!("hash index backfill was requested, but no hash index is enabled");
+        return;
+    }
+
+    for dataset_id in datasets {
+        let db = db.clone();
+        let span = tracing::info_span!("dataset", dataset_id = %dataset_id);
+        let result = tokio::task::spawn_blocking(move || {
+            let _entered = span.enter();
+            info!("hash index backfill started");
+            let mut last_report = Instant::now();
+            HashIndexBackfill::new(&db, dataset_id)
+                .with_max_blocks_per_second(max_blocks_per_second)
+                .run(|progress| {
+                    if last_report.elapsed() >= BACKFILL_PROGRESS_INTERVAL {
+                        last_report = Instant::now();
+                        info!(
+                            chunks_done = progress.chunks_done,
+                            chunks_total = progress.chunks_total,
+                            blocks_indexed = progress.blocks_indexed,
+                            last_block = progress.last_block,
+                            "hash index backfill progress"
+                        );
+                    }
+                    true
+                })
+        })
+        .await;
+
+        match result {
+            Ok(Ok(progress)) => info!(
+                dataset_id = %dataset_id,
+                chunks_total = progress.chunks_total,
+                chunks_skipped = progress.chunks_skipped,
+                blocks_indexed = progress.blocks_indexed,
+                "hash index backfill finished"
+            ),
+            Ok(Err(err)) => error!(dataset_id = %dataset_id, error =? err, "hash index backfill failed"),
+            Err(_) => error!(dataset_id = %dataset_id, "hash index backfill task panicked")
+        }
+    }
+}
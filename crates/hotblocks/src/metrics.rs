@@ -18,7 +18,7 @@ use sqd_storage::db::{
 };
 use tracing::error;
 
-use crate::{errors::UnapplicableFork, query::QueryExecutorCollector, types::DBRef};
+use crate::{data_service::DataServiceRef, errors::UnapplicableFork, query::QueryExecutorCollector, types::DBRef};
 
 #[derive(Copy, Clone, Hash, Debug, Default, Ord, PartialOrd, Eq, PartialEq, EncodeLabelSet)]
 struct DatasetLabel {
@@ -197,14 +197,14 @@ pub fn report_http_response(labels: &Vec<(&'static str, String)>, to_first_byte:
 #[derive(Debug)]
 pub struct DatasetMetricsCollector {
     pub db: DBRef,
-    pub datasets: Vec<DatasetId>
+    pub data_service: DataServiceRef
 }
 
 impl Collector for DatasetMetricsCollector {
     fn encode(&self, mut encoder: DescriptorEncoder) -> Result<(), std::fmt::Error> {
         let db = self.db.snapshot();
 
-        for dataset_id in self.datasets.iter().copied() {
+        for dataset_id in self.data_service.dataset_ids() {
             if let Err(err) = collect_dataset_metrics(&mut encoder, &db, dataset_id) {
                 return if err.is::<std::fmt::Error>() {
                     Err(err.downcast().unwrap())
@@ -504,6 +504,44 @@ pub fn build_metrics_registry() -> Registry {
         sqd_data_source::metrics::INGEST_FORK_SIGNALS.clone()
     );
 
+    registry.register(
+        "ingest_source_rank",
+        "Place of the upstream data source endpoint in the poll order, by source endpoint. \
+         Endpoints are ordered by lag, latency and error rate, rank 0 wins ties for new blocks",
+        sqd_data_source::metrics::INGEST_SOURCE_RANK.clone()
+    );
+
+    registry.register(
+        "ingest_source_lag_blocks",
+        "Number of blocks the upstream data source endpoint is behind the ingested position, by source endpoint",
+        sqd_data_source::metrics::INGEST_SOURCE_LAG.clone()
+    );
+
+    registry.register(
+        "ingest_source_latency_seconds",
+        "Smoothed response time of block stream requests, by source endpoint",
+        sqd_data_source::metrics::INGEST_SOURCE_LATENCY.clone()
+    );
+
+    registry.register(
+        "ingest_source_error_rate",
+        "Smoothed share of failed block stream requests, by source endpoint",
+        sqd_data_source::metrics::INGEST_SOURCE_ERROR_RATE.clone()
+    );
+
+    registry.register(
+        "ingest_hash_disagreements",
+        "Blocks proposed by an upstream data source endpoint, that lost the block hash quorum, \
+         by source endpoint. Only counted for datasets with block_hash_quorum",
+        sqd_data_source::metrics::INGEST_HASH_DISAGREEMENTS.clone()
+    );
+
+    registry.register(
+        "ingest_hash_quorum_timeouts",
+        "Blocks accepted with the most votes, because the block hash quorum was not reached in time",
+        sqd_data_source::metrics::INGEST_HASH_QUORUM_TIMEOUTS.clone()
+    );
+
     registry.register(
         "dataset_epoch_failures",
         "Dataset update task failures, by dataset and cause; each one parks ingestion for \
@@ -0,0 +1,152 @@
+use bytes::Bytes;
+use serde::Serialize;
+use sqd_primitives::BlockRef;
+use sqd_storage::db::DatasetId;
+
+use super::running::compress;
+use crate::{encoding::ContentEncoding, types::DBRef};
+
+/// Max number of chunk heads a live stream remembers to report orphaned blocks
+pub const LIVE_STREAM_CHAIN_LIMIT: usize = 1000;
+
+/// Inline marker of a live stream, telling the client
+/// to discard all blocks above `base`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Rollback {
+    /// Last block that is still valid
+    pub base: BlockRef,
+    /// Heads of the returned chunks, that are no longer part of the chain
+    pub orphaned: Vec<BlockRef>
+}
+
+impl Rollback {
+    /// Serializes the marker as a separate JSON line: `{"rollback": {...}}`
+    pub fn to_json_line(&self, encoding: ContentEncoding) -> anyhow::Result<Bytes> {
+        #[derive(Serialize)]
+        struct Line<'a> {
+            rollback: &'a Rollback
+        }
+
+        let mut line = serde_json::to_vec(&Line { rollback: self })?;
+        line.push(b'\n');
+        compress(encoding, &line)
+    }
+}
+
+/// Inline marker of a live stream, telling the client, that the stream ends because of an error.
+///
+/// `code` is the error code of the equivalent regular query. The client resumes with a regular query
+/// from the last block it has received.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct StreamError {
+    pub code: &'static str,
+    pub message: String
+}
+
+impl StreamError {
+    /// Serializes the marker as a separate JSON line: `{"error": {...}}`
+    pub fn to_json_line(&self, encoding: ContentEncoding) -> anyhow::Result<Bytes> {
+        #[derive(Serialize)]
+        struct Line<'a> {
+            error: &'a StreamError
+        }
+
+        let mut line = serde_json::to_vec(&Line { error: self })?;
+        line.push(b'\n');
+        compress(encoding, &line)
+    }
+}
+
+/// Finds the last block of the `chain` (heads of chunks passed to a client),
+/// that is still present in the dataset.
+///
+/// Matches chunk heads the same way the write controller resolves ingestion rollbacks.
+/// When no head matches, falls back to the finalized head. `None` means, the fork point is unknown.
+pub fn find_fork_base(db: &DBRef, dataset_id: DatasetId, chain: &[BlockRef]) -> anyhow::Result<Option<BlockRef>> {
+    let Some(top) = chain.last() else { return Ok(None) };
+
+    let snapshot = db.snapshot();
+    let mut refs = chain.iter().rev().peekable();
+
+    for chunk_result in snapshot.list_chunks(dataset_id, 0, Some(top.number)).into_reversed() {
+        let chunk = chunk_result?;
+
+        while refs.peek().map_or(false, |b| b.number > chunk.last_block()) {
+            refs.next();
+        }
+
+        let Some(&b) = refs.peek() else { break };
+
+        if b.number == chunk.last_block() && b.hash == chunk.last_block_hash() {
+            return Ok(Some(b.clone()));
+        }
+    }
+
+    let finalized_head = snapshot
+        .get_label(dataset_id)?
+        .and_then(|label| label.finalized_head().cloned())
+        .filter(|head| head.number <= top.number);
+
+    Ok(finalized_head)
+}
+
+/// Splits off chain refs above the `base`
+pub fn orphaned_blocks(chain: &mut Vec<BlockRef>, base: &BlockRef) -> Vec<BlockRef> {
+    let pos = chain.partition_point(|b| b.number <= base.number);
+    chain.split_off(pos)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(number: u64, hash: &str) -> BlockRef {
+        BlockRef {
+            number,
+            hash: hash.to_string()
+        }
+    }
+
+    #[test]
+    fn orphaned_blocks_are_the_ones_above_the_base() {
+        let mut chain = vec![block(10, "a"), block(12, "b"), block(15, "c")];
+        let orphaned = orphaned_blocks(&mut chain, &block(12, "b"));
+        assert_eq!(orphaned, vec![block(15, "c")]);
+        assert_eq!(chain, vec![block(10, "a"), block(12, "b")]);
+
+        let orphaned = orphaned_blocks(&mut chain, &block(11, "x"));
+        assert_eq!(orphaned, vec![block(12, "b")]);
+        assert_eq!(chain, vec![block(10, "a")]);
+    }
+
+    #[test]
+    fn rollback_is_a_separate_json_line() {
+        let rollback = Rollback {
+            base: block(12, "b"),
+            orphaned: vec![block(15, "c")]
+        };
+
+        let mut decoder = flate2::read::GzDecoder::new(rollback.to_json_line(ContentEncoding::Gzip).unwrap().as_ref());
+        let mut line = String::new();
+        std::io::Read::read_to_string(&mut decoder, &mut line).unwrap();
+
+        assert_eq!(
+            line,
+            "{\"rollback\":{\"base\":{\"number\":12,\"hash\":\"b\"},\"orphaned\":[{\"number\":15,\"hash\":\"c\"}]}}\n"
+        );
+    }
+
+    #[test]
+    fn stream_error_is_a_separate_json_line() {
+        let error = StreamError {
+            code: "OVERLOADED",
+            message: "busy".to_string()
+        };
+
+        let mut decoder = flate2::read::GzDecoder::new(error.to_json_line(ContentEncoding::Gzip).unwrap().as_ref());
+        let mut line = String::new();
+        std::io::Read::read_to_string(&mut decoder, &mut line).unwrap();
+
+        assert_eq!(line, "{\"error\":{\"code\":\"OVERLOADED\",\"message\":\"busy\"}}\n");
+    }
+}
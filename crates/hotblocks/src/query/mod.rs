@@ -1,9 +1,11 @@
 mod executor;
+mod follow;
 mod response;
 mod running;
 mod service;
 mod static_snapshot;
 
 pub use executor::QueryExecutorCollector;
+pub use follow::*;
 pub use response::*;
 pub use service::*;
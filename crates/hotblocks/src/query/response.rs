@@ -11,7 +11,7 @@ use super::{
     running::{RunningQuery, RunningQueryStats}
 };
 use crate::{
-    encoding::ContentEncoding,
+    encoding::{ContentEncoding, ResponseFormat},
     errors::Busy,
     metrics::{
         STREAM_BLOCKS, STREAM_BLOCKS_PER_SECOND, STREAM_BYTES, STREAM_BYTES_PER_SECOND, STREAM_CHUNKS, STREAM_DURATIONS
@@ -19,16 +19,18 @@ use crate::{
     types::{ClientId, DBRef}
 };
 
-const DEFAULT_QUERY_LIMIT: Duration = Duration::from_secs(10);
-
 pub struct QueryResponse {
     executor: QueryExecutor,
     runner: Option<Box<RunningQuery>>,
     finalized_head: Option<BlockRef>,
+    chunk_heads: Vec<BlockRef>,
+    reached_last_block: bool,
+    limit_reached: bool,
     dataset_id: DatasetId,
     client_id: ClientId,
     stats: QueryStreamStats,
-    time_limit: Duration
+    /// `None` lets the response run until the end of the requested range
+    time_limit: Option<Duration>
 }
 
 pub struct QueryStreamStats {
@@ -87,24 +89,28 @@ impl QueryResponse {
         only_finalized: bool,
         time_limit: Option<Duration>,
         client_id: ClientId,
-        encoding: ContentEncoding
+        encoding: ContentEncoding,
+        format: ResponseFormat
     ) -> anyhow::Result<Self> {
         let Some(slot) = executor.get_slot() else { bail!(Busy) };
 
         let stats = QueryStreamStats::new();
         let mut runner = slot
             .run(move |slot| -> anyhow::Result<_> {
-                let mut runner = RunningQuery::new(db, dataset_id, &query, only_finalized, encoding).map(Box::new)?;
+                let mut runner =
+                    RunningQuery::new(db, dataset_id, &query, only_finalized, encoding, format).map(Box::new)?;
                 next_run(&mut runner, slot)?;
                 Ok(runner)
             })
             .await??;
 
-        let time_limit = time_limit.unwrap_or(DEFAULT_QUERY_LIMIT);
         let response = Self {
             executor,
             finalized_head: runner.take_finalized_head(),
             runner: Some(runner),
+            chunk_heads: Vec::new(),
+            reached_last_block: false,
+            limit_reached: false,
             stats,
             dataset_id,
             client_id,
@@ -118,6 +124,28 @@ impl QueryResponse {
         self.finalized_head.as_ref()
     }
 
+    /// Heads of the chunks, whose data was completely passed to the client
+    /// since the last call.
+    ///
+    /// Only complete after the response was finished.
+    pub fn take_chunk_heads(&mut self) -> Vec<BlockRef> {
+        let mut heads = std::mem::take(&mut self.chunk_heads);
+        if let Some(runner) = self.runner.as_mut() {
+            heads.extend(runner.take_chunk_heads())
+        }
+        heads
+    }
+
+    /// Whether the response covered the whole requested range
+    pub fn reached_last_block(&self) -> bool {
+        self.reached_last_block
+    }
+
+    /// Whether the response was ended by the result limit of the query
+    pub fn limit_reached(&self) -> bool {
+        self.limit_reached
+    }
+
     pub async fn next_data_pack(&mut self) -> anyhow::Result<Option<Bytes>> {
         let Some(mut runner) = self.runner.take() else {
             return Ok(None);
@@ -127,7 +155,10 @@ impl QueryResponse {
             return Ok(self.finish_with_runner(runner));
         }
 
-        if self.stats.start_time.elapsed() > self.time_limit {
+        if self
+            .time_limit
+            .is_some_and(|limit| self.stats.start_time.elapsed() > limit)
+        {
             // Client is expected to retry the query based on the data that they have received
             return Ok(self.finish_with_runner(runner));
         }
@@ -167,7 +198,10 @@ impl QueryResponse {
         }
     }
 
-    fn finish_with_runner(&mut self, runner: Box<RunningQuery>) -> Option<Bytes> {
+    fn finish_with_runner(&mut self, mut runner: Box<RunningQuery>) -> Option<Bytes> {
+        self.chunk_heads.extend(runner.take_chunk_heads());
+        self.reached_last_block = runner.reached_last_block();
+        self.limit_reached = runner.limit_reached();
         let stats = runner.stats();
         stats.report_metrics(&self.dataset_id, &self.client_id);
         self.stats.add_running_stats(stats);
@@ -4,12 +4,12 @@ use anyhow::{anyhow, bail, ensure};
 use bytes::{BufMut, Bytes, BytesMut};
 use flate2::{Compression, write::GzEncoder};
 use sqd_primitives::{BlockNumber, BlockRef};
-use sqd_query::{JsonLinesWriter, Plan, Query};
+use sqd_query::{ArrowIpcWriter, JsonLinesWriter, Plan, Query};
 use sqd_storage::db::{Chunk as StorageChunk, DatasetId};
 use zstd::stream::write::Encoder as ZstdEncoder;
 
 use crate::{
-    encoding::ContentEncoding,
+    encoding::{ContentEncoding, ResponseFormat},
     errors::{BlockItemIsNotAvailable, BlockRangeMissing, QueryIsAboveTheHead, QueryKindMismatch},
     metrics::{QUERIED_BLOCKS, QUERIED_CHUNKS},
     query::static_snapshot::{StaticChunkIterator, StaticChunkReader, StaticSnapshot},
@@ -103,6 +103,16 @@ impl Compressor {
     }
 }
 
+/// Compresses a standalone part of a response body.
+///
+/// Concatenation of such parts with query outputs is still a valid body,
+/// because both gzip and zstd streams may consist of multiple members.
+pub fn compress(encoding: ContentEncoding, data: &[u8]) -> anyhow::Result<Bytes> {
+    let mut buf = Compressor::new(encoding)?;
+    buf.write_all(data)?;
+    Ok(buf.finish().freeze())
+}
+
 pub struct RunningQuery {
     plan: Plan,
     last_block: Option<BlockNumber>,
@@ -110,7 +120,15 @@ pub struct RunningQuery {
     next_chunk: Option<anyhow::Result<StorageChunk>>,
     chunk_iterator: StaticChunkIterator,
     finalized_head: Option<BlockRef>,
+    chunk_heads: Vec<BlockRef>,
+    reached_last_block: bool,
+    /// Blocks left in the response budget of a limited query
+    remaining_blocks: Option<usize>,
+    /// Items (per table) left in the response budget of a limited query
+    remaining_items: Option<usize>,
+    limit_reached: bool,
     buf: Compressor,
+    format: ResponseFormat,
     stats: RunningQueryStats
 }
 
@@ -129,14 +147,15 @@ impl RunningQuery {
         dataset_id: DatasetId,
         query: &Query,
         only_finalized: bool,
-        encoding: ContentEncoding
+        encoding: ContentEncoding,
+        format: ResponseFormat
     ) -> anyhow::Result<Self> {
         let snapshot = StaticSnapshot::new(db);
 
         let finalized_head = match snapshot.get_label(dataset_id)? {
             None => bail!("dataset {} does not exist", dataset_id),
             Some(label) => {
-                let kind = DatasetKind::from_query(query)?;
+                let kind = DatasetKind::from_query(query);
                 ensure!(
                     kind.storage_kind() == label.kind(),
                     QueryKindMismatch {
@@ -193,14 +212,20 @@ impl RunningQuery {
         };
 
         Ok(Self {
-            plan,
             last_block,
             left_over: None,
             next_chunk: Some(Ok(first_chunk)),
             chunk_iterator,
             finalized_head,
+            chunk_heads: Vec::new(),
+            reached_last_block: false,
+            remaining_blocks: plan.max_blocks(),
+            remaining_items: plan.max_items(),
+            limit_reached: false,
             buf: Compressor::new(encoding)?,
-            stats
+            format,
+            stats,
+            plan
         })
     }
 
@@ -208,6 +233,22 @@ impl RunningQuery {
         self.finalized_head.take()
     }
 
+    /// Heads of the chunks, that were completely written to the output
+    /// since the last call.
+    pub fn take_chunk_heads(&mut self) -> Vec<BlockRef> {
+        std::mem::take(&mut self.chunk_heads)
+    }
+
+    /// Whether the output has reached the last block requested by the query
+    pub fn reached_last_block(&self) -> bool {
+        self.reached_last_block
+    }
+
+    /// Whether the output was ended by the result limit of the query
+    pub fn limit_reached(&self) -> bool {
+        self.limit_reached
+    }
+
     pub fn stats(&self) -> &RunningQueryStats {
         &self.stats
     }
@@ -225,7 +266,7 @@ impl RunningQuery {
     }
 
     pub fn has_next_chunk(&self) -> bool {
-        self.next_chunk.is_some() || self.left_over.is_some()
+        !self.limit_reached && (self.next_chunk.is_some() || self.left_over.is_some())
     }
 
     /// Query the next chunk and write results to buffer.
@@ -249,6 +290,9 @@ impl RunningQuery {
             self.plan.set_last_block(None);
         }
 
+        self.plan.set_max_blocks(self.remaining_blocks);
+        self.plan.set_max_items(self.remaining_items);
+
         let query_result = chunk.with_reader(|reader| self.plan.execute(reader)).map_err(|err| {
             if let Some(err) = err.downcast_ref::<sqd_query::TableDoesNotExist>() {
                 return anyhow!(BlockItemIsNotAvailable {
@@ -265,6 +309,7 @@ impl RunningQuery {
         self.plan.set_parent_block_hash(None);
 
         let Some(mut block_writer) = query_result? else {
+            self.complete_chunk(&chunk);
             return Ok(());
         };
 
@@ -273,6 +318,22 @@ impl RunningQuery {
         }
         self.stats.blocks_returned += block_writer.num_blocks() as u64;
 
+        if let Some(remaining) = self.remaining_blocks.as_mut() {
+            *remaining = remaining.saturating_sub(block_writer.num_counted_blocks());
+        }
+        if let Some(remaining) = self.remaining_items.as_mut() {
+            *remaining = remaining.saturating_sub(block_writer.max_num_items());
+        }
+        if block_writer.limit_reached() || self.remaining_blocks == Some(0) || self.remaining_items == Some(0) {
+            self.limit_reached = true;
+        }
+        if block_writer.num_blocks() == 0 {
+            // the next block doesn't fit into the rest of the limit
+            return Ok(());
+        }
+        // only the first returned block may exceed the limit
+        self.plan.set_allow_first_block_overflow(false);
+
         if chunk.last_block() > block_writer.last_block()
             && self.last_block.map_or(true, |end| end > block_writer.last_block())
         {
@@ -280,21 +341,44 @@ impl RunningQuery {
                 chunk,
                 next_block: block_writer.last_block() + 1
             })
+        } else {
+            self.complete_chunk(&chunk);
         }
 
-        let mut json_lines_writer = JsonLinesWriter::new(&mut self.buf);
+        match self.format {
+            ResponseFormat::JsonLines => {
+                let mut json_lines_writer = JsonLinesWriter::new(&mut self.buf);
 
-        json_lines_writer
-            .write_blocks(&mut block_writer)
-            .expect("IO errors are not possible");
+                json_lines_writer
+                    .write_blocks(&mut block_writer)
+                    .expect("IO errors are not possible");
 
-        json_lines_writer.finish().expect("IO errors are not possible");
+                json_lines_writer.finish().expect("IO errors are not possible");
+            }
+            ResponseFormat::ArrowIpc => {
+                let mut arrow_writer = ArrowIpcWriter::new(&mut self.buf);
+                arrow_writer.write_blocks(&mut block_writer)?;
+                arrow_writer.finish().expect("IO errors are not possible");
+            }
+        }
 
         self.buf.flush().expect("IO errors are not possible");
 
         Ok(())
     }
 
+    fn complete_chunk(&mut self, chunk: &StaticChunkReader) {
+        match self.last_block {
+            Some(end) if end <= chunk.last_block() => {
+                self.reached_last_block = true;
+                if end == chunk.last_block() {
+                    self.chunk_heads.push(chunk.head())
+                }
+            }
+            _ => self.chunk_heads.push(chunk.head())
+        }
+    }
+
     fn next_chunk(&mut self) -> anyhow::Result<StorageChunk> {
         let Some(chunk) = self.next_chunk.take().transpose()? else {
             bail!("no more chunks left")
@@ -12,7 +12,7 @@ use sqd_query::Query;
 use super::{executor::QueryExecutor, response::QueryResponse};
 use crate::{
     dataset_controller::DatasetController,
-    encoding::ContentEncoding,
+    encoding::{ContentEncoding, ResponseFormat},
     errors::{Busy, QueryIsAboveTheHead, QueryKindMismatch},
     query::QueryExecutorCollector,
     types::{ClientId, DBRef, DatasetKind}
@@ -20,6 +20,9 @@ use crate::{
 
 pub type QueryServiceRef = Arc<QueryService>;
 
+/// Response time limit of regular queries
+const DEFAULT_QUERY_LIMIT: Duration = Duration::from_secs(10);
+
 pub struct QueryServiceBuilder {
     db: DBRef,
     max_data_waiters: usize,
@@ -82,13 +85,46 @@ impl QueryService {
     }
 
     pub async fn query(
+        &self,
+        dataset: &DatasetController,
+        query: Query,
+        client_id: ClientId,
+        encoding: ContentEncoding,
+        format: ResponseFormat
+    ) -> anyhow::Result<QueryResponse> {
+        self.query_internal(
+            dataset,
+            query,
+            false,
+            Some(DEFAULT_QUERY_LIMIT),
+            client_id,
+            encoding,
+            format
+        )
+        .await
+    }
+
+    /// Like [`QueryService::query`], but without the response time limit.
+    ///
+    /// The response always ends at the end of available data or at the last requested block,
+    /// so that a live stream can continue from the head of the last returned chunk.
+    pub async fn query_live(
         &self,
         dataset: &DatasetController,
         query: Query,
         client_id: ClientId,
         encoding: ContentEncoding
     ) -> anyhow::Result<QueryResponse> {
-        self.query_internal(dataset, query, false, client_id, encoding).await
+        self.query_internal(
+            dataset,
+            query,
+            false,
+            None,
+            client_id,
+            encoding,
+            ResponseFormat::JsonLines
+        )
+        .await
     }
 
     pub async fn query_finalized(
@@ -96,9 +132,19 @@ impl QueryService {
         dataset: &DatasetController,
         query: Query,
         client_id: ClientId,
-        encoding: ContentEncoding
+        encoding: ContentEncoding,
+        format: ResponseFormat
     ) -> anyhow::Result<QueryResponse> {
-        self.query_internal(dataset, query, true, client_id, encoding).await
+        self.query_internal(
+            dataset,
+            query,
+            true,
+            Some(DEFAULT_QUERY_LIMIT),
+            client_id,
+            encoding,
+            format
+        )
+        .await
     }
 
     async fn query_internal(
@@ -106,10 +152,12 @@ impl QueryService {
         dataset: &DatasetController,
         query: Query,
         finalized: bool,
+        time_limit: Option<Duration>,
         client_id: ClientId,
-        encoding: ContentEncoding
+        encoding: ContentEncoding,
+        format: ResponseFormat
     ) -> anyhow::Result<QueryResponse> {
-        let query_kind = DatasetKind::from_query(&query)?;
+        let query_kind = DatasetKind::from_query(&query);
         ensure!(
             dataset.dataset_kind() == query_kind,
             QueryKindMismatch {
@@ -162,9 +210,10 @@ impl QueryService {
             dataset.dataset_id(),
             query,
             finalized,
-            None,
+            time_limit,
             client_id,
-            encoding
+            encoding,
+            format
         )
         .await
     }
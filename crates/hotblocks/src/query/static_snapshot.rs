@@ -1,7 +1,7 @@
 use std::sync::Arc;
 
 use ouroboros::self_referencing;
-use sqd_primitives::BlockNumber;
+use sqd_primitives::{BlockNumber, BlockRef};
 use sqd_storage::db::{Chunk, ChunkReader, DatasetId, DatasetLabel, ReadSnapshot, ReadSnapshotChunkIterator};
 
 use crate::types::DBRef;
@@ -119,4 +119,11 @@ impl StaticChunkReader {
     pub fn last_block(&self) -> BlockNumber {
         self.inner.with_reader(|r| r.last_block())
     }
+
+    pub fn head(&self) -> BlockRef {
+        self.inner.with_reader(|r| BlockRef {
+            number: r.last_block(),
+            hash: r.last_block_hash().to_string()
+        })
+    }
 }
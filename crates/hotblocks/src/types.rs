@@ -2,11 +2,10 @@ use std::sync::Arc;
 
 use serde::{Deserialize, Serialize};
 use sqd_dataset::DatasetDescriptionRef;
+use sqd_primitives::BlockRef;
 use sqd_query::{BlockNumber, Query};
 use sqd_storage::db::Database;
 
-use crate::errors::UnsupportedQuery;
-
 pub type DBRef = Arc<Database>;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -22,7 +21,11 @@ pub enum DatasetKind {
     #[serde(rename = "hyperliquid-replica-cmds")]
     HyperliquidReplicaCmds,
     #[serde(rename = "tron")]
-    Tron
+    Tron,
+    #[serde(rename = "substrate")]
+    Substrate,
+    #[serde(rename = "fuel")]
+    Fuel
 }
 
 impl DatasetKind {
@@ -37,7 +40,9 @@ impl DatasetKind {
             DatasetKind::Bitcoin => "bitcoin",
             DatasetKind::HyperliquidFills => "hl-fills",
             DatasetKind::HyperliquidReplicaCmds => "hl-replica-cmds",
-            DatasetKind::Tron => "tron"
+            DatasetKind::Tron => "tron",
+            DatasetKind::Substrate => "substrate",
+            DatasetKind::Fuel => "fuel"
         }
     }
 
@@ -52,26 +57,38 @@ impl DatasetKind {
             DatasetKind::HyperliquidReplicaCmds => {
                 sqd_data::hyperliquid_replica_cmds::tables::HyperliquidReplicaCmdsChunkBuilder::dataset_description()
             }
-            DatasetKind::Tron => sqd_data::tron::tables::TronChunkBuilder::dataset_description()
+            DatasetKind::Tron => sqd_data::tron::tables::TronChunkBuilder::dataset_description(),
+            DatasetKind::Substrate => sqd_data::substrate::tables::SubstrateChunkBuilder::dataset_description(),
+            DatasetKind::Fuel => sqd_data::fuel::tables::FuelChunkBuilder::dataset_description()
         }
     }
 
-    pub fn from_query(query: &Query) -> Result<Self, UnsupportedQuery> {
+    pub fn from_query(query: &Query) -> Self {
         match query {
-            Query::Eth(_) => Ok(Self::Evm),
-            Query::Solana(_) => Ok(Self::Solana),
-            Query::Bitcoin(_) => Ok(Self::Bitcoin),
-            Query::HyperliquidFills(_) => Ok(Self::HyperliquidFills),
-            Query::HyperliquidReplicaCmds(_) => Ok(Self::HyperliquidReplicaCmds),
-            Query::Tron(_) => Ok(Self::Tron),
-            Query::Substrate(_) => Err(UnsupportedQuery {
-                query_kind: "substrate"
-            }),
-            Query::Fuel(_) => Err(UnsupportedQuery { query_kind: "fuel" })
+            Query::Eth(_) => Self::Evm,
+            Query::Solana(_) => Self::Solana,
+            Query::Bitcoin(_) => Self::Bitcoin,
+            Query::HyperliquidFills(_) => Self::HyperliquidFills,
+            Query::HyperliquidReplicaCmds(_) => Self::HyperliquidReplicaCmds,
+            Query::Tron(_) => Self::Tron,
+            Query::Substrate(_) => Self::Substrate,
+            Query::Fuel(_) => Self::Fuel
         }
     }
 }
 
+/// Blocks above `base` were replaced by a different branch.
+///
+/// Published right before the head of the new branch.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ForkEvent {
+    /// Last block shared by both branches
+    pub base: BlockRef,
+    /// Head of the abandoned branch
+    pub previous_head: BlockRef
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RetentionStrategy {
     FromBlock {
@@ -0,0 +1,108 @@
+//! `--bootstrap-from`: an empty dataset of a new replica is copied from a peer instance.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use serde_json::Value;
+use sqd_hotblocks_harness::{
+    chain::Evm,
+    harness::{Harness, HarnessConfig},
+    sut::{DatasetSpec, Retention, Sut, SutConfig}
+};
+
+const START: u64 = 1_000;
+
+/// A peer with several finalized chunks
+async fn start_peer() -> Result<Harness> {
+    let mut cfg = HarnessConfig::from_block(env!("CARGO_BIN_EXE_sqd-hotblocks"), Arc::new(Evm), START);
+    cfg.disable_compaction = true;
+    let mut h = Harness::start(cfg).await?;
+
+    for _ in 0..3 {
+        h.produce(10)?;
+        h.settle().await?;
+    }
+    h.finalize(START + 29)?;
+    h.settle().await?;
+    Ok(h)
+}
+
+/// A replica of the peer's dataset, fed by the peer's source
+async fn start_replica(peer: &Harness, retention: Retention, bootstrap_from: &str, args: &[&str]) -> Result<Sut> {
+    let mut cfg = SutConfig::new(
+        env!("CARGO_BIN_EXE_sqd-hotblocks"),
+        vec![DatasetSpec {
+            id: peer.dataset.clone(),
+            kind: peer.chain.config_kind().to_string(),
+            retention,
+            sources: vec![peer.sim.base_url(&peer.dataset)],
+            disable_compaction: true,
+            block_hash_quorum: None
+        }]
+    );
+    cfg.args.push("--bootstrap-from".into());
+    cfg.args.push(bootstrap_from.into());
+    cfg.args.extend(args.iter().map(|arg| arg.to_string()));
+    Sut::start(cfg).await
+}
+
+async fn finalized_chunks(base_url: &str, dataset: &str) -> Result<Vec<Value>> {
+    let url = format!("{base_url}/datasets/{dataset}/finalized-chunks");
+    Ok(reqwest::get(&url).await?.error_for_status()?.json().await?)
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn head_retention_copies_from_the_first_peer_chunk() -> Result<()> {
+    let peer = start_peer().await?;
+    let peer_chunks = finalized_chunks(&peer.sut.base_url(), &peer.dataset).await?;
+    assert!(peer_chunks.len() > 1, "the script must produce more than one chunk");
+
+    // Serving starts after the bootstrap, the copy is complete by now
+    let replica = start_replica(&peer, Retention::Head(1_000), &peer.sut.base_url(), &[]).await?;
+
+    let replica_chunks = finalized_chunks(&replica.base_url(), &peer.dataset).await?;
+    assert_eq!(replica_chunks, peer_chunks);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn from_block_retention_copies_from_the_floor() -> Result<()> {
+    let peer = start_peer().await?;
+    let peer_chunks = finalized_chunks(&peer.sut.base_url(), &peer.dataset).await?;
+    assert!(peer_chunks.len() > 1, "the script must produce more than one chunk");
+
+    let floor = Retention::FromBlock {
+        number: peer_chunks[1]["firstBlock"].as_u64().unwrap(),
+        parent_hash: peer_chunks[0]["lastBlockHash"].as_str().map(str::to_string)
+    };
+    let replica = start_replica(&peer, floor, &peer.sut.base_url(), &[]).await?;
+
+    let replica_chunks = finalized_chunks(&replica.base_url(), &peer.dataset).await?;
+    assert_eq!(replica_chunks, peer_chunks[1..]);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unresponsive_peer_does_not_block_startup() -> Result<()> {
+    let peer = start_peer().await?;
+
+    // Accepts connections, but never answers
+    let silent = std::net::TcpListener::bind("127.0.0.1:0")?;
+    let silent_url = format!("http://{}", silent.local_addr()?);
+
+    let replica = start_replica(
+        &peer,
+        Retention::Head(1_000),
+        &silent_url,
+        &["--bootstrap-timeout-secs", "1"]
+    )
+    .await?;
+
+    assert!(
+        replica.last_startup < Duration::from_secs(15),
+        "the bootstrap must give up at its deadline, startup took {:?}",
+        replica.last_startup
+    );
+    drop(silent);
+    Ok(())
+}
@@ -3,14 +3,15 @@
 //! Production configures a handful of sources per dataset and they do not stay in step: on
 //! `base-mainnet` one endpoint has been observed minutes behind two that sit on the head. Every
 //! script before this one ran exactly **one** source, so `StandardDataSource`'s multi-endpoint
-//! path had never been executed by a test at all — not the fixed-order poll race
+//! path had never been executed by a test at all — not the poll race
 //! (`poll_next_event`), not the fork consensus, not the per-endpoint `MaybeOnHead` flush trigger.
 //! `crates/data-source` carries no unit tests either.
 //!
-//! What makes lag worth a script of its own: the selector has no notion of *slow*. An endpoint
-//! leaves the rotation only by erroring (`on_error` → `Backoff`); `is_active` knows `Backoff` and
-//! nothing else. A healthy endpoint minutes behind the tip therefore keeps full standing in the
-//! race forever, and two mechanisms could let it hold the served head back —
+//! What makes lag worth a script of its own: lag only demotes an endpoint in the poll order
+//! (`update_poll_order` scores lag, latency and error rate), it never takes it out of the race. An
+//! endpoint leaves the rotation only by erroring (`on_error` → `Backoff`); `is_active` knows
+//! `Backoff` and nothing else. A healthy endpoint minutes behind the tip therefore keeps polling
+//! forever, and two mechanisms could let it hold the served head back —
 //!
 //!  - the flush at the tip is `DataEvent::MaybeOnHead`, and it fires only when the endpoint whose
 //!    stream just ended is *itself* the one that committed the current head block
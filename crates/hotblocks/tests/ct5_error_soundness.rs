@@ -85,16 +85,15 @@ async fn ct5_query_errors_are_internally_classified_and_remain_text() -> Result<
 
     assert!(
         h.client.head().await?.is_some(),
-        "unsupported queries must not kill the service"
+        "mismatched dialect queries must not kill the service"
     );
 
     let metrics = h.client.metrics().await?;
     for (code, minimum) in [
         ("MALFORMED_REQUEST", 1.0),
-        ("KIND_MISMATCH", 1.0),
+        ("KIND_MISMATCH", 3.0),
         ("RANGE_UNAVAILABLE", 1.0),
-        ("ITEM_UNAVAILABLE", 1.0),
-        ("UNSUPPORTED_QUERY", 2.0)
+        ("ITEM_UNAVAILABLE", 1.0)
     ] {
         let count = metrics
             .get("hotblocks_http_status_total", Some(("error_class", code)))
@@ -79,7 +79,9 @@ async fn ct9_a_total_source_outage_is_counted_before_ingestion_starts() -> Resul
             kind: Evm.config_kind().to_string(),
             // `Head` is what routes the dataset through the probe at all.
             retention: Retention::Head(100),
-            sources: vec![format!("http://127.0.0.1:{dead}/{DS}")]
+            sources: vec![format!("http://127.0.0.1:{dead}/{DS}")],
+            disable_compaction: false,
+            block_hash_quorum: None
         }]
     ))
     .await?;
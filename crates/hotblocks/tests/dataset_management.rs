@@ -0,0 +1,162 @@
+//! `PUT` / `DELETE /datasets/{id}` while a client follows the dataset.
+//!
+//! Queries keep the controller they started on alive, so replacing or deleting the dataset must
+//! stop its old controller explicitly: two writers on one dataset, or a writer racing the
+//! deletion, would corrupt it.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::{Context, Result};
+use reqwest::StatusCode;
+use serde_json::{Value, json};
+use sqd_hotblocks_harness::{
+    Evm,
+    harness::{Harness, HarnessConfig},
+    types::block_hash
+};
+
+const START: u64 = 1_000;
+
+fn dataset_url(h: &Harness) -> String {
+    format!("{}/datasets/{}", h.sut.base_url(), h.dataset)
+}
+
+/// The config the harness started the dataset with, but with compaction disabled
+fn changed_config(h: &Harness) -> Value {
+    json!({
+        "kind": h.chain.config_kind(),
+        "retention_strategy": {
+            "FromBlock": {
+                "number": START,
+                "parent_hash": block_hash(START - 1, 0)
+            }
+        },
+        "disable_compaction": true,
+        "data_sources": [h.sim.base_url(&h.dataset)]
+    })
+}
+
+async fn follow(h: &Harness) -> Result<reqwest::Response> {
+    let query = h.chain.scan_query(START, None, None);
+    let res = reqwest::Client::new()
+        .post(format!("{}/stream?follow=true", dataset_url(h)))
+        .json(&query)
+        .send()
+        .await?
+        .error_for_status()?;
+    Ok(res)
+}
+
+/// Reads the follow stream until a block at or above `number` arrives
+async fn read_until(res: &mut reqwest::Response, number: u64) -> Result<()> {
+    let mut buf = Vec::new();
+    loop {
+        let chunk = res.chunk().await?.context("the follow stream ended")?;
+        buf.extend_from_slice(&chunk);
+        while let Some(pos) = buf.iter().position(|b| *b == b'\n') {
+            let line: Vec<u8> = buf.drain(..=pos).collect();
+            let value: Value = serde_json::from_slice(&line)?;
+            if value["header"]["number"].as_u64().is_some_and(|n| n >= number) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_while_following_restarts_ingestion() -> Result<()> {
+    let mut h = Harness::start(HarnessConfig::from_block(
+        env!("CARGO_BIN_EXE_sqd-hotblocks"),
+        Arc::new(Evm),
+        START
+    ))
+    .await?;
+
+    h.produce(20)?;
+    h.finalize_with_lag(5)?;
+    h.settle().await?;
+
+    let mut stream = follow(&h).await?;
+    read_until(&mut stream, START + 19).await?;
+
+    let res = reqwest::Client::new()
+        .put(dataset_url(&h))
+        .json(&changed_config(&h))
+        .send()
+        .await?;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    // The new controller ingests, and the open stream moves over to it
+    h.produce(10)?;
+    h.finalize_with_lag(5)?;
+    h.settle().await?;
+    h.assert_conforms().await?;
+
+    tokio::time::timeout(Duration::from_secs(30), read_until(&mut stream, START + 29))
+        .await
+        .context("the follow stream did not deliver blocks of the new controller")??;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn delete_while_following_ends_the_stream() -> Result<()> {
+    let mut h = Harness::start(HarnessConfig::from_block(
+        env!("CARGO_BIN_EXE_sqd-hotblocks"),
+        Arc::new(Evm),
+        START
+    ))
+    .await?;
+
+    h.produce(20)?;
+    h.finalize_with_lag(5)?;
+    h.settle().await?;
+
+    let mut stream = follow(&h).await?;
+    read_until(&mut stream, START + 19).await?;
+
+    let res = reqwest::Client::new().delete(dataset_url(&h)).send().await?;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let res = reqwest::get(format!("{}/head", dataset_url(&h))).await?;
+    assert_eq!(res.status(), StatusCode::NOT_FOUND);
+
+    // The source keeps producing, but nothing ingests the deleted dataset anymore
+    h.produce(10)?;
+    // A body aborted mid-stream is as good as a closed one here
+    tokio::time::timeout(Duration::from_secs(30), async {
+        while let Ok(Some(_)) = stream.chunk().await {}
+    })
+    .await
+    .context("the follow stream outlived its dataset")?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn put_with_a_malformed_config_is_a_bad_request() -> Result<()> {
+    let h = Harness::start(HarnessConfig::from_block(
+        env!("CARGO_BIN_EXE_sqd-hotblocks"),
+        Arc::new(Evm),
+        START
+    ))
+    .await?;
+
+    let mut missing_sources = changed_config(&h);
+    missing_sources.as_object_mut().unwrap().remove("data_sources");
+    let bodies = [b"{\"kind\":".to_vec(), serde_json::to_vec(&missing_sources)?];
+
+    for body in bodies {
+        let res = reqwest::Client::new()
+            .put(dataset_url(&h))
+            .header("content-type", "application/json")
+            .body(body)
+            .send()
+            .await?;
+        assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+        assert!(res.text().await?.starts_with("invalid dataset config"));
+    }
+
+    // The dataset keeps its old config
+    let res = reqwest::get(format!("{}/head", dataset_url(&h))).await?;
+    assert_eq!(res.status(), StatusCode::OK);
+    Ok(())
+}
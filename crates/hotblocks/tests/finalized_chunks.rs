@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use anyhow::Result;
+use arrow::{
+    array::AsArray,
+    compute::cast,
+    datatypes::{DataType, UInt64Type},
+    ipc::reader::StreamReader
+};
+use serde::Deserialize;
+use sqd_hotblocks_harness::{
+    chain::Evm,
+    harness::{Harness, HarnessConfig},
+    types::block_hash
+};
+
+const START: u64 = 1_000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FinalizedChunk {
+    first_block: u64,
+    last_block: u64,
+    tables: Vec<String>
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_chunks_below_the_finalized_head_are_exported() -> Result<()> {
+    let mut cfg = HarnessConfig::from_block(env!("CARGO_BIN_EXE_sqd-hotblocks"), Arc::new(Evm), START);
+    // Each settled batch stays a chunk of its own, so the first one ends at the finalized head
+    cfg.disable_compaction = true;
+    let mut h = Harness::start(cfg).await?;
+
+    h.produce(10)?;
+    h.settle().await?;
+    h.produce(10)?;
+    h.settle().await?;
+    h.finalize(START + 9)?;
+    h.settle().await?;
+
+    let url = format!("{}/datasets/{}/finalized-chunks", h.sut.base_url(), h.dataset);
+    let chunks: Vec<FinalizedChunk> = reqwest::get(&url).await?.error_for_status()?.json().await?;
+
+    assert!(
+        chunks.iter().all(|chunk| chunk.last_block <= START + 9),
+        "a chunk above the finalized head must not be listed"
+    );
+    assert!(
+        chunks.windows(2).all(|w| w[0].last_block + 1 == w[1].first_block),
+        "listed chunks must be contiguous"
+    );
+
+    let first = chunks
+        .first()
+        .expect("the chunk below the finalized head must be listed");
+    assert_eq!(first.first_block, START);
+    assert!(first.tables.iter().any(|table| table == "blocks"));
+
+    let url = format!(
+        "{}/datasets/{}/finalized-chunks/{}/blocks",
+        h.sut.base_url(),
+        h.dataset,
+        first.first_block
+    );
+    let bytes = reqwest::get(&url).await?.error_for_status()?.bytes().await?;
+    let mut numbers = Vec::new();
+    let mut hashes = Vec::new();
+    for batch in StreamReader::try_new(bytes.as_ref(), None)? {
+        let batch = batch?;
+        let column = cast(batch.column_by_name("number").unwrap(), &DataType::UInt64)?;
+        numbers.extend(column.as_primitive::<UInt64Type>().values().iter().copied());
+        let column = cast(batch.column_by_name("hash").unwrap(), &DataType::Utf8)?;
+        hashes.extend(
+            column
+                .as_string::<i32>()
+                .iter()
+                .map(|hash| hash.unwrap_or_default().to_string())
+        );
+    }
+    assert_eq!(numbers, (first.first_block..=first.last_block).collect::<Vec<_>>());
+    assert_eq!(
+        hashes,
+        numbers.iter().map(|number| block_hash(*number, 0)).collect::<Vec<_>>()
+    );
+
+    if first.last_block > first.first_block {
+        let url = format!(
+            "{}/datasets/{}/finalized-chunks/{}/blocks",
+            h.sut.base_url(),
+            h.dataset,
+            first.first_block + 1
+        );
+        assert_eq!(
+            reqwest::get(&url).await?.status(),
+            404,
+            "a table is only addressable by the first block of its chunk"
+        );
+    }
+
+    Ok(())
+}
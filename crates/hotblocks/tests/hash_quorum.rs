@@ -0,0 +1,139 @@
+//! `block_hash_quorum` — several endpoints per dataset, and a block is accepted only once enough
+//! of them sent the same one.
+//!
+//! A source that disagrees is the fault the quorum exists for: one endpoint of three minting its
+//! own block on top of the canonical tip must be outvoted, not followed. The sources here are the
+//! harness peers, and a peer forked on its own holds a chain the model doesn't know — so it is
+//! never caught up again, and finality is declared only above its tip.
+
+use std::{sync::Arc, time::Duration};
+
+use anyhow::Result;
+use sqd_hotblocks_harness::{
+    Evm,
+    harness::{Harness, HarnessConfig}
+};
+
+fn config(quorum: usize) -> HarnessConfig {
+    let mut cfg = HarnessConfig::from_block(env!("CARGO_BIN_EXE_sqd-hotblocks"), Arc::new(Evm), 1_000);
+    cfg.sources = 3;
+    cfg.block_hash_quorum = Some(quorum);
+    // Blocks short of the quorum wait out `P-HASH-QUORUM-WAIT` one by one
+    cfg.quiescence.timeout = Duration::from_secs(90);
+    cfg
+}
+
+async fn counter(h: &Harness, name: &str) -> Result<f64> {
+    Ok(h.client.metrics().await?.get(name, None).unwrap_or_default())
+}
+
+/// Peer 0 mints its own block on top of the canonical tip and falls out of step for good.
+fn disagree(h: &Harness) -> Result<()> {
+    let tip = h.sim.tip(&h.dataset).expect("the script produced blocks");
+    h.peers[0].fork(&h.dataset, tip.number + 1, 1)?;
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn three_agreeing_sources_conform() -> Result<()> {
+    let mut h = Harness::start(config(2)).await?;
+
+    h.produce(30)?;
+    h.finalize_with_lag(5)?;
+    h.settle().await?;
+    h.assert_conforms().await?;
+
+    assert_eq!(counter(&h, "hotblocks_ingest_hash_disagreements_total").await?, 0.0);
+    assert_eq!(counter(&h, "hotblocks_ingest_hash_quorum_timeouts_total").await?, 0.0);
+    Ok(())
+}
+
+/// The quorum is reachable without the disagreeing source, so its block is simply outvoted.
+#[tokio::test(flavor = "multi_thread")]
+async fn a_disagreeing_source_is_outvoted() -> Result<()> {
+    let mut h = Harness::start(config(2)).await?;
+
+    h.produce(20)?;
+    h.finalize_with_lag(5)?;
+    h.settle().await?;
+
+    disagree(&h)?;
+    for _ in 0..3 {
+        h.produce_lagging(&[0], 5)?;
+        h.finalize_with_lag(5)?;
+        tokio::time::sleep(Duration::from_millis(50)).await;
+    }
+
+    h.settle().await?;
+    h.assert_conforms().await?;
+
+    // Whether it voted in time or arrived late, the disagreement is counted
+    assert!(counter(&h, "hotblocks_ingest_hash_disagreements_total").await? > 0.0);
+    Ok(())
+}
+
+/// All three must agree, which the disagreeing source prevents. After the wait, the canonical
+/// block carries the majority of the votes cast and is accepted.
+#[tokio::test(flavor = "multi_thread")]
+async fn the_majority_block_is_accepted_after_the_timeout() -> Result<()> {
+    let mut h = Harness::start(config(3)).await?;
+
+    h.produce(20)?;
+    h.finalize_with_lag(5)?;
+    h.settle().await?;
+
+    disagree(&h)?;
+    h.produce_lagging(&[0], 6)?;
+    h.finalize_with_lag(2)?;
+
+    h.settle().await?;
+    h.assert_conforms().await?;
+
+    assert!(counter(&h, "hotblocks_ingest_hash_quorum_timeouts_total").await? > 0.0);
+    assert!(counter(&h, "hotblocks_ingest_hash_disagreements_total").await? > 0.0);
+    Ok(())
+}
+
+/// A reorg seen by every source reaches the quorum of fork signals right away.
+#[tokio::test(flavor = "multi_thread")]
+async fn a_reorg_on_every_source_is_followed() -> Result<()> {
+    let mut h = Harness::start(config(2)).await?;
+
+    h.produce(20)?;
+    h.finalize_with_lag(10)?;
+    h.settle().await?;
+
+    let blocks = h.sim.fork(&h.dataset, 1_015, 8)?;
+    h.model.replace(1_015, &blocks, None)?;
+    for peer in &h.peers {
+        peer.fork(&h.dataset, 1_015, 8)?;
+    }
+    h.finalize_with_lag(5)?;
+
+    h.settle().await?;
+    h.assert_conforms().await?;
+    Ok(())
+}
+
+/// All three must agree, but one source stays on the old chain and signals nothing. The fork
+/// signalled by the other two is the majority and is followed after the fork consensus timeout.
+#[tokio::test(flavor = "multi_thread")]
+async fn a_reorg_on_the_majority_is_followed_after_the_timeout() -> Result<()> {
+    let mut h = Harness::start(config(3)).await?;
+
+    h.produce(20)?;
+    h.finalize_with_lag(10)?;
+    h.settle().await?;
+
+    let blocks = h.sim.fork(&h.dataset, 1_018, 3)?;
+    h.model.replace(1_018, &blocks, None)?;
+    h.peers[1].fork(&h.dataset, 1_018, 3)?;
+
+    // Finality stays above the old chain of peer 0
+    h.produce_lagging(&[0], 5)?;
+    h.finalize_with_lag(2)?;
+
+    h.settle().await?;
+    h.assert_conforms().await?;
+    Ok(())
+}
@@ -0,0 +1,70 @@
+//! A `file://` data source replays blocks recorded on disk.
+
+use std::{io::Write, time::Duration};
+
+use anyhow::{Result, bail};
+use sqd_hotblocks_harness::{
+    Block, Chain, Client, Evm,
+    sut::{DatasetSpec, Retention, Sut, SutConfig},
+    types::block_hash
+};
+
+const START: u64 = 1_000;
+const END: u64 = 1_049;
+
+fn recorded_blocks() -> Vec<Block> {
+    (START..=END)
+        .map(|number| Block {
+            number,
+            hash: block_hash(number, 0),
+            parent_number: number - 1,
+            parent_hash: block_hash(number - 1, 0),
+            timestamp_ms: 1_760_000_000_000 + number as i64 * 1_000,
+            fork_id: 0
+        })
+        .collect()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn replays_a_recording() -> Result<()> {
+    let blocks = recorded_blocks();
+
+    let dir = tempfile::tempdir()?;
+    let mut file = std::fs::File::create(dir.path().join("blocks.jsonl"))?;
+    for block in blocks.iter() {
+        writeln!(file, "{}", Evm.source_block(block))?;
+    }
+    drop(file);
+
+    let sut = Sut::start(SutConfig::new(
+        env!("CARGO_BIN_EXE_sqd-hotblocks"),
+        vec![DatasetSpec {
+            id: "replay".to_string(),
+            kind: Evm.config_kind().to_string(),
+            retention: Retention::FromBlock {
+                number: START,
+                parent_hash: Some(block_hash(START - 1, 0))
+            },
+            sources: vec![url::Url::from_directory_path(dir.path()).unwrap().to_string()],
+            disable_compaction: false,
+            block_hash_quorum: None
+        }]
+    ))
+    .await?;
+    let client = Client::new(sut.base_url(), "replay")?;
+
+    // The whole recording is final
+    let deadline = tokio::time::Instant::now() + Duration::from_secs(30);
+    while client.finalized_head().await?.map(|h| h.number) != Some(END) {
+        if tokio::time::Instant::now() > deadline {
+            bail!("the recording was not ingested in time");
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+
+    let emitted = client.scan(&Evm, START, END, None).await?;
+    let emitted: Vec<_> = emitted.into_iter().map(|b| b.raw).collect();
+    let expected: Vec<_> = blocks.iter().map(|b| Evm.expected_emission(b)).collect();
+    assert_eq!(emitted, expected);
+    Ok(())
+}
@@ -0,0 +1,174 @@
+//! Result limits of a query span the whole response, not a single chunk.
+
+use std::sync::Arc;
+
+use anyhow::{Result, bail};
+use serde::Deserialize;
+use serde_json::{Value, json};
+use sqd_hotblocks_harness::{
+    chain::Evm,
+    driver::{Emitted, Outcome},
+    harness::{Harness, HarnessConfig}
+};
+
+const START: u64 = 1_000;
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct FinalizedChunk {
+    last_block: u64
+}
+
+/// Logs of the simulated EVM chain, see `log_count` in the harness.
+fn log_count(number: u64) -> usize {
+    if number % 2 == 0 { 0 } else { (number % 3) as usize }
+}
+
+fn logs_query(limit: Value) -> Value {
+    json!({
+        "type": "evm",
+        "fromBlock": START,
+        "fields": {
+            "block": {"number": true, "hash": true, "parentHash": true},
+            "log": {"logIndex": true}
+        },
+        "logs": [{}],
+        "limit": limit
+    })
+}
+
+fn logs_per_block(blocks: &[Emitted]) -> Vec<(u64, usize)> {
+    blocks
+        .iter()
+        .filter_map(|block| {
+            let logs = block.raw.get("logs")?.as_array()?.len();
+            Some((block.number, logs))
+        })
+        .collect()
+}
+
+async fn query(h: &Harness, body: &Value) -> Result<Vec<Emitted>> {
+    match h.client.query(body).await? {
+        Outcome::Ok { blocks, .. } => Ok(blocks),
+        other => bail!("unexpected query outcome: {other:?}")
+    }
+}
+
+/// Three ingested batches, compaction off, hence several chunks.
+async fn start() -> Result<(Harness, Vec<FinalizedChunk>)> {
+    start_with_batches(&[10, 10, 10]).await
+}
+
+/// A chunk per ingested batch of the given sizes, starting at [`START`]
+async fn start_with_batches(batches: &[u32]) -> Result<(Harness, Vec<FinalizedChunk>)> {
+    let mut cfg = HarnessConfig::from_block(env!("CARGO_BIN_EXE_sqd-hotblocks"), Arc::new(Evm), START);
+    cfg.disable_compaction = true;
+    let mut h = Harness::start(cfg).await?;
+
+    for size in batches {
+        h.produce(*size)?;
+        h.settle().await?;
+    }
+    h.finalize(START + batches.iter().map(|size| u64::from(*size)).sum::<u64>() - 1)?;
+    h.settle().await?;
+
+    let url = format!("{}/datasets/{}/finalized-chunks", h.sut.base_url(), h.dataset);
+    let chunks: Vec<FinalizedChunk> = reqwest::get(&url).await?.error_for_status()?.json().await?;
+    assert!(chunks.len() > 1, "the script must produce more than one chunk");
+
+    Ok((h, chunks))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn block_limit_spans_chunks() -> Result<()> {
+    let (h, chunks) = start().await?;
+
+    let expected: Vec<(u64, usize)> = (START..START + 30)
+        .map(|number| (number, log_count(number)))
+        .filter(|(_, logs)| *logs > 0)
+        .take(8)
+        .collect();
+    let cut = expected.last().unwrap().0;
+    assert!(
+        cut > chunks[0].last_block,
+        "the limit must not be reached within the first chunk"
+    );
+
+    let blocks = query(&h, &logs_query(json!({"blocks": 8}))).await?;
+
+    // Empty blocks at chunk boundaries are returned, but don't count against the limit
+    assert_eq!(blocks.first().unwrap().number, START);
+    assert_eq!(logs_per_block(&blocks), expected);
+    assert_eq!(blocks.last().unwrap().number, cut);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn item_limit_spans_chunks() -> Result<()> {
+    let (h, chunks) = start().await?;
+
+    let mut total = 0;
+    let expected: Vec<(u64, usize)> = (START..START + 30)
+        .map(|number| (number, log_count(number)))
+        .filter(|(_, logs)| *logs > 0)
+        .take_while(|(_, logs)| {
+            total += logs;
+            total <= 10
+        })
+        .collect();
+    let overflow = (expected.last().unwrap().0 + 1..)
+        .find(|number| log_count(*number) > 0)
+        .unwrap();
+    assert!(
+        overflow > chunks[0].last_block,
+        "the limit must not be reached within the first chunk"
+    );
+
+    let blocks = query(&h, &logs_query(json!({"items": 10}))).await?;
+
+    assert_eq!(logs_per_block(&blocks), expected);
+    assert!(
+        blocks.last().unwrap().number < overflow,
+        "the response must end before the block, that doesn't fit into the limit"
+    );
+
+    // The client resumes from the next block and gets the rest
+    let next = START.max(blocks.last().unwrap().number + 1);
+    let mut rest = logs_query(json!({"items": 100}));
+    rest["fromBlock"] = json!(next);
+    let blocks = query(&h, &rest).await?;
+    assert_eq!(
+        logs_per_block(&blocks).first().map(|(number, _)| *number),
+        Some(overflow)
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn only_the_first_block_of_a_response_exceeds_the_item_limit() -> Result<()> {
+    // The second chunk starts with a block, that has more logs than remain in the budget
+    let (h, chunks) = start_with_batches(&[7, 10]).await?;
+    let boundary = chunks[0].last_block + 1;
+    let first_chunk_logs: usize = (START..boundary).map(log_count).sum();
+    let limit = first_chunk_logs + 1;
+    assert!(
+        log_count(boundary) > limit - first_chunk_logs,
+        "the chunk boundary must fall on a block, that doesn't fit into the rest of the limit"
+    );
+
+    let blocks = query(&h, &logs_query(json!({"items": limit}))).await?;
+
+    let expected: Vec<(u64, usize)> = (START..boundary)
+        .map(|number| (number, log_count(number)))
+        .filter(|(_, logs)| *logs > 0)
+        .collect();
+    assert_eq!(logs_per_block(&blocks), expected);
+    assert!(blocks.last().unwrap().number < boundary);
+
+    // A response starting at that block returns it, even if it alone exceeds the limit
+    let mut rest = logs_query(json!({"items": 1}));
+    rest["fromBlock"] = json!(boundary);
+    let blocks = query(&h, &rest).await?;
+    assert_eq!(logs_per_block(&blocks).first(), Some(&(boundary, log_count(boundary))));
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use std::{collections::HashMap, io::Write, sync::Arc};
+
+use arrow::ipc::writer::StreamWriter;
+
+use crate::plan::BlockWriter;
+
+/// Schema metadata key holding the name of the result item (`blocks`, `logs`, etc)
+/// a particular Arrow IPC stream belongs to.
+pub const ARROW_ITEM_NAME_METADATA_KEY: &str = "sqd_item";
+
+/// Writes query results as a sequence of Arrow IPC streams.
+///
+/// Result items have different schemas, so they can't share a single IPC stream.
+/// Each call to [`ArrowIpcWriter::write_blocks`] emits one complete IPC stream
+/// (schema, single record batch, end-of-stream marker) per non-empty result item,
+/// with the item name stored in the schema metadata under [`ARROW_ITEM_NAME_METADATA_KEY`].
+/// The block headers stream always comes first.
+///
+/// The streams are written back to back without any extra framing: the next stream
+/// starts right after the end-of-stream marker of the previous one. Readers consume
+/// the output by opening an unbuffered stream reader at the current position,
+/// reading it to the end and repeating until the input is exhausted.
+pub struct ArrowIpcWriter<W> {
+    write: W
+}
+
+impl<W: Write> ArrowIpcWriter<W> {
+    pub fn new(write: W) -> Self {
+        Self { write }
+    }
+
+    pub fn write_blocks(&mut self, blocks: &mut BlockWriter) -> anyhow::Result<()> {
+        for (name, batch) in blocks.take_record_batches()? {
+            let metadata = HashMap::from([(ARROW_ITEM_NAME_METADATA_KEY.to_string(), name.to_string())]);
+            let schema = batch.schema().as_ref().clone().with_metadata(metadata);
+            let batch = batch.with_schema(Arc::new(schema))?;
+
+            let mut writer = StreamWriter::try_new(&mut self.write, batch.schema_ref())?;
+            writer.write(&batch)?;
+            writer.finish()?;
+        }
+        Ok(())
+    }
+
+    pub fn finish(mut self) -> std::io::Result<W> {
+        self.write.flush()?;
+        Ok(self.write)
+    }
+}
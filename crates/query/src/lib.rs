@@ -1,4 +1,5 @@
 #![allow(dead_code)]
+mod arrow_writer;
 mod json;
 mod json_writer;
 mod plan;
@@ -6,6 +7,7 @@ mod primitives;
 mod query;
 mod scan;
 
+pub use arrow_writer::*;
 pub use json_writer::*;
 pub use plan::{BlockWriter, Plan, UnexpectedBaseBlock};
 pub use primitives::BlockNumber;
@@ -1,6 +1,10 @@
 use std::collections::{HashMap, HashSet};
 
-use anyhow::{anyhow, bail};
+use anyhow::{anyhow, bail, Context};
+use arrow::{
+    array::{AsArray, PrimitiveArray},
+    datatypes::UInt64Type
+};
 use rayon::prelude::*;
 use sqd_polars::arrow::record_batch_vec_to_lazy_polars_df;
 use sqd_primitives::BlockRef;
@@ -13,8 +17,8 @@ use crate::{
         row_list::RowList,
         table::{ColumnWeight, TableSet}
     },
-    primitives::{BlockNumber, Name, RowRangeList, RowWeight, RowWeightPolarsType},
-    scan::{col_between, col_gt_eq, col_lt_eq, Chunk, RowPredicateRef},
+    primitives::{BlockNumber, Name, RowIndex, RowIndexArrowType, RowRangeList, RowWeight, RowWeightPolarsType},
+    scan::{and, col_between, col_gt_eq, col_lt_eq, Chunk, RowPredicateRef},
     UnexpectedBaseBlock
 };
 
@@ -45,7 +49,10 @@ pub struct Plan {
     include_all_blocks: bool,
     parent_block_hash: Option<String>,
     first_block: Option<BlockNumber>,
-    last_block: Option<BlockNumber>
+    last_block: Option<BlockNumber>,
+    max_blocks: Option<usize>,
+    max_items: Option<usize>,
+    allow_first_block_overflow: bool
 }
 
 impl Plan {
@@ -71,6 +78,35 @@ impl Plan {
     pub fn set_last_block(&mut self, block_number: impl Into<Option<BlockNumber>>) {
         self.last_block = block_number.into()
     }
+
+    pub fn max_blocks(&self) -> Option<usize> {
+        self.max_blocks
+    }
+
+    pub fn max_items(&self) -> Option<usize> {
+        self.max_items
+    }
+
+    pub fn set_max_blocks(&mut self, max_blocks: impl Into<Option<usize>>) {
+        self.max_blocks = max_blocks.into()
+    }
+
+    pub fn set_max_items(&mut self, max_items: impl Into<Option<usize>>) {
+        self.max_items = max_items.into()
+    }
+
+    /// Whether the first block of the result may exceed the item limit (`true` by default).
+    ///
+    /// Only the first block of a response may exceed the limit, otherwise a client could never get past it.
+    /// Responses spanning several chunks must disable this, once some blocks were returned.
+    /// Then, when the first block doesn't fit, the result has no blocks and reports the limit as reached.
+    pub fn set_allow_first_block_overflow(&mut self, allow: bool) {
+        self.allow_first_block_overflow = allow
+    }
+
+    fn has_limit(&self) -> bool {
+        self.max_blocks.is_some() || self.max_items.is_some()
+    }
 }
 
 /// A wrapper around `Chunk` that automatically attaches default-null columns
@@ -106,6 +142,9 @@ impl<'a> PlanExecution<'a> {
     /// 1. Scans - find matching row indexes in each table (parallel per scan)
     /// 2. Relations - propagate row selections across related tables (e.g. join, children)
     /// 3. Output - read actual data for selected rows from each table and build the result
+    ///
+    /// When the plan has a result limit, scans establish an upper bound for the
+    /// last block of the response, and all subsequent phases skip the rows beyond it.
     fn execute(&self) -> anyhow::Result<Option<BlockWriter>> {
         self.check_parent_block()?;
 
@@ -113,9 +152,9 @@ impl<'a> PlanExecution<'a> {
 
         let output_inputs = self.plan.outputs.iter().map(|_| RowList::new()).collect();
 
-        self.execute_scans(&relation_inputs, &output_inputs)?;
+        let scan_bound = self.execute_scans(&relation_inputs, &output_inputs)?;
         self.execute_relations(relation_inputs, &output_inputs)?;
-        self.execute_output(output_inputs)
+        self.execute_output(output_inputs, scan_bound)
     }
 
     fn check_parent_block(&self) -> anyhow::Result<()> {
@@ -198,7 +237,17 @@ impl<'a> PlanExecution<'a> {
     /// its predicate. These row indexes are distributed to relation inputs
     /// (for cross-table joins in `execute_relations`) and/or output inputs (for direct
     /// data reading in `execute_output`). Scans run in parallel.
-    fn execute_scans(&self, relation_inputs: &Vec<RowList>, output_inputs: &Vec<RowList>) -> anyhow::Result<()> {
+    ///
+    /// Returns an upper bound for the last block of the response, if the plan is limited.
+    fn execute_scans(
+        &self,
+        relation_inputs: &Vec<RowList>,
+        output_inputs: &Vec<RowList>
+    ) -> anyhow::Result<Option<ScanBound>> {
+        if self.plan.has_limit() {
+            return self.execute_limited_scans(relation_inputs, output_inputs);
+        }
+
         self.plan.scans.par_iter().try_for_each(|scan| -> anyhow::Result<()> {
             let rows = self
                 .chunk
@@ -217,7 +266,104 @@ impl<'a> PlanExecution<'a> {
             }
 
             Ok(())
-        })
+        })?;
+
+        Ok(None)
+    }
+
+    /// Same as `execute_scans`, but for plans with a result limit.
+    ///
+    /// Scans are restricted to the requested block range and additionally fetch
+    /// the block number of each matched row. Any scan feeding an output
+    /// gives an upper bound for the last block of the response:
+    /// the block of its `max_items + 1`-th row or its `max_blocks`-th block.
+    /// Rows beyond the lowest bound are never passed to relations and outputs.
+    fn execute_limited_scans(
+        &self,
+        relation_inputs: &Vec<RowList>,
+        output_inputs: &Vec<RowList>
+    ) -> anyhow::Result<Option<ScanBound>> {
+        let scan_rows = self
+            .plan
+            .scans
+            .par_iter()
+            .map(
+                |scan| -> anyhow::Result<(Vec<RowIndex>, Vec<BlockNumber>, Option<BlockNumber>)> {
+                    let block_number_column = self.plan.tables.get(scan.table).primary_key[0];
+
+                    let predicate = match (scan.predicate.clone(), self.block_range_predicate(block_number_column)) {
+                        (Some(p), Some(range)) => Some(and(vec![p, range])),
+                        (p, range) => p.or(range)
+                    };
+
+                    let record_batches = self
+                        .chunk
+                        .scan_table(scan.table)?
+                        .with_row_index(true)
+                        .with_column(block_number_column)
+                        .with_predicate(predicate)
+                        .execute()?;
+
+                    let mut row_indexes = Vec::new();
+                    let mut block_numbers = Vec::new();
+                    for batch in record_batches.iter() {
+                        let rows: &PrimitiveArray<RowIndexArrowType> = batch
+                            .column_by_name("row_index")
+                            .expect("No row_index column in the batch")
+                            .as_primitive();
+                        row_indexes.extend(rows.values().iter().copied());
+
+                        let numbers = arrow::compute::cast(
+                            batch.column_by_name(block_number_column).unwrap(),
+                            &arrow::datatypes::DataType::UInt64
+                        )
+                        .with_context(|| format!("failed to cast '{}' to block number", block_number_column))?;
+                        block_numbers.extend(numbers.as_primitive::<UInt64Type>().values().iter().copied());
+                    }
+
+                    let bound = if scan.output.is_some() {
+                        limit_bound(block_numbers.clone(), self.plan.max_items, self.plan.max_blocks)
+                    } else {
+                        None
+                    };
+
+                    Ok((row_indexes, block_numbers, bound))
+                }
+            )
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        let block_bound = scan_rows.iter().filter_map(|rows| rows.2).min();
+
+        let scan_bound = block_bound.map(|bound| ScanBound {
+            last_block: bound,
+            has_rows_beyond: scan_rows
+                .iter()
+                .any(|(_, block_numbers, _)| block_numbers.iter().any(|block_number| *block_number > bound))
+        });
+
+        self.plan
+            .scans
+            .par_iter()
+            .zip(scan_rows.par_iter())
+            .for_each(|(scan, (row_indexes, block_numbers, _))| {
+                let rows = || {
+                    row_indexes
+                        .iter()
+                        .zip(block_numbers.iter())
+                        .filter(|(_, block_number)| block_bound.map_or(true, |bound| **block_number <= bound))
+                        .map(|(row_index, _)| *row_index)
+                };
+
+                for rel_idx in scan.relations.iter() {
+                    relation_inputs[*rel_idx].extend(rows());
+                }
+
+                if let Some(idx) = &scan.output {
+                    output_inputs[*idx].extend(rows())
+                }
+            });
+
+        Ok(scan_bound)
     }
 
     /// Propagate row selections through relations.
@@ -253,9 +399,23 @@ impl<'a> PlanExecution<'a> {
     /// Columns missing from the parquet file are handled gracefully if they
     /// have a default value of Null (via default_null_columns) — they are
     /// replaced with NullArrays so the encoder outputs "null" for them.
-    fn execute_output(&self, output_inputs: Vec<RowList>) -> anyhow::Result<Option<BlockWriter>> {
+    ///
+    /// For limited plans, rows beyond the scan bound are not read at all, and
+    /// the selected blocks are further cut, so that every table has at most
+    /// `max_items` items and there are at most `max_blocks` blocks with items
+    /// (or just blocks, when all blocks are included).
+    /// The cut always happens at a block boundary, hence a client can resume
+    /// from the block following the last returned one. The first block is returned
+    /// even if it alone exceeds the item limit, unless the plan disallows that.
+    fn execute_output(
+        &self,
+        output_inputs: Vec<RowList>,
+        scan_bound: Option<ScanBound>
+    ) -> anyhow::Result<Option<BlockWriter>> {
         use sqd_polars::prelude::*;
 
+        let block_bound = scan_bound.as_ref().map(|bound| bound.last_block);
+
         let rows = output_inputs
             .into_par_iter()
             .enumerate()
@@ -277,7 +437,7 @@ impl<'a> PlanExecution<'a> {
                     .chunk
                     .scan_table(output.table)?
                     .with_row_selection(maybe_row_selection)
-                    .with_predicate(self.get_block_number_predicate(idx))
+                    .with_predicate(self.get_block_number_predicate(idx, block_bound))
                     .with_row_index(true)
                     .with_column(output.key[0])
                     .with_columns(output.weight_columns.iter().copied())
@@ -367,15 +527,91 @@ impl<'a> PlanExecution<'a> {
             .select([col("block_number"), col("weight").cum_sum(false)])
             .collect()?;
 
-        let mut selected_blocks = package_weight
+        let weight_selected_blocks = package_weight
             .clone()
             .lazy()
             .filter(col("weight").lt_eq(lit(20 * 1024 * 1024)))
             .select([col("block_number")])
             .collect()?;
 
-        if selected_blocks.shape().0 == 0 {
-            selected_blocks = package_weight.head(Some(1))
+        let mut selected_blocks = weight_selected_blocks.clone().lazy();
+
+        let first_block = package_weight
+            .column("block_number")?
+            .cast(&DataType::UInt64)?
+            .u64()?
+            .get(0)
+            .unwrap();
+        let mut first_block_overflows = false;
+
+        if let Some(max_items) = self.plan.max_items {
+            for df in rows.iter().skip(1).filter(|df| !df.is_empty()) {
+                let overflow = df
+                    .clone()
+                    .lazy()
+                    .group_by([col("block_number")])
+                    .agg([col("row_index").count().cast(DataType::UInt64).alias("items")])
+                    .sort(["block_number"], SortMultipleOptions::default())
+                    .filter(col("items").cum_sum(false).gt(lit(max_items as u64)))
+                    .select([min("block_number")])
+                    .collect()?;
+
+                let overflow_block = overflow.column("block_number")?.cast(&DataType::UInt64)?.u64()?.get(0);
+
+                if let Some(block_number) = overflow_block {
+                    first_block_overflows |= block_number == first_block;
+                    selected_blocks = selected_blocks.filter(col("block_number").lt(lit(block_number)));
+                }
+            }
+        }
+
+        // Blocks, that count against `max_blocks`. The boundary blocks of the chunk,
+        // which are always present in the output, are not counted unless they have items.
+        let counted_blocks = if self.plan.has_limit() {
+            let block_numbers = if self.plan.include_all_blocks {
+                vec![header_rows.clone().lazy().select([col("block_number")])]
+            } else {
+                rows.iter()
+                    .skip(1)
+                    .filter(|df| !df.is_empty())
+                    .map(|df| df.clone().lazy().select([col("block_number")]))
+                    .collect()
+            };
+            let mut counted_blocks = Vec::new();
+            for df in block_numbers {
+                let df = df.collect()?;
+                let numbers = df.column("block_number")?.cast(&DataType::UInt64)?;
+                counted_blocks.extend(numbers.u64()?.into_no_null_iter());
+            }
+            counted_blocks.sort_unstable();
+            counted_blocks.dedup();
+            counted_blocks
+        } else {
+            Vec::new()
+        };
+
+        if let Some(max_blocks) = self.plan.max_blocks {
+            if counted_blocks.len() > max_blocks {
+                let bound = counted_blocks[max_blocks - 1];
+                selected_blocks = selected_blocks.filter(col("block_number").lt_eq(lit(bound)));
+            }
+        }
+
+        let mut selected_blocks = selected_blocks.collect()?;
+
+        // The limit cut the output, if it dropped blocks, that otherwise would have been returned,
+        // or if scans dropped rows and the output reaches the scan bound.
+        let num_weighted_blocks = package_weight.height() - package_weight.column("weight")?.null_count();
+        let limit_reached = selected_blocks.height() < weight_selected_blocks.height()
+            || scan_bound.as_ref().map_or(false, |bound| {
+                bound.has_rows_beyond && weight_selected_blocks.height() == num_weighted_blocks
+            });
+
+        if selected_blocks.height() == 0 {
+            if first_block_overflows && !self.plan.allow_first_block_overflow {
+                return Ok(Some(BlockWriter::empty(true)));
+            }
+            selected_blocks = package_weight.head(Some(1)).select(["block_number"])?
         }
 
         let last_block: BlockNumber = selected_blocks
@@ -429,8 +665,12 @@ impl<'a> PlanExecution<'a> {
                 Ok(())
             })?;
 
+        let num_counted_blocks = counted_blocks.partition_point(|block_number| *block_number <= last_block);
+
         Ok(Some(BlockWriter::new(
-            data_items_mutex.into_inner().into_iter().flatten().collect()
+            data_items_mutex.into_inner().into_iter().flatten().collect(),
+            num_counted_blocks,
+            limit_reached
         )))
     }
 
@@ -438,9 +678,29 @@ impl<'a> PlanExecution<'a> {
         self.plan.outputs.iter().position(|o| o.table == table).unwrap()
     }
 
-    fn get_block_number_predicate(&self, output_idx: usize) -> Option<RowPredicateRef> {
+    fn get_block_number_predicate(
+        &self,
+        output_idx: usize,
+        block_bound: Option<BlockNumber>
+    ) -> Option<RowPredicateRef> {
         let column = self.plan.outputs[output_idx].key[0];
-        match (self.plan.first_block, self.plan.last_block) {
+        let last_block = match (self.plan.last_block, block_bound) {
+            (Some(lst), Some(bound)) => Some(lst.min(bound)),
+            (lst, bound) => lst.or(bound)
+        };
+        self.block_range_predicate_with_last(column, last_block)
+    }
+
+    fn block_range_predicate(&self, column: Name) -> Option<RowPredicateRef> {
+        self.block_range_predicate_with_last(column, self.plan.last_block)
+    }
+
+    fn block_range_predicate_with_last(
+        &self,
+        column: Name,
+        last_block: Option<BlockNumber>
+    ) -> Option<RowPredicateRef> {
+        match (self.plan.first_block, last_block) {
             (Some(fst), Some(lst)) => Some(col_between(column, fst, lst)),
             (None, Some(lst)) => Some(col_lt_eq(column, lst)),
             (Some(fst), None) => Some(col_gt_eq(column, fst)),
@@ -457,7 +717,9 @@ pub struct PlanBuilder {
     include_all_blocks: bool,
     parent_block_hash: Option<String>,
     first_block: Option<BlockNumber>,
-    last_block: Option<BlockNumber>
+    last_block: Option<BlockNumber>,
+    max_blocks: Option<usize>,
+    max_items: Option<usize>
 }
 
 impl PlanBuilder {
@@ -481,7 +743,9 @@ impl PlanBuilder {
             include_all_blocks: false,
             parent_block_hash: None,
             first_block: None,
-            last_block: None
+            last_block: None,
+            max_blocks: None,
+            max_items: None
         }
     }
 
@@ -523,6 +787,21 @@ impl PlanBuilder {
         self
     }
 
+    /// Limits the number of blocks in a single result.
+    pub fn set_max_blocks(&mut self, max_blocks: impl Into<Option<usize>>) -> &mut Self {
+        self.max_blocks = max_blocks.into();
+        self
+    }
+
+    /// Limits the number of items of each table in a single result.
+    ///
+    /// The result is cut at block boundaries, so only the first block
+    /// might contain more items than that.
+    pub fn set_max_items(&mut self, max_items: impl Into<Option<usize>>) -> &mut Self {
+        self.max_items = max_items.into();
+        self
+    }
+
     pub fn build(mut self) -> Plan {
         self.simplify();
         self.set_output_weights();
@@ -534,7 +813,10 @@ impl PlanBuilder {
             include_all_blocks: self.include_all_blocks,
             parent_block_hash: self.parent_block_hash,
             first_block: self.first_block,
-            last_block: self.last_block
+            last_block: self.last_block,
+            max_blocks: self.max_blocks,
+            max_items: self.max_items,
+            allow_first_block_overflow: true
         }
     }
 
@@ -772,6 +1054,35 @@ impl PlanBuilder {
     }
 }
 
+/// Upper bound for the last block of a limited result, established by scans
+struct ScanBound {
+    last_block: BlockNumber,
+    /// Whether some of the matched rows were dropped for being beyond the bound
+    has_rows_beyond: bool
+}
+
+/// Computes the last block, that might be included into a limited result,
+/// given the (unsorted) block numbers of the matching items of a single table.
+fn limit_bound(
+    mut block_numbers: Vec<BlockNumber>,
+    max_items: Option<usize>,
+    max_blocks: Option<usize>
+) -> Option<BlockNumber> {
+    block_numbers.sort_unstable();
+
+    let items_bound = max_items.and_then(|n| block_numbers.get(n).copied());
+
+    let blocks_bound = max_blocks.and_then(|n| {
+        block_numbers.dedup();
+        block_numbers.get(n.saturating_sub(1)).copied()
+    });
+
+    match (items_bound, blocks_bound) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b)
+    }
+}
+
 fn remove_elements<T>(vec: &mut Vec<T>, remove_mask: &[bool]) {
     let mut idx = 0;
     vec.retain(|_| {
@@ -1002,6 +1313,30 @@ mod tests {
         assert_eq!(owning_scan.table, "logs");
     }
 
+    /// The items bound is the block of the first item, that doesn't fit into the limit.
+    /// Everything before it can be returned, and the block itself is the worst case
+    /// when the first block alone exceeds the limit.
+    #[test]
+    fn limit_bound_by_items() {
+        assert_eq!(limit_bound(vec![7, 5, 5, 6, 8], Some(3), None), Some(7));
+        assert_eq!(limit_bound(vec![5, 5, 5, 6], Some(1), None), Some(5));
+        assert_eq!(limit_bound(vec![5, 6], Some(2), None), None);
+    }
+
+    #[test]
+    fn limit_bound_by_blocks() {
+        assert_eq!(limit_bound(vec![5, 5, 6, 9, 9, 10], None, Some(3)), Some(9));
+        assert_eq!(limit_bound(vec![5, 5, 6], None, Some(1)), Some(5));
+        assert_eq!(limit_bound(vec![5, 5, 6], None, Some(3)), None);
+        assert_eq!(limit_bound(vec![], Some(1), Some(1)), None);
+    }
+
+    #[test]
+    fn limit_bound_takes_the_lowest_of_both() {
+        assert_eq!(limit_bound(vec![1, 2, 2, 2, 3, 4], Some(2), Some(3)), Some(2));
+        assert_eq!(limit_bound(vec![1, 2, 3, 4, 5, 6], Some(4), Some(2)), Some(2));
+    }
+
     /// Direct guard test: assemble a PlanBuilder whose scan owns a relation
     /// with a mismatched input_table and confirm the invariant assertion fires.
     /// This is the safety net that turns every future planning code path into
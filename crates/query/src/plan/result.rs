@@ -2,7 +2,7 @@ use std::fmt::{Display, Formatter};
 
 use anyhow::{anyhow, Context};
 use arrow::{
-    array::{AsArray, PrimitiveArray, RecordBatch, StructArray},
+    array::{Array, AsArray, PrimitiveArray, RecordBatch, StructArray},
     datatypes::{DataType, UInt64Type}
 };
 use sqd_primitives::BlockRef;
@@ -20,7 +20,9 @@ use crate::{
 };
 
 pub(super) struct DataItem {
+    name: String,
     prop: Vec<u8>,
+    records: Vec<RecordBatch>,
     block_numbers: Vec<PrimitiveArray<UInt64Type>>,
     encoders: Vec<EncoderObject>,
     order: Vec<Position>,
@@ -56,15 +58,17 @@ impl DataItem {
         let size = records.iter().map(|b| b.get_array_memory_size()).sum();
 
         let encoders = records
-            .into_iter()
+            .iter()
             .map(|b| {
-                let struct_array = StructArray::from(b);
+                let struct_array = StructArray::from(b.clone());
                 exp.eval(&struct_array)
             })
             .collect::<Result<Vec<_>, _>>()?;
 
         Ok(Self {
+            name: name.to_string(),
             prop: make_object_prop(name),
+            records,
             block_numbers,
             encoders,
             order,
@@ -82,6 +86,28 @@ impl DataItem {
         self.order.get(idx).map(|pos| self.block_numbers[pos.0].value(pos.1))
     }
 
+    /// Gather all not yet written rows into a single record batch, preserving the output order
+    fn take_record_batch(&mut self) -> anyhow::Result<Option<RecordBatch>> {
+        let indices = &self.order[self.pos..];
+        if indices.is_empty() {
+            return Ok(None);
+        }
+
+        let schema = self.records[0].schema();
+
+        let columns = (0..schema.fields().len())
+            .map(|i| {
+                let arrays: Vec<&dyn Array> = self.records.iter().map(|b| b.column(i).as_ref()).collect();
+                arrow::compute::interleave(&arrays, indices)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        self.pos = self.order.len();
+
+        let batch = RecordBatch::try_new(schema, columns)?;
+        Ok(Some(batch))
+    }
+
     fn write_header(&mut self, out: &mut Vec<u8>) {
         out.extend_from_slice(b"\"header\":");
         let pos = self.order[self.pos];
@@ -118,13 +144,28 @@ impl DataItem {
 }
 
 pub struct BlockWriter {
-    items: Vec<DataItem>
+    items: Vec<DataItem>,
+    num_counted_blocks: usize,
+    limit_reached: bool
 }
 
 impl BlockWriter {
-    pub(super) fn new(items: Vec<DataItem>) -> Self {
+    /// Result without blocks, when the first block doesn't fit into the limit of the plan
+    pub(super) fn empty(limit_reached: bool) -> Self {
+        Self {
+            items: Vec::new(),
+            num_counted_blocks: 0,
+            limit_reached
+        }
+    }
+
+    pub(super) fn new(items: Vec<DataItem>, num_counted_blocks: usize, limit_reached: bool) -> Self {
         assert!(items.len() > 0 && items[0].is_block_header);
-        Self { items }
+        Self {
+            items,
+            num_counted_blocks,
+            limit_reached
+        }
     }
 
     pub fn data_size(&self) -> usize {
@@ -132,13 +173,41 @@ impl BlockWriter {
     }
 
     pub fn num_blocks(&self) -> usize {
-        self.items[0].order.len()
+        self.items.first().map_or(0, |header| header.order.len())
+    }
+
+    /// Number of blocks, that count against the block limit of the plan.
+    ///
+    /// These are the blocks with items, or all blocks, when the plan includes all blocks.
+    /// Always zero for plans without a limit.
+    pub fn num_counted_blocks(&self) -> usize {
+        self.num_counted_blocks
+    }
+
+    /// The largest number of items among the tables of the result
+    pub fn max_num_items(&self) -> usize {
+        self.items
+            .iter()
+            .skip(1)
+            .map(|item| item.order.len())
+            .max()
+            .unwrap_or(0)
     }
 
+    /// Whether the result was cut short by the item or block limit of the plan.
+    ///
+    /// When it is, there is more matching data after the last block,
+    /// and the limit is the only reason it was not included.
+    pub fn limit_reached(&self) -> bool {
+        self.limit_reached
+    }
+
+    /// Panics, when the result has no blocks
     pub fn first_block(&self) -> BlockNumber {
         self.items[0].get_block_at(0).unwrap()
     }
 
+    /// Panics, when the result has no blocks
     pub fn last_block(&self) -> BlockNumber {
         self.items[0].get_block_at(self.num_blocks() - 1).unwrap()
     }
@@ -149,7 +218,9 @@ impl BlockWriter {
     }
 
     pub fn has_next_block(&self) -> bool {
-        self.items[0].get_current_block().is_some()
+        self.items
+            .first()
+            .map_or(false, |header| header.get_current_block().is_some())
     }
 
     pub fn write_next_block(&mut self, out: &mut Vec<u8>) {
@@ -161,6 +232,20 @@ impl BlockWriter {
         }
         json_close(b'}', out)
     }
+
+    /// Take all remaining blocks as `(item name, record batch)` pairs, one per non-empty item.
+    ///
+    /// Block headers always come first. Rows within each batch follow the same order
+    /// as in the JSON output.
+    pub fn take_record_batches(&mut self) -> anyhow::Result<Vec<(&str, RecordBatch)>> {
+        let mut batches = Vec::with_capacity(self.items.len());
+        for item in self.items.iter_mut() {
+            if let Some(batch) = item.take_record_batch()? {
+                batches.push((item.name.as_str(), batch))
+            }
+        }
+        Ok(batches)
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -3,8 +3,8 @@ use std::sync::LazyLock;
 use serde::{Deserialize, Serialize};
 
 use super::util::{
-    compile_plan, ensure_block_range, ensure_item_count, field_selection, item_field_selection, request,
-    PredicateBuilder
+    compile_plan, ensure_block_range, ensure_item_count, ensure_limit, field_selection, item_field_selection, request,
+    PredicateBuilder, ResultLimit
 };
 use crate::{
     json::{exp::Exp, lang::*},
@@ -206,6 +206,7 @@ request! {
         pub prevout_script_pub_key_address: Option<Vec<Bytes>>,
         pub prevout_script_pub_key_type: Option<Vec<String>>,
         pub prevout_generated: Option<bool>,
+        pub exclude: Option<InputExclusion>,
         pub transaction: bool,
         pub transaction_inputs: bool,
         pub transaction_outputs: bool,
@@ -224,6 +225,9 @@ impl InputRequest {
             self.prevout_script_pub_key_type.as_deref()
         );
         p.col_eq("prevout_generated", self.prevout_generated);
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -251,10 +255,33 @@ impl InputRequest {
     }
 }
 
+request! {
+    pub struct InputExclusion {
+        pub r#type: Option<Vec<String>>,
+        pub prevout_script_pub_key_address: Option<Vec<Bytes>>,
+        pub prevout_script_pub_key_type: Option<Vec<String>>,
+    }
+}
+
+impl InputExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("type", self.r#type.as_deref());
+        p.col_not_in_list(
+            "prevout_script_pub_key_address",
+            self.prevout_script_pub_key_address.as_deref()
+        );
+        p.col_not_in_list(
+            "prevout_script_pub_key_type",
+            self.prevout_script_pub_key_type.as_deref()
+        );
+    }
+}
+
 request! {
     pub struct OutputRequest {
         pub script_pub_key_address: Option<Vec<Bytes>>,
         pub script_pub_key_type: Option<Vec<String>>,
+        pub exclude: Option<OutputExclusion>,
         pub transaction: bool,
         pub transaction_inputs: bool,
         pub transaction_outputs: bool,
@@ -265,6 +292,9 @@ impl OutputRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("script_pub_key_address", self.script_pub_key_address.as_deref());
         p.col_in_list("script_pub_key_type", self.script_pub_key_type.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -292,6 +322,20 @@ impl OutputRequest {
     }
 }
 
+request! {
+    pub struct OutputExclusion {
+        pub script_pub_key_address: Option<Vec<Bytes>>,
+        pub script_pub_key_type: Option<Vec<String>>,
+    }
+}
+
+impl OutputExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("script_pub_key_address", self.script_pub_key_address.as_deref());
+        p.col_not_in_list("script_pub_key_type", self.script_pub_key_type.as_deref());
+    }
+}
+
 request! {
     pub struct BitcoinQuery {
         pub from_block: BlockNumber,
@@ -299,6 +343,7 @@ request! {
         pub to_block: Option<BlockNumber>,
         pub fields: FieldSelection,
         pub include_all_blocks: bool,
+        pub limit: Option<ResultLimit>,
         pub transactions: Vec<TransactionRequest>,
         pub inputs: Vec<InputRequest>,
         pub outputs: Vec<OutputRequest>,
@@ -308,6 +353,7 @@ request! {
 impl BitcoinQuery {
     pub fn validate(&self) -> anyhow::Result<()> {
         ensure_block_range!(self);
+        ensure_limit!(self);
         ensure_item_count!(self, transactions, inputs, outputs);
         Ok(())
     }
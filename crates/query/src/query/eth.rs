@@ -7,8 +7,8 @@ use crate::{
     json::{exp::Exp, lang::*},
     plan::{ScanBuilder, TableSet},
     query::util::{
-        compile_plan, ensure_block_range, ensure_item_count, field_selection, item_field_selection, request,
-        PredicateBuilder
+        compile_plan, ensure_block_range, ensure_item_count, ensure_limit, field_selection, item_field_selection,
+        request, PredicateBuilder, ResultLimit
     },
     BlockNumber, Plan
 };
@@ -471,6 +471,7 @@ request! {
         pub sighash: Option<Vec<Bytes>>,
         pub first_nonce: Option<u64>,
         pub last_nonce: Option<u64>,
+        pub exclude: Option<TransactionExclusion>,
         pub logs: bool,
         pub traces: bool,
         pub state_diffs: bool,
@@ -484,6 +485,9 @@ impl TransactionRequest {
         p.col_in_list("sighash", to_lowercase_list(&self.sighash));
         p.col_gt_eq("nonce", self.first_nonce);
         p.col_lt_eq("nonce", self.last_nonce);
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -511,6 +515,22 @@ impl TransactionRequest {
     }
 }
 
+request! {
+    pub struct TransactionExclusion {
+        pub from: Option<Vec<Bytes>>,
+        pub to: Option<Vec<Bytes>>,
+        pub sighash: Option<Vec<Bytes>>,
+    }
+}
+
+impl TransactionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("from", to_lowercase_list(&self.from));
+        p.col_not_in_list("to", to_lowercase_list(&self.to));
+        p.col_not_in_list("sighash", to_lowercase_list(&self.sighash));
+    }
+}
+
 request! {
     pub struct LogRequest {
         pub address: Option<Vec<Bytes>>,
@@ -518,6 +538,7 @@ request! {
         pub topic1: Option<Vec<Bytes>>,
         pub topic2: Option<Vec<Bytes>>,
         pub topic3: Option<Vec<Bytes>>,
+        pub exclude: Option<LogExclusion>,
         pub transaction: bool,
         pub transaction_traces: bool,
         pub transaction_logs: bool,
@@ -532,6 +553,9 @@ impl LogRequest {
         p.col_in_list("topic1", to_lowercase_list(&self.topic1));
         p.col_in_list("topic2", to_lowercase_list(&self.topic2));
         p.col_in_list("topic3", to_lowercase_list(&self.topic3));
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -566,6 +590,26 @@ impl LogRequest {
     }
 }
 
+request! {
+    pub struct LogExclusion {
+        pub address: Option<Vec<Bytes>>,
+        pub topic0: Option<Vec<Bytes>>,
+        pub topic1: Option<Vec<Bytes>>,
+        pub topic2: Option<Vec<Bytes>>,
+        pub topic3: Option<Vec<Bytes>>,
+    }
+}
+
+impl LogExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("address", to_lowercase_list(&self.address));
+        p.col_not_in_list("topic0", to_lowercase_list(&self.topic0));
+        p.col_not_in_list("topic1", to_lowercase_list(&self.topic1));
+        p.col_not_in_list("topic2", to_lowercase_list(&self.topic2));
+        p.col_not_in_list("topic3", to_lowercase_list(&self.topic3));
+    }
+}
+
 request! {
     pub struct TraceRequest {
         pub r#type: Option<Vec<String>>,
@@ -582,6 +626,7 @@ request! {
         pub suicide_balance_non_zero: bool,
         pub reward_author: Option<Vec<Bytes>>,
         pub reward_value_non_zero: bool,
+        pub exclude: Option<TraceExclusion>,
         pub transaction: bool,
         pub transaction_logs: bool,
         pub transaction_traces: bool,
@@ -620,6 +665,9 @@ impl TraceRequest {
         if self.reward_value_non_zero {
             p.col_gt_eq("reward_value", Some("0x1"));
         }
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -653,11 +701,45 @@ impl TraceRequest {
     }
 }
 
+request! {
+    pub struct TraceExclusion {
+        pub r#type: Option<Vec<String>>,
+        pub create_from: Option<Vec<Bytes>>,
+        pub create_result_address: Option<Vec<Bytes>>,
+        pub call_from: Option<Vec<Bytes>>,
+        pub call_to: Option<Vec<Bytes>>,
+        pub call_sighash: Option<Vec<Bytes>>,
+        pub call_call_type: Option<Vec<String>>,
+        pub suicide_address: Option<Vec<Bytes>>,
+        pub suicide_refund_address: Option<Vec<Bytes>>,
+        pub reward_author: Option<Vec<Bytes>>,
+    }
+}
+
+impl TraceExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("type", self.r#type.as_deref());
+        p.col_not_in_list("create_from", to_lowercase_list(&self.create_from));
+        p.col_not_in_list("create_result_address", to_lowercase_list(&self.create_result_address));
+        p.col_not_in_list("call_from", to_lowercase_list(&self.call_from));
+        p.col_not_in_list("call_to", to_lowercase_list(&self.call_to));
+        p.col_not_in_list("call_sighash", to_lowercase_list(&self.call_sighash));
+        p.col_not_in_list("call_type", self.call_call_type.as_deref());
+        p.col_not_in_list("suicide_address", to_lowercase_list(&self.suicide_address));
+        p.col_not_in_list(
+            "suicide_refund_address",
+            to_lowercase_list(&self.suicide_refund_address)
+        );
+        p.col_not_in_list("reward_author", to_lowercase_list(&self.reward_author));
+    }
+}
+
 request! {
     pub struct StateDiffRequest {
         pub address: Option<Vec<Bytes>>,
         pub key: Option<Vec<Bytes>>,
         pub kind: Option<Vec<String>>,
+        pub exclude: Option<StateDiffExclusion>,
         pub transaction: bool,
     }
 }
@@ -667,6 +749,9 @@ impl StateDiffRequest {
         p.col_in_list("address", to_lowercase_list(&self.address));
         p.col_in_list("key", self.key.as_deref());
         p.col_in_list("kind", self.kind.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -680,6 +765,22 @@ impl StateDiffRequest {
     }
 }
 
+request! {
+    pub struct StateDiffExclusion {
+        pub address: Option<Vec<Bytes>>,
+        pub key: Option<Vec<Bytes>>,
+        pub kind: Option<Vec<String>>,
+    }
+}
+
+impl StateDiffExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("address", to_lowercase_list(&self.address));
+        p.col_not_in_list("key", self.key.as_deref());
+        p.col_not_in_list("kind", self.kind.as_deref());
+    }
+}
+
 request! {
     pub struct EthQuery {
         pub from_block: BlockNumber,
@@ -687,6 +788,7 @@ request! {
         pub to_block: Option<BlockNumber>,
         pub fields: FieldSelection,
         pub include_all_blocks: bool,
+        pub limit: Option<ResultLimit>,
         pub transactions: Vec<TransactionRequest>,
         pub logs: Vec<LogRequest>,
         pub traces: Vec<TraceRequest>,
@@ -698,6 +800,7 @@ request! {
 impl EthQuery {
     pub fn validate(&self) -> anyhow::Result<()> {
         ensure_block_range!(self);
+        ensure_limit!(self);
         ensure_item_count!(self, transactions, logs, traces, statediffs);
         Ok(())
     }
@@ -3,8 +3,8 @@ use std::sync::LazyLock;
 use serde::{Deserialize, Serialize};
 
 use super::util::{
-    compile_plan, ensure_block_range, ensure_item_count, field_selection, item_field_selection, request,
-    PredicateBuilder
+    compile_plan, ensure_block_range, ensure_item_count, ensure_limit, field_selection, item_field_selection, request,
+    PredicateBuilder, ResultLimit
 };
 use crate::{
     json::{exp::Exp, lang::*},
@@ -413,7 +413,7 @@ item_field_selection! {
             contract.add("balanceRoot", prop("contract_balance_root", Exp::Value));
         }
         if this.contract_state_root {
-            contract.add("stateRoot", prop("state_root", Exp::Value));
+            contract.add("stateRoot", prop("contract_state_root", Exp::Value));
         }
 
         let mut change = base.clone();
@@ -465,6 +465,7 @@ request! {
     pub struct ReceiptRequest {
         pub r#type: Option<Vec<String>>,
         pub contract: Option<Vec<Bytes>>,
+        pub exclude: Option<ReceiptExclusion>,
         pub transaction: bool,
     }
 }
@@ -473,6 +474,9 @@ impl ReceiptRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("receipt_type", self.r#type.clone());
         p.col_in_list("contract", self.contract.clone());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -486,9 +490,24 @@ impl ReceiptRequest {
     }
 }
 
+request! {
+    pub struct ReceiptExclusion {
+        pub r#type: Option<Vec<String>>,
+        pub contract: Option<Vec<Bytes>>,
+    }
+}
+
+impl ReceiptExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("receipt_type", self.r#type.as_deref());
+        p.col_not_in_list("contract", self.contract.as_deref());
+    }
+}
+
 request! {
     pub struct TransactionRequest {
         pub r#type: Option<Vec<String>>,
+        pub exclude: Option<TransactionExclusion>,
         pub receipts: bool,
         pub inputs: bool,
         pub outputs: bool,
@@ -498,6 +517,9 @@ request! {
 impl TransactionRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("type", self.r#type.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -525,6 +547,18 @@ impl TransactionRequest {
     }
 }
 
+request! {
+    pub struct TransactionExclusion {
+        pub r#type: Option<Vec<String>>,
+    }
+}
+
+impl TransactionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("type", self.r#type.as_deref());
+    }
+}
+
 request! {
     pub struct InputRequest {
         pub r#type: Option<Vec<String>>,
@@ -533,6 +567,7 @@ request! {
         pub contract_contract: Option<Vec<Bytes>>,
         pub message_sender: Option<Vec<Bytes>>,
         pub message_recipient: Option<Vec<Bytes>>,
+        pub exclude: Option<InputExclusion>,
         pub transaction: bool,
     }
 }
@@ -542,9 +577,12 @@ impl InputRequest {
         p.col_in_list("type", self.r#type.as_deref());
         p.col_in_list("coin_owner", self.coin_owner.as_deref());
         p.col_in_list("coin_asset_id", self.coin_asset_id.as_deref());
-        p.col_in_list("contract_contract", self.contract_contract.as_deref());
+        p.col_in_list("contract_contract_id", self.contract_contract.as_deref());
         p.col_in_list("message_sender", self.message_sender.as_deref());
         p.col_in_list("message_recipient", self.message_recipient.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -558,9 +596,32 @@ impl InputRequest {
     }
 }
 
+request! {
+    pub struct InputExclusion {
+        pub r#type: Option<Vec<String>>,
+        pub coin_owner: Option<Vec<Bytes>>,
+        pub coin_asset_id: Option<Vec<Bytes>>,
+        pub contract_contract: Option<Vec<Bytes>>,
+        pub message_sender: Option<Vec<Bytes>>,
+        pub message_recipient: Option<Vec<Bytes>>,
+    }
+}
+
+impl InputExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("type", self.r#type.as_deref());
+        p.col_not_in_list("coin_owner", self.coin_owner.as_deref());
+        p.col_not_in_list("coin_asset_id", self.coin_asset_id.as_deref());
+        p.col_not_in_list("contract_contract_id", self.contract_contract.as_deref());
+        p.col_not_in_list("message_sender", self.message_sender.as_deref());
+        p.col_not_in_list("message_recipient", self.message_recipient.as_deref());
+    }
+}
+
 request! {
     pub struct OutputRequest {
         pub r#type: Option<Vec<String>>,
+        pub exclude: Option<OutputExclusion>,
         pub transaction: bool,
     }
 }
@@ -568,6 +629,9 @@ request! {
 impl OutputRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("type", self.r#type.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -581,6 +645,18 @@ impl OutputRequest {
     }
 }
 
+request! {
+    pub struct OutputExclusion {
+        pub r#type: Option<Vec<String>>,
+    }
+}
+
+impl OutputExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("type", self.r#type.as_deref());
+    }
+}
+
 request! {
     pub struct FuelQuery {
         pub from_block: BlockNumber,
@@ -588,6 +664,7 @@ request! {
         pub to_block: Option<BlockNumber>,
         pub fields: FieldSelection,
         pub include_all_blocks: bool,
+        pub limit: Option<ResultLimit>,
         pub transactions: Vec<TransactionRequest>,
         pub receipts: Vec<ReceiptRequest>,
         pub inputs: Vec<InputRequest>,
@@ -598,6 +675,7 @@ request! {
 impl FuelQuery {
     pub fn validate(&self) -> anyhow::Result<()> {
         ensure_block_range!(self);
+        ensure_limit!(self);
         ensure_item_count!(self, transactions, receipts, inputs, outputs);
         Ok(())
     }
@@ -6,8 +6,8 @@ use crate::{
     json::{exp::Exp, lang::*},
     plan::{ScanBuilder, TableSet},
     query::util::{
-        compile_plan, ensure_block_range, ensure_item_count, field_selection, item_field_selection, request,
-        PredicateBuilder
+        compile_plan, ensure_block_range, ensure_item_count, ensure_limit, field_selection, item_field_selection,
+        request, PredicateBuilder, ResultLimit
     },
     BlockNumber, Plan
 };
@@ -101,6 +101,7 @@ request! {
         pub cloid: Option<Vec<Bytes>>,
         pub fee_token: Option<Vec<String>>,
         pub builder: Option<Vec<Bytes>>,
+        pub exclude: Option<FillExclusion>,
     }
 }
 
@@ -112,11 +113,36 @@ impl FillRequest {
         p.col_in_list("cloid", self.cloid.as_deref());
         p.col_in_list("fee_token", self.fee_token.as_deref());
         p.col_in_list("builder", self.builder.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, _scan: &mut ScanBuilder) {}
 }
 
+request! {
+    pub struct FillExclusion {
+        pub user: Option<Vec<Bytes>>,
+        pub coin: Option<Vec<String>>,
+        pub dir: Option<Vec<String>>,
+        pub cloid: Option<Vec<Bytes>>,
+        pub fee_token: Option<Vec<String>>,
+        pub builder: Option<Vec<Bytes>>,
+    }
+}
+
+impl FillExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("user", self.user.as_deref());
+        p.col_not_in_list("coin", self.coin.as_deref());
+        p.col_not_in_list("dir", self.dir.as_deref());
+        p.col_not_in_list("cloid", self.cloid.as_deref());
+        p.col_not_in_list("fee_token", self.fee_token.as_deref());
+        p.col_not_in_list("builder", self.builder.as_deref());
+    }
+}
+
 request! {
     pub struct HyperliquidFillsQuery {
         pub from_block: BlockNumber,
@@ -124,6 +150,7 @@ request! {
         pub to_block: Option<BlockNumber>,
         pub fields: FieldSelection,
         pub include_all_blocks: bool,
+        pub limit: Option<ResultLimit>,
         pub fills: Vec<FillRequest>,
     }
 }
@@ -131,6 +158,7 @@ request! {
 impl HyperliquidFillsQuery {
     pub fn validate(&self) -> anyhow::Result<()> {
         ensure_block_range!(self);
+        ensure_limit!(self);
         ensure_item_count!(self, fills);
         Ok(())
     }
@@ -7,8 +7,8 @@ use crate::{
     json::{exp::Exp, lang::*},
     plan::{ScanBuilder, TableSet},
     query::util::{
-        compile_plan, ensure_block_range, ensure_item_count, field_selection, item_field_selection, request,
-        PredicateBuilder
+        compile_plan, ensure_block_range, ensure_item_count, ensure_limit, field_selection, item_field_selection,
+        request, PredicateBuilder, ResultLimit
     },
     BlockNumber, Plan
 };
@@ -95,6 +95,7 @@ request! {
         pub user: Option<Vec<Bytes>>,
         pub vault_address: Option<Vec<Bytes>>,
         pub status: Option<Status>,
+        pub exclude: Option<ActionExclusion>,
     }
 }
 
@@ -110,11 +111,30 @@ impl ActionRequest {
                 Status::Err => "err"
             })
         );
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, _scan: &mut ScanBuilder) {}
 }
 
+request! {
+    pub struct ActionExclusion {
+        pub action_type: Option<Vec<String>>,
+        pub user: Option<Vec<Bytes>>,
+        pub vault_address: Option<Vec<Bytes>>,
+    }
+}
+
+impl ActionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("action_type", self.action_type.as_deref());
+        p.col_not_in_list("user", self.user.as_deref());
+        p.col_not_in_list("vault_address", self.vault_address.as_deref());
+    }
+}
+
 request! {
     pub struct OrderActionRequest {
         pub contains_asset: Option<Vec<AssetIndex>>,
@@ -122,6 +142,7 @@ request! {
         pub user: Option<Vec<Bytes>>,
         pub vault_address: Option<Vec<Bytes>>,
         pub status: Option<Status>,
+        pub exclude: Option<OrderActionExclusion>,
     }
 }
 
@@ -138,17 +159,39 @@ impl OrderActionRequest {
                 Status::Err => "err"
             })
         );
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, _scan: &mut ScanBuilder) {}
 }
 
+request! {
+    pub struct OrderActionExclusion {
+        pub contains_asset: Option<Vec<AssetIndex>>,
+        pub contains_cloid: Option<Vec<Bytes>>,
+        pub user: Option<Vec<Bytes>>,
+        pub vault_address: Option<Vec<Bytes>>,
+    }
+}
+
+impl OrderActionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_primitive_list_contains_none::<UInt32Type>("order_asset", self.contains_asset.as_deref());
+        p.col_string_list_contains_none("order_cloid", self.contains_cloid.as_deref());
+        p.col_not_in_list("user", self.user.as_deref());
+        p.col_not_in_list("vault_address", self.vault_address.as_deref());
+    }
+}
+
 request! {
     pub struct CancelActionRequest {
         pub contains_asset: Option<Vec<AssetIndex>>,
         pub user: Option<Vec<Bytes>>,
         pub vault_address: Option<Vec<Bytes>>,
         pub status: Option<Status>,
+        pub exclude: Option<CancelActionExclusion>,
     }
 }
 
@@ -164,11 +207,30 @@ impl CancelActionRequest {
                 Status::Err => "err"
             })
         );
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, _scan: &mut ScanBuilder) {}
 }
 
+request! {
+    pub struct CancelActionExclusion {
+        pub contains_asset: Option<Vec<AssetIndex>>,
+        pub user: Option<Vec<Bytes>>,
+        pub vault_address: Option<Vec<Bytes>>,
+    }
+}
+
+impl CancelActionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_primitive_list_contains_none::<UInt32Type>("cancel_asset", self.contains_asset.as_deref());
+        p.col_not_in_list("user", self.user.as_deref());
+        p.col_not_in_list("vault_address", self.vault_address.as_deref());
+    }
+}
+
 request! {
     pub struct CancelByCloidActionRequest {
         pub contains_asset: Option<Vec<AssetIndex>>,
@@ -176,6 +238,7 @@ request! {
         pub user: Option<Vec<Bytes>>,
         pub vault_address: Option<Vec<Bytes>>,
         pub status: Option<Status>,
+        pub exclude: Option<CancelByCloidActionExclusion>,
     }
 }
 
@@ -192,11 +255,32 @@ impl CancelByCloidActionRequest {
                 Status::Err => "err"
             })
         );
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, _scan: &mut ScanBuilder) {}
 }
 
+request! {
+    pub struct CancelByCloidActionExclusion {
+        pub contains_asset: Option<Vec<AssetIndex>>,
+        pub contains_cloid: Option<Vec<Bytes>>,
+        pub user: Option<Vec<Bytes>>,
+        pub vault_address: Option<Vec<Bytes>>,
+    }
+}
+
+impl CancelByCloidActionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_primitive_list_contains_none::<UInt32Type>("asset", self.contains_asset.as_deref());
+        p.col_string_list_contains_none("cloid", self.contains_cloid.as_deref());
+        p.col_not_in_list("user", self.user.as_deref());
+        p.col_not_in_list("vault_address", self.vault_address.as_deref());
+    }
+}
+
 request! {
     pub struct BatchModifyActionRequest {
         pub contains_asset: Option<Vec<AssetIndex>>,
@@ -204,6 +288,7 @@ request! {
         pub user: Option<Vec<Bytes>>,
         pub vault_address: Option<Vec<Bytes>>,
         pub status: Option<Status>,
+        pub exclude: Option<BatchModifyActionExclusion>,
     }
 }
 
@@ -220,11 +305,32 @@ impl BatchModifyActionRequest {
                 Status::Err => "err"
             })
         );
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, _scan: &mut ScanBuilder) {}
 }
 
+request! {
+    pub struct BatchModifyActionExclusion {
+        pub contains_asset: Option<Vec<AssetIndex>>,
+        pub contains_cloid: Option<Vec<Bytes>>,
+        pub user: Option<Vec<Bytes>>,
+        pub vault_address: Option<Vec<Bytes>>,
+    }
+}
+
+impl BatchModifyActionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_primitive_list_contains_none::<UInt32Type>("batch_modify_asset", self.contains_asset.as_deref());
+        p.col_string_list_contains_none("batch_modify_cloid", self.contains_cloid.as_deref());
+        p.col_not_in_list("user", self.user.as_deref());
+        p.col_not_in_list("vault_address", self.vault_address.as_deref());
+    }
+}
+
 request! {
     pub struct HyperliquidReplicaCmdsQuery {
         pub from_block: BlockNumber,
@@ -232,6 +338,7 @@ request! {
         pub to_block: Option<BlockNumber>,
         pub fields: FieldSelection,
         pub include_all_blocks: bool,
+        pub limit: Option<ResultLimit>,
         pub actions: Vec<ActionRequest>,
         pub order_actions: Vec<OrderActionRequest>,
         pub cancel_actions: Vec<CancelActionRequest>,
@@ -243,6 +350,7 @@ request! {
 impl HyperliquidReplicaCmdsQuery {
     pub fn validate(&self) -> anyhow::Result<()> {
         ensure_block_range!(self);
+        ensure_limit!(self);
         ensure_item_count!(self, actions);
         Ok(())
     }
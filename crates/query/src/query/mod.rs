@@ -12,6 +12,8 @@ pub mod substrate;
 pub mod tron;
 mod util;
 
+pub use util::ResultLimit;
+
 #[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
 #[serde(tag = "type")]
 pub enum Query {
@@ -77,6 +79,20 @@ impl Query {
         .map(|s| s.as_str())
     }
 
+    pub fn set_parent_block_hash(&mut self, hash: impl Into<Option<String>>) {
+        let hash = hash.into();
+        match self {
+            Query::Bitcoin(q) => q.parent_block_hash = hash,
+            Query::Eth(q) => q.parent_block_hash = hash,
+            Query::Solana(q) => q.parent_block_hash = hash,
+            Query::Substrate(q) => q.parent_block_hash = hash,
+            Query::Fuel(q) => q.parent_block_hash = hash,
+            Query::HyperliquidFills(q) => q.parent_block_hash = hash,
+            Query::HyperliquidReplicaCmds(q) => q.parent_block_hash = hash,
+            Query::Tron(q) => q.parent_block_hash = hash
+        }
+    }
+
     pub fn first_block(&self) -> BlockNumber {
         match self {
             Query::Bitcoin(q) => q.from_block,
@@ -5,8 +5,8 @@ use arrow::array::{ArrayRef, FixedSizeBinaryBuilder, UInt16Array, UInt32Array, U
 use serde::{Deserialize, Serialize};
 
 use super::util::{
-    check_hex, compile_plan, ensure_block_range, ensure_item_count, field_selection, item_field_selection, parse_hex,
-    parse_static_hex, request, PredicateBuilder
+    check_hex, compile_plan, ensure_block_range, ensure_item_count, ensure_limit, field_selection,
+    item_field_selection, parse_hex, parse_static_hex, request, PredicateBuilder, ResultLimit
 };
 use crate::{
     json::{exp::Exp, lang::*},
@@ -318,6 +318,7 @@ request! {
         pub a14: Option<Vec<Bytes>>,
         pub a15: Option<Vec<Bytes>>,
         pub is_committed: Option<bool>,
+        pub exclude: Option<InstructionExclusion>,
         pub transaction: bool,
         pub transaction_balances: bool,
         pub transaction_token_balances: bool,
@@ -386,6 +387,9 @@ impl InstructionRequest {
         p.col_in_list("a14", self.a14.as_deref());
         p.col_in_list("a15", self.a15.as_deref());
         p.col_eq("is_committed", self.is_committed);
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn discriminator_predicate(&self, p: &mut PredicateBuilder) {
@@ -521,10 +525,55 @@ impl InstructionRequest {
     }
 }
 
+request! {
+    pub struct InstructionExclusion {
+        pub program_id: Option<Vec<Base58Bytes>>,
+        pub a0: Option<Vec<Bytes>>,
+        pub a1: Option<Vec<Bytes>>,
+        pub a2: Option<Vec<Bytes>>,
+        pub a3: Option<Vec<Bytes>>,
+        pub a4: Option<Vec<Bytes>>,
+        pub a5: Option<Vec<Bytes>>,
+        pub a6: Option<Vec<Bytes>>,
+        pub a7: Option<Vec<Bytes>>,
+        pub a8: Option<Vec<Bytes>>,
+        pub a9: Option<Vec<Bytes>>,
+        pub a10: Option<Vec<Bytes>>,
+        pub a11: Option<Vec<Bytes>>,
+        pub a12: Option<Vec<Bytes>>,
+        pub a13: Option<Vec<Bytes>>,
+        pub a14: Option<Vec<Bytes>>,
+        pub a15: Option<Vec<Bytes>>,
+    }
+}
+
+impl InstructionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("program_id", self.program_id.as_deref());
+        p.col_not_in_list("a0", self.a0.as_deref());
+        p.col_not_in_list("a1", self.a1.as_deref());
+        p.col_not_in_list("a2", self.a2.as_deref());
+        p.col_not_in_list("a3", self.a3.as_deref());
+        p.col_not_in_list("a4", self.a4.as_deref());
+        p.col_not_in_list("a5", self.a5.as_deref());
+        p.col_not_in_list("a6", self.a6.as_deref());
+        p.col_not_in_list("a7", self.a7.as_deref());
+        p.col_not_in_list("a8", self.a8.as_deref());
+        p.col_not_in_list("a9", self.a9.as_deref());
+        p.col_not_in_list("a10", self.a10.as_deref());
+        p.col_not_in_list("a11", self.a11.as_deref());
+        p.col_not_in_list("a12", self.a12.as_deref());
+        p.col_not_in_list("a13", self.a13.as_deref());
+        p.col_not_in_list("a14", self.a14.as_deref());
+        p.col_not_in_list("a15", self.a15.as_deref());
+    }
+}
+
 request! {
     pub struct TransactionRequest {
         pub fee_payer: Option<Vec<Bytes>>,
         pub mentions_account: Option<Vec<Bytes>>,
+        pub exclude: Option<TransactionExclusion>,
         pub instructions: bool,
         pub logs: bool,
         pub balances: bool,
@@ -536,6 +585,9 @@ impl TransactionRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("fee_payer", self.fee_payer.as_deref());
         p.bloom_filter("accounts_bloom", 64, 7, self.mentions_account.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -570,10 +622,23 @@ impl TransactionRequest {
     }
 }
 
+request! {
+    pub struct TransactionExclusion {
+        pub fee_payer: Option<Vec<Bytes>>,
+    }
+}
+
+impl TransactionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("fee_payer", self.fee_payer.as_deref());
+    }
+}
+
 request! {
     pub struct LogRequest {
         pub program_id: Option<Vec<Bytes>>,
         pub kind: Option<Vec<String>>,
+        pub exclude: Option<LogExclusion>,
         pub instruction: bool,
         pub transaction: bool,
     }
@@ -583,6 +648,9 @@ impl LogRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("program_id", self.program_id.as_deref());
         p.col_in_list("kind", self.kind.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -604,9 +672,24 @@ impl LogRequest {
     }
 }
 
+request! {
+    pub struct LogExclusion {
+        pub program_id: Option<Vec<Bytes>>,
+        pub kind: Option<Vec<String>>,
+    }
+}
+
+impl LogExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("program_id", self.program_id.as_deref());
+        p.col_not_in_list("kind", self.kind.as_deref());
+    }
+}
+
 request! {
     pub struct BalanceRequest {
         pub account: Option<Vec<Bytes>>,
+        pub exclude: Option<BalanceExclusion>,
         pub transaction: bool,
         pub transaction_instructions: bool,
     }
@@ -615,6 +698,9 @@ request! {
 impl BalanceRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("account", self.account.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -635,6 +721,18 @@ impl BalanceRequest {
     }
 }
 
+request! {
+    pub struct BalanceExclusion {
+        pub account: Option<Vec<Bytes>>,
+    }
+}
+
+impl BalanceExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("account", self.account.as_deref());
+    }
+}
+
 request! {
     pub struct TokenBalanceRequest {
         pub account: Option<Vec<Bytes>>,
@@ -644,6 +742,7 @@ request! {
         pub post_program_id: Option<Vec<Bytes>>,
         pub pre_owner: Option<Vec<Bytes>>,
         pub post_owner: Option<Vec<Bytes>>,
+        pub exclude: Option<TokenBalanceExclusion>,
         pub transaction: bool,
         pub transaction_instructions: bool,
         pub transaction_balances: bool,
@@ -660,6 +759,9 @@ impl TokenBalanceRequest {
         p.col_in_list("post_program_id", self.post_program_id.as_deref());
         p.col_in_list("pre_owner", self.pre_owner.as_deref());
         p.col_in_list("post_owner", self.post_owner.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -694,20 +796,60 @@ impl TokenBalanceRequest {
     }
 }
 
+request! {
+    pub struct TokenBalanceExclusion {
+        pub account: Option<Vec<Bytes>>,
+        pub pre_mint: Option<Vec<Bytes>>,
+        pub post_mint: Option<Vec<Bytes>>,
+        pub pre_program_id: Option<Vec<Bytes>>,
+        pub post_program_id: Option<Vec<Bytes>>,
+        pub pre_owner: Option<Vec<Bytes>>,
+        pub post_owner: Option<Vec<Bytes>>,
+    }
+}
+
+impl TokenBalanceExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("account", self.account.as_deref());
+        p.col_not_in_list("pre_mint", self.pre_mint.as_deref());
+        p.col_not_in_list("post_mint", self.post_mint.as_deref());
+        p.col_not_in_list("pre_program_id", self.pre_program_id.as_deref());
+        p.col_not_in_list("post_program_id", self.post_program_id.as_deref());
+        p.col_not_in_list("pre_owner", self.pre_owner.as_deref());
+        p.col_not_in_list("post_owner", self.post_owner.as_deref());
+    }
+}
+
 request! {
     pub struct RewardRequest {
         pub pubkey: Option<Vec<Bytes>>,
+        pub exclude: Option<RewardExclusion>,
     }
 }
 
 impl RewardRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("pubkey", self.pubkey.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, _scan: &mut ScanBuilder) {}
 }
 
+request! {
+    pub struct RewardExclusion {
+        pub pubkey: Option<Vec<Bytes>>,
+    }
+}
+
+impl RewardExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("pubkey", self.pubkey.as_deref());
+    }
+}
+
 request! {
     pub struct SolanaQuery {
         pub from_block: BlockNumber,
@@ -715,6 +857,7 @@ request! {
         pub to_block: Option<BlockNumber>,
         pub fields: FieldSelection,
         pub include_all_blocks: bool,
+        pub limit: Option<ResultLimit>,
         pub transactions: Vec<TransactionRequest>,
         pub instructions: Vec<InstructionRequest>,
         pub logs: Vec<LogRequest>,
@@ -727,6 +870,7 @@ request! {
 impl SolanaQuery {
     pub fn validate(&self) -> anyhow::Result<()> {
         ensure_block_range!(self);
+        ensure_limit!(self);
         ensure_item_count!(
             self,
             transactions,
@@ -7,8 +7,8 @@ use crate::{
     plan::{Plan, ScanBuilder, TableSet},
     primitives::BlockNumber,
     query::util::{
-        compile_plan, ensure_block_range, ensure_item_count, field_selection, item_field_selection, request,
-        to_lowercase_list, PredicateBuilder
+        compile_plan, ensure_block_range, ensure_item_count, ensure_limit, field_selection, item_field_selection,
+        request, to_lowercase_list, PredicateBuilder, ResultLimit
     }
 };
 
@@ -145,6 +145,7 @@ type Bytes = String;
 request! {
     pub struct EventRequest {
         pub name: Option<Vec<String>>,
+        pub exclude: Option<EventExclusion>,
         pub extrinsic: bool,
         pub call: bool,
         pub stack: bool,
@@ -154,6 +155,9 @@ request! {
 impl EventRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("name", self.name.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -183,9 +187,22 @@ impl EventRequest {
     }
 }
 
+request! {
+    pub struct EventExclusion {
+        pub name: Option<Vec<String>>,
+    }
+}
+
+impl EventExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("name", self.name.as_deref());
+    }
+}
+
 request! {
     pub struct CallRequest {
         pub name: Option<Vec<String>>,
+        pub exclude: Option<CallExclusion>,
         pub subcalls: bool,
         pub extrinsic: bool,
         pub stack: bool,
@@ -196,6 +213,9 @@ request! {
 impl CallRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("name", self.name.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -225,6 +245,18 @@ impl CallRequest {
     }
 }
 
+request! {
+    pub struct CallExclusion {
+        pub name: Option<Vec<String>>,
+    }
+}
+
+impl CallExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("name", self.name.as_deref());
+    }
+}
+
 request! {
     pub struct EvmLogRequest {
         pub address: Option<Vec<Bytes>>,
@@ -232,6 +264,7 @@ request! {
         pub topic1: Option<Vec<Bytes>>,
         pub topic2: Option<Vec<Bytes>>,
         pub topic3: Option<Vec<Bytes>>,
+        pub exclude: Option<EvmLogExclusion>,
         pub extrinsic: bool,
         pub call: bool,
         pub stack: bool,
@@ -246,6 +279,9 @@ impl EvmLogRequest {
         p.col_in_list("_evm_log_topic1", to_lowercase_list(&self.topic1));
         p.col_in_list("_evm_log_topic2", to_lowercase_list(&self.topic2));
         p.col_in_list("_evm_log_topic3", to_lowercase_list(&self.topic3));
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -275,6 +311,26 @@ impl EvmLogRequest {
     }
 }
 
+request! {
+    pub struct EvmLogExclusion {
+        pub address: Option<Vec<Bytes>>,
+        pub topic0: Option<Vec<Bytes>>,
+        pub topic1: Option<Vec<Bytes>>,
+        pub topic2: Option<Vec<Bytes>>,
+        pub topic3: Option<Vec<Bytes>>,
+    }
+}
+
+impl EvmLogExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("_evm_log_address", to_lowercase_list(&self.address));
+        p.col_not_in_list("_evm_log_topic0", to_lowercase_list(&self.topic0));
+        p.col_not_in_list("_evm_log_topic1", to_lowercase_list(&self.topic1));
+        p.col_not_in_list("_evm_log_topic2", to_lowercase_list(&self.topic2));
+        p.col_not_in_list("_evm_log_topic3", to_lowercase_list(&self.topic3));
+    }
+}
+
 request! {
     pub struct EthereumTransactionRequest {
         pub to: Option<Vec<Bytes>>,
@@ -498,6 +554,7 @@ request! {
         pub to_block: Option<BlockNumber>,
         pub fields: FieldSelection,
         pub include_all_blocks: bool,
+        pub limit: Option<ResultLimit>,
         pub calls: Vec<CallRequest>,
         pub events: Vec<EventRequest>,
         pub evm_logs: Vec<EvmLogRequest>,
@@ -512,6 +569,7 @@ request! {
 impl SubstrateQuery {
     pub fn validate(&self) -> anyhow::Result<()> {
         ensure_block_range!(self);
+        ensure_limit!(self);
         ensure_item_count!(
             self,
             calls,
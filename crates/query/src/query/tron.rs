@@ -7,8 +7,8 @@ use crate::{
     plan::{Plan, ScanBuilder, TableSet},
     primitives::BlockNumber,
     query::util::{
-        compile_plan, ensure_block_range, ensure_item_count, field_selection, item_field_selection, request,
-        to_lowercase_list, PredicateBuilder
+        compile_plan, ensure_block_range, ensure_item_count, ensure_limit, field_selection, item_field_selection,
+        request, to_lowercase_list, PredicateBuilder, ResultLimit
     }
 };
 
@@ -192,6 +192,7 @@ type Bytes = String;
 request! {
     pub struct TransactionRequest {
         pub r#type: Option<Vec<String>>,
+        pub exclude: Option<TransactionExclusion>,
         pub logs: bool,
         pub internal_transactions: bool,
     }
@@ -200,6 +201,9 @@ request! {
 impl TransactionRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("type", self.r#type.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -220,10 +224,23 @@ impl TransactionRequest {
     }
 }
 
+request! {
+    pub struct TransactionExclusion {
+        pub r#type: Option<Vec<String>>,
+    }
+}
+
+impl TransactionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("type", self.r#type.as_deref());
+    }
+}
+
 request! {
     pub struct TransferTransactionRequest {
         pub owner: Option<Vec<Bytes>>,
         pub to: Option<Vec<Bytes>>,
+        pub exclude: Option<TransferTransactionExclusion>,
         pub logs: bool,
         pub internal_transactions: bool,
     }
@@ -234,6 +251,9 @@ impl TransferTransactionRequest {
         p.col_eq("type", Some("TransferContract"));
         p.col_in_list("_transfer_contract_owner", to_lowercase_list(&self.owner));
         p.col_in_list("_transfer_contract_to", to_lowercase_list(&self.to));
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -254,11 +274,26 @@ impl TransferTransactionRequest {
     }
 }
 
+request! {
+    pub struct TransferTransactionExclusion {
+        pub owner: Option<Vec<Bytes>>,
+        pub to: Option<Vec<Bytes>>,
+    }
+}
+
+impl TransferTransactionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("_transfer_contract_owner", to_lowercase_list(&self.owner));
+        p.col_not_in_list("_transfer_contract_to", to_lowercase_list(&self.to));
+    }
+}
+
 request! {
     pub struct TransferAssetTransactionRequest {
         pub owner: Option<Vec<Bytes>>,
         pub to: Option<Vec<Bytes>>,
         pub asset: Option<Vec<String>>,
+        pub exclude: Option<TransferAssetTransactionExclusion>,
         pub logs: bool,
         pub internal_transactions: bool,
     }
@@ -270,6 +305,9 @@ impl TransferAssetTransactionRequest {
         p.col_in_list("_transfer_asset_contract_owner", to_lowercase_list(&self.owner));
         p.col_in_list("_transfer_asset_contract_to", to_lowercase_list(&self.to));
         p.col_in_list("_transfer_asset_contract_asset", self.asset.as_deref());
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -290,11 +328,28 @@ impl TransferAssetTransactionRequest {
     }
 }
 
+request! {
+    pub struct TransferAssetTransactionExclusion {
+        pub owner: Option<Vec<Bytes>>,
+        pub to: Option<Vec<Bytes>>,
+        pub asset: Option<Vec<String>>,
+    }
+}
+
+impl TransferAssetTransactionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("_transfer_asset_contract_owner", to_lowercase_list(&self.owner));
+        p.col_not_in_list("_transfer_asset_contract_to", to_lowercase_list(&self.to));
+        p.col_not_in_list("_transfer_asset_contract_asset", self.asset.as_deref());
+    }
+}
+
 request! {
     pub struct TriggerSmartContractTransactionRequest {
         pub owner: Option<Vec<Bytes>>,
         pub contract: Option<Vec<Bytes>>,
         pub sighash: Option<Vec<Bytes>>,
+        pub exclude: Option<TriggerSmartContractTransactionExclusion>,
         pub logs: bool,
         pub internal_transactions: bool,
     }
@@ -306,6 +361,9 @@ impl TriggerSmartContractTransactionRequest {
         p.col_in_list("_trigger_smart_contract_owner", to_lowercase_list(&self.owner));
         p.col_in_list("_trigger_smart_contract_contract", to_lowercase_list(&self.contract));
         p.col_in_list("_trigger_smart_contract_sighash", to_lowercase_list(&self.sighash));
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -326,6 +384,22 @@ impl TriggerSmartContractTransactionRequest {
     }
 }
 
+request! {
+    pub struct TriggerSmartContractTransactionExclusion {
+        pub owner: Option<Vec<Bytes>>,
+        pub contract: Option<Vec<Bytes>>,
+        pub sighash: Option<Vec<Bytes>>,
+    }
+}
+
+impl TriggerSmartContractTransactionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("_trigger_smart_contract_owner", to_lowercase_list(&self.owner));
+        p.col_not_in_list("_trigger_smart_contract_contract", to_lowercase_list(&self.contract));
+        p.col_not_in_list("_trigger_smart_contract_sighash", to_lowercase_list(&self.sighash));
+    }
+}
+
 request! {
     pub struct LogRequest {
         pub address: Option<Vec<Bytes>>,
@@ -333,6 +407,7 @@ request! {
         pub topic1: Option<Vec<Bytes>>,
         pub topic2: Option<Vec<Bytes>>,
         pub topic3: Option<Vec<Bytes>>,
+        pub exclude: Option<LogExclusion>,
         pub transaction: bool,
     }
 }
@@ -344,6 +419,9 @@ impl LogRequest {
         p.col_in_list("topic1", to_lowercase_list(&self.topic1));
         p.col_in_list("topic2", to_lowercase_list(&self.topic2));
         p.col_in_list("topic3", to_lowercase_list(&self.topic3));
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -357,10 +435,31 @@ impl LogRequest {
     }
 }
 
+request! {
+    pub struct LogExclusion {
+        pub address: Option<Vec<Bytes>>,
+        pub topic0: Option<Vec<Bytes>>,
+        pub topic1: Option<Vec<Bytes>>,
+        pub topic2: Option<Vec<Bytes>>,
+        pub topic3: Option<Vec<Bytes>>,
+    }
+}
+
+impl LogExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("address", to_lowercase_list(&self.address));
+        p.col_not_in_list("topic0", to_lowercase_list(&self.topic0));
+        p.col_not_in_list("topic1", to_lowercase_list(&self.topic1));
+        p.col_not_in_list("topic2", to_lowercase_list(&self.topic2));
+        p.col_not_in_list("topic3", to_lowercase_list(&self.topic3));
+    }
+}
+
 request! {
     pub struct InternalTransactionRequest {
         pub caller: Option<Vec<Bytes>>,
         pub transfer_to: Option<Vec<Bytes>>,
+        pub exclude: Option<InternalTransactionExclusion>,
         pub transaction: bool,
     }
 }
@@ -369,6 +468,9 @@ impl InternalTransactionRequest {
     fn predicate(&self, p: &mut PredicateBuilder) {
         p.col_in_list("caller_address", to_lowercase_list(&self.caller));
         p.col_in_list("transfer_to_address", to_lowercase_list(&self.transfer_to));
+        if let Some(exclude) = &self.exclude {
+            exclude.predicate(p);
+        }
     }
 
     fn relations(&self, scan: &mut ScanBuilder) {
@@ -382,6 +484,20 @@ impl InternalTransactionRequest {
     }
 }
 
+request! {
+    pub struct InternalTransactionExclusion {
+        pub caller: Option<Vec<Bytes>>,
+        pub transfer_to: Option<Vec<Bytes>>,
+    }
+}
+
+impl InternalTransactionExclusion {
+    fn predicate(&self, p: &mut PredicateBuilder) {
+        p.col_not_in_list("caller_address", to_lowercase_list(&self.caller));
+        p.col_not_in_list("transfer_to_address", to_lowercase_list(&self.transfer_to));
+    }
+}
+
 request! {
     pub struct TronQuery {
         pub from_block: BlockNumber,
@@ -389,6 +505,7 @@ request! {
         pub to_block: Option<BlockNumber>,
         pub fields: FieldSelection,
         pub include_all_blocks: bool,
+        pub limit: Option<ResultLimit>,
         pub transactions: Vec<TransactionRequest>,
         pub transfer_transactions: Vec<TransferTransactionRequest>,
         pub transfer_asset_transactions: Vec<TransferAssetTransactionRequest>,
@@ -401,6 +518,7 @@ request! {
 impl TronQuery {
     pub fn validate(&self) -> anyhow::Result<()> {
         ensure_block_range!(self);
+        ensure_limit!(self);
         ensure_item_count!(
             self,
             transactions,
@@ -1,12 +1,14 @@
 use std::hash::Hash;
 
 use arrow::{array::StringArray, datatypes::ArrowPrimitiveType};
+use serde::{Deserialize, Serialize};
 
 use crate::{
     primitives::Name,
     scan::{
-        and, bloom_filter, col_eq, col_gt_eq, col_in_list, col_lt_eq, col_primitive_list_contains_any,
-        col_string_list_contains_any, IntoArrowArray, IntoArrowScalar, RowPredicateRef
+        and, bloom_filter, col_eq, col_gt_eq, col_in_list, col_lt_eq, col_not_eq, col_not_in_list,
+        col_primitive_list_contains_any, col_primitive_list_contains_none, col_string_list_contains_any,
+        col_string_list_contains_none, IntoArrowArray, IntoArrowScalar, RowPredicateRef
     }
 };
 
@@ -103,6 +105,23 @@ macro_rules! ensure_item_count {
 }
 pub(crate) use ensure_item_count;
 
+macro_rules! ensure_limit {
+    ($query:ident) => {
+        if let Some(limit) = &$query.limit {
+            anyhow::ensure!(limit.blocks != Some(0), "\"limit.blocks\" must be positive");
+            anyhow::ensure!(limit.items != Some(0), "\"limit.items\" must be positive");
+        }
+    };
+}
+pub(crate) use ensure_limit;
+
+request! {
+    pub struct ResultLimit {
+        pub blocks: Option<usize>,
+        pub items: Option<usize>,
+    }
+}
+
 pub struct PredicateBuilder {
     conditions: Vec<RowPredicateRef>,
     is_never: bool
@@ -139,6 +158,30 @@ impl PredicateBuilder {
         self
     }
 
+    pub fn col_not_eq<T: IntoArrowScalar>(&mut self, name: Name, maybe_value: Option<T>) -> &mut Self {
+        if let Some(value) = maybe_value {
+            let predicate = col_not_eq(name, value);
+            self.conditions.push(predicate)
+        }
+        self
+    }
+
+    /// Rows with a null value in the column are kept, as with SQL `column IS NULL OR column NOT IN (...)`.
+    pub fn col_not_in_list<L>(&mut self, name: Name, maybe_list: Option<L>) -> &mut Self
+    where
+        L: IntoArrowArray
+    {
+        if let Some(list) = maybe_list {
+            let values = list.into_array();
+            // excluding nothing keeps everything
+            if values.len() > 0 {
+                let predicate = col_not_in_list(name, values);
+                self.conditions.push(predicate)
+            }
+        }
+        self
+    }
+
     pub fn col_gt_eq<T: IntoArrowScalar>(&mut self, name: Name, maybe_value: Option<T>) -> &mut Self {
         if let Some(value) = maybe_value {
             let predicate = col_gt_eq(name, value);
@@ -205,6 +248,30 @@ impl PredicateBuilder {
         self
     }
 
+    pub fn col_primitive_list_contains_none<T>(&mut self, name: Name, maybe_list: Option<&[T::Native]>) -> &mut Self
+    where
+        T: ArrowPrimitiveType,
+        T::Native: Eq + Hash
+    {
+        if let Some(list) = maybe_list {
+            if !list.is_empty() {
+                let predicate = col_primitive_list_contains_none::<T>(name, list);
+                self.conditions.push(predicate);
+            }
+        }
+        self
+    }
+
+    pub fn col_string_list_contains_none<S: AsRef<str>>(&mut self, name: Name, maybe_list: Option<&[S]>) -> &mut Self {
+        if let Some(list) = maybe_list {
+            if !list.is_empty() {
+                let predicate = col_string_list_contains_none(name, list);
+                self.conditions.push(predicate);
+            }
+        }
+        self
+    }
+
     pub fn is_never(&self) -> bool {
         self.is_never
     }
@@ -236,6 +303,10 @@ macro_rules! compile_plan {
         plan.set_parent_block_hash($this.parent_block_hash.clone());
         plan.set_first_block($this.from_block);
         plan.set_last_block($this.to_block);
+        if let Some(limit) = &$this.limit {
+            plan.set_max_blocks(limit.blocks);
+            plan.set_max_items(limit.items);
+        }
         $(
             plan.set_projection(stringify!($out), $fields);
         )*
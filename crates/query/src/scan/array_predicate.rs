@@ -25,6 +25,27 @@ pub trait ArrayPredicate: Sync + Send {
     fn evaluate_stats(&self, _stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
         bail!("Stats evaluation is not supported by this predicate")
     }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        false
+    }
+
+    /// Returns a mask of stats entries, that may contain items for which the predicate is false.
+    ///
+    /// Unset entries are guaranteed to hold only items matching the predicate (or nulls),
+    /// which is what allows to prune pages for negated predicates.
+    fn evaluate_negated_stats(&self, _stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
+        bail!("Negated stats evaluation is not supported by this predicate")
+    }
+
+    fn can_evaluate_index(&self) -> bool {
+        false
+    }
+
+    /// Returns a mask of index pages, that may contain items matching the predicate.
+    fn evaluate_index(&self, _index: &dyn ArrayIndex) -> anyhow::Result<BooleanArray> {
+        bail!("Index evaluation is not supported by this predicate")
+    }
 }
 
 #[derive(Clone)]
@@ -33,6 +54,18 @@ pub struct ArrayStats {
     pub max: ArrayRef
 }
 
+/// Inverted value index of an array, split into pages
+pub trait ArrayIndex: Sync + Send {
+    fn data_type(&self) -> &DataType;
+
+    fn num_pages(&self) -> usize;
+
+    /// Returns a mask of pages, that contain any of the (non-null) `values`.
+    ///
+    /// `values` must be of [`ArrayIndex::data_type()`].
+    fn lookup(&self, values: &dyn Array) -> anyhow::Result<BooleanArray>;
+}
+
 pub struct And {
     predicates: Vec<ArrayPredicateRef>
 }
@@ -71,6 +104,41 @@ impl ArrayPredicate for And {
         }
         Ok(result_mask)
     }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        self.predicates.iter().all(|p| p.can_evaluate_negated_stats())
+    }
+
+    fn evaluate_negated_stats(&self, stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
+        if self.predicates.len() == 0 {
+            return Ok(zero_mask(stats.min.len(), false));
+        }
+        let mut result_mask = self.predicates[0].evaluate_negated_stats(stats)?;
+        for i in 1..self.predicates.len() {
+            let m = self.predicates[i].evaluate_negated_stats(stats)?;
+            result_mask = arrow::compute::or(&result_mask, &m)?;
+        }
+        Ok(result_mask)
+    }
+
+    fn can_evaluate_index(&self) -> bool {
+        self.predicates.iter().any(|p| p.can_evaluate_index())
+    }
+
+    fn evaluate_index(&self, index: &dyn ArrayIndex) -> anyhow::Result<BooleanArray> {
+        let mut result_mask: Option<BooleanArray> = None;
+        for p in self.predicates.iter() {
+            if p.can_evaluate_index() {
+                let m = p.evaluate_index(index)?;
+                result_mask = Some(if let Some(prev) = result_mask {
+                    arrow::compute::and(&prev, &m)?
+                } else {
+                    m
+                })
+            }
+        }
+        Ok(result_mask.unwrap_or_else(|| zero_mask(index.num_pages(), true)))
+    }
 }
 
 pub struct Or {
@@ -111,6 +179,40 @@ impl ArrayPredicate for Or {
         }
         Ok(result_mask)
     }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        self.predicates.iter().any(|p| p.can_evaluate_negated_stats())
+    }
+
+    fn evaluate_negated_stats(&self, stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
+        // An item falsifies the disjunction only if it falsifies every member,
+        // so it is enough to intersect the masks we are able to compute.
+        let mut result_mask: Option<BooleanArray> = None;
+        for p in self.predicates.iter() {
+            if p.can_evaluate_negated_stats() {
+                let m = p.evaluate_negated_stats(stats)?;
+                result_mask = Some(if let Some(prev) = result_mask {
+                    arrow::compute::and(&prev, &m)?
+                } else {
+                    m
+                })
+            }
+        }
+        Ok(result_mask.unwrap_or_else(|| zero_mask(stats.min.len(), true)))
+    }
+
+    fn can_evaluate_index(&self) -> bool {
+        self.predicates.iter().all(|p| p.can_evaluate_index())
+    }
+
+    fn evaluate_index(&self, index: &dyn ArrayIndex) -> anyhow::Result<BooleanArray> {
+        let mut result_mask = zero_mask(index.num_pages(), false);
+        for p in self.predicates.iter() {
+            let m = p.evaluate_index(index)?;
+            result_mask = arrow::compute::or(&result_mask, &m)?;
+        }
+        Ok(result_mask)
+    }
 }
 
 pub fn or(predicates: Vec<ArrayPredicateRef>) -> ArrayPredicateRef {
@@ -121,6 +223,70 @@ pub fn or(predicates: Vec<ArrayPredicateRef>) -> ArrayPredicateRef {
     }
 }
 
+/// Kleene negation: null items stay null and thus are never selected, as with SQL `NOT`.
+pub struct Not {
+    predicate: ArrayPredicateRef
+}
+
+impl Not {
+    pub fn new(predicate: ArrayPredicateRef) -> Self {
+        Self { predicate }
+    }
+}
+
+impl ArrayPredicate for Not {
+    fn evaluate(&self, arr: &dyn Array) -> anyhow::Result<BooleanArray> {
+        let mask = self.predicate.evaluate(arr)?;
+        Ok(arrow::compute::not(&mask)?)
+    }
+
+    fn can_evaluate_stats(&self) -> bool {
+        self.predicate.can_evaluate_negated_stats()
+    }
+
+    fn evaluate_stats(&self, stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
+        self.predicate.evaluate_negated_stats(stats)
+    }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        self.predicate.can_evaluate_stats()
+    }
+
+    fn evaluate_negated_stats(&self, stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
+        self.predicate.evaluate_stats(stats)
+    }
+}
+
+/// Exclusion: selects items, that don't match the predicate, including nulls,
+/// as with SQL `item IS NULL OR NOT predicate`.
+///
+/// Stats don't tell, whether a page holds nulls, so they can't prune pages for an exclusion.
+pub struct NotOrNull {
+    predicate: ArrayPredicateRef
+}
+
+impl NotOrNull {
+    pub fn new(predicate: ArrayPredicateRef) -> Self {
+        Self { predicate }
+    }
+}
+
+impl ArrayPredicate for NotOrNull {
+    fn evaluate(&self, arr: &dyn Array) -> anyhow::Result<BooleanArray> {
+        let mask = self.predicate.evaluate(arr)?;
+        let matches = arrow::compute::prep_null_mask_filter(&mask);
+        Ok(arrow::compute::not(&matches)?)
+    }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        self.predicate.can_evaluate_stats()
+    }
+
+    fn evaluate_negated_stats(&self, stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
+        self.predicate.evaluate_stats(stats)
+    }
+}
+
 macro_rules! cast_scalar {
     ($value:ident, $scalar:expr, $arr:ident, Less: $less:literal, Greater: $greater:literal) => {
         let scalar = $scalar;
@@ -164,6 +330,32 @@ impl ArrayPredicate for Eq {
         let max_boundary = arrow::compute::kernels::cmp::lt_eq(value, &stats.max)?;
         Ok(arrow::compute::and(&min_boundary, &max_boundary)?)
     }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        true
+    }
+
+    fn evaluate_negated_stats(&self, stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
+        let min_array = &stats.min;
+        cast_scalar!(value, &self.value, min_array, Less: true, Greater: true);
+        let min_differs = arrow::compute::kernels::cmp::neq(value, min_array)?;
+        let max_differs = arrow::compute::kernels::cmp::neq(value, &stats.max)?;
+        Ok(arrow::compute::or(&min_differs, &max_differs)?)
+    }
+
+    fn can_evaluate_index(&self) -> bool {
+        true
+    }
+
+    fn evaluate_index(&self, index: &dyn ArrayIndex) -> anyhow::Result<BooleanArray> {
+        let cast_result = cast_scalar(&self.value, index.data_type())?;
+        let value = match &cast_result {
+            CastResult::Same => &self.value,
+            CastResult::Cast(value) => value,
+            CastResult::Less | CastResult::Greater => return Ok(zero_mask(index.num_pages(), false))
+        };
+        index.lookup(value.get().0)
+    }
 }
 
 /// value >= item
@@ -196,6 +388,17 @@ impl ArrayPredicate for GtEq {
         let result_mask = arrow::compute::kernels::cmp::gt_eq(value, min)?;
         Ok(result_mask)
     }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        true
+    }
+
+    fn evaluate_negated_stats(&self, stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
+        let max = &stats.max;
+        cast_scalar!(value, &self.value, max, Less: true, Greater: false);
+        let result_mask = arrow::compute::kernels::cmp::lt(value, max)?;
+        Ok(result_mask)
+    }
 }
 
 /// value <= item
@@ -228,6 +431,17 @@ impl ArrayPredicate for LtEq {
         let result_mask = arrow::compute::kernels::cmp::lt_eq(value, max)?;
         Ok(result_mask)
     }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        true
+    }
+
+    fn evaluate_negated_stats(&self, stats: &ArrayStats) -> anyhow::Result<BooleanArray> {
+        let min = &stats.min;
+        cast_scalar!(value, &self.value, min, Less: false, Greater: true);
+        let result_mask = arrow::compute::kernels::cmp::gt(value, min)?;
+        Ok(result_mask)
+    }
 }
 
 pub fn zero_mask(len: usize, is_set: bool) -> BooleanArray {
@@ -373,14 +587,15 @@ where
 }
 
 pub struct InList {
+    values: ArrayRef,
     list: sqd_polars::prelude::Series
 }
 
 impl InList {
     pub fn new<L: IntoArrowArray>(values: L) -> Self {
-        let arr = values.into_array();
-        let list = sqd_polars::arrow::array_series("value_list", &arr).unwrap();
-        Self { list }
+        let values = values.into_array();
+        let list = sqd_polars::arrow::array_series("value_list", &values).unwrap();
+        Self { values, list }
     }
 }
 
@@ -391,6 +606,25 @@ impl ArrayPredicate for InList {
         let mask = sqd_polars::arrow::polars_boolean_to_arrow_boolean(&polars_mask);
         Ok(mask)
     }
+
+    fn can_evaluate_index(&self) -> bool {
+        true
+    }
+
+    fn evaluate_index(&self, index: &dyn ArrayIndex) -> anyhow::Result<BooleanArray> {
+        if self.values.data_type() == index.data_type() {
+            return index.lookup(self.values.as_ref());
+        }
+        let values = cast_with_options(
+            &self.values,
+            index.data_type(),
+            &CastOptions {
+                safe: false,
+                ..CastOptions::default()
+            }
+        )?;
+        index.lookup(values.as_ref())
+    }
 }
 
 fn bitwise_and<const N: usize>(value: &[u8; N], other: &[u8; N]) -> [u8; N] {
@@ -590,6 +824,168 @@ impl ArrayPredicate for StringListContainsAny {
     }
 }
 
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{Array, ArrayRef, AsArray, BooleanArray, UInt64Array},
+        buffer::BooleanBuffer,
+        datatypes::DataType
+    };
+
+    use super::{And, ArrayIndex, ArrayPredicate, ArrayPredicateRef, ArrayStats, Eq, GtEq, InList, Not, NotOrNull, Or};
+
+    fn stats(pages: &[Option<(u64, u64)>]) -> ArrayStats {
+        let min: UInt64Array = pages.iter().map(|p| p.map(|p| p.0)).collect();
+        let max: UInt64Array = pages.iter().map(|p| p.map(|p| p.1)).collect();
+        ArrayStats {
+            min: Arc::new(min) as ArrayRef,
+            max: Arc::new(max) as ArrayRef
+        }
+    }
+
+    fn selected(mask: &BooleanArray) -> Vec<bool> {
+        (0..mask.len()).map(|i| mask.is_valid(i) && mask.value(i)).collect()
+    }
+
+    #[test]
+    fn not_keeps_nulls_unselected() {
+        let pred = Not::new(Arc::new(Eq::new(1u64)));
+        let arr = UInt64Array::from(vec![Some(1), Some(2), None]);
+        let mask = pred.evaluate(&arr).unwrap();
+        assert_eq!(selected(&mask), vec![false, true, false]);
+    }
+
+    #[test]
+    fn not_or_null_selects_nulls() {
+        let list: Vec<ArrayPredicateRef> = vec![Arc::new(Eq::new(1u64)), Arc::new(Eq::new(2u64))];
+        let pred = NotOrNull::new(Arc::new(Or::new(list)));
+        let arr = UInt64Array::from(vec![Some(1), Some(2), Some(3), None]);
+        let mask = pred.evaluate(&arr).unwrap();
+        assert_eq!(mask.null_count(), 0);
+        assert_eq!(selected(&mask), vec![false, false, true, true]);
+    }
+
+    #[test]
+    fn not_or_null_does_not_prune_pages() {
+        let pred = NotOrNull::new(Arc::new(Eq::new(1u64)));
+        assert!(!pred.can_evaluate_stats());
+        assert!(!pred.can_evaluate_index());
+        assert!(pred.can_evaluate_negated_stats());
+    }
+
+    #[test]
+    fn not_eq_prunes_only_single_value_pages() {
+        let pred = Not::new(Arc::new(Eq::new(1u64)));
+        assert!(pred.can_evaluate_stats());
+        let mask = pred
+            .evaluate_stats(&stats(&[Some((1, 1)), Some((1, 2)), Some((0, 1)), None]))
+            .unwrap();
+        assert_eq!(selected(&mask), vec![false, true, true, false]);
+    }
+
+    #[test]
+    fn not_in_list_prunes_pages_covered_by_the_list() {
+        let list: Vec<ArrayPredicateRef> = vec![Arc::new(Eq::new(1u64)), Arc::new(Eq::new(2u64))];
+        let pred = Not::new(Arc::new(Or::new(list)));
+        assert!(pred.can_evaluate_stats());
+        let mask = pred
+            .evaluate_stats(&stats(&[Some((1, 1)), Some((2, 2)), Some((1, 3))]))
+            .unwrap();
+        assert_eq!(selected(&mask), vec![false, false, true]);
+    }
+
+    #[test]
+    fn not_range_predicate() {
+        // not (5 >= item)
+        let pred = Not::new(Arc::new(GtEq::new(5u64)));
+        let mask = pred
+            .evaluate_stats(&stats(&[Some((1, 5)), Some((1, 6)), Some((7, 9))]))
+            .unwrap();
+        assert_eq!(selected(&mask), vec![false, true, true]);
+    }
+
+    /// Three pages: `a` in the first, `b` in the first two, `c` in the last one
+    struct TestIndex;
+
+    impl ArrayIndex for TestIndex {
+        fn data_type(&self) -> &DataType {
+            &DataType::Utf8
+        }
+
+        fn num_pages(&self) -> usize {
+            3
+        }
+
+        fn lookup(&self, values: &dyn Array) -> anyhow::Result<BooleanArray> {
+            let values = values.as_string::<i32>();
+            let contains = |value: &str| (0..values.len()).any(|i| values.is_valid(i) && values.value(i) == value);
+            let pages = [contains("a") || contains("b"), contains("b"), contains("c")];
+            Ok(BooleanArray::from(BooleanBuffer::from_iter(pages)))
+        }
+    }
+
+    #[test]
+    fn eq_selects_indexed_pages() {
+        let pred = Eq::new("b");
+        assert!(pred.can_evaluate_index());
+        assert_eq!(
+            selected(&pred.evaluate_index(&TestIndex).unwrap()),
+            vec![true, true, false]
+        );
+
+        let missing = Eq::new("z");
+        assert_eq!(
+            selected(&missing.evaluate_index(&TestIndex).unwrap()),
+            vec![false, false, false]
+        );
+    }
+
+    #[test]
+    fn in_list_and_or_unite_indexed_pages() {
+        let list = InList::new(vec!["a", "c", "z"]);
+        assert!(list.can_evaluate_index());
+        assert_eq!(
+            selected(&list.evaluate_index(&TestIndex).unwrap()),
+            vec![true, false, true]
+        );
+
+        let or = Or::new(vec![Arc::new(Eq::new("a")), Arc::new(Eq::new("c"))]);
+        assert_eq!(
+            selected(&or.evaluate_index(&TestIndex).unwrap()),
+            vec![true, false, true]
+        );
+    }
+
+    #[test]
+    fn index_can_not_prune_negations() {
+        let not = Not::new(Arc::new(Eq::new("a")));
+        assert!(!not.can_evaluate_index());
+
+        let or = Or::new(vec![Arc::new(Eq::new("a")), Arc::new(not)]);
+        assert!(!or.can_evaluate_index());
+
+        let and = And::new(vec![Arc::new(Eq::new("b")), Arc::new(Not::new(Arc::new(Eq::new("a"))))]);
+        assert!(and.can_evaluate_index());
+        assert_eq!(
+            selected(&and.evaluate_index(&TestIndex).unwrap()),
+            vec![true, true, false]
+        );
+    }
+
+    #[test]
+    fn double_negation_matches_original_stats() {
+        let stats = stats(&[Some((1, 1)), Some((2, 3)), Some((0, 1))]);
+        let pred = Eq::new(1u64);
+        let double = Not::new(Arc::new(Not::new(Arc::new(Eq::new(1u64)))));
+        assert_eq!(
+            selected(&double.evaluate_stats(&stats).unwrap()),
+            selected(&pred.evaluate_stats(&stats).unwrap())
+        );
+    }
+}
+
 #[cfg(feature = "_bench")]
 mod bench {
     use arrow::{array::FixedSizeBinaryArray, buffer::MutableBuffer};
@@ -45,10 +45,11 @@ impl ParquetFile {
         let io = MmapIO::open(&path)?;
 
         let metadata = ArrowReaderMetadata::load(&io, ArrowReaderOptions::new().with_page_index(true))?;
+        let metadata = Arc::new(ParquetMetadata::new(metadata, io.clone()));
 
         Ok(Self {
             io,
-            metadata: Arc::new(ParquetMetadata::new(metadata)),
+            metadata,
             table_name
         })
     }
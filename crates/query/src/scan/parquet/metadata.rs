@@ -1,5 +1,6 @@
 use std::{collections::HashMap, sync::Arc};
 
+use anyhow::ensure;
 use arrow::{
     array::{
         Array, ArrayBuilder, ArrayRef, AsArray, BinaryArray, BinaryBuilder, BooleanArray, BooleanBuilder, Int32Array,
@@ -10,12 +11,22 @@ use arrow::{
 };
 use parquet::{
     arrow::arrow_reader::ArrowReaderMetadata,
-    file::{page_index::index::Index, statistics::Statistics}
+    bloom_filter::Sbbf,
+    file::{
+        page_index::index::Index,
+        reader::{FileReader, RowGroupReader},
+        serialized_reader::{ReadOptionsBuilder, SerializedFileReader},
+        statistics::Statistics
+    }
 };
 
 use crate::{
     primitives::Name,
-    scan::row_predicate::{ColumnStats, RowStats}
+    scan::{
+        array_predicate::ArrayIndex,
+        parquet::io::MmapIO,
+        row_predicate::{ColumnIndex, ColumnStats, RowStats}
+    }
 };
 
 pub struct ParquetMetadata {
@@ -25,11 +36,11 @@ pub struct ParquetMetadata {
 }
 
 impl ParquetMetadata {
-    pub fn new(metadata: ArrowReaderMetadata) -> Self {
+    pub fn new(metadata: ArrowReaderMetadata, io: MmapIO) -> Self {
         let num_row_groups = metadata.metadata().num_row_groups();
         Self {
             metadata: metadata.clone(),
-            row_group_stats: RowGroupStats::new(metadata.clone()),
+            row_group_stats: RowGroupStats::new(metadata.clone(), io),
             page_stats: (0..num_row_groups)
                 .map(|i| PageStats::new(metadata.clone(), i))
                 .collect()
@@ -51,15 +62,19 @@ impl ParquetMetadata {
 
 struct RowGroupStats {
     metadata: ArrowReaderMetadata,
-    column_stats: parking_lot::Mutex<HashMap<Name, Option<ColumnStats>>>
+    io: MmapIO,
+    column_stats: parking_lot::Mutex<HashMap<Name, Option<ColumnStats>>>,
+    column_index: parking_lot::Mutex<HashMap<Name, Option<ColumnIndex>>>
 }
 
 impl RowGroupStats {
-    pub fn new(metadata: ArrowReaderMetadata) -> Self {
+    pub fn new(metadata: ArrowReaderMetadata, io: MmapIO) -> Self {
         let num_columns = metadata.parquet_schema().num_columns();
         Self {
             metadata,
-            column_stats: parking_lot::Mutex::new(HashMap::with_capacity(num_columns))
+            io,
+            column_stats: parking_lot::Mutex::new(HashMap::with_capacity(num_columns)),
+            column_index: parking_lot::Mutex::new(HashMap::new())
         }
     }
 }
@@ -74,6 +89,18 @@ impl RowStats for RowGroupStats {
 
         Ok(s.clone())
     }
+
+    fn get_column_index(&self, column: Name) -> anyhow::Result<Option<ColumnIndex>> {
+        let mut column_index = self.column_index.lock();
+
+        if let Some(index) = column_index.get(column) {
+            return Ok(index.clone());
+        }
+
+        let index = self.build_column_index(column)?;
+        column_index.insert(column, index.clone());
+        Ok(index)
+    }
 }
 
 impl RowGroupStats {
@@ -204,6 +231,113 @@ impl RowGroupStats {
     }
 }
 
+impl RowGroupStats {
+    /// Builds a row group index from the bloom filters of the column, if it has any
+    fn build_column_index(&self, column_name: Name) -> anyhow::Result<Option<ColumnIndex>> {
+        let Ok(arrow_col_idx) = self.metadata.schema().index_of(column_name) else {
+            return Ok(None);
+        };
+        let Some(parquet_col_idx) = find_primitive_column(&self.metadata, column_name) else {
+            return Ok(None);
+        };
+
+        let data_type = self.metadata.schema().field(arrow_col_idx).data_type();
+        if !can_check_bloom_filter(data_type) {
+            return Ok(None);
+        }
+
+        let row_groups = self.metadata.metadata().row_groups();
+        if row_groups
+            .iter()
+            .all(|rg| rg.column(parquet_col_idx).bloom_filter_offset().is_none())
+        {
+            return Ok(None);
+        }
+
+        let options = ReadOptionsBuilder::new().with_bloom_filter().build();
+        let reader = SerializedFileReader::new_with_options(self.io.clone(), options)?;
+
+        let mut offsets = UInt32Array::builder(row_groups.len() + 1);
+        let mut offset = 0u32;
+        offsets.append_value(0);
+
+        let mut filters = Vec::with_capacity(row_groups.len());
+        for (i, rg) in row_groups.iter().enumerate() {
+            filters.push(
+                reader
+                    .get_row_group(i)?
+                    .get_column_bloom_filter(parquet_col_idx)
+                    .cloned()
+            );
+            offset += rg.num_rows() as u32;
+            offsets.append_value(offset);
+        }
+
+        Ok(Some(ColumnIndex {
+            offsets: OffsetBuffer::new(offsets.finish().into_parts().1),
+            index: Arc::new(BloomFilterIndex {
+                data_type: data_type.clone(),
+                filters
+            })
+        }))
+    }
+}
+
+/// Row group bloom filters of a column.
+///
+/// Row groups without a bloom filter are assumed to contain any value.
+struct BloomFilterIndex {
+    data_type: DataType,
+    filters: Vec<Option<Sbbf>>
+}
+
+impl ArrayIndex for BloomFilterIndex {
+    fn data_type(&self) -> &DataType {
+        &self.data_type
+    }
+
+    fn num_pages(&self) -> usize {
+        self.filters.len()
+    }
+
+    fn lookup(&self, values: &dyn Array) -> anyhow::Result<BooleanArray> {
+        ensure!(
+            values.data_type() == &self.data_type,
+            "expected values of type {}, but got {}",
+            self.data_type,
+            values.data_type()
+        );
+        let mask = self.filters.iter().map(|filter| {
+            filter.as_ref().map_or(true, |filter| {
+                (0..values.len()).any(|i| values.is_valid(i) && check_bloom_filter(filter, values, i))
+            })
+        });
+        Ok(BooleanArray::from_iter(mask.map(Some)))
+    }
+}
+
+fn can_check_bloom_filter(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Utf8 | DataType::Binary | DataType::FixedSizeBinary(_) => true,
+        DataType::Int32 | DataType::Int64 | DataType::UInt32 | DataType::UInt64 => true,
+        _ => false
+    }
+}
+
+/// Checks the value in the way parquet hashes its physical representation
+fn check_bloom_filter(filter: &Sbbf, values: &dyn Array, i: usize) -> bool {
+    match values.data_type() {
+        DataType::Utf8 => filter.check(values.as_string::<i32>().value(i)),
+        DataType::Binary => filter.check(values.as_binary::<i32>().value(i)),
+        DataType::FixedSizeBinary(_) => filter.check(values.as_fixed_size_binary().value(i)),
+        DataType::Int32 => filter.check(&values.as_primitive::<Int32Type>().value(i)),
+        DataType::Int64 => filter.check(&values.as_primitive::<Int64Type>().value(i)),
+        DataType::UInt32 => filter.check(&(values.as_primitive::<UInt32Type>().value(i) as i32)),
+        DataType::UInt64 => filter.check(&(values.as_primitive::<UInt64Type>().value(i) as i64)),
+        ty => unreachable!("unexpected arrow type - {}", ty)
+    }
+}
+
 fn cast_stat_array(array: ArrayRef, target_type: &DataType) -> Option<ArrayRef> {
     if array.data_type() == target_type {
         return Some(array);
@@ -310,3 +444,92 @@ fn find_primitive_column(metadata: &ArrowReaderMetadata, name: Name) -> Option<u
     }
     None
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{RecordBatch, StringArray, UInt64Array},
+        datatypes::{DataType, Field, Schema}
+    };
+    use parquet::{
+        arrow::{
+            arrow_reader::{ArrowReaderMetadata, ArrowReaderOptions},
+            ArrowWriter
+        },
+        file::properties::WriterProperties
+    };
+
+    use super::ParquetMetadata;
+    use crate::scan::{
+        array_predicate::{ArrayPredicate, Eq, InList},
+        parquet::io::MmapIO
+    };
+
+    /// Three row groups of 100 rows, row group `g` holds addresses `0x{g}00..0x{g}99`
+    fn write_file(path: &std::path::Path) {
+        let schema = Arc::new(Schema::new(vec![
+            Field::new("block_number", DataType::UInt64, false),
+            Field::new("address", DataType::Utf8, true),
+        ]));
+        let props = WriterProperties::builder()
+            .set_max_row_group_size(100)
+            .set_column_bloom_filter_enabled("address".into(), true)
+            .build();
+        let file = std::fs::File::create(path).unwrap();
+        let mut writer = ArrowWriter::try_new(file, schema.clone(), Some(props)).unwrap();
+        for group in 0..3u64 {
+            let block_numbers = UInt64Array::from_iter_values((0..100).map(|i| group * 100 + i));
+            let addresses = StringArray::from_iter_values((0..100).map(|i| format!("0x{group}{i:02}")));
+            let batch =
+                RecordBatch::try_new(schema.clone(), vec![Arc::new(block_numbers), Arc::new(addresses)]).unwrap();
+            writer.write(&batch).unwrap();
+        }
+        writer.close().unwrap();
+    }
+
+    fn open(path: &std::path::Path) -> ParquetMetadata {
+        let io = MmapIO::open(path).unwrap();
+        let metadata = ArrowReaderMetadata::load(&io, ArrowReaderOptions::new()).unwrap();
+        ParquetMetadata::new(metadata, io)
+    }
+
+    #[test]
+    fn bloom_filters_select_row_groups() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.parquet");
+        write_file(&path);
+        let metadata = open(&path);
+
+        let index = metadata.row_group_stats().get_column_index("address").unwrap().unwrap();
+        assert_eq!(index.offsets.as_ref(), &[0, 100, 200, 300]);
+
+        let mask = Eq::new("0x105").evaluate_index(index.index.as_ref()).unwrap();
+        assert_eq!(mask.values().iter().collect::<Vec<_>>(), vec![false, true, false]);
+
+        let mask = InList::new(vec!["0x000", "0x299", "0x999"])
+            .evaluate_index(index.index.as_ref())
+            .unwrap();
+        assert_eq!(mask.values().iter().collect::<Vec<_>>(), vec![true, false, true]);
+    }
+
+    #[test]
+    fn columns_without_bloom_filters_have_no_index() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("logs.parquet");
+        write_file(&path);
+        let metadata = open(&path);
+
+        assert!(metadata
+            .row_group_stats()
+            .get_column_index("block_number")
+            .unwrap()
+            .is_none());
+        assert!(metadata
+            .row_group_stats()
+            .get_column_index("missing")
+            .unwrap()
+            .is_none());
+    }
+}
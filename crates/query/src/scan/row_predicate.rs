@@ -10,7 +10,7 @@ use crate::{
     primitives::{Name, RowRangeList},
     scan::{
         array_predicate,
-        array_predicate::{ArrayPredicateRef, ArrayStats}
+        array_predicate::{ArrayIndex, ArrayPredicateRef, ArrayStats}
     }
 };
 
@@ -28,10 +28,23 @@ pub trait RowPredicate: Sync + Send {
     fn evaluate_stats(&self, _stats: &dyn RowStats) -> anyhow::Result<Option<RowRangeList>> {
         Ok(None)
     }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        false
+    }
+
+    /// Returns row ranges, that may contain rows for which the predicate is false.
+    fn evaluate_negated_stats(&self, _stats: &dyn RowStats) -> anyhow::Result<Option<RowRangeList>> {
+        Ok(None)
+    }
 }
 
 pub trait RowStats {
     fn get_column_stats(&self, column: Name) -> anyhow::Result<Option<ColumnStats>>;
+
+    fn get_column_index(&self, _column: Name) -> anyhow::Result<Option<ColumnIndex>> {
+        Ok(None)
+    }
 }
 
 #[derive(Clone)]
@@ -41,6 +54,12 @@ pub struct ColumnStats {
     pub max: ArrayRef
 }
 
+#[derive(Clone)]
+pub struct ColumnIndex {
+    pub offsets: OffsetBuffer<u32>,
+    pub index: Arc<dyn ArrayIndex>
+}
+
 pub struct ColumnPredicate {
     column: [Name; 1],
     array_predicate: ArrayPredicateRef
@@ -69,30 +88,60 @@ impl RowPredicate for ColumnPredicate {
     }
 
     fn can_evaluate_stats(&self) -> bool {
-        self.array_predicate.can_evaluate_stats()
+        self.array_predicate.can_evaluate_stats() || self.array_predicate.can_evaluate_index()
     }
 
     fn evaluate_stats(&self, row_stats: &dyn RowStats) -> anyhow::Result<Option<RowRangeList>> {
+        // An index tells which pages hold a value, so for point lookups it beats min/max stats
+        if self.array_predicate.can_evaluate_index() {
+            if let Some(column_index) = row_stats.get_column_index(self.column[0])? {
+                let mask = self.array_predicate.evaluate_index(column_index.index.as_ref())?;
+                return Ok(Some(select_pages(&mask, &column_index.offsets)));
+            }
+        }
+        if !self.array_predicate.can_evaluate_stats() {
+            return Ok(None);
+        }
+        self.select_ranges(row_stats, |stats| self.array_predicate.evaluate_stats(stats))
+    }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        self.array_predicate.can_evaluate_negated_stats()
+    }
+
+    fn evaluate_negated_stats(&self, row_stats: &dyn RowStats) -> anyhow::Result<Option<RowRangeList>> {
+        self.select_ranges(row_stats, |stats| self.array_predicate.evaluate_negated_stats(stats))
+    }
+}
+
+impl ColumnPredicate {
+    fn select_ranges(
+        &self,
+        row_stats: &dyn RowStats,
+        eval: impl FnOnce(&ArrayStats) -> anyhow::Result<BooleanArray>
+    ) -> anyhow::Result<Option<RowRangeList>> {
         row_stats
             .get_column_stats(self.column[0])?
             .map(|column_stats| {
-                let mask = self.array_predicate.evaluate_stats(&ArrayStats {
+                let mask = eval(&ArrayStats {
                     min: column_stats.min.clone(),
                     max: column_stats.max.clone()
                 })?;
 
-                let offsets = &column_stats.offsets;
-
-                let ranges = (0..offsets.len() - 1)
-                    .filter(|&i| mask.value(i) && !mask.is_null(i))
-                    .map(|i| offsets[i]..offsets[i + 1]);
-
-                Ok(RowRangeList::seal(ranges))
+                Ok(select_pages(&mask, &column_stats.offsets))
             })
             .transpose()
     }
 }
 
+fn select_pages(mask: &BooleanArray, offsets: &OffsetBuffer<u32>) -> RowRangeList {
+    let ranges = (0..offsets.len() - 1)
+        .filter(|&i| mask.value(i) && !mask.is_null(i))
+        .map(|i| offsets[i]..offsets[i + 1]);
+
+    RowRangeList::seal(ranges)
+}
+
 pub struct AndPredicate {
     predicates: Vec<RowPredicateRef>,
     projection: Vec<Name>
@@ -141,6 +190,14 @@ impl RowPredicate for AndPredicate {
         }
         Ok(selection)
     }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        self.predicates.iter().all(|p| p.can_evaluate_negated_stats())
+    }
+
+    fn evaluate_negated_stats(&self, stats: &dyn RowStats) -> anyhow::Result<Option<RowRangeList>> {
+        union_selection(&self.predicates, stats, |p, stats| p.evaluate_negated_stats(stats))
+    }
 }
 
 pub struct OrPredicate {
@@ -177,26 +234,93 @@ impl RowPredicate for OrPredicate {
     }
 
     fn evaluate_stats(&self, stats: &dyn RowStats) -> anyhow::Result<Option<RowRangeList>> {
+        if !self.predicates.iter().all(|p| p.can_evaluate_stats()) {
+            return Ok(None);
+        }
+        union_selection(&self.predicates, stats, |p, stats| p.evaluate_stats(stats))
+    }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        self.predicates.iter().any(|p| p.can_evaluate_negated_stats())
+    }
+
+    fn evaluate_negated_stats(&self, stats: &dyn RowStats) -> anyhow::Result<Option<RowRangeList>> {
         let mut selection: Option<RowRangeList> = None;
         for p in self.predicates.iter() {
-            if p.can_evaluate_stats() {
-                if let Some(sel) = p.evaluate_stats(stats)? {
+            if p.can_evaluate_negated_stats() {
+                if let Some(sel) = p.evaluate_negated_stats(stats)? {
                     selection = Some(if let Some(prev) = selection {
-                        prev.union(&sel)
+                        prev.intersection(&sel)
                     } else {
                         sel
                     })
-                } else {
-                    return Ok(None);
                 }
-            } else {
-                return Ok(None);
             }
         }
         Ok(selection)
     }
 }
 
+pub struct NotPredicate {
+    predicate: RowPredicateRef
+}
+
+impl NotPredicate {
+    pub fn new(predicate: RowPredicateRef) -> Self {
+        Self { predicate }
+    }
+}
+
+impl RowPredicate for NotPredicate {
+    fn projection(&self) -> &[Name] {
+        self.predicate.projection()
+    }
+
+    fn evaluate(&self, batch: &RecordBatch) -> anyhow::Result<BooleanArray> {
+        let mask = self.predicate.evaluate(batch)?;
+        Ok(arrow::compute::not(&mask)?)
+    }
+
+    fn can_evaluate_stats(&self) -> bool {
+        self.predicate.can_evaluate_negated_stats()
+    }
+
+    fn evaluate_stats(&self, stats: &dyn RowStats) -> anyhow::Result<Option<RowRangeList>> {
+        self.predicate.evaluate_negated_stats(stats)
+    }
+
+    fn can_evaluate_negated_stats(&self) -> bool {
+        self.predicate.can_evaluate_stats()
+    }
+
+    fn evaluate_negated_stats(&self, stats: &dyn RowStats) -> anyhow::Result<Option<RowRangeList>> {
+        self.predicate.evaluate_stats(stats)
+    }
+}
+
+/// Unites selections of all given predicates.
+///
+/// Returns `None` if any of the predicates can't restrict the selection.
+fn union_selection(
+    predicates: &[RowPredicateRef],
+    stats: &dyn RowStats,
+    eval: impl Fn(&dyn RowPredicate, &dyn RowStats) -> anyhow::Result<Option<RowRangeList>>
+) -> anyhow::Result<Option<RowRangeList>> {
+    let mut selection: Option<RowRangeList> = None;
+    for p in predicates.iter() {
+        if let Some(sel) = eval(p.as_ref(), stats)? {
+            selection = Some(if let Some(prev) = selection {
+                prev.union(&sel)
+            } else {
+                sel
+            })
+        } else {
+            return Ok(None);
+        }
+    }
+    Ok(selection)
+}
+
 fn predicates_projection(predicates: &[RowPredicateRef]) -> Vec<Name> {
     let n_columns = predicates.iter().map(|p| p.projection().len()).sum();
     let mut projected_set: HashSet<Name> = HashSet::with_capacity(n_columns);
@@ -1,6 +1,6 @@
 use std::{hash::Hash, sync::Arc};
 
-use arrow::array::{Array, Scalar};
+use arrow::array::{Array, ArrayRef, Scalar};
 
 use crate::{
     primitives::Name,
@@ -8,7 +8,7 @@ use crate::{
         array_predicate,
         array_predicate::ArrayPredicateRef,
         arrow::IntoArrowScalar,
-        row_predicate::{AndPredicate, ColumnPredicate, OrPredicate, RowPredicateRef},
+        row_predicate::{AndPredicate, ColumnPredicate, NotPredicate, OrPredicate, RowPredicateRef},
         IntoArrowArray
     }
 };
@@ -23,26 +23,35 @@ pub fn col_eq<T: IntoArrowScalar>(name: Name, value: T) -> RowPredicateRef {
     make_column_predicate!(name, array_predicate::Eq::new(value))
 }
 
+/// column IS NULL OR column != value
+pub fn col_not_eq<T: IntoArrowScalar>(name: Name, value: T) -> RowPredicateRef {
+    make_column_predicate!(
+        name,
+        array_predicate::NotOrNull::new(Arc::new(array_predicate::Eq::new(value)))
+    )
+}
+
 pub fn col_in_list<L: IntoArrowArray>(name: Name, values: L) -> RowPredicateRef {
-    let values = values.into_array();
+    Arc::new(ColumnPredicate::new(name, in_list(values.into_array())))
+}
+
+/// column IS NULL OR column NOT IN values
+pub fn col_not_in_list<L: IntoArrowArray>(name: Name, values: L) -> RowPredicateRef {
+    make_column_predicate!(name, array_predicate::NotOrNull::new(in_list(values.into_array())))
+}
+
+fn in_list(values: ArrayRef) -> ArrayPredicateRef {
     match values.len() {
-        1 => col_eq(name, Scalar::new(values)),
-        0 | 2..10 => {
-            make_column_predicate!(
-                name,
-                array_predicate::Or::new(
-                    (0..values.len())
-                        .map(|i| {
-                            let val = Scalar::new(values.slice(i, 1));
-                            Arc::new(array_predicate::Eq::new(val)) as ArrayPredicateRef
-                        })
-                        .collect()
-                )
-            )
-        }
-        _ => {
-            make_column_predicate!(name, array_predicate::InList::new(values))
-        }
+        1 => Arc::new(array_predicate::Eq::new(Scalar::new(values))),
+        0 | 2..10 => Arc::new(array_predicate::Or::new(
+            (0..values.len())
+                .map(|i| {
+                    let val = Scalar::new(values.slice(i, 1));
+                    Arc::new(array_predicate::Eq::new(val)) as ArrayPredicateRef
+                })
+                .collect()
+        )),
+        _ => Arc::new(array_predicate::InList::new(values))
     }
 }
 
@@ -96,6 +105,10 @@ pub fn or(predicates: Vec<RowPredicateRef>) -> RowPredicateRef {
     }
 }
 
+pub fn not(predicate: RowPredicateRef) -> RowPredicateRef {
+    Arc::new(NotPredicate::new(predicate))
+}
+
 pub fn col_primitive_list_contains_any<T>(name: Name, values: &[T::Native]) -> RowPredicateRef
 where
     T: arrow::datatypes::ArrowPrimitiveType,
@@ -104,6 +117,55 @@ where
     make_column_predicate!(name, array_predicate::PrimitiveListContainsAny::<T>::new(values))
 }
 
+pub fn col_primitive_list_contains_none<T>(name: Name, values: &[T::Native]) -> RowPredicateRef
+where
+    T: arrow::datatypes::ArrowPrimitiveType,
+    T::Native: Eq + std::hash::Hash
+{
+    make_column_predicate!(
+        name,
+        array_predicate::NotOrNull::new(Arc::new(array_predicate::PrimitiveListContainsAny::<T>::new(values)))
+    )
+}
+
 pub fn col_string_list_contains_any<S: AsRef<str>>(name: Name, values: &[S]) -> RowPredicateRef {
     make_column_predicate!(name, array_predicate::StringListContainsAny::new(values))
 }
+
+pub fn col_string_list_contains_none<S: AsRef<str>>(name: Name, values: &[S]) -> RowPredicateRef {
+    make_column_predicate!(
+        name,
+        array_predicate::NotOrNull::new(Arc::new(array_predicate::StringListContainsAny::new(values)))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use arrow::{
+        array::{ArrayRef, RecordBatch, StringArray},
+        datatypes::{DataType, Field, Schema}
+    };
+
+    use super::{col_not_eq, col_not_in_list};
+
+    fn batch() -> RecordBatch {
+        let schema = Schema::new(vec![Field::new("to", DataType::Utf8, true)]);
+        let to: ArrayRef = Arc::new(StringArray::from(vec![Some("a"), None, Some("b"), Some("c")]));
+        RecordBatch::try_new(Arc::new(schema), vec![to]).unwrap()
+    }
+
+    fn selected(mask: &arrow::array::BooleanArray) -> Vec<bool> {
+        (0..mask.len()).map(|i| mask.is_valid(i) && mask.value(i)).collect()
+    }
+
+    #[test]
+    fn exclusions_keep_null_rows() {
+        let mask = col_not_in_list("to", vec!["a", "b"]).evaluate(&batch()).unwrap();
+        assert_eq!(selected(&mask), vec![false, true, false, true]);
+
+        let mask = col_not_eq("to", "c").evaluate(&batch()).unwrap();
+        assert_eq!(selected(&mask), vec![true, true, true, false]);
+    }
+}
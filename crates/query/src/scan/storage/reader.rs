@@ -1,13 +1,21 @@
-use std::collections::HashSet;
+use std::{collections::HashSet, sync::Arc};
 
-use arrow::{array::RecordBatch, datatypes::SchemaRef};
-use sqd_storage::db::SnapshotTableReader;
+use anyhow::ensure;
+use arrow::{
+    array::{Array, BooleanArray, BooleanBufferBuilder, RecordBatch},
+    datatypes::{DataType, SchemaRef}
+};
+use sqd_storage::{
+    db::SnapshotTableReader,
+    table::index::{index_value, ValueIndex}
+};
 
 use crate::{
     primitives::{Name, RowRangeList},
     scan::{
+        array_predicate::ArrayIndex,
         reader::TableReader,
-        row_predicate::{ColumnStats, RowStats},
+        row_predicate::{ColumnIndex, ColumnStats, RowStats},
         util::{add_row_index, build_row_index_array},
         RowPredicateRef
     }
@@ -106,4 +114,42 @@ impl<'a> RowStats for SnapshotTableReader<'a> {
             max: stats.max
         }))
     }
+
+    fn get_column_index(&self, column: Name) -> anyhow::Result<Option<ColumnIndex>> {
+        let index = self.schema().index_of(column)?;
+        let value_index = self.get_column_index(index)?;
+        Ok(value_index.map(|value_index| ColumnIndex {
+            offsets: value_index.offsets.clone(),
+            index: Arc::new(value_index)
+        }))
+    }
+}
+
+impl ArrayIndex for ValueIndex {
+    fn data_type(&self) -> &DataType {
+        self.values.data_type()
+    }
+
+    fn num_pages(&self) -> usize {
+        self.num_pages()
+    }
+
+    fn lookup(&self, values: &dyn Array) -> anyhow::Result<BooleanArray> {
+        ensure!(
+            values.data_type() == self.values.data_type(),
+            "expected values of type {}, but got {}",
+            self.values.data_type(),
+            values.data_type()
+        );
+        let mut mask = BooleanBufferBuilder::new(self.num_pages());
+        mask.append_n(self.num_pages(), false);
+        for i in 0..values.len() {
+            if values.is_valid(i) {
+                for &page in self.find_pages(index_value(values, i)) {
+                    mask.set_bit(page as usize, true);
+                }
+            }
+        }
+        Ok(BooleanArray::new(mask.finish(), None))
+    }
 }
@@ -134,10 +134,17 @@ fn test_fixture(chunk: &dyn Chunk, query_file: PathBuf) {
 
 #[cfg(feature = "parquet")]
 mod parquet {
-    use std::path::{Path, PathBuf};
+    use std::{
+        io::Cursor,
+        path::{Path, PathBuf}
+    };
 
+    use arrow::ipc::reader::StreamReader;
+    use parquet::arrow::ArrowWriter;
     use rstest::rstest;
-    use sqd_query::ParquetChunk;
+    use sqd_data::fuel::tables::FuelChunkBuilder;
+    use sqd_data_core::BlockChunkBuilder;
+    use sqd_query::{ArrowIpcWriter, ParquetChunk, Query, ARROW_ITEM_NAME_METADATA_KEY};
 
     use crate::{assert_unique_keys, execute_query_bytes, test_fixture};
 
@@ -191,6 +198,151 @@ mod parquet {
             );
         }
     }
+
+    /// An exclusion drops only the listed values: logs without the excluded topic stay.
+    #[test]
+    fn exclusion_keeps_items_with_null_fields() {
+        let chunk = ParquetChunk::new("fixtures/ethereum/chunk");
+        let logs_query = |exclude: serde_json::Value| {
+            let query = serde_json::json!({
+                "type": "evm",
+                "fromBlock": 17881390,
+                "toBlock": 17881391,
+                "logs": [{"exclude": exclude}],
+                "fields": {"log": {"logIndex": true, "topics": true}}
+            });
+            let bytes = execute_query_bytes(&chunk, query.to_string().as_bytes()).unwrap();
+            let blocks: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+            blocks
+                .as_array()
+                .unwrap()
+                .iter()
+                .filter_map(|block| block.get("logs"))
+                .flat_map(|logs| logs.as_array().unwrap().clone())
+                .collect::<Vec<_>>()
+        };
+        let topic3 = |log: &serde_json::Value| log["topics"].as_array().unwrap().get(3).cloned();
+
+        let all = logs_query(serde_json::json!({}));
+        let excluded = all
+            .iter()
+            .find_map(|log| topic3(log))
+            .expect("fixture chunk has no logs with topic3");
+        assert!(
+            all.iter().any(|log| topic3(log).is_none()),
+            "fixture chunk has no logs without topic3"
+        );
+
+        let expected: Vec<_> = all
+            .iter()
+            .filter(|log| topic3(log).as_ref() != Some(&excluded))
+            .cloned()
+            .collect();
+        let actual = logs_query(serde_json::json!({"topic3": [excluded]}));
+        assert_eq!(actual, expected);
+    }
+
+    /// A fuel contract output must expose its state root as `contractStateRoot`.
+    ///
+    /// The fixture chunk is not used here, because its `query.json` files do not
+    /// select this field. The chunk is built from a single block instead.
+    #[test]
+    fn fuel_contract_output_state_root() {
+        let block: sqd_data::fuel::model::Block = serde_json::from_value(serde_json::json!({
+            "header": {
+                "height": 12000000,
+                "hash": format!("0x{:064x}", 1),
+                "parentHash": format!("0x{:064x}", 0),
+                "daHeight": "21000000",
+                "consensusParametersVersion": 1,
+                "stateTransitionBytecodeVersion": 2,
+                "transactionsCount": "0",
+                "messageReceiptCount": "0",
+                "transactionsRoot": format!("0x{:064x}", 2),
+                "messageOutboxRoot": format!("0x{:064x}", 3),
+                "eventInboxRoot": format!("0x{:064x}", 4),
+                "prevRoot": format!("0x{:064x}", 5),
+                "time": "4611686020187387914",
+                "applicationHash": format!("0x{:064x}", 6)
+            },
+            "outputs": [{
+                "index": 0,
+                "transactionIndex": 0,
+                "type": "ContractOutput",
+                "inputIndex": 1,
+                "balanceRoot": format!("0x{:064x}", 7),
+                "stateRoot": format!("0x{:064x}", 8)
+            }]
+        }))
+        .unwrap();
+
+        let mut builder = FuelChunkBuilder::new();
+        builder.push(&block).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        for (name, mut table) in builder.prepare_in_memory().unwrap() {
+            let batch = table.read_record_batch(0, table.num_rows()).unwrap();
+            let file = std::fs::File::create(dir.path().join(format!("{name}.parquet"))).unwrap();
+            let mut writer = ArrowWriter::try_new(file, batch.schema(), None).unwrap();
+            writer.write(&batch).unwrap();
+            writer.close().unwrap();
+        }
+        let chunk = ParquetChunk::new(dir.path().to_str().unwrap());
+
+        let query = br#"{
+            "type": "fuel",
+            "fields": {"output": {"index": true, "contractStateRoot": true}},
+            "outputs": [{}]
+        }"#;
+        let bytes = execute_query_bytes(&chunk, query).unwrap();
+        let blocks: serde_json::Value = serde_json::from_slice(&bytes).unwrap();
+        assert_eq!(
+            blocks[0]["outputs"][0]["stateRoot"],
+            serde_json::json!(format!("0x{:064x}", 8))
+        );
+    }
+
+    /// Arrow IPC output must carry exactly the rows of the JSON output,
+    /// as one IPC stream per item with block headers first.
+    #[test]
+    fn arrow_ipc_output_matches_json_output() {
+        let chunk = ParquetChunk::new("fixtures/ethereum/chunk");
+        let query_json = std::fs::read("fixtures/ethereum/queries/all_logs_and_logs+tx_regression/query.json").unwrap();
+
+        let json = execute_query_bytes(&chunk, &query_json).unwrap();
+        let blocks: serde_json::Value = serde_json::from_slice(&json).unwrap();
+        let blocks = blocks.as_array().unwrap();
+        let count_items = |name: &str| -> usize {
+            blocks
+                .iter()
+                .filter_map(|block| block.get(name))
+                .map(|items| items.as_array().unwrap().len())
+                .sum()
+        };
+
+        let query = Query::from_json_bytes(&query_json).unwrap();
+        let mut block_writer = query.compile().execute(&chunk).unwrap().unwrap();
+        let mut writer = ArrowIpcWriter::new(Vec::new());
+        writer.write_blocks(&mut block_writer).unwrap();
+        assert!(!block_writer.has_next_block(), "all blocks must be consumed");
+
+        let mut cursor = Cursor::new(writer.finish().unwrap());
+        let mut items = Vec::new();
+        while (cursor.position() as usize) < cursor.get_ref().len() {
+            let reader = StreamReader::try_new_unbuffered(&mut cursor, None).unwrap();
+            let name = reader.schema().metadata()[ARROW_ITEM_NAME_METADATA_KEY].clone();
+            let rows: usize = reader.map(|batch| batch.unwrap().num_rows()).sum();
+            items.push((name, rows));
+        }
+
+        assert_eq!(items[0], ("blocks".to_string(), blocks.len()));
+        for (name, rows) in items.iter().skip(1) {
+            assert_eq!(*rows, count_items(name), "row count mismatch for {name}");
+        }
+        assert!(
+            items.iter().any(|(name, _)| name == "logs"),
+            "fixture query returned no logs"
+        );
+    }
 }
 
 #[cfg(feature = "storage")]
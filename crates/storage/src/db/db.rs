@@ -1,6 +1,6 @@
 use std::path::Path;
 
-use anyhow::ensure;
+use anyhow::{bail, ensure, Context};
 use arrow::datatypes::SchemaRef;
 use parking_lot::Mutex;
 use rocksdb::{ColumnFamilyDescriptor, Options as RocksOptions};
@@ -421,6 +421,11 @@ impl Database {
             })
     }
 
+    /// Whether any of the hash indexes is enabled for newly written chunks
+    pub fn is_hash_index_enabled(&self) -> bool {
+        self.block_hash_index || self.transaction_hash_index
+    }
+
     pub fn snapshot(&self) -> ReadSnapshot<'_> {
         ReadSnapshot::new(&self.db)
     }
@@ -569,6 +574,59 @@ impl Database {
         self.db.compact_range_cf(cf, None::<&[u8]>, None::<&[u8]>);
     }
 
+    /// Creates a consistent copy of the whole database in `dir`, which must not exist.
+    ///
+    /// The copy is taken with a RocksDB checkpoint and does not block writers. SST files are
+    /// hard-linked when `dir` is on the same filesystem, so it is cheap, but the linked files
+    /// keep taking space after compaction drops them here. The result is a regular database
+    /// directory: open it with [`DatabaseSettings::open`] or install it with [`restore_backup`].
+    pub fn create_backup(&self, dir: impl AsRef<Path>) -> anyhow::Result<()> {
+        let checkpoint = rocksdb::checkpoint::Checkpoint::new(&self.db)?;
+        checkpoint.create_checkpoint(dir)?;
+        Ok(())
+    }
+
+    /// Same as [`Database::create_backup`], but only the given datasets are kept.
+    ///
+    /// Other datasets are dropped from the copy and its tables are compacted,
+    /// so this needs scratch space of up to the size of the kept data.
+    pub fn create_dataset_backup(&self, dir: impl AsRef<Path>, datasets: &[DatasetId]) -> anyhow::Result<()> {
+        let dir = dir.as_ref();
+        let snapshot = self.snapshot();
+        for id in datasets.iter().copied() {
+            ensure!(snapshot.has_dataset(id)?, "dataset {} does not exist", id);
+        }
+        drop(snapshot);
+
+        self.create_backup(dir)?;
+
+        let result = (|| {
+            let backup = DatabaseSettings::default()
+                .with_auto_compactions(false)
+                .with_block_hash_index(self.block_hash_index)
+                .with_transaction_hash_index(self.transaction_hash_index)
+                .open(dir)?;
+
+            // nothing is ingested into the copy, so table builds caught by the checkpoint are orphans
+            backup.purge_orphan_dirty_tables()?;
+
+            for dataset in backup.get_all_datasets()? {
+                if !datasets.contains(&dataset.id) {
+                    backup.delete_dataset(dataset.id)?;
+                }
+            }
+
+            backup.flush_all()?;
+            backup.compact_tables();
+            Ok(())
+        })();
+
+        if result.is_err() {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+        result
+    }
+
     pub fn get_statistics(&self) -> Option<String> {
         self.options.get_statistics()
     }
@@ -594,6 +652,80 @@ impl Database {
     }
 }
 
+/// Installs a backup created by [`Database::create_backup`] as the database at `db_dir`.
+///
+/// `db_dir` must not exist or be empty. Table files (`*.sst`) are immutable, so they are
+/// hard-linked when possible and copied otherwise. All other files (`MANIFEST-*`, `OPTIONS-*`,
+/// logs, ...) are modified in place by RocksDB and are always copied, so the backup stays intact
+/// and can seed any number of replicas.
+///
+/// The backup is restored into a sibling directory, which is then renamed into place,
+/// so an interrupted restore never leaves a partial database at `db_dir`.
+pub fn restore_backup(backup_dir: impl AsRef<Path>, db_dir: impl AsRef<Path>) -> anyhow::Result<()> {
+    let backup_dir = backup_dir.as_ref();
+    let db_dir = db_dir.as_ref();
+
+    ensure!(
+        backup_dir.join("CURRENT").is_file(),
+        "{} is not a database backup",
+        backup_dir.display()
+    );
+
+    let db_dir_exists = db_dir.exists();
+    if db_dir_exists {
+        ensure!(
+            std::fs::read_dir(db_dir)?.next().is_none(),
+            "database directory {} is not empty",
+            db_dir.display()
+        );
+    }
+
+    let Some(db_name) = db_dir.file_name() else {
+        bail!("invalid database directory {}", db_dir.display())
+    };
+    let mut tmp_name = std::ffi::OsString::from(".");
+    tmp_name.push(db_name);
+    tmp_name.push(".restore");
+    let tmp_dir = db_dir.with_file_name(tmp_name);
+
+    // left over from an interrupted restore
+    if tmp_dir.exists() {
+        std::fs::remove_dir_all(&tmp_dir).with_context(|| format!("failed to remove {}", tmp_dir.display()))?;
+    }
+    std::fs::create_dir_all(&tmp_dir)?;
+
+    let restored = copy_backup_files(backup_dir, &tmp_dir).and_then(|_| {
+        if db_dir_exists {
+            std::fs::remove_dir(db_dir)?;
+        }
+        std::fs::rename(&tmp_dir, db_dir)?;
+        Ok(())
+    });
+
+    if restored.is_err() {
+        let _ = std::fs::remove_dir_all(&tmp_dir);
+    }
+    restored
+}
+
+fn copy_backup_files(backup_dir: &Path, dest_dir: &Path) -> anyhow::Result<()> {
+    for entry in std::fs::read_dir(backup_dir)? {
+        let entry = entry?;
+        ensure!(
+            entry.file_type()?.is_file(),
+            "unexpected entry in the backup: {}",
+            entry.path().display()
+        );
+        let src = entry.path();
+        let dest = dest_dir.join(entry.file_name());
+        let is_table = src.extension().is_some_and(|ext| ext == "sst");
+        if !is_table || std::fs::hard_link(&src, &dest).is_err() {
+            std::fs::copy(&src, &dest).with_context(|| format!("failed to copy {}", src.display()))?;
+        }
+    }
+    Ok(())
+}
+
 impl std::fmt::Debug for Database {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Database").field("path", &self.db.path()).finish()
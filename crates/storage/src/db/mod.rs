@@ -14,6 +14,7 @@ pub use read::snapshot::*;
 pub use table_id::TableId;
 pub use write::{
     dataset_update::*,
+    hash_index_backfill::*,
     table_builder::*,
     tx::{get_global_tx_restarts, get_local_tx_restarts, HashIndexWriteMetrics}
 };
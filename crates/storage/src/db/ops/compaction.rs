@@ -173,6 +173,8 @@ impl<'a> DatasetCompaction<'a> {
         let src = TableMerge::prepare(&chunks)?;
         let mut table_builder = TableBuilder::new(self.db, src.schema());
         table_builder.set_stats(src.columns_with_stats().iter().copied())?;
+        table_builder.set_indexes(src.columns_with_index().iter().copied())?;
+        table_builder.set_codecs(src.column_codecs().iter().copied())?;
         src.write(&mut table_builder)?;
         let table_id = table_builder.finish()?;
 
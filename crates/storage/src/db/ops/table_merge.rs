@@ -18,13 +18,15 @@ use super::{
     cast::{IndexCastReader, MaybeCastedReader},
     schema_merge::{data_types_equal, merge_schema}
 };
-use crate::db::SnapshotTableReader;
+use crate::{db::SnapshotTableReader, table::codec::Codec};
 
 pub struct TableMerge<'a> {
     chunks: &'a [Arc<SnapshotTableReader<'a>>],
     schema: SchemaRef,
     sort_key: Vec<usize>,
     columns_with_stats: Vec<usize>,
+    columns_with_index: Vec<usize>,
+    column_codecs: Vec<(usize, Codec)>,
     column_offsets: Vec<usize>
 }
 
@@ -52,11 +54,27 @@ impl<'a> TableMerge<'a> {
             })
             .collect::<anyhow::Result<Vec<usize>>>()?;
 
+        let columns_with_index = (0..last_chunk.schema().fields().len())
+            .filter_map(|i| {
+                last_chunk
+                    .get_column_index(i)
+                    .map(|maybe_index| maybe_index.map(|_| i))
+                    .transpose()
+            })
+            .collect::<anyhow::Result<Vec<usize>>>()?;
+
+        let column_codecs = (0..last_chunk.schema().fields().len())
+            .map(|i| (i, last_chunk.get_column_codec(i)))
+            .filter(|(_, codec)| *codec != Codec::None)
+            .collect();
+
         Ok(Self {
             chunks,
             schema,
             sort_key,
             columns_with_stats,
+            columns_with_index,
+            column_codecs,
             column_offsets
         })
     }
@@ -73,6 +91,14 @@ impl<'a> TableMerge<'a> {
         &self.columns_with_stats
     }
 
+    pub fn columns_with_index(&self) -> &[usize] {
+        &self.columns_with_index
+    }
+
+    pub fn column_codecs(&self) -> &[(usize, Codec)] {
+        &self.column_codecs
+    }
+
     pub fn write(&self, dst: &mut impl ArrayWriter) -> anyhow::Result<()> {
         if self.sort_key.len() > 0 {
             self.sorted_write(dst)
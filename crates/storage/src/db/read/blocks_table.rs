@@ -57,11 +57,12 @@ fn find_block_row<BN: Copy + Ord>(numbers: &[BN], block: BN) -> Option<usize> {
 
 /// Streams all `(block number, hash)` pairs of a `blocks` table, reading the
 /// columns in batches so peak memory stays `O(batch)` even for large compacted
-/// chunks. `number` must be `UInt32`/`UInt64`, `hash` must be `Utf8`, and both
+/// chunks. `number` must be `UInt32`/`UInt64`, `hash_column` must be `Utf8`, and both
 /// columns must be non-null; anything else is a hard error rather than
 /// silently indexing incomplete data.
 pub fn for_each_block_hash<S: KvRead + Sync>(
     blocks_table: &TableReader<S>,
+    hash_column: &str,
     mut visit: impl FnMut(BlockNumber, &str) -> anyhow::Result<()>
 ) -> anyhow::Result<()> {
     const BLOCK_HASH_BATCH_SIZE: usize = 4096;
@@ -75,10 +76,10 @@ pub fn for_each_block_hash<S: KvRead + Sync>(
         ref ty => bail!("'number' column has unexpected data type - {}", ty)
     }
 
-    let hash_idx = schema.index_of("hash")?;
+    let hash_idx = schema.index_of(hash_column)?;
     let hash_type = schema.field(hash_idx).data_type().clone();
     if hash_type != DataType::Utf8 {
-        bail!("'hash' column has unexpected data type - {}", hash_type)
+        bail!("'{}' column has unexpected data type - {}", hash_column, hash_type)
     }
 
     let num_rows = blocks_table.num_rows();
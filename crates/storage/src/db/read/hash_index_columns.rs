@@ -0,0 +1,49 @@
+use crate::db::DatasetKind;
+
+/// Columns, that feed the derived hash indexes of a dataset kind.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct HashIndexColumns {
+    /// `Utf8` column of the `blocks` table, read along with `number`
+    pub block_hash: &'static str,
+    /// Column of the `transactions` table, read along with `block_number` and `transaction_index`
+    pub transaction_hash: TransactionHashColumn
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TransactionHashColumn {
+    /// `Utf8` column
+    Value(&'static str),
+    /// First element of a `List<Utf8>` column, e.g. the signature that identifies a Solana transaction
+    FirstElement(&'static str)
+}
+
+impl TransactionHashColumn {
+    pub fn name(&self) -> &'static str {
+        match self {
+            TransactionHashColumn::Value(name) => name,
+            TransactionHashColumn::FirstElement(name) => name
+        }
+    }
+}
+
+/// Dataset kinds covered by the derived hash indexes and where their hashes live.
+///
+/// Hyperliquid must stay out because its `hash` is not a crypto hash and can collide.
+pub fn hash_index_columns(kind: DatasetKind) -> Option<HashIndexColumns> {
+    let columns = match kind.as_str() {
+        "evm" | "tron" => HashIndexColumns {
+            block_hash: "hash",
+            transaction_hash: TransactionHashColumn::Value("hash")
+        },
+        "bitcoin" => HashIndexColumns {
+            block_hash: "hash",
+            transaction_hash: TransactionHashColumn::Value("txid")
+        },
+        "solana" => HashIndexColumns {
+            block_hash: "hash",
+            transaction_hash: TransactionHashColumn::FirstElement("signatures")
+        },
+        _ => return None
+    };
+    Some(columns)
+}
@@ -1,5 +1,6 @@
 pub mod blocks_table;
 pub mod chunk;
 pub mod datasets;
+pub mod hash_index_columns;
 pub mod snapshot;
 pub mod transactions_table;
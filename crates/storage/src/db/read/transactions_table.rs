@@ -1,6 +1,6 @@
 use anyhow::{bail, ensure};
 use arrow::{
-    array::{Array, AsArray},
+    array::{Array, ArrayRef, AsArray, GenericListArray, StringArray},
     datatypes::{DataType, UInt16Type, UInt32Type, UInt64Type}
 };
 use sqd_array::{
@@ -9,14 +9,16 @@ use sqd_array::{
 };
 use sqd_primitives::{BlockNumber, ItemIndex};
 
-use crate::{kv::KvRead, table::read::TableReader};
+use crate::{db::read::hash_index_columns::TransactionHashColumn, kv::KvRead, table::read::TableReader};
 
-/// Streams `(block number, transaction index, hash)` from an EVM transactions
-/// table in bounded batches. Required position/hash columns must contain no
-/// nulls, and each transaction index must fit [`ItemIndex`]; accepting an
-/// invalid row would create an index entry that cannot be sound.
+/// Streams `(block number, transaction index, hash)` from a transactions
+/// table in bounded batches, taking the hash from `hash_column`. Required
+/// position/hash columns must contain no nulls (nor empty lists), and each
+/// transaction index must fit [`ItemIndex`]; accepting an invalid row would
+/// create an index entry that cannot be sound.
 pub fn for_each_transaction_hash<S: KvRead + Sync>(
     transactions_table: &TableReader<S>,
+    hash_column: TransactionHashColumn,
     mut visit: impl FnMut(BlockNumber, ItemIndex, &str) -> anyhow::Result<()>
 ) -> anyhow::Result<()> {
     const TRANSACTION_HASH_BATCH_SIZE: usize = 4096;
@@ -37,10 +39,19 @@ pub fn for_each_transaction_hash<S: KvRead + Sync>(
         ref ty => bail!("'transaction_index' column has unexpected data type - {}", ty)
     }
 
-    let hash_idx = schema.index_of("hash")?;
+    let hash_idx = schema.index_of(hash_column.name())?;
     let hash_type = schema.field(hash_idx).data_type().clone();
-    if hash_type != DataType::Utf8 {
-        bail!("'hash' column has unexpected data type - {}", hash_type)
+    let is_valid_hash_type = match (hash_column, &hash_type) {
+        (TransactionHashColumn::Value(_), DataType::Utf8) => true,
+        (TransactionHashColumn::FirstElement(_), DataType::List(item)) => item.data_type() == &DataType::Utf8,
+        _ => false
+    };
+    if !is_valid_hash_type {
+        bail!(
+            "'{}' column has unexpected data type - {}",
+            hash_column.name(),
+            hash_type
+        )
     }
 
     let num_rows = transactions_table.num_rows();
@@ -79,7 +90,7 @@ pub fn for_each_transaction_hash<S: KvRead + Sync>(
             DataType::UInt64 => TransactionIndexes::UInt64(transaction_indexes.as_primitive::<UInt64Type>().values()),
             _ => unreachable!("'transaction_index' column type was validated above")
         };
-        let hashes = hashes.as_string::<i32>();
+        let hashes = Hashes::new(&hashes);
 
         match block_numbers.data_type() {
             DataType::UInt32 => {
@@ -88,14 +99,14 @@ pub fn for_each_transaction_hash<S: KvRead + Sync>(
                     visit(
                         BlockNumber::from(block_numbers[i]),
                         transaction_indexes.value(i)?,
-                        hashes.value(i)
+                        hashes.value(i)?
                     )?;
                 }
             }
             DataType::UInt64 => {
                 let block_numbers = block_numbers.as_primitive::<UInt64Type>().values();
                 for i in 0..len {
-                    visit(block_numbers[i], transaction_indexes.value(i)?, hashes.value(i))?;
+                    visit(block_numbers[i], transaction_indexes.value(i)?, hashes.value(i)?)?;
                 }
             }
             _ => unreachable!("'block_number' column type was validated above")
@@ -123,3 +134,32 @@ impl TransactionIndexes<'_> {
         }
     }
 }
+
+enum Hashes<'a> {
+    Value(&'a StringArray),
+    FirstElement(&'a GenericListArray<i32>)
+}
+
+impl<'a> Hashes<'a> {
+    fn new(array: &'a ArrayRef) -> Self {
+        match array.data_type() {
+            DataType::Utf8 => Self::Value(array.as_string::<i32>()),
+            DataType::List(_) => Self::FirstElement(array.as_list::<i32>()),
+            _ => unreachable!("hash column type was validated above")
+        }
+    }
+
+    fn value(&self, index: usize) -> anyhow::Result<&'a str> {
+        match self {
+            Self::Value(values) => Ok(values.value(index)),
+            Self::FirstElement(lists) => {
+                let offsets = lists.value_offsets();
+                let (start, end) = (offsets[index] as usize, offsets[index + 1] as usize);
+                ensure!(start < end, "transaction hash list must not be empty");
+                let values = lists.values().as_string::<i32>();
+                ensure!(values.is_valid(start), "transaction hash must not be null");
+                Ok(values.value(start))
+            }
+        }
+    }
+}
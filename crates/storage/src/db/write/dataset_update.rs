@@ -47,6 +47,24 @@ impl<'a> DatasetUpdate<'a> {
             .validate_parent_block_hash(chunk, block_number, expected_parent_hash)
     }
 
+    /// Indexes hashes of an already committed `chunk` according to the enabled indexes.
+    ///
+    /// Returns `false` and does nothing, when the chunk is no longer present.
+    pub fn index_existing_chunk(&self, chunk: &Chunk) -> anyhow::Result<bool> {
+        let Some(current) = self
+            .list_chunks(chunk.first_block(), Some(chunk.first_block()))
+            .next()
+            .transpose()?
+        else {
+            return Ok(false);
+        };
+        if &current != chunk {
+            return Ok(false);
+        }
+        self.tx.index_hashes(self.dataset_id, chunk)?;
+        Ok(true)
+    }
+
     pub fn delete_chunk(&self, chunk: &Chunk) -> anyhow::Result<()> {
         self.tx.unindex_hashes(self.dataset_id, chunk)?;
         self.tx.delete_chunk(self.dataset_id, chunk)
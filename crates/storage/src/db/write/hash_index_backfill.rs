@@ -0,0 +1,122 @@
+use std::time::{Duration, Instant};
+
+use sqd_primitives::BlockNumber;
+
+use crate::db::{Chunk, Database, DatasetId};
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HashIndexBackfillProgress {
+    /// Chunks of the dataset at the moment the backfill has started
+    pub chunks_total: usize,
+    /// Chunks visited so far, including skipped ones
+    pub chunks_done: usize,
+    /// Chunks, that were replaced or pruned before the backfill got to them
+    pub chunks_skipped: usize,
+    /// Blocks of the indexed chunks
+    pub blocks_indexed: u64,
+    /// Last block of the most recently visited chunk
+    pub last_block: Option<BlockNumber>
+}
+
+impl HashIndexBackfillProgress {
+    pub fn is_complete(&self) -> bool {
+        self.chunks_done == self.chunks_total
+    }
+}
+
+/// Populates enabled hash indexes with entries of chunks,
+/// that were committed before the index was switched on.
+///
+/// Each chunk is indexed in its own dataset update, so ingestion and retention
+/// are never blocked for longer than a single chunk takes. Chunks,
+/// that were replaced or pruned in the meantime, are skipped, and re-indexing
+/// an already indexed chunk is a no-op, so the backfill can be restarted at any point.
+pub struct HashIndexBackfill<'a> {
+    db: &'a Database,
+    dataset_id: DatasetId,
+    max_blocks_per_second: Option<u64>
+}
+
+impl<'a> HashIndexBackfill<'a> {
+    pub fn new(db: &'a Database, dataset_id: DatasetId) -> Self {
+        Self {
+            db,
+            dataset_id,
+            max_blocks_per_second: None
+        }
+    }
+
+    /// Throttles the backfill to the given indexing rate
+    pub fn with_max_blocks_per_second(mut self, rate: Option<u64>) -> Self {
+        self.max_blocks_per_second = rate.filter(|r| *r > 0);
+        self
+    }
+
+    /// Runs the backfill to completion.
+    ///
+    /// `on_progress` is called after every chunk and may stop the backfill by returning `false`.
+    pub fn run<F>(self, mut on_progress: F) -> anyhow::Result<HashIndexBackfillProgress>
+    where
+        F: FnMut(&HashIndexBackfillProgress) -> bool
+    {
+        let mut progress = HashIndexBackfillProgress::default();
+
+        if !self.db.is_hash_index_enabled() || !self.db.snapshot().has_dataset(self.dataset_id)? {
+            return Ok(progress);
+        }
+
+        // Chunk records are small, listing them up front avoids pinning
+        // a snapshot for the whole duration of the backfill.
+        let chunks = self
+            .db
+            .snapshot()
+            .list_chunks(self.dataset_id, 0, None)
+            .collect::<anyhow::Result<Vec<_>>>()?;
+
+        progress.chunks_total = chunks.len();
+
+        let started = Instant::now();
+
+        for chunk in chunks {
+            let indexed = match self
+                .db
+                .update_dataset(self.dataset_id, |upd| upd.index_existing_chunk(&chunk))
+            {
+                Ok(indexed) => indexed,
+                Err(_) if !self.db.snapshot().has_dataset(self.dataset_id)? => {
+                    // Dataset was deleted, there is nothing left to index
+                    progress.chunks_skipped += progress.chunks_total - progress.chunks_done;
+                    progress.chunks_done = progress.chunks_total;
+                    on_progress(&progress);
+                    break;
+                }
+                Err(err) => return Err(err)
+            };
+
+            progress.chunks_done += 1;
+            progress.last_block = Some(chunk.last_block());
+            if indexed {
+                progress.blocks_indexed += chunk_size(&chunk);
+            } else {
+                progress.chunks_skipped += 1;
+            }
+
+            if !on_progress(&progress) {
+                break;
+            }
+
+            if let Some(rate) = self.max_blocks_per_second {
+                let target = Duration::from_secs_f64(progress.blocks_indexed as f64 / rate as f64);
+                if let Some(pause) = target.checked_sub(started.elapsed()) {
+                    std::thread::sleep(pause)
+                }
+            }
+        }
+
+        Ok(progress)
+    }
+}
+
+fn chunk_size(chunk: &Chunk) -> u64 {
+    chunk.last_block() - chunk.first_block() + 1
+}
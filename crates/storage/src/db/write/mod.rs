@@ -1,4 +1,5 @@
 pub mod dataset_update;
+pub mod hash_index_backfill;
 pub mod ops;
 mod storage;
 pub mod table_builder;
@@ -15,6 +15,8 @@ use crate::{
         ReadSnapshot
     },
     table::{
+        codec::Codec,
+        index::{can_have_index, serialize_index},
         key::TableKeyFactory,
         stats::{can_have_stats, serialize_stats},
         write::StorageCell
@@ -27,6 +29,7 @@ pub struct TableBuilder<'a> {
     table_id: TableId,
     schema: SchemaRef,
     columns_with_stats: BTreeSet<usize>,
+    columns_with_index: BTreeSet<usize>,
     writer: TableWriter<'a>,
     db: &'a RocksDB
 }
@@ -44,6 +47,7 @@ impl<'a> TableBuilder<'a> {
             table_id,
             schema,
             columns_with_stats: BTreeSet::new(),
+            columns_with_index: BTreeSet::new(),
             writer,
             db
         }
@@ -86,9 +90,58 @@ impl<'a> TableBuilder<'a> {
         Ok(())
     }
 
+    pub fn add_index_by_name(&mut self, name: &str) -> anyhow::Result<()> {
+        let index = self.schema.index_of(name)?;
+        let data_type = self.schema.field(index).data_type();
+        ensure!(
+            can_have_index(data_type),
+            "can't index column `{}`: columns of type {} can't have index",
+            name,
+            data_type
+        );
+        self.columns_with_index.insert(index);
+        Ok(())
+    }
+
+    pub fn set_indexes(&mut self, columns: impl IntoIterator<Item = usize>) -> anyhow::Result<()> {
+        let num_columns = self.schema.fields().len();
+        self.columns_with_index = columns
+            .into_iter()
+            .map(|index| {
+                ensure!(index < num_columns, "column {} does not exist", index);
+                let field = self.schema.field(index);
+                ensure!(
+                    can_have_index(field.data_type()),
+                    "can't index column {} ({}): columns of type {} can't have index",
+                    index,
+                    field.name(),
+                    field.data_type()
+                );
+                Ok(index)
+            })
+            .collect::<anyhow::Result<BTreeSet<_>>>()?;
+        Ok(())
+    }
+
+    /// Compresses pages of the column with the given codec.
+    ///
+    /// Must be called before any data is written.
+    pub fn set_codec_by_name(&mut self, name: &str, codec: Codec) -> anyhow::Result<()> {
+        let index = self.schema.index_of(name)?;
+        self.writer.set_codec(index, codec)
+    }
+
+    pub fn set_codecs(&mut self, columns: impl IntoIterator<Item = (usize, Codec)>) -> anyhow::Result<()> {
+        for (index, codec) in columns {
+            self.writer.set_codec(index, codec)?;
+        }
+        Ok(())
+    }
+
     pub fn finish(self) -> anyhow::Result<TableId> {
         self.writer.finish()?.into_inner().finish()?;
         build_table_stats(self.db, self.table_id, &self.columns_with_stats)?;
+        build_table_indexes(self.db, self.table_id, &self.columns_with_index)?;
         Ok(self.table_id)
     }
 }
@@ -126,6 +179,39 @@ fn build_table_stats(db: &RocksDB, table_id: TableId, columns_with_stats: &BTree
     Ok(())
 }
 
+fn build_table_indexes(db: &RocksDB, table_id: TableId, columns_with_index: &BTreeSet<usize>) -> anyhow::Result<()> {
+    if columns_with_index.is_empty() {
+        return Ok(());
+    }
+
+    let snapshot = ReadSnapshot::new(db);
+    let table_cf = db.cf_handle(CF_TABLES).unwrap();
+    let table_reader = snapshot.create_table_reader(table_id)?;
+    let mut bytes = Vec::new();
+    let mut key = TableKeyFactory::new(table_id);
+
+    for column_index in columns_with_index.iter().copied() {
+        let index = table_reader.build_column_index(4096, column_index).with_context(|| {
+            format!(
+                "failed to build index for column '{}'",
+                table_reader.schema().field(column_index).name()
+            )
+        })?;
+
+        bytes.clear();
+        serialize_index(&mut bytes, &index).with_context(|| {
+            format!(
+                "failed to serialize index of column {}",
+                table_reader.schema().field(column_index).name()
+            )
+        })?;
+
+        db.put_cf(table_cf, key.index(column_index), &bytes)?
+    }
+
+    Ok(())
+}
+
 impl<'a> ArrayWriter for TableBuilder<'a> {
     type Writer = <TableWriter<'a> as ArrayWriter>::Writer;
 
@@ -18,10 +18,11 @@ use crate::db::{
     read::{
         blocks_table::{for_each_block_hash, get_parent_block_hash},
         chunk::ChunkIterator,
+        hash_index_columns::{hash_index_columns, HashIndexColumns},
         transactions_table::for_each_transaction_hash
     },
     table_id::TableId,
-    Chunk, DatasetId, DatasetKind, DatasetLabel, ReadSnapshot
+    Chunk, DatasetId, DatasetLabel, ReadSnapshot
 };
 
 static GLOBAL_RESTARTS: AtomicU64 = AtomicU64::new(0);
@@ -43,13 +44,6 @@ fn record_restart() {
     LOCAL_RESTARTS.with_borrow_mut(|val| *val = val.wrapping_add(1))
 }
 
-/// Dataset kinds covered by the derived hash indexes. EVM-only for now;
-/// hyperliquid must stay out because its `hash` is not a crypto hash and can
-/// collide.
-fn is_indexed_kind(kind: DatasetKind) -> bool {
-    kind == DatasetKind::from_str("evm")
-}
-
 /// Aggregate time spent staging derived hash-index changes in an optimistic
 /// storage transaction, including work repeated after transaction conflicts.
 /// Timings are collected once per index scan, never once per entry.
@@ -282,25 +276,36 @@ impl<'a> Tx<'a> {
             return Ok(());
         }
 
-        let Some(label) = self.find_label_for_update(dataset_id)? else {
-            return Ok(()); // dataset does not exist - nothing to index
+        let Some(columns) = self.find_hash_index_columns(dataset_id)? else {
+            return Ok(()); // dataset does not exist or its kind is not indexed
         };
-        if !is_indexed_kind(label.kind()) {
-            return Ok(());
-        }
 
         if self.block_hash_index {
-            self.measure_hash_index(HashIndex::Block, || self.index_block_hashes(dataset_id, chunk))?;
+            self.measure_hash_index(HashIndex::Block, || {
+                self.index_block_hashes(dataset_id, chunk, &columns)
+            })?;
         }
         if self.transaction_hash_index {
             self.measure_hash_index(HashIndex::Transaction, || {
-                self.index_transaction_hashes(dataset_id, chunk)
+                self.index_transaction_hashes(dataset_id, chunk, &columns)
             })?;
         }
         Ok(())
     }
 
-    fn index_block_hashes(&self, dataset_id: DatasetId, chunk: &Chunk) -> anyhow::Result<()> {
+    fn find_hash_index_columns(&self, dataset_id: DatasetId) -> anyhow::Result<Option<HashIndexColumns>> {
+        let columns = self
+            .find_label_for_update(dataset_id)?
+            .and_then(|label| hash_index_columns(label.kind()));
+        Ok(columns)
+    }
+
+    fn index_block_hashes(
+        &self,
+        dataset_id: DatasetId,
+        chunk: &Chunk,
+        columns: &HashIndexColumns
+    ) -> anyhow::Result<()> {
         let Some(blocks_table_id) = chunk.tables().get("blocks").copied() else {
             return Ok(()); // defensively skip chunks without a blocks table
         };
@@ -309,14 +314,19 @@ impl<'a> Tx<'a> {
         let reader = snapshot.create_table_reader(blocks_table_id)?;
         let cf = self.cf_handle(CF_BLOCK_HASHES);
         let mut key = HashIndexKey::new(dataset_id, "");
-        for_each_block_hash(&reader, |number, hash| {
+        for_each_block_hash(&reader, columns.block_hash, |number, hash| {
             key.set_hash(hash);
             self.transaction.put_cf(cf, &key, number.to_be_bytes())?;
             Ok(())
         })
     }
 
-    fn index_transaction_hashes(&self, dataset_id: DatasetId, chunk: &Chunk) -> anyhow::Result<()> {
+    fn index_transaction_hashes(
+        &self,
+        dataset_id: DatasetId,
+        chunk: &Chunk,
+        columns: &HashIndexColumns
+    ) -> anyhow::Result<()> {
         let Some(transactions_table_id) = chunk.tables().get("transactions").copied() else {
             return Ok(());
         };
@@ -325,28 +335,44 @@ impl<'a> Tx<'a> {
         let reader = snapshot.create_table_reader(transactions_table_id)?;
         let cf = self.cf_handle(CF_TRANSACTION_HASHES);
         let mut key = HashIndexKey::new(dataset_id, "");
-        for_each_transaction_hash(&reader, |block_number, transaction_index, hash| {
-            key.set_hash(hash);
-            self.transaction
-                .put_cf(cf, &key, encode_transaction_position(block_number, transaction_index))?;
-            Ok(())
-        })
+        for_each_transaction_hash(
+            &reader,
+            columns.transaction_hash,
+            |block_number, transaction_index, hash| {
+                key.set_hash(hash);
+                self.transaction
+                    .put_cf(cf, &key, encode_transaction_position(block_number, transaction_index))?;
+                Ok(())
+            }
+        )
     }
 
     /// Removes every hash-index entry contributed by `chunk`.
     ///
-    /// Removal is gated on neither flag nor dataset kind: entries written while
-    /// a flag was on must still be removed when their chunk is pruned, or they
-    /// would be stranded forever. Each column family is first probed with one
-    /// prefix seek, making never-indexed chunks cheap and idempotent.
+    /// Removal is not gated on the flags: entries written while a flag was on
+    /// must still be removed when their chunk is pruned, or they would be
+    /// stranded forever. The dataset kind only tells which columns hold the
+    /// hashes; a kind without index columns never had entries. Each column
+    /// family is first probed with one prefix seek, making never-indexed chunks
+    /// cheap and idempotent.
     pub(crate) fn unindex_hashes(&self, dataset_id: DatasetId, chunk: &Chunk) -> anyhow::Result<()> {
-        self.measure_hash_index(HashIndex::Block, || self.unindex_block_hashes(dataset_id, chunk))?;
+        let Some(columns) = self.find_hash_index_columns(dataset_id)? else {
+            return Ok(());
+        };
+        self.measure_hash_index(HashIndex::Block, || {
+            self.unindex_block_hashes(dataset_id, chunk, &columns)
+        })?;
         self.measure_hash_index(HashIndex::Transaction, || {
-            self.unindex_transaction_hashes(dataset_id, chunk)
+            self.unindex_transaction_hashes(dataset_id, chunk, &columns)
         })
     }
 
-    fn unindex_block_hashes(&self, dataset_id: DatasetId, chunk: &Chunk) -> anyhow::Result<()> {
+    fn unindex_block_hashes(
+        &self,
+        dataset_id: DatasetId,
+        chunk: &Chunk,
+        columns: &HashIndexColumns
+    ) -> anyhow::Result<()> {
         let cf = self.cf_handle(CF_BLOCK_HASHES);
         if !self.has_hash_entries(cf, dataset_id)? {
             return Ok(());
@@ -359,14 +385,19 @@ impl<'a> Tx<'a> {
         let snapshot = ReadSnapshot::new(self.db);
         let reader = snapshot.create_table_reader(blocks_table_id)?;
         let mut key = HashIndexKey::new(dataset_id, "");
-        for_each_block_hash(&reader, |_number, hash| {
+        for_each_block_hash(&reader, columns.block_hash, |_number, hash| {
             key.set_hash(hash);
             self.transaction.delete_cf(cf, &key)?;
             Ok(())
         })
     }
 
-    fn unindex_transaction_hashes(&self, dataset_id: DatasetId, chunk: &Chunk) -> anyhow::Result<()> {
+    fn unindex_transaction_hashes(
+        &self,
+        dataset_id: DatasetId,
+        chunk: &Chunk,
+        columns: &HashIndexColumns
+    ) -> anyhow::Result<()> {
         let cf = self.cf_handle(CF_TRANSACTION_HASHES);
         if !self.has_hash_entries(cf, dataset_id)? {
             return Ok(());
@@ -379,11 +410,15 @@ impl<'a> Tx<'a> {
         let snapshot = ReadSnapshot::new(self.db);
         let reader = snapshot.create_table_reader(transactions_table_id)?;
         let mut key = HashIndexKey::new(dataset_id, "");
-        for_each_transaction_hash(&reader, |_block_number, _transaction_index, hash| {
-            key.set_hash(hash);
-            self.transaction.delete_cf(cf, &key)?;
-            Ok(())
-        })
+        for_each_transaction_hash(
+            &reader,
+            columns.transaction_hash,
+            |_block_number, _transaction_index, hash| {
+                key.set_hash(hash);
+                self.transaction.delete_cf(cf, &key)?;
+                Ok(())
+            }
+        )
     }
 
     /// Whether `dataset_id` holds at least one entry in `cf`: a single seek.
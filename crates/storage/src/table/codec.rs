@@ -0,0 +1,77 @@
+use std::borrow::Cow;
+
+use anyhow::{bail, ensure, Context};
+pub use sqd_dataset::Codec;
+
+const ZSTD_LEVEL: i32 = 3;
+
+/// Page encoding of a [`Codec`] and its on-disk tag
+pub(crate) trait PageCodec: Sized {
+    fn tag(&self) -> u8;
+
+    fn from_tag(tag: u8) -> anyhow::Result<Self>;
+
+    fn encode<'a>(&self, page: &'a [u8], out: &'a mut Vec<u8>) -> anyhow::Result<&'a [u8]>;
+
+    /// Restores the raw page, which is expected to have `len` bytes
+    fn decode<'a>(&self, page: &'a [u8], len: usize) -> anyhow::Result<Cow<'a, [u8]>>;
+}
+
+impl PageCodec for Codec {
+    fn tag(&self) -> u8 {
+        match self {
+            Codec::None => 0,
+            Codec::Zstd => 1
+        }
+    }
+
+    fn from_tag(tag: u8) -> anyhow::Result<Self> {
+        Ok(match tag {
+            0 => Codec::None,
+            1 => Codec::Zstd,
+            _ => bail!("unknown page codec {}", tag)
+        })
+    }
+
+    fn encode<'a>(&self, page: &'a [u8], out: &'a mut Vec<u8>) -> anyhow::Result<&'a [u8]> {
+        match self {
+            Codec::None => Ok(page),
+            Codec::Zstd => {
+                out.clear();
+                zstd::stream::copy_encode(page, &mut *out, ZSTD_LEVEL)?;
+                Ok(out.as_slice())
+            }
+        }
+    }
+
+    fn decode<'a>(&self, page: &'a [u8], len: usize) -> anyhow::Result<Cow<'a, [u8]>> {
+        match self {
+            Codec::None => Ok(Cow::Borrowed(page)),
+            Codec::Zstd => {
+                let data = zstd::bulk::decompress(page, len).context("failed to decompress zstd page")?;
+                ensure!(
+                    data.len() == len,
+                    "expected decompressed page to have length {}, but got {}",
+                    len,
+                    data.len()
+                );
+                Ok(Cow::Owned(data))
+            }
+        }
+    }
+}
+
+/// Codecs of table buffers, one byte per buffer
+pub(crate) fn serialize_codecs(codecs: &[Codec]) -> Vec<u8> {
+    codecs.iter().map(Codec::tag).collect()
+}
+
+pub(crate) fn deserialize_codecs(bytes: &[u8], num_buffers: usize) -> anyhow::Result<Vec<Codec>> {
+    ensure!(
+        bytes.len() == num_buffers,
+        "expected codecs of {} buffers, but got {}",
+        num_buffers,
+        bytes.len()
+    );
+    bytes.iter().copied().map(Codec::from_tag).collect()
+}
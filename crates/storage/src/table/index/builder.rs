@@ -0,0 +1,100 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use anyhow::Context;
+use arrow::{
+    array::{ArrayRef, BinaryArray, FixedSizeBinaryArray, StringArray},
+    datatypes::DataType
+};
+use arrow_buffer::{OffsetBuffer, ScalarBuffer};
+use sqd_array::{
+    access::Access,
+    slice::{AnySlice, Slice}
+};
+
+use super::{can_have_index, ValueIndex};
+
+pub struct ValueIndexBuilder {
+    data_type: DataType,
+    offsets: Vec<u32>,
+    last_offset: u32,
+    pages: BTreeMap<Vec<u8>, Vec<u32>>
+}
+
+impl ValueIndexBuilder {
+    pub fn new(data_type: DataType) -> Self {
+        assert!(can_have_index(&data_type), "data type {} can't have index", data_type);
+        Self {
+            data_type,
+            offsets: vec![0],
+            last_offset: 0,
+            pages: BTreeMap::new()
+        }
+    }
+
+    pub fn push_entry(&mut self, values: &AnySlice<'_>) {
+        let page = (self.offsets.len() - 1) as u32;
+        self.last_offset += values.len() as u32;
+        self.offsets.push(self.last_offset);
+        match &self.data_type {
+            DataType::Binary | DataType::Utf8 => self.push_values(page, values.as_binary()),
+            DataType::FixedSizeBinary(_) => self.push_values(page, values.as_fixed_size_binary()),
+            ty => unreachable!("unexpected arrow type - {}", ty)
+        }
+    }
+
+    fn push_values<'a>(&mut self, page: u32, values: impl Access<Value = &'a [u8]> + Slice) {
+        for i in 0..values.len() {
+            if !values.is_valid(i) {
+                continue;
+            }
+            let value = values.get(i);
+            if let Some(pages) = self.pages.get_mut(value) {
+                if pages.last() != Some(&page) {
+                    pages.push(page)
+                }
+            } else {
+                self.pages.insert(value.to_vec(), vec![page]);
+            }
+        }
+    }
+
+    pub fn finish(self) -> anyhow::Result<ValueIndex> {
+        let offsets = unsafe { OffsetBuffer::new_unchecked(self.offsets.into()) };
+
+        let mut page_offsets = Vec::with_capacity(self.pages.len() + 1);
+        let mut pages = Vec::new();
+        page_offsets.push(0);
+        for value_pages in self.pages.values() {
+            pages.extend_from_slice(value_pages);
+            page_offsets.push(pages.len() as u32);
+        }
+        let page_offsets = unsafe { OffsetBuffer::new_unchecked(page_offsets.into()) };
+
+        let values = self.pages.into_keys();
+        let values: ArrayRef = match &self.data_type {
+            DataType::Binary => Arc::new(BinaryArray::from_iter_values(values)),
+            DataType::Utf8 => {
+                let values = values
+                    .map(String::from_utf8)
+                    .collect::<Result<Vec<_>, _>>()
+                    .context("indexed column contains invalid utf8")?;
+                Arc::new(StringArray::from_iter_values(values))
+            }
+            DataType::FixedSizeBinary(size) => {
+                if values.len() == 0 {
+                    Arc::new(FixedSizeBinaryArray::new_null(*size, 0))
+                } else {
+                    Arc::new(FixedSizeBinaryArray::try_from_iter(values)?)
+                }
+            }
+            ty => unreachable!("unexpected arrow type - {}", ty)
+        };
+
+        Ok(ValueIndex {
+            offsets,
+            values,
+            page_offsets,
+            pages: ScalarBuffer::from(pages)
+        })
+    }
+}
@@ -0,0 +1,68 @@
+mod builder;
+mod serde;
+
+use arrow::{
+    array::{Array, ArrayRef, AsArray},
+    datatypes::DataType
+};
+use arrow_buffer::{OffsetBuffer, ScalarBuffer};
+pub use builder::*;
+pub use serde::*;
+
+/// Inverted index of a column: for each distinct value lists the pages,
+/// where the value occurs.
+///
+/// Pages are row ranges of the table, described by `offsets` in the same way as for [Stats](super::stats::Stats).
+#[derive(Clone)]
+pub struct ValueIndex {
+    pub offsets: OffsetBuffer<u32>,
+    /// Sorted distinct non-null values of the column
+    pub values: ArrayRef,
+    /// `pages[page_offsets[i]..page_offsets[i + 1]]` are the pages containing `values[i]`
+    pub page_offsets: OffsetBuffer<u32>,
+    pub pages: ScalarBuffer<u32>
+}
+
+impl ValueIndex {
+    pub fn num_pages(&self) -> usize {
+        self.offsets.len() - 1
+    }
+
+    /// Returns the (ascending) pages, that contain the given value.
+    pub fn find_pages(&self, value: &[u8]) -> &[u32] {
+        let mut lo = 0;
+        let mut hi = self.values.len();
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            match index_value(&self.values, mid).cmp(value) {
+                std::cmp::Ordering::Less => lo = mid + 1,
+                std::cmp::Ordering::Greater => hi = mid,
+                std::cmp::Ordering::Equal => {
+                    let beg = self.page_offsets[mid] as usize;
+                    let end = self.page_offsets[mid + 1] as usize;
+                    return &self.pages[beg..end];
+                }
+            }
+        }
+        &[]
+    }
+}
+
+pub fn can_have_index(data_type: &DataType) -> bool {
+    match data_type {
+        DataType::Binary => true,
+        DataType::FixedSizeBinary(_) => true,
+        DataType::Utf8 => true,
+        _ => false
+    }
+}
+
+/// Returns the bytes of an item of a [can_have_index] array.
+pub fn index_value(array: &dyn Array, i: usize) -> &[u8] {
+    match array.data_type() {
+        DataType::Binary => array.as_binary::<i32>().value(i),
+        DataType::FixedSizeBinary(_) => array.as_fixed_size_binary().value(i),
+        DataType::Utf8 => array.as_string::<i32>().value(i).as_bytes(),
+        ty => unreachable!("unexpected arrow type - {}", ty)
+    }
+}
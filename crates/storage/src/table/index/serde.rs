@@ -0,0 +1,81 @@
+use std::io::Write;
+
+use anyhow::{anyhow, ensure};
+use arrow::datatypes::DataType;
+use arrow_buffer::{MutableBuffer, OffsetBuffer, ScalarBuffer};
+use sqd_array::{
+    io::dense::{DenseReader, DenseWriter},
+    reader::{NativeReader, ReaderFactory},
+    util::validate_offsets,
+    writer::{NativeWriter, WriterFactory}
+};
+
+use super::ValueIndex;
+use crate::table::stats::{de_array, ser_array};
+
+pub fn serialize_index<W: Write>(out: &mut W, index: &ValueIndex) -> anyhow::Result<()> {
+    let mut file = DenseWriter::new(out);
+
+    let mut offsets_writer = file.native::<u32>()?;
+    offsets_writer.write_slice(&index.offsets)?;
+    offsets_writer.into_write().finish();
+
+    ser_array(&mut file, &index.values)?;
+
+    let mut page_offsets_writer = file.native::<u32>()?;
+    page_offsets_writer.write_slice(&index.page_offsets)?;
+    page_offsets_writer.into_write().finish();
+
+    let mut pages_writer = file.native::<u32>()?;
+    pages_writer.write_slice(&index.pages)?;
+    pages_writer.into_write().finish();
+
+    file.finish()?;
+    Ok(())
+}
+
+pub fn deserialize_index(input: &[u8], data_type: &DataType) -> anyhow::Result<ValueIndex> {
+    let mut reader = DenseReader::new(input)?;
+
+    let offsets = read_offsets(&mut reader)?;
+    let values = de_array(&mut reader, data_type)?;
+    let page_offsets = read_offsets(&mut reader)?;
+
+    let pages = {
+        let mut builder = MutableBuffer::new(0);
+        reader.native::<u32>()?.read(&mut builder)?;
+        ScalarBuffer::<u32>::from(builder)
+    };
+
+    ensure!(
+        page_offsets.len() - 1 == values.len(),
+        "page offsets array and values array lengths don't correspond each other"
+    );
+
+    ensure!(
+        *page_offsets.last().unwrap() as usize == pages.len(),
+        "page offsets array does not end with the number of page references"
+    );
+
+    let num_pages = offsets.len() - 1;
+    ensure!(
+        pages.iter().all(|&page| (page as usize) < num_pages),
+        "index references a non-existent page"
+    );
+
+    Ok(ValueIndex {
+        offsets,
+        values,
+        page_offsets,
+        pages
+    })
+}
+
+fn read_offsets(reader: &mut DenseReader<'_>) -> anyhow::Result<OffsetBuffer<u32>> {
+    let mut builder = MutableBuffer::new(0);
+    reader.native::<u32>()?.read(&mut builder)?;
+    let offsets = ScalarBuffer::<u32>::from(builder);
+    validate_offsets(&offsets, 0).map_err(|msg| anyhow!(msg))?;
+    ensure!(offsets[0] == 0, "offsets array does not start with 0");
+    Ok(unsafe { OffsetBuffer::new_unchecked(offsets) })
+}
@@ -2,7 +2,9 @@ enum TableKey {
     Schema,
     Statistic { column: u16 },
     Offsets { buffer: u16 },
-    Page { buffer: u16, index: u32 }
+    Page { buffer: u16, index: u32 },
+    Index { column: u16 },
+    Codecs
 }
 
 impl TableKey {
@@ -24,6 +26,13 @@ impl TableKey {
                 out.extend_from_slice(&buffer.to_be_bytes());
                 out.extend_from_slice(&index.to_be_bytes());
             }
+            TableKey::Index { column } => {
+                out.push(4);
+                out.extend_from_slice(&column.to_be_bytes());
+            }
+            TableKey::Codecs => {
+                out.push(5);
+            }
         }
     }
 }
@@ -79,6 +88,16 @@ impl TableKeyFactory {
         })
     }
 
+    pub fn index(&mut self, column_index: usize) -> &[u8] {
+        self.make(TableKey::Index {
+            column: column_index as u16
+        })
+    }
+
+    pub fn codecs(&mut self) -> &[u8] {
+        self.make(TableKey::Codecs)
+    }
+
     pub fn offsets(&mut self, buffer: usize) -> &[u8] {
         self.make(TableKey::Offsets { buffer: buffer as u16 })
     }
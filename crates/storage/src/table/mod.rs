@@ -1,3 +1,5 @@
+pub mod codec;
+pub mod index;
 pub(crate) mod key;
 pub mod read;
 pub mod stats;
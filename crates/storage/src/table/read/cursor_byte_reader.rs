@@ -1,24 +1,34 @@
 use anyhow::{ensure, Context};
 use sqd_array::{io::reader::ByteReader, util::bisect_offsets};
 
-use crate::{kv::KvReadCursor, table::key::TableKeyFactory};
+use crate::{
+    kv::KvReadCursor,
+    table::{
+        codec::{Codec, PageCodec},
+        key::TableKeyFactory
+    }
+};
 
 pub struct CursorByteReader<C> {
     cursor: C,
     key: TableKeyFactory,
     buffer: usize,
+    codec: Codec,
     page_offsets: Vec<u32>,
-    current_page: Option<usize>
+    current_page: Option<usize>,
+    decoded_page: Vec<u8>
 }
 
 impl<C: KvReadCursor> CursorByteReader<C> {
-    pub fn new(cursor: C, key: TableKeyFactory, buffer: usize, page_offsets: Vec<u32>) -> Self {
+    pub fn new(cursor: C, key: TableKeyFactory, buffer: usize, codec: Codec, page_offsets: Vec<u32>) -> Self {
         Self {
             cursor,
             key,
             buffer,
+            codec,
             page_offsets,
-            current_page: None
+            current_page: None,
+            decoded_page: Vec::new()
         }
     }
 
@@ -53,16 +63,32 @@ impl<C: KvReadCursor> CursorByteReader<C> {
         }
 
         let expected_len = self.page_offsets[page + 1] - self.page_offsets[page];
+
+        if self.codec != Codec::None {
+            self.decoded_page = self
+                .codec
+                .decode(self.cursor.value(), expected_len as usize)?
+                .into_owned();
+        }
+
         ensure!(
-            self.cursor.value().len() == expected_len as usize,
+            self.page().len() == expected_len as usize,
             "expected page to have length {}, but got {}",
             expected_len,
-            self.cursor.value().len()
+            self.page().len()
         );
 
         self.current_page = Some(page);
         Ok(())
     }
+
+    fn page(&self) -> &[u8] {
+        if self.codec == Codec::None {
+            self.cursor.value()
+        } else {
+            &self.decoded_page
+        }
+    }
 }
 
 impl<C: KvReadCursor> ByteReader for CursorByteReader<C> {
@@ -83,6 +109,6 @@ impl<C: KvReadCursor> ByteReader for CursorByteReader<C> {
             self.page_offsets[page + 1] as usize - self.page_offsets[page] as usize
         );
 
-        Ok(&self.cursor.value()[beg..end])
+        Ok(&self.page()[beg..end])
     }
 }
@@ -14,7 +14,7 @@ use sqd_array::{
     builder::{AnyBuilder, ArrayBuilder},
     io::reader::{BitmaskIOReader, IOReader, NativeIOReader, NullmaskIOReader, OffsetsIOReader},
     reader::{AnyReader, ArrayReader, Reader, ReaderFactory},
-    slice::AsSlice,
+    slice::{AnySlice, AsSlice},
     util::{build_field_offsets, validate_offsets}
 };
 use sqd_primitives::range::RangeList;
@@ -27,6 +27,8 @@ use super::{
 use crate::{
     kv::{KvRead, KvReadCursor},
     table::{
+        codec::{deserialize_codecs, Codec, PageCodec},
+        index::{can_have_index, deserialize_index, ValueIndex, ValueIndexBuilder},
         key::TableKeyFactory,
         stats::{can_have_stats, deserialize_stats, Stats, StatsBuilder}
     }
@@ -37,8 +39,10 @@ pub struct TableReader<S> {
     key: TableKeyFactory,
     schema: SchemaRef,
     column_positions: Vec<usize>,
+    codecs: Vec<Codec>,
     offsets: Vec<Mutex<Option<OffsetBuffer<u32>>>>,
     stats: Vec<Mutex<Option<Option<Stats>>>>,
+    indexes: Vec<Mutex<Option<Option<ValueIndex>>>>,
     num_rows: usize
 }
 
@@ -56,22 +60,33 @@ impl<S: KvRead + Sync> TableReader<S> {
         };
 
         let column_positions = build_field_offsets(0, schema.fields());
+        let num_buffers = column_positions.last().copied().unwrap();
 
-        let offsets = std::iter::repeat_with(Mutex::default)
-            .take(column_positions.last().copied().unwrap())
-            .collect();
+        // tables written before codecs were introduced don't have this key
+        let codecs = match storage.get(key.codecs())? {
+            Some(bytes) => deserialize_codecs(&bytes, num_buffers).context("invalid table codecs")?,
+            None => vec![Codec::None; num_buffers]
+        };
+
+        let offsets = std::iter::repeat_with(Mutex::default).take(num_buffers).collect();
 
         let stats = std::iter::repeat_with(Mutex::default)
             .take(schema.fields().len())
             .collect();
 
+        let indexes = std::iter::repeat_with(Mutex::default)
+            .take(schema.fields().len())
+            .collect();
+
         let mut table = Self {
             storage,
             key,
             schema: Arc::new(schema),
             column_positions,
+            codecs,
             offsets,
             stats,
+            indexes,
             num_rows: 0
         };
 
@@ -93,6 +108,10 @@ impl<S: KvRead + Sync> TableReader<S> {
         self.num_rows
     }
 
+    pub fn get_column_codec(&self, column_index: usize) -> Codec {
+        self.codecs[self.column_positions[column_index]]
+    }
+
     pub fn get_column_stats(&self, column_index: usize) -> anyhow::Result<Option<Stats>> {
         let mut stats_lock = self.stats[column_index].lock();
         Ok(if let Some(stats) = stats_lock.as_ref() {
@@ -114,6 +133,27 @@ impl<S: KvRead + Sync> TableReader<S> {
             .transpose()
     }
 
+    pub fn get_column_index(&self, column_index: usize) -> anyhow::Result<Option<ValueIndex>> {
+        let mut index_lock = self.indexes[column_index].lock();
+        Ok(if let Some(index) = index_lock.as_ref() {
+            index.clone()
+        } else {
+            let index = self.read_column_index(column_index)?;
+            *index_lock = Some(index.clone());
+            index
+        })
+    }
+
+    fn read_column_index(&self, column_index: usize) -> anyhow::Result<Option<ValueIndex>> {
+        self.storage
+            .get(self.key.clone().index(column_index))?
+            .map(|data| {
+                let data_type = self.schema.field(column_index).data_type();
+                deserialize_index(&data, data_type)
+            })
+            .transpose()
+    }
+
     pub fn read_table(
         &self,
         projection: Option<&HashSet<&str>>,
@@ -195,8 +235,6 @@ impl<S: KvRead + Sync> TableReader<S> {
     }
 
     pub fn build_column_stats(&self, window: usize, column_index: usize) -> anyhow::Result<Stats> {
-        ensure!(window > 0);
-
         let data_type = self.schema.field(column_index).data_type();
 
         ensure!(
@@ -205,15 +243,43 @@ impl<S: KvRead + Sync> TableReader<S> {
             data_type
         );
 
+        let mut stats_builder = StatsBuilder::new(data_type.clone());
+        self.for_each_column_window(window, column_index, |values| stats_builder.push_entry(values))?;
+        Ok(stats_builder.finish())
+    }
+
+    /// Builds [ValueIndex] over the same pages, that [Self::build_column_stats] uses for the given `window`.
+    pub fn build_column_index(&self, window: usize, column_index: usize) -> anyhow::Result<ValueIndex> {
+        let data_type = self.schema.field(column_index).data_type();
+
+        ensure!(
+            can_have_index(data_type),
+            "index is not supported for columns of type {}",
+            data_type
+        );
+
+        let mut index_builder = ValueIndexBuilder::new(data_type.clone());
+        self.for_each_column_window(window, column_index, |values| index_builder.push_entry(values))?;
+        index_builder.finish()
+    }
+
+    fn for_each_column_window(
+        &self,
+        window: usize,
+        column_index: usize,
+        mut cb: impl FnMut(&AnySlice<'_>)
+    ) -> anyhow::Result<()> {
+        ensure!(window > 0);
+
+        let data_type = self.schema.field(column_index).data_type();
         let mut reader = self.create_column_reader(column_index)?;
         let mut array_builder = AnyBuilder::new(data_type);
-        let mut stats_builder = StatsBuilder::new(data_type.clone());
         let mut pos = 0;
         let end = reader.len();
 
         while end - pos > window * 3 / 2 {
             reader.read_slice(&mut array_builder, pos, window)?;
-            stats_builder.push_entry(&array_builder.as_slice());
+            cb(&array_builder.as_slice());
             array_builder.clear();
             pos += window;
         }
@@ -221,17 +287,17 @@ impl<S: KvRead + Sync> TableReader<S> {
         if end - pos > window {
             let window = (end - pos) / 2;
             reader.read_slice(&mut array_builder, pos, window)?;
-            stats_builder.push_entry(&array_builder.as_slice());
+            cb(&array_builder.as_slice());
             array_builder.clear();
             pos += window;
         }
 
         if end > pos {
             reader.read_slice(&mut array_builder, pos, end - pos)?;
-            stats_builder.push_entry(&array_builder.as_slice());
+            cb(&array_builder.as_slice());
         }
 
-        Ok(stats_builder.finish())
+        Ok(())
     }
 
     fn get_buffer_pages(&self, buffer: usize) -> anyhow::Result<OffsetBuffer<u32>> {
@@ -468,6 +534,11 @@ impl<S: KvRead + Sync> TableReader<S> {
             .get(key.page(buffer, page_idx))?
             .with_context(|| anyhow!("page {} was not found", page_idx))?;
 
+        let page_len = pagination.page_range(page_seq).len();
+        let data = self.codecs[buffer]
+            .decode(&data, page_len * item_size)
+            .with_context(|| format!("failed to decode page {}", page_idx))?;
+
         ensure!(
             data.len() % item_size == 0,
             "page {} byte size expected to be multiple of {}, but got {}",
@@ -476,7 +547,6 @@ impl<S: KvRead + Sync> TableReader<S> {
             data.len()
         );
 
-        let page_len = pagination.page_range(page_seq).len();
         ensure!(
             data.len() / item_size == page_len,
             "expected page {} to contain {} items, but got {}",
@@ -540,6 +610,9 @@ impl<S: KvRead + Sync> TableReader<S> {
         self.for_each_page(buffer, &pagination, |i, data| {
             let expected_bit_len = pagination.page_range(i).len();
             let expected_byte_len = bit_util::ceil(expected_bit_len, 8);
+            let data = self.codecs[buffer]
+                .decode(data, expected_byte_len)
+                .with_context(|| format!("failed to decode page {}", pagination.page_index(i)))?;
             ensure!(
                 expected_byte_len == data.len(),
                 "expected for page {} to have byte length {}, but got {}",
@@ -548,7 +621,7 @@ impl<S: KvRead + Sync> TableReader<S> {
                 data.len()
             );
             for r in pagination.iter_ranges(i) {
-                buf.append_packed_range(r, data)
+                buf.append_packed_range(r, &data)
             }
             Ok(())
         })?;
@@ -617,6 +690,7 @@ impl<'a, S: KvRead + Sync> CursorReaderFactory<'a, S> {
             self.table.storage.new_cursor(),
             self.table.key.clone(),
             self.buffer,
+            self.table.codecs[self.buffer],
             byte_offsets
         );
 
@@ -633,6 +707,7 @@ impl<'a, S: KvRead + Sync> CursorReaderFactory<'a, S> {
             self.table.storage.new_cursor(),
             self.table.key.clone(),
             self.buffer,
+            self.table.codecs[self.buffer],
             byte_offsets
         );
 
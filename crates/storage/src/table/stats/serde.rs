@@ -33,7 +33,7 @@ pub fn serialize_stats<W: Write>(out: &mut W, stats: &Stats) -> anyhow::Result<(
     Ok(())
 }
 
-fn ser_array<W: Write>(file: &mut DenseWriter<W>, array: &dyn Array) -> anyhow::Result<()> {
+pub(crate) fn ser_array<W: Write>(file: &mut DenseWriter<W>, array: &dyn Array) -> anyhow::Result<()> {
     let mut writer = AnyArrayWriter::from_factory(file, array.data_type())?;
     array.as_slice().write(&mut writer)?;
     for buf in writer.into_inner() {
@@ -75,7 +75,7 @@ pub fn deserialize_stats(input: &[u8], data_type: &DataType) -> anyhow::Result<S
     Ok(Stats { offsets, min, max })
 }
 
-fn de_array(reader: &mut DenseReader<'_>, data_type: &DataType) -> anyhow::Result<ArrayRef> {
+pub(crate) fn de_array(reader: &mut DenseReader<'_>, data_type: &DataType) -> anyhow::Result<ArrayRef> {
     let mut builder = AnyBuilder::new(data_type);
     AnyReader::from_factory(reader, data_type)?.read(&mut builder)?;
     let array = builder.finish();
@@ -44,6 +44,10 @@ impl<P: PageWriter> BitmaskPageWriter<P> {
         Ok(())
     }
 
+    pub fn page_writer_mut(&mut self) -> &mut P {
+        &mut self.page_writer
+    }
+
     pub fn finish(mut self) -> anyhow::Result<P> {
         let mut byte_offset = 0;
         let byte_end = self.builder.bytes_size();
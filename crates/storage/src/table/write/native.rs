@@ -55,6 +55,10 @@ impl<P: PageWriter> NativePageWriter<P> {
         Ok(())
     }
 
+    pub fn page_writer_mut(&mut self) -> &mut P {
+        &mut self.page_writer
+    }
+
     pub fn finish(mut self) -> anyhow::Result<P> {
         ensure!(self.buffer.len() % self.item_size == 0, "got partially written item");
 
@@ -31,6 +31,10 @@ impl<P: PageWriter> NullmaskPageWriter<P> {
         Ok(true)
     }
 
+    pub fn page_writer_mut(&mut self) -> &mut P {
+        self.nulls.page_writer_mut()
+    }
+
     pub fn finish(self) -> anyhow::Result<P> {
         let mut page_writer = self.nulls.finish()?;
         page_writer.write_page(if self.has_nulls { 0 } else { self.len }, &[])?;
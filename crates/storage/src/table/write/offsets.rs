@@ -48,6 +48,10 @@ impl<P: PageWriter> OffsetPageWriter<P> {
         Ok(())
     }
 
+    pub fn page_writer_mut(&mut self) -> &mut P {
+        &mut self.page_writer
+    }
+
     pub fn finish(mut self) -> anyhow::Result<P> {
         let slice = self.builder.as_slice();
         let data = slice.values();
@@ -1,6 +1,13 @@
+use anyhow::ensure;
 use arrow_buffer::ToByteSlice;
 
-use crate::{kv::KvWrite, table::key::TableKeyFactory};
+use crate::{
+    kv::KvWrite,
+    table::{
+        codec::{Codec, PageCodec},
+        key::TableKeyFactory
+    }
+};
 
 pub trait PageWriter {
     fn write_page(&mut self, item_count: usize, bytes: &[u8]) -> anyhow::Result<()>;
@@ -10,7 +17,9 @@ pub struct BufferPageWriter<S> {
     storage: S,
     key: TableKeyFactory,
     buffer_index: usize,
-    page_offsets: Vec<u32>
+    page_offsets: Vec<u32>,
+    codec: Codec,
+    encoded: Vec<u8>
 }
 
 impl<S: KvWrite> BufferPageWriter<S> {
@@ -19,10 +28,22 @@ impl<S: KvWrite> BufferPageWriter<S> {
             storage,
             key,
             buffer_index,
-            page_offsets: vec![0]
+            page_offsets: vec![0],
+            codec: Codec::None,
+            encoded: Vec::new()
         }
     }
 
+    pub fn set_codec(&mut self, codec: Codec) -> anyhow::Result<()> {
+        ensure!(
+            self.num_pages() == 0,
+            "can't change codec of buffer {}, because some pages were already written",
+            self.buffer_index
+        );
+        self.codec = codec;
+        Ok(())
+    }
+
     pub fn num_pages(&self) -> usize {
         self.page_offsets.len() - 1
     }
@@ -41,6 +62,7 @@ impl<S: KvWrite> BufferPageWriter<S> {
 impl<S: KvWrite> PageWriter for BufferPageWriter<S> {
     fn write_page(&mut self, item_count: usize, bytes: &[u8]) -> anyhow::Result<()> {
         let key = self.key.page(self.buffer_index, self.num_pages());
+        let bytes = self.codec.encode(bytes, &mut self.encoded)?;
         self.storage.put(key, bytes)?;
         self.page_offsets.push((self.num_items() + item_count) as u32);
         Ok(())
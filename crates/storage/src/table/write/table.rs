@@ -1,14 +1,26 @@
+use anyhow::ensure;
 use arrow::{datatypes::SchemaRef, ipc::convert::IpcSchemaEncoder};
-use sqd_array::writer::{AnyArrayWriter, AnyWriter, ArrayWriter, Writer};
+use sqd_array::{
+    util::build_field_offsets,
+    writer::{AnyArrayWriter, AnyWriter, ArrayWriter, Writer}
+};
 
 use super::storage_writer::{StorageWriter, StorageWriterFactory};
-use crate::{kv::KvWrite, table::key::TableKeyFactory};
+use crate::{
+    kv::KvWrite,
+    table::{
+        codec::{serialize_codecs, Codec},
+        key::TableKeyFactory
+    }
+};
 
 pub struct TableWriter<S: KvWrite> {
     storage: S,
     schema: SchemaRef,
     key: TableKeyFactory,
-    writer: AnyArrayWriter<StorageWriter<S>>
+    writer: AnyArrayWriter<StorageWriter<S>>,
+    column_positions: Vec<usize>,
+    codecs: Vec<Codec>
 }
 
 impl<S: KvWrite + Clone> TableWriter<S> {
@@ -16,16 +28,42 @@ impl<S: KvWrite + Clone> TableWriter<S> {
         let key = TableKeyFactory::new(table_name);
         let mut factory = StorageWriterFactory::new(storage.clone(), key.clone());
         let writer = AnyArrayWriter::table_writer_from_factory(&mut factory, &schema).unwrap();
+        let column_positions = build_field_offsets(0, schema.fields());
+        let codecs = vec![Codec::None; column_positions.last().copied().unwrap()];
         Self {
             storage,
             schema,
             key,
-            writer
+            writer,
+            column_positions,
+            codecs
         }
     }
 }
 
 impl<S: KvWrite> TableWriter<S> {
+    /// Sets the codec for all buffers of the column.
+    ///
+    /// Must be called before the column data is written.
+    pub fn set_codec(&mut self, column_index: usize, codec: Codec) -> anyhow::Result<()> {
+        ensure!(
+            column_index < self.schema.fields().len(),
+            "column {} does not exist",
+            column_index
+        );
+        let buffers = self.column_positions[column_index]..self.column_positions[column_index + 1];
+        for buf in buffers {
+            match &mut self.writer.buffers_mut()[buf] {
+                AnyWriter::Bitmask(writer) => writer.page_writer_mut().set_codec(codec),
+                AnyWriter::Nullmask(writer) => writer.page_writer_mut().set_codec(codec),
+                AnyWriter::Native(writer) => writer.page_writer_mut().set_codec(codec),
+                AnyWriter::Offsets(writer) => writer.page_writer_mut().set_codec(codec)
+            }?;
+            self.codecs[buf] = codec;
+        }
+        Ok(())
+    }
+
     pub fn finish(mut self) -> anyhow::Result<S> {
         for buf in self.writer.into_inner() {
             match buf {
@@ -42,6 +80,11 @@ impl<S: KvWrite> TableWriter<S> {
             IpcSchemaEncoder::new().schema_to_fb(&self.schema).finished_data()
         )?;
 
+        // tables without codecs keep the original layout
+        if self.codecs.iter().any(|codec| *codec != Codec::None) {
+            self.storage.put(self.key.codecs(), &serialize_codecs(&self.codecs))?;
+        }
+
         Ok(self.storage)
     }
 }
@@ -0,0 +1,139 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use arrow::{
+    array::{AsArray, RecordBatch, UInt32Array},
+    datatypes::{DataType, Field, Schema, UInt32Type}
+};
+use sqd_storage::db::{restore_backup, Chunk, Database, DatabaseSettings, DatasetId, DatasetKind};
+
+fn insert_chunk(db: &Database, dataset_id: DatasetId, values: Vec<u32>) -> Chunk {
+    let schema = Arc::new(Schema::new(vec![Field::new("data", DataType::UInt32, true)]));
+    let batch = RecordBatch::try_new(schema.clone(), vec![Arc::new(UInt32Array::from(values))]).unwrap();
+
+    let mut builder = db.new_table_builder(schema);
+    builder.write_record_batch(&batch).unwrap();
+
+    let mut tables = BTreeMap::new();
+    tables.insert("block".to_owned(), builder.finish().unwrap());
+
+    let chunk = Chunk::V0 {
+        first_block: 0,
+        last_block: 100,
+        last_block_hash: "last".to_owned(),
+        parent_block_hash: "base".to_owned(),
+        tables
+    };
+    db.insert_chunk(dataset_id, &chunk).unwrap();
+    chunk
+}
+
+fn read_values(db: &Database, dataset_id: DatasetId) -> Vec<u32> {
+    let snapshot = db.snapshot();
+    let chunk = snapshot.get_first_chunk(dataset_id).unwrap().unwrap();
+    let chunk_reader = snapshot.create_chunk_reader(chunk);
+    let column = chunk_reader
+        .get_table_reader("block")
+        .unwrap()
+        .read_column(0, None)
+        .unwrap();
+    column.as_primitive::<UInt32Type>().values().to_vec()
+}
+
+fn setup_datasets(db: &Database) -> (DatasetId, DatasetId) {
+    let solana = DatasetId::from_str("solana");
+    let ethereum = DatasetId::from_str("ethereum");
+    db.create_dataset(solana, DatasetKind::from_str("solana")).unwrap();
+    db.create_dataset(ethereum, DatasetKind::from_str("evm")).unwrap();
+    insert_chunk(db, solana, vec![1, 2, 3]);
+    insert_chunk(db, ethereum, vec![4, 5, 6]);
+    (solana, ethereum)
+}
+
+#[test]
+fn full_backup_is_restored() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = DatabaseSettings::default().open(dir.path().join("db")).unwrap();
+    let (solana, ethereum) = setup_datasets(&db);
+
+    db.create_backup(dir.path().join("backup")).unwrap();
+
+    // writes after the backup don't leak into it
+    db.delete_dataset(ethereum).unwrap();
+
+    restore_backup(dir.path().join("backup"), dir.path().join("replica")).unwrap();
+    let replica = DatabaseSettings::default().open(dir.path().join("replica")).unwrap();
+
+    assert_eq!(replica.get_all_datasets().unwrap().len(), 2);
+    assert_eq!(read_values(&replica, solana), vec![1, 2, 3]);
+    assert_eq!(read_values(&replica, ethereum), vec![4, 5, 6]);
+}
+
+#[test]
+fn dataset_backup_keeps_only_requested_datasets() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = DatabaseSettings::default().open(dir.path().join("db")).unwrap();
+    let (solana, ethereum) = setup_datasets(&db);
+
+    assert!(db
+        .create_dataset_backup(dir.path().join("missing"), &[DatasetId::from_str("missing")])
+        .is_err());
+
+    db.create_dataset_backup(dir.path().join("backup"), &[solana]).unwrap();
+
+    let backup = DatabaseSettings::default().open(dir.path().join("backup")).unwrap();
+    let datasets = backup.get_all_datasets().unwrap();
+    assert_eq!(datasets.len(), 1);
+    assert_eq!(datasets[0].id, solana);
+    assert_eq!(read_values(&backup, solana), vec![1, 2, 3]);
+
+    assert_eq!(read_values(&db, ethereum), vec![4, 5, 6]);
+}
+
+#[test]
+fn backup_is_not_restored_over_existing_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = DatabaseSettings::default().open(dir.path().join("db")).unwrap();
+    setup_datasets(&db);
+
+    db.create_backup(dir.path().join("backup")).unwrap();
+
+    assert!(restore_backup(dir.path().join("backup"), dir.path().join("db")).is_err());
+    assert!(restore_backup(dir.path().join("nothing"), dir.path().join("other")).is_err());
+}
+
+#[test]
+fn restored_database_does_not_change_the_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = DatabaseSettings::default().open(dir.path().join("db")).unwrap();
+    let (solana, ethereum) = setup_datasets(&db);
+
+    db.create_backup(dir.path().join("backup")).unwrap();
+
+    restore_backup(dir.path().join("backup"), dir.path().join("replica")).unwrap();
+    {
+        let replica = DatabaseSettings::default().open(dir.path().join("replica")).unwrap();
+        replica.delete_dataset(ethereum).unwrap();
+    }
+
+    restore_backup(dir.path().join("backup"), dir.path().join("second-replica")).unwrap();
+    let replica = DatabaseSettings::default()
+        .open(dir.path().join("second-replica"))
+        .unwrap();
+    assert_eq!(replica.get_all_datasets().unwrap().len(), 2);
+    assert_eq!(read_values(&replica, solana), vec![1, 2, 3]);
+    assert_eq!(read_values(&replica, ethereum), vec![4, 5, 6]);
+}
+
+#[test]
+fn failed_restore_leaves_no_database() {
+    let dir = tempfile::tempdir().unwrap();
+    let db = DatabaseSettings::default().open(dir.path().join("db")).unwrap();
+    setup_datasets(&db);
+
+    db.create_backup(dir.path().join("backup")).unwrap();
+    std::fs::create_dir(dir.path().join("backup").join("unexpected")).unwrap();
+
+    assert!(restore_backup(dir.path().join("backup"), dir.path().join("replica")).is_err());
+    assert!(!dir.path().join("replica").exists());
+    assert!(!dir.path().join(".replica.restore").exists());
+}
@@ -6,7 +6,10 @@ use arrow::{
 };
 use sqd_primitives::BlockRef;
 use sqd_storage::{
-    db::{Chunk, CompactionStatus, Database, DatabaseSettings, DatasetId, DatasetKind, HashIndexWriteMetrics},
+    db::{
+        Chunk, CompactionStatus, Database, DatabaseSettings, DatasetId, DatasetKind, HashIndexBackfill,
+        HashIndexWriteMetrics
+    },
     table::write::use_small_buffers
 };
 use tempfile::TempDir;
@@ -310,9 +313,9 @@ fn index_survives_compaction() {
 }
 
 #[test]
-fn non_evm_dataset_is_not_indexed() {
-    // Same EVM-shaped blocks table, but a solana dataset -> nothing is indexed.
-    let (_dir, db, dataset_id) = open_db("solana");
+fn hyperliquid_dataset_is_not_indexed() {
+    // Same EVM-shaped blocks table, but hyperliquid hashes can collide -> nothing is indexed.
+    let (_dir, db, dataset_id) = open_db("hl-fills");
 
     let chunk = make_evm_chunk(&db, 0, 9, "base");
     db.insert_chunk(dataset_id, &chunk).unwrap();
@@ -322,6 +325,20 @@ fn non_evm_dataset_is_not_indexed() {
     }
 }
 
+#[test]
+fn solana_tron_and_bitcoin_blocks_are_indexed() {
+    for kind in ["solana", "tron", "bitcoin"] {
+        let (_dir, db, dataset_id) = open_db(kind);
+
+        let chunk = make_evm_chunk(&db, 0, 9, "base");
+        db.insert_chunk(dataset_id, &chunk).unwrap();
+
+        for n in 0..=9 {
+            assert_resolves(&db, dataset_id, n);
+        }
+    }
+}
+
 #[test]
 fn index_disabled_writes_nothing() {
     // An EVM dataset still isn't indexed while the flag is off.
@@ -397,3 +414,91 @@ fn probe_sees_pending_writes_within_the_same_transaction() {
         assert_absent(&db, dataset_id, &block_hash(n));
     }
 }
+
+#[test]
+fn backfill_indexes_chunks_written_before_the_flag_was_turned_on() {
+    let (dir, db, dataset_id) = open_db_with("evm", false);
+
+    let chunk1 = make_evm_chunk(&db, 0, 9, "base");
+    let chunk2 = make_evm_chunk(&db, 10, 19, &block_hash(9));
+    db.insert_chunk(dataset_id, &chunk1).unwrap();
+    db.insert_chunk(dataset_id, &chunk2).unwrap();
+    drop(db);
+
+    let db = reopen(&dir, true);
+    for n in 0..=19 {
+        assert_absent(&db, dataset_id, &block_hash(n));
+    }
+
+    let mut reports = 0;
+    let progress = HashIndexBackfill::new(&db, dataset_id)
+        .run(|_| {
+            reports += 1;
+            true
+        })
+        .unwrap();
+
+    assert_eq!(reports, 2);
+    assert!(progress.is_complete());
+    assert_eq!(progress.chunks_skipped, 0);
+    assert_eq!(progress.blocks_indexed, 20);
+    assert_eq!(progress.last_block, Some(19));
+    for n in 0..=19 {
+        assert_resolves(&db, dataset_id, n);
+    }
+
+    // Repeated runs are harmless
+    HashIndexBackfill::new(&db, dataset_id).run(|_| true).unwrap();
+    for n in 0..=19 {
+        assert_resolves(&db, dataset_id, n);
+    }
+
+    // Pruning still removes everything the backfill has written
+    db.update_dataset(dataset_id, |tx| {
+        tx.delete_chunk(&chunk1)?;
+        tx.delete_chunk(&chunk2)
+    })
+    .unwrap();
+    for n in 0..=19 {
+        assert_absent(&db, dataset_id, &block_hash(n));
+    }
+}
+
+#[test]
+fn backfill_skips_chunks_that_are_gone() {
+    let (dir, db, dataset_id) = open_db_with("evm", false);
+
+    let chunk = make_evm_chunk(&db, 0, 9, "base");
+    db.insert_chunk(dataset_id, &chunk).unwrap();
+    drop(db);
+
+    let db = reopen(&dir, true);
+
+    let fork = make_evm_chunk_with(&db, 5, 9, &block_hash(4), |n| format!("0xfork{}", n));
+    let indexed = db
+        .update_dataset(dataset_id, |tx| tx.index_existing_chunk(&fork))
+        .unwrap();
+    assert!(!indexed);
+
+    db.update_dataset(dataset_id, |tx| tx.delete_chunk(&chunk)).unwrap();
+    let indexed = db
+        .update_dataset(dataset_id, |tx| tx.index_existing_chunk(&chunk))
+        .unwrap();
+    assert!(!indexed);
+
+    for n in 0..=9 {
+        assert_absent(&db, dataset_id, &block_hash(n));
+    }
+}
+
+#[test]
+fn backfill_is_a_noop_when_indexes_are_disabled() {
+    let (_dir, db, dataset_id) = open_db_with("evm", false);
+
+    let chunk = make_evm_chunk(&db, 0, 9, "base");
+    db.insert_chunk(dataset_id, &chunk).unwrap();
+
+    let progress = HashIndexBackfill::new(&db, dataset_id).run(|_| true).unwrap();
+    assert_eq!(progress.chunks_total, 0);
+    assert_absent(&db, dataset_id, &block_hash(0));
+}
@@ -6,7 +6,7 @@ use std::{
 };
 
 use arrow::{
-    array::{ArrayRef, RecordBatch, StringArray, UInt32Array, UInt64Array},
+    array::{ArrayRef, ListBuilder, RecordBatch, StringArray, StringBuilder, UInt32Array, UInt64Array},
     datatypes::{DataType, Field, Schema}
 };
 use sqd_primitives::TransactionRef;
@@ -64,10 +64,63 @@ fn transaction_rows(first: u64, last: u64, per_block: u32, fork: u32) -> Vec<Tra
 }
 
 fn make_evm_chunk(db: &Database, first: u64, last: u64, parent_hash: &str, transactions: &[TransactionRow]) -> Chunk {
+    let hashes = Arc::new(StringArray::from(
+        transactions.iter().map(|row| row.hash.as_str()).collect::<Vec<_>>()
+    )) as ArrayRef;
+    make_chunk_with_hashes(
+        db,
+        first,
+        last,
+        parent_hash,
+        transactions,
+        Field::new("hash", DataType::Utf8, false),
+        hashes
+    )
+}
+
+/// Solana transactions are named by their first signature; the rest must not be indexed.
+fn make_solana_chunk(
+    db: &Database,
+    first: u64,
+    last: u64,
+    parent_hash: &str,
+    transactions: &[TransactionRow]
+) -> Chunk {
+    let mut signatures = ListBuilder::new(StringBuilder::new());
+    for row in transactions {
+        signatures.values().append_value(&row.hash);
+        signatures.values().append_value(secondary_signature(row));
+        signatures.append(true);
+    }
+    let item = Arc::new(Field::new("item", DataType::Utf8, true));
+    make_chunk_with_hashes(
+        db,
+        first,
+        last,
+        parent_hash,
+        transactions,
+        Field::new("signatures", DataType::List(item), false),
+        Arc::new(signatures.finish()) as ArrayRef
+    )
+}
+
+fn secondary_signature(row: &TransactionRow) -> String {
+    format!("{}-secondary", row.hash)
+}
+
+fn make_chunk_with_hashes(
+    db: &Database,
+    first: u64,
+    last: u64,
+    parent_hash: &str,
+    transactions: &[TransactionRow],
+    hash_field: Field,
+    hashes: ArrayRef
+) -> Chunk {
     let schema = Arc::new(Schema::new(vec![
         Field::new("block_number", DataType::UInt64, false),
         Field::new("transaction_index", DataType::UInt32, false),
-        Field::new("hash", DataType::Utf8, false),
+        hash_field,
     ]));
     let block_numbers = Arc::new(UInt64Array::from(
         transactions.iter().map(|row| row.block_number).collect::<Vec<_>>()
@@ -75,9 +128,6 @@ fn make_evm_chunk(db: &Database, first: u64, last: u64, parent_hash: &str, trans
     let transaction_indexes = Arc::new(UInt32Array::from(
         transactions.iter().map(|row| row.transaction_index).collect::<Vec<_>>()
     )) as ArrayRef;
-    let hashes = Arc::new(StringArray::from(
-        transactions.iter().map(|row| row.hash.as_str()).collect::<Vec<_>>()
-    )) as ArrayRef;
 
     let mut builder = db.new_table_builder(schema.clone());
     let batch = RecordBatch::try_new(schema, vec![block_numbers, transaction_indexes, hashes]).unwrap();
@@ -319,8 +369,8 @@ fn compaction_preserves_the_transaction_index() {
 }
 
 #[test]
-fn transaction_index_is_evm_only_and_independent_from_block_index() {
-    let (_dir, db, dataset_id) = open_db_with("solana", true, true);
+fn transaction_index_skips_unindexed_kinds_and_is_independent_from_block_index() {
+    let (_dir, db, dataset_id) = open_db_with("hl-fills", true, true);
     let rows = transaction_rows(0, 1, 2, 0);
     let chunk = make_evm_chunk(&db, 0, 1, "base", &rows);
     db.insert_chunk(dataset_id, &chunk).unwrap();
@@ -337,6 +387,85 @@ fn transaction_index_is_evm_only_and_independent_from_block_index() {
     }
 }
 
+#[test]
+fn solana_transactions_are_indexed_by_their_first_signature() {
+    let (_dir, db, dataset_id) = open_db_with("solana", false, true);
+    let rows1 = transaction_rows(0, 9, 2, 0);
+    let rows2 = transaction_rows(10, 19, 2, 0);
+    let chunk1 = make_solana_chunk(&db, 0, 9, "base", &rows1);
+    let chunk2 = make_solana_chunk(&db, 10, 19, &block_hash(9), &rows2);
+    db.insert_chunk(dataset_id, &chunk1).unwrap();
+    db.insert_chunk(dataset_id, &chunk2).unwrap();
+
+    for row in rows1.iter().chain(&rows2) {
+        assert_resolves(&db, dataset_id, row);
+        assert_absent(&db, dataset_id, &secondary_signature(row));
+    }
+
+    db.update_dataset(dataset_id, |tx| tx.delete_chunk(&chunk1)).unwrap();
+
+    for row in &rows1 {
+        assert_absent(&db, dataset_id, &row.hash);
+    }
+    for row in &rows2 {
+        assert_resolves(&db, dataset_id, row);
+    }
+}
+
+#[test]
+fn solana_transaction_without_signatures_is_rejected() {
+    let (_dir, db, dataset_id) = open_db_with("solana", false, true);
+    let mut signatures = ListBuilder::new(StringBuilder::new());
+    signatures.append(true);
+    let item = Arc::new(Field::new("item", DataType::Utf8, true));
+    let chunk = make_chunk_with_hashes(
+        &db,
+        0,
+        0,
+        "base",
+        &transaction_rows(0, 0, 1, 0),
+        Field::new("signatures", DataType::List(item), false),
+        Arc::new(signatures.finish()) as ArrayRef
+    );
+
+    let err = db.insert_chunk(dataset_id, &chunk).unwrap_err();
+
+    assert!(
+        format!("{err:#}").contains("transaction hash list must not be empty"),
+        "unexpected error: {err:#}"
+    );
+    assert!(db.snapshot().get_last_chunk(dataset_id).unwrap().is_none());
+}
+
+#[test]
+fn bitcoin_transactions_are_indexed_by_txid_and_tron_by_hash() {
+    let (_dir, db, dataset_id) = open_db_with("bitcoin", false, true);
+    let rows = transaction_rows(0, 9, 2, 0);
+    let txids = Arc::new(StringArray::from(
+        rows.iter().map(|row| row.hash.as_str()).collect::<Vec<_>>()
+    )) as ArrayRef;
+    let chunk = make_chunk_with_hashes(
+        &db,
+        0,
+        9,
+        "base",
+        &rows,
+        Field::new("txid", DataType::Utf8, false),
+        txids
+    );
+    db.insert_chunk(dataset_id, &chunk).unwrap();
+    for row in &rows {
+        assert_resolves(&db, dataset_id, row);
+    }
+
+    let (_dir, db, dataset_id) = open_db_with("tron", false, true);
+    let chunk = make_evm_chunk(&db, 0, 9, "base", &rows);
+    db.insert_chunk(dataset_id, &chunk).unwrap();
+    for row in &rows {
+        assert_resolves(&db, dataset_id, row);
+    }
+}
+
 #[test]
 fn enabling_the_index_does_not_backfill_existing_chunks() {
     let (dir, db, dataset_id) = open_db_with("evm", false, false);
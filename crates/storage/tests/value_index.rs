@@ -0,0 +1,129 @@
+use std::{collections::BTreeMap, sync::Arc};
+
+use arrow::{
+    array::{ArrayRef, RecordBatch, StringArray, UInt64Array},
+    datatypes::{DataType, Field, Schema, SchemaRef}
+};
+use sqd_storage::db::{Chunk, CompactionStatus, Database, TableId};
+use utils::setup_db;
+
+mod utils;
+
+const NUM_ROWS: u64 = 3 * 4096;
+
+fn schema() -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("block_number", DataType::UInt64, false),
+        Field::new("address", DataType::Utf8, true),
+    ]))
+}
+
+/// `0xaa` sits in the first and the last page only, `0xbb` is everywhere and every third row is null
+fn address(row: u64) -> Option<&'static str> {
+    match row {
+        5 | 9000 => Some("0xaa"),
+        _ if row % 3 == 0 => None,
+        _ => Some("0xbb")
+    }
+}
+
+fn write_table(db: &Database, first_block: u64, num_rows: u64, index: bool) -> TableId {
+    let schema = schema();
+    let block_numbers = Arc::new(UInt64Array::from_iter_values(
+        (0..num_rows).map(|row| first_block + row / 100)
+    )) as ArrayRef;
+    let addresses = Arc::new(StringArray::from_iter((0..num_rows).map(address))) as ArrayRef;
+    let batch = RecordBatch::try_new(schema.clone(), vec![block_numbers, addresses]).unwrap();
+
+    let mut builder = db.new_table_builder(schema);
+    builder.write_record_batch(&batch).unwrap();
+    builder.add_stat_by_name("address").unwrap();
+    if index {
+        builder.add_index_by_name("address").unwrap();
+    }
+    builder.finish().unwrap()
+}
+
+fn make_chunk(db: &Database, first_block: u64, last_block: u64, parent_hash: &str) -> Chunk {
+    let num_rows = (last_block - first_block + 1) * 100;
+    let mut tables = BTreeMap::new();
+    tables.insert("logs".to_owned(), write_table(db, first_block, num_rows, true));
+    Chunk::V1 {
+        first_block,
+        last_block,
+        last_block_hash: format!("hash_{last_block}"),
+        parent_block_hash: parent_hash.to_owned(),
+        first_block_time: None,
+        last_block_time: None,
+        tables
+    }
+}
+
+#[test]
+fn index_lists_the_pages_of_each_value() {
+    let (db, _dataset_id) = setup_db();
+    let table_id = write_table(&db, 0, NUM_ROWS, true);
+
+    let snapshot = db.snapshot();
+    let reader = snapshot.create_table_reader(table_id).unwrap();
+    let index = reader.get_column_index(1).unwrap().unwrap();
+    let stats = reader.get_column_stats(1).unwrap().unwrap();
+
+    assert_eq!(index.offsets, stats.offsets);
+    assert_eq!(index.num_pages(), 3);
+    assert_eq!(index.values.len(), 2, "nulls must not be indexed");
+    assert_eq!(index.find_pages(b"0xaa"), &[0, 2]);
+    assert_eq!(index.find_pages(b"0xbb"), &[0, 1, 2]);
+    assert!(index.find_pages(b"0xcc").is_empty());
+    assert!(index.find_pages(b"").is_empty());
+}
+
+#[test]
+fn index_is_optional() {
+    let (db, _dataset_id) = setup_db();
+    let table_id = write_table(&db, 0, 10, false);
+
+    let snapshot = db.snapshot();
+    let reader = snapshot.create_table_reader(table_id).unwrap();
+    assert!(reader.get_column_index(1).unwrap().is_none());
+    assert!(reader.get_column_stats(1).unwrap().is_some());
+}
+
+#[test]
+fn only_binary_columns_can_be_indexed() {
+    let (db, _dataset_id) = setup_db();
+    let mut builder = db.new_table_builder(schema());
+    assert!(builder.add_index_by_name("block_number").is_err());
+    assert!(builder.add_index_by_name("missing").is_err());
+    assert!(builder.set_indexes([0]).is_err());
+    assert!(builder.set_indexes([2]).is_err());
+    assert!(builder.set_indexes([1]).is_ok());
+}
+
+#[test]
+fn compaction_keeps_the_index() {
+    let (db, dataset_id) = setup_db();
+    let chunk1 = make_chunk(&db, 0, 49, "base");
+    let chunk2 = make_chunk(&db, 50, 99, "hash_49");
+    db.insert_chunk(dataset_id, &chunk1).unwrap();
+    db.insert_chunk(dataset_id, &chunk2).unwrap();
+
+    let mut merged = false;
+    while let CompactionStatus::Ok(_) = db
+        .perform_dataset_compaction(dataset_id, None, Some(1.25), None)
+        .unwrap()
+    {
+        merged = true;
+    }
+    assert!(merged, "expected compaction to merge the chunks");
+
+    let snapshot = db.snapshot();
+    let chunk = snapshot.get_last_chunk(dataset_id).unwrap().unwrap();
+    assert_eq!(chunk.first_block(), 0);
+    let reader = snapshot.create_table_reader(chunk.tables()["logs"]).unwrap();
+    let index = reader.get_column_index(1).unwrap().unwrap();
+
+    assert_eq!(*index.offsets.last().unwrap() as usize, reader.num_rows());
+    assert!(!index.find_pages(b"0xaa").is_empty());
+    assert_eq!(index.find_pages(b"0xbb").len(), index.num_pages());
+}
@@ -16,7 +16,7 @@ use sqd_array::{
 };
 use sqd_storage::{
     db::{Database, DatabaseSettings},
-    table::write::use_small_buffers
+    table::{codec::Codec, write::use_small_buffers}
 };
 
 mod arb_array;
@@ -31,10 +31,11 @@ fn to_record_batch(array: ArrayRef) -> RecordBatch {
     RecordBatch::try_new(Arc::new(schema), vec![array]).unwrap()
 }
 
-fn check_write_read(db: &Database, batches: Vec<RecordBatch>, stats_type: bool) -> anyhow::Result<()> {
+fn check_write_read(db: &Database, batches: Vec<RecordBatch>, stats_type: bool, codec: Codec) -> anyhow::Result<()> {
     let schema = batches[0].schema();
 
     let mut builder = db.new_table_builder(schema.clone());
+    builder.set_codec_by_name("c0", codec)?;
     for batch in batches.iter() {
         builder.write_record_batch(batch)?;
     }
@@ -47,6 +48,7 @@ fn check_write_read(db: &Database, batches: Vec<RecordBatch>, stats_type: bool)
 
     let snapshot = db.snapshot();
     let reader = snapshot.create_table_reader(table_id)?;
+    assert_eq!(codec, reader.get_column_codec(0));
 
     let input_table = arrow::compute::concat_batches(&schema, batches.iter())?;
     if have_stats {
@@ -84,8 +86,10 @@ fn test_write_read(array: impl Strategy<Value = ArrayRef>) -> anyhow::Result<()>
 
     let tables_strategy = prop::collection::vec(array.prop_map(to_record_batch), 1..=2);
 
-    proptest!(ProptestConfig::with_cases(WRITE_READ_ITERATIONS), |(tables in tables_strategy, stats_type in prop::bool::ANY)| {
-        check_write_read(&db, tables, stats_type).unwrap()
+    let codec_strategy = prop::sample::select(vec![Codec::None, Codec::Zstd]);
+
+    proptest!(ProptestConfig::with_cases(WRITE_READ_ITERATIONS), |(tables in tables_strategy, stats_type in prop::bool::ANY, codec in codec_strategy)| {
+        check_write_read(&db, tables, stats_type, codec).unwrap()
     });
 
     Ok(())
@@ -177,3 +181,18 @@ fn list_write_read() {
 fn struct_write_read() {
     test_write_read(arb_array::with_nullmask(arb_array::structs(0..WRITE_READ_ARRAY_SIZE))).unwrap()
 }
+
+#[test]
+fn codec_cant_be_changed_after_pages_were_written() {
+    let db_dir = tempfile::tempdir().unwrap();
+    let db = DatabaseSettings::default().open(db_dir.path()).unwrap();
+    let _sg = use_small_buffers();
+
+    let array = Arc::new(arrow::array::UInt64Array::from_iter_values(0..10_000)) as ArrayRef;
+    let batch = to_record_batch(array);
+
+    let mut builder = db.new_table_builder(batch.schema());
+    builder.write_record_batch(&batch).unwrap();
+    assert!(builder.set_codec_by_name("c0", Codec::Zstd).is_err());
+    assert!(builder.set_codec_by_name("missing", Codec::Zstd).is_err());
+}